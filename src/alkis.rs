@@ -0,0 +1,1279 @@
+//! Gemarkungskatalog und Flurstückskennzeichen nach ALKIS
+//!
+//! Im Grundbuch werden Gemarkungen nur mit Namen geführt, oft in abweichender
+//! Schreibweise oder mit OCR-Fehlern. Der Katalog ordnet diese Namen der
+//! amtlichen Gemarkungsnummer zu, daraus wird das 20-stellige
+//! Flurstückskennzeichen für den LEFIS- / ALKIS-Export gebildet.
+
+use crate::analyse::{AnalyseWarnung, BefundCode, GrundbuchAnalysiert};
+use crate::digital::{BvEintrag, FlurstueckNummer};
+use crate::Grundbuch;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Abweichung der Fläche im BV von der amtlichen Fläche, ab der gewarnt wird
+const FLAECHE_TOLERANZ_QM: f64 = 1.0;
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Gemarkung {
+    pub name: String,
+    // Gemarkungsnummer, innerhalb des Landes eindeutig (max. 4 Stellen)
+    pub nummer: usize,
+    // Länderschlüssel, z.B. "12" für Brandenburg
+    pub land: String,
+    // Abweichende Schreibweisen, unter denen die Gemarkung im Grundbuch vorkommt
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub aliase: Vec<String>,
+}
+
+// Vergleichsschlüssel für Gemarkungsnamen: Groß- / Kleinschreibung, Umlaute,
+// Leer- und Satzzeichen werden ignoriert
+fn namen_schluessel(name: &str) -> String {
+    let mut s = String::new();
+    for c in name.trim().to_lowercase().chars() {
+        match c {
+            'ä' => s.push_str("ae"),
+            'ö' => s.push_str("oe"),
+            'ü' => s.push_str("ue"),
+            'ß' => s.push_str("ss"),
+            c if c.is_alphanumeric() => s.push(c),
+            _ => {}
+        }
+    }
+    s
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let mut vorher = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.iter().enumerate() {
+        let mut aktuell = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let kosten = if ca == cb { 0 } else { 1 };
+            let wert = (vorher[j] + kosten)
+                .min(vorher[j + 1] + 1)
+                .min(aktuell[j] + 1);
+            aktuell.push(wert);
+        }
+        vorher = aktuell;
+    }
+    vorher[b.len()]
+}
+
+// Schlüssel für Name und Aliase einer Gemarkung
+fn gemarkung_schluessel(g: &Gemarkung) -> Vec<String> {
+    std::iter::once(&g.name)
+        .chain(g.aliase.iter())
+        .map(|n| namen_schluessel(n))
+        .collect()
+}
+
+/// Sucht die Gemarkung zu einem Namen aus dem Grundbuch über Name und Aliase.
+/// Abweichende Schreibweisen werden nur über den Alias-Katalog zugeordnet, damit
+/// verschiedene Gemarkungen (z.B. "Gatow" / "Satow") nie zusammenfallen.
+pub fn gemarkung_finden<'a>(katalog: &'a [Gemarkung], name: &str) -> Option<&'a Gemarkung> {
+    let gesucht = namen_schluessel(name);
+    if gesucht.is_empty() {
+        return None;
+    }
+
+    katalog
+        .iter()
+        .find(|g| gemarkung_schluessel(g).iter().any(|s| *s == gesucht))
+}
+
+/// Schlägt für einen nicht gefundenen Namen eine Gemarkung vor, OCR-Fehler
+/// (1 Zeichen, bei langen Namen 2 Zeichen) werden toleriert, solange der
+/// Vorschlag eindeutig bleibt. Nur für Hinweise an den Benutzer, nicht zum
+/// Filtern oder Zuordnen von Flurstücken verwenden.
+pub fn gemarkung_vorschlagen<'a>(katalog: &'a [Gemarkung], name: &str) -> Option<&'a Gemarkung> {
+    if let Some(g) = gemarkung_finden(katalog, name) {
+        return Some(g);
+    }
+
+    let gesucht = namen_schluessel(name);
+    let max_abstand = match gesucht.chars().count() {
+        0..=4 => return None,
+        5..=9 => 1,
+        _ => 2,
+    };
+
+    let mut treffer = katalog
+        .iter()
+        .filter_map(|g| {
+            let abstand = gemarkung_schluessel(g)
+                .iter()
+                .map(|s| levenshtein(s, &gesucht))
+                .min()?;
+            if abstand <= max_abstand {
+                Some((abstand, g))
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    treffer.sort_by_key(|(abstand, _)| *abstand);
+
+    match treffer.as_slice() {
+        [(_, g)] => Some(g),
+        [(a, g), (b, _), ..] if a < b => Some(g),
+        _ => None,
+    }
+}
+
+/// Gibt den Katalognamen der Gemarkung zurück, sonst den Namen unverändert
+pub fn gemarkung_normalisieren(katalog: &[Gemarkung], name: &str) -> String {
+    match gemarkung_finden(katalog, name) {
+        Some(g) => g.name.clone(),
+        None => name.trim().to_string(),
+    }
+}
+
+/// Liest einen Gemarkungskatalog im Format
+/// `LAND \t GEMARKUNGSNUMMER \t GEMARKUNG \t ALIASE` (Aliase durch ";" getrennt)
+pub fn gemarkungen_lesen(tsv: &str) -> Result<Vec<Gemarkung>, String> {
+    let mut gemarkungen = Vec::new();
+
+    for (i, line) in tsv.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with("LAND") {
+            continue;
+        }
+
+        let values = line.split('\t').map(|s| s.trim()).collect::<Vec<_>>();
+
+        let land = values.first().copied().unwrap_or_default();
+        if land.len() != 2 || !land.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!(
+                "Zeile {}: ungültiger Länderschlüssel \"{}\"",
+                i + 1,
+                land
+            ));
+        }
+
+        let nummer = values.get(1).copied().unwrap_or_default();
+        let nummer = match nummer.parse::<usize>() {
+            Ok(o) if o <= 9999 => o,
+            _ => {
+                return Err(format!(
+                    "Zeile {}: ungültige Gemarkungsnummer \"{}\"",
+                    i + 1,
+                    nummer
+                ))
+            }
+        };
+
+        let name = values.get(2).copied().unwrap_or_default();
+        if name.is_empty() {
+            return Err(format!("Zeile {}: Gemarkungsname fehlt", i + 1));
+        }
+
+        let aliase = values
+            .get(3)
+            .map(|a| {
+                a.split(';')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        gemarkungen.push(Gemarkung {
+            name: name.to_string(),
+            nummer,
+            land: land.to_string(),
+            aliase,
+        });
+    }
+
+    Ok(gemarkungen)
+}
+
+/// Übernimmt importierte Gemarkungen in den Katalog, vorhandene Einträge
+/// (gleiches Land und gleiche Nummer) werden ersetzt
+pub fn gemarkungen_zusammenfuehren(katalog: &mut Vec<Gemarkung>, neu: Vec<Gemarkung>) {
+    for g in neu {
+        match katalog
+            .iter_mut()
+            .find(|k| k.land == g.land && k.nummer == g.nummer)
+        {
+            Some(k) => *k = g,
+            None => katalog.push(g),
+        }
+    }
+    katalog.sort_by(|a, b| (&a.land, a.nummer).cmp(&(&b.land, b.nummer)));
+}
+
+/// Bildet das 20-stellige Flurstückskennzeichen:
+/// Land (2), Gemarkung (4), Flur (3), Zähler (5), Nenner (4, sonst "____"), "__"
+pub fn flurstueckskennzeichen(
+    gemarkung: &Gemarkung,
+    flur: usize,
+    flurstueck: &FlurstueckNummer,
+) -> Result<String, String> {
+    if gemarkung.land.len() != 2 || !gemarkung.land.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!(
+            "Ungültiger Länderschlüssel \"{}\" für Gemarkung {}",
+            gemarkung.land, gemarkung.name
+        ));
+    }
+    if gemarkung.nummer > 9999 {
+        return Err(format!(
+            "Gemarkungsnummer {} für Gemarkung {} hat mehr als 4 Stellen",
+            gemarkung.nummer, gemarkung.name
+        ));
+    }
+    if flur > 999 {
+        return Err(format!("Flur {} hat mehr als 3 Stellen", flur));
+    }
+    if flurstueck.zaehler > 99999 {
+        return Err(format!(
+            "Flurstückszähler {} hat mehr als 5 Stellen",
+            flurstueck.zaehler
+        ));
+    }
+
+    let nenner = match flurstueck.nenner {
+        Some(n) if n > 9999 => {
+            return Err(format!("Flurstücksnenner {} hat mehr als 4 Stellen", n));
+        }
+        Some(n) => format!("{:04}", n),
+        None => "____".to_string(),
+    };
+
+    Ok(format!(
+        "{}{:04}{:03}{:05}{}__",
+        gemarkung.land, gemarkung.nummer, flur, flurstueck.zaehler, nenner
+    ))
+}
+
+/// Flurstückskennzeichen für einen BV-Eintrag, ohne eigene Gemarkung gilt
+/// die Gemarkung des Grundbuchs (`grundbuch_von`)
+pub fn bv_flurstueckskennzeichen(
+    bv: &BvEintrag,
+    grundbuch_von: &str,
+    katalog: &[Gemarkung],
+) -> Result<String, String> {
+    let gemarkung_name = bv
+        .get_gemarkung()
+        .unwrap_or_else(|| grundbuch_von.to_string());
+
+    let gemarkung = gemarkung_finden(katalog, &gemarkung_name).ok_or_else(|| {
+        match gemarkung_vorschlagen(katalog, &gemarkung_name) {
+            Some(g) => format!(
+                "Gemarkung \"{}\" nicht im Gemarkungskatalog (meinten Sie \"{}\"? Dann bitte als Alias eintragen)",
+                gemarkung_name, g.name
+            ),
+            None => format!(
+                "Gemarkung \"{}\" nicht im Gemarkungskatalog",
+                gemarkung_name
+            ),
+        }
+    })?;
+
+    let flurstueck = bv.get_flurstueck_nummer().ok_or_else(|| {
+        format!(
+            "BV-Nr. {}: Flurstücksnummer \"{}\" nicht lesbar",
+            bv.get_lfd_nr(),
+            bv.get_flurstueck()
+        )
+    })?;
+
+    flurstueckskennzeichen(gemarkung, bv.get_flur(), &flurstueck)
+}
+
+// Kennzeichen aus NAS / GeoJSON vereinheitlichen: Flurstücksfolge ("00")
+// und leerer Nenner ("0000") werden wie beim selbst gebildeten Kennzeichen
+// als "__" / "____" geschrieben
+fn kennzeichen_normalisieren(kennzeichen: &str) -> String {
+    let k = kennzeichen
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+
+    if k.len() != 20 || !k.is_ascii() {
+        return k;
+    }
+
+    let nenner = match &k[14..18] {
+        "0000" => "____",
+        n => n,
+    };
+
+    format!("{}{}__", &k[..14], nenner)
+}
+
+// Zähler / Nenner aus einem (normalisierten) Flurstückskennzeichen
+fn kennzeichen_flurstueck(kennzeichen: &str) -> Option<FlurstueckNummer> {
+    if kennzeichen.len() != 20 || !kennzeichen.is_ascii() {
+        return None;
+    }
+    Some(FlurstueckNummer {
+        zaehler: kennzeichen[9..14].parse().ok()?,
+        nenner: match &kennzeichen[14..18] {
+            "____" => None,
+            n => Some(n.parse().ok()?),
+        },
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlkisFlurstueck {
+    pub kennzeichen: String,
+    pub amtliche_flaeche_qm: Option<f64>,
+    pub historisch: bool,
+}
+
+/// Lokaler ALKIS-Auszug (NAS-XML oder GeoJSON), Flurstücke nach Kennzeichen
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AlkisBestand {
+    pub flurstuecke: BTreeMap<String, AlkisFlurstueck>,
+}
+
+// NAS-Auszüge werden in Stücken dieser Größe gelesen, da sie mehrere
+// hundert MB groß sein können
+const NAS_PUFFER_GROESSE: usize = 1024 * 1024;
+
+impl AlkisBestand {
+    pub fn ist_leer(&self) -> bool {
+        self.flurstuecke.is_empty()
+    }
+
+    /// Lädt eine NAS-XML- oder GeoJSON-Datei, das Format wird am Inhalt erkannt
+    pub fn datei_laden(pfad: &Path) -> Result<Self, String> {
+        use std::io::{BufRead, BufReader};
+
+        let fehler =
+            |e: std::io::Error| format!("{} konnte nicht gelesen werden: {}", pfad.display(), e);
+
+        let mut reader = BufReader::new(std::fs::File::open(pfad).map_err(fehler)?);
+        let ist_geojson = loop {
+            let puffer = reader.fill_buf().map_err(fehler)?;
+            match puffer.iter().position(|b| !b.is_ascii_whitespace()) {
+                Some(i) => break puffer[i] == b'{',
+                None if puffer.is_empty() => break false,
+                None => {
+                    let n = puffer.len();
+                    reader.consume(n);
+                }
+            }
+        };
+
+        let bestand = if ist_geojson {
+            let json = serde_json::from_reader::<_, Value>(reader)
+                .map_err(|e| format!("Datei ist kein gültiges GeoJSON: {}", e))?;
+            Self::geojson_auswerten(&json)?
+        } else {
+            Self::nas_stueckweise_lesen(reader, NAS_PUFFER_GROESSE)?
+        };
+
+        if bestand.ist_leer() {
+            return Err(format!("{} enthält keine Flurstücke", pfad.display()));
+        }
+
+        Ok(bestand)
+    }
+
+    /// Liest AX_Flurstueck und AX_HistorischesFlurstueck* aus einem NAS-Auszug
+    pub fn nas_lesen(xml: &str) -> Result<Self, String> {
+        Self::nas_stueckweise_lesen(xml.as_bytes(), NAS_PUFFER_GROESSE)
+    }
+
+    // Liest den Auszug in Stücken von `puffer_groesse` Bytes, im Speicher
+    // bleibt nur der Rest nach dem letzten vollständigen Flurstück
+    fn nas_stueckweise_lesen<R: std::io::Read>(
+        mut reader: R,
+        puffer_groesse: usize,
+    ) -> Result<Self, String> {
+        use lazy_static::lazy_static;
+        use regex::bytes::Regex;
+
+        lazy_static! {
+            static ref REGEX_BLOCK: Regex = Regex::new(
+                r"(?s)<(?:\w+:)?AX_Flurstueck[\s>].*?</(?:\w+:)?AX_Flurstueck>|(?P<historisch><(?:\w+:)?AX_HistorischesFlurstueck\w*[\s>]).*?</(?:\w+:)?AX_HistorischesFlurstueck\w*>"
+            )
+            .unwrap();
+            static ref REGEX_BLOCK_ANFANG: Regex =
+                Regex::new(r"<(?:\w+:)?AX_(?:Historisches)?Flurstueck\w*[\s>]").unwrap();
+            static ref REGEX_KENNZEICHEN: Regex = Regex::new(
+                r"<(?:\w+:)?flurstueckskennzeichen>\s*([^<]+?)\s*</"
+            )
+            .unwrap();
+            static ref REGEX_FLAECHE: Regex =
+                Regex::new(r"<(?:\w+:)?amtlicheFlaeche[^>]*>\s*([0-9.,]+)\s*</").unwrap();
+            static ref REGEX_ENDET: Regex = Regex::new(r"<(?:\w+:)?endet>").unwrap();
+        }
+
+        // Ein Start-Tag kann am Ende eines Stücks abgeschnitten sein
+        const TAG_REST: usize = 256;
+
+        let mut bestand = AlkisBestand::default();
+        let mut ist_nas = false;
+        let mut puffer = Vec::new();
+        let mut stueck = vec![0; puffer_groesse.max(1)];
+
+        loop {
+            let gelesen = reader
+                .read(&mut stueck)
+                .map_err(|e| format!("NAS-Auszug konnte nicht gelesen werden: {}", e))?;
+            puffer.extend_from_slice(&stueck[..gelesen]);
+
+            ist_nas = ist_nas || puffer.windows(3).any(|w| w == b"AX_");
+
+            let mut verarbeitet = 0;
+            while let Some(c) = REGEX_BLOCK.captures_at(&puffer, verarbeitet) {
+                let m = c.get(0).unwrap();
+                let block = m.as_bytes();
+                verarbeitet = m.end();
+
+                let kennzeichen = match REGEX_KENNZEICHEN.captures(block) {
+                    Some(k) => kennzeichen_normalisieren(&String::from_utf8_lossy(&k[1])),
+                    None => continue,
+                };
+                let amtliche_flaeche_qm = REGEX_FLAECHE.captures(block).and_then(|f| {
+                    String::from_utf8_lossy(&f[1])
+                        .replace(',', ".")
+                        .parse()
+                        .ok()
+                });
+
+                bestand.einfuegen(AlkisFlurstueck {
+                    kennzeichen,
+                    amtliche_flaeche_qm,
+                    // Untergegangene Versionen eines Flurstücks tragen ein Enddatum
+                    historisch: c.name("historisch").is_some() || REGEX_ENDET.is_match(block),
+                });
+            }
+
+            if gelesen == 0 {
+                break;
+            }
+
+            // Unvollständiges Flurstück für das nächste Stück aufheben
+            let rest_ab = match REGEX_BLOCK_ANFANG.find_at(&puffer, verarbeitet) {
+                Some(m) => m.start(),
+                None => puffer.len().saturating_sub(TAG_REST).max(verarbeitet),
+            };
+            puffer.drain(..rest_ab);
+        }
+
+        if !ist_nas {
+            return Err(format!(
+                "Datei ist kein NAS-Auszug (keine AX_-Objekte gefunden)"
+            ));
+        }
+
+        Ok(bestand)
+    }
+
+    /// Liest eine GeoJSON-FeatureCollection, das Kennzeichen steht entweder
+    /// direkt in den Eigenschaften oder wird aus Land / Gemarkung / Flur /
+    /// Zähler / Nenner gebildet
+    pub fn geojson_lesen(json: &str) -> Result<Self, String> {
+        let json = serde_json::from_str::<Value>(json)
+            .map_err(|e| format!("Datei ist kein gültiges GeoJSON: {}", e))?;
+        Self::geojson_auswerten(&json)
+    }
+
+    fn geojson_auswerten(json: &Value) -> Result<Self, String> {
+        let features = json
+            .get("features")
+            .and_then(|f| f.as_array())
+            .ok_or_else(|| "GeoJSON enthält keine FeatureCollection".to_string())?;
+
+        let mut bestand = AlkisBestand::default();
+
+        for feature in features {
+            let eigenschaften = match feature.get("properties").and_then(|p| p.as_object()) {
+                Some(s) => s,
+                None => continue,
+            };
+
+            let kennzeichen = match geojson_kennzeichen(eigenschaften) {
+                Some(s) => s,
+                None => continue,
+            };
+
+            let amtliche_flaeche_qm =
+                eigenschaft(eigenschaften, &["amtlicheFlaeche", "flaeche", "afl"])
+                    .and_then(als_zahl);
+
+            let historisch = eigenschaft(eigenschaften, &["historisch"])
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+                || eigenschaft(eigenschaften, &["endet"]).map_or(false, |v| !v.is_null());
+
+            bestand.einfuegen(AlkisFlurstueck {
+                kennzeichen,
+                amtliche_flaeche_qm,
+                historisch,
+            });
+        }
+
+        Ok(bestand)
+    }
+
+    // Der aktuelle Stand hat Vorrang vor historischen Flurstücken
+    fn einfuegen(&mut self, flurstueck: AlkisFlurstueck) {
+        match self.flurstuecke.get(&flurstueck.kennzeichen) {
+            Some(alt) if !alt.historisch && flurstueck.historisch => {}
+            _ => {
+                self.flurstuecke
+                    .insert(flurstueck.kennzeichen.clone(), flurstueck);
+            }
+        }
+    }
+
+    /// Übernimmt die Flurstücke eines weiteren Auszugs
+    pub fn zusammenfuehren(&mut self, anderer: AlkisBestand) {
+        for f in anderer.flurstuecke.into_values() {
+            self.einfuegen(f);
+        }
+    }
+
+    // Enthält der Auszug Flurstücke mit diesem Präfix (z.B. Land + Gemarkung)?
+    fn enthaelt_praefix(&self, praefix: &str) -> bool {
+        self.flurstuecke
+            .range(praefix.to_string()..)
+            .next()
+            .map_or(false, |(k, _)| k.starts_with(praefix))
+    }
+
+    // Aktuelle Flurstücke in derselben Flur, deren Nummer sich nur in einem
+    // Zeichen unterscheidet - typische OCR-Verwechslungen (1 / 7, 3 / 8, ...)
+    fn aehnliche_flurstuecke(&self, kennzeichen: &str) -> Vec<String> {
+        let gesucht = match kennzeichen_flurstueck(kennzeichen) {
+            Some(s) => s.to_string(),
+            None => return Vec::new(),
+        };
+        let praefix = &kennzeichen[..9];
+
+        self.flurstuecke
+            .range(praefix.to_string()..)
+            .take_while(|(k, _)| k.starts_with(praefix))
+            .filter(|(_, f)| !f.historisch)
+            .filter_map(|(k, _)| kennzeichen_flurstueck(k))
+            .map(|f| f.to_string())
+            .filter(|f| levenshtein(f, &gesucht) == 1)
+            .take(5)
+            .collect()
+    }
+}
+
+fn eigenschaft<'a>(
+    eigenschaften: &'a serde_json::Map<String, Value>,
+    namen: &[&str],
+) -> Option<&'a Value> {
+    eigenschaften
+        .iter()
+        .find(|(k, _)| namen.iter().any(|n| k.eq_ignore_ascii_case(n)))
+        .map(|(_, v)| v)
+}
+
+fn als_text(v: &Value) -> Option<String> {
+    match v {
+        Value::String(s) => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn als_zahl(v: &Value) -> Option<f64> {
+    match v {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().replace(',', ".").parse().ok(),
+        _ => None,
+    }
+}
+
+fn geojson_kennzeichen(eigenschaften: &serde_json::Map<String, Value>) -> Option<String> {
+    if let Some(k) = eigenschaft(
+        eigenschaften,
+        &["flurstueckskennzeichen", "flstkennz", "kennzeichen", "fsk"],
+    )
+    .and_then(als_text)
+    {
+        return Some(kennzeichen_normalisieren(&k));
+    }
+
+    let zahl = |namen: &[&str]| -> Option<usize> {
+        als_text(eigenschaft(eigenschaften, namen)?)?.parse().ok()
+    };
+
+    let gemarkung = Gemarkung {
+        land: format!("{:02}", zahl(&["land", "landschl"])?),
+        nummer: zahl(&["gemarkungsnummer", "gemarkung", "gmkn"])?,
+        ..Gemarkung::default()
+    };
+    let flurstueck = FlurstueckNummer {
+        zaehler: zahl(&["zaehler", "flstnrzae"])?,
+        nenner: zahl(&["nenner", "flstnrnen"]),
+    };
+
+    flurstueckskennzeichen(&gemarkung, zahl(&["flurnummer", "flur"])?, &flurstueck).ok()
+}
+
+/// Ergebnis des Abgleichs der BV-Flurstücke mit dem ALKIS-Auszug
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlkisAbgleich {
+    pub warnungen: Vec<AnalyseWarnung>,
+}
+
+/// Prüft für jedes (nicht gerötete) Flurstück im BV, ob es in ALKIS existiert,
+/// ob es historisch ist und ob die Fläche mit der amtlichen Fläche übereinstimmt
+pub fn bv_abgleichen(
+    grundbuch: &Grundbuch,
+    katalog: &[Gemarkung],
+    bestand: &AlkisBestand,
+) -> AlkisAbgleich {
+    let mut warnungen = Vec::new();
+
+    if bestand.ist_leer() {
+        return AlkisAbgleich { warnungen };
+    }
+
+    let grundbuch_von = &grundbuch.titelblatt.grundbuch_von;
+    let mut gemarkungen_nicht_enthalten = BTreeSet::new();
+
+    for bv in grundbuch.bestandsverzeichnis.eintraege.iter() {
+        let flst = match bv {
+            BvEintrag::Flurstueck(f) if !bv.ist_geroetet() => f,
+            _ => continue,
+        };
+
+        let bezeichnung = format!(
+            "BV-Nr. {}: Flur {} Flst. {}",
+            flst.lfd_nr, flst.flur, flst.flurstueck
+        );
+
+        let kennzeichen = match bv_flurstueckskennzeichen(bv, grundbuch_von, katalog) {
+            Ok(o) => o,
+            Err(e) => {
+                warnungen.push(
+                    BefundCode::AlkisKeinAbgleich {
+                        flurstueck: bezeichnung,
+                        grund: e,
+                    }
+                    .into(),
+                );
+                continue;
+            }
+        };
+
+        // Gemarkungen außerhalb des Auszugs nur einmal melden
+        if !bestand.enthaelt_praefix(&kennzeichen[..6]) {
+            let gemarkung = flst.gemarkung.clone().unwrap_or(grundbuch_von.clone());
+            if gemarkungen_nicht_enthalten.insert(gemarkung.clone()) {
+                warnungen.push(BefundCode::AlkisGemarkungFehlt { gemarkung }.into());
+            }
+            continue;
+        }
+
+        match bestand.flurstuecke.get(&kennzeichen) {
+            None => {
+                warnungen.push(
+                    BefundCode::AlkisFlurstueckFehlt {
+                        flurstueck: bezeichnung,
+                        aehnlich: bestand.aehnliche_flurstuecke(&kennzeichen),
+                    }
+                    .into(),
+                );
+            }
+            Some(f) if f.historisch => {
+                warnungen.push(
+                    BefundCode::AlkisFlurstueckHistorisch {
+                        flurstueck: bezeichnung,
+                    }
+                    .into(),
+                );
+            }
+            Some(f) => {
+                let amtlich = match f.amtliche_flaeche_qm {
+                    Some(s) => s,
+                    None => continue,
+                };
+                if flst.groesse.ist_leer() {
+                    continue;
+                }
+                let bv_qm = flst.groesse.get_m2();
+                if (bv_qm as f64 - amtlich).abs() > FLAECHE_TOLERANZ_QM {
+                    warnungen.push(
+                        BefundCode::AlkisFlaecheAbweichend {
+                            flurstueck: bezeichnung,
+                            flaeche_qm: bv_qm,
+                            amtlich_qm: amtlich.to_string(),
+                        }
+                        .into(),
+                    );
+                }
+            }
+        }
+    }
+
+    AlkisAbgleich { warnungen }
+}
+
+/// Flurstücksgeometrien nach Flurstückskennzeichen, aus GeoJSON oder Shapefile
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FlurstueckGeometrien {
+    pub geometrien: BTreeMap<String, Value>,
+    // Koordinatenreferenzsystem der Quelldatei als GeoJSON-"crs"-Objekt
+    pub crs: Option<Value>,
+}
+
+impl FlurstueckGeometrien {
+    /// Lädt eine GeoJSON-Datei oder ein Shapefile (.shp mit .dbf und optional .prj)
+    pub fn datei_laden(pfad: &Path) -> Result<Self, String> {
+        let ist_shapefile = pfad
+            .extension()
+            .map_or(false, |e| e.to_string_lossy().eq_ignore_ascii_case("shp"));
+
+        let geometrien = if ist_shapefile {
+            Self::shapefile_laden(pfad)?
+        } else {
+            let text = std::fs::read_to_string(pfad)
+                .map_err(|e| format!("{} konnte nicht gelesen werden: {}", pfad.display(), e))?;
+            Self::geojson_lesen(&text)?
+        };
+
+        if geometrien.geometrien.is_empty() {
+            return Err(format!(
+                "{} enthält keine Flurstücke mit Flurstückskennzeichen",
+                pfad.display()
+            ));
+        }
+
+        Ok(geometrien)
+    }
+
+    pub fn geojson_lesen(json: &str) -> Result<Self, String> {
+        let json = serde_json::from_str::<Value>(json)
+            .map_err(|e| format!("Datei ist kein gültiges GeoJSON: {}", e))?;
+
+        let features = json
+            .get("features")
+            .and_then(|f| f.as_array())
+            .ok_or_else(|| "GeoJSON enthält keine FeatureCollection".to_string())?;
+
+        let mut geometrien = BTreeMap::new();
+        for feature in features {
+            let kennzeichen = match feature
+                .get("properties")
+                .and_then(|p| p.as_object())
+                .and_then(geojson_kennzeichen)
+            {
+                Some(s) => s,
+                None => continue,
+            };
+            match feature.get("geometry") {
+                Some(g) if !g.is_null() => {
+                    geometrien.insert(kennzeichen, g.clone());
+                }
+                _ => {}
+            }
+        }
+
+        Ok(FlurstueckGeometrien {
+            geometrien,
+            crs: json.get("crs").cloned(),
+        })
+    }
+
+    fn shapefile_laden(pfad: &Path) -> Result<Self, String> {
+        let lesen = |endung: &str| -> Result<Vec<u8>, String> {
+            let p = pfad.with_extension(endung);
+            std::fs::read(&p)
+                .or_else(|_| std::fs::read(pfad.with_extension(endung.to_uppercase())))
+                .map_err(|e| format!("{} konnte nicht gelesen werden: {}", p.display(), e))
+        };
+
+        let shapes = shp_lesen(&lesen("shp")?)?;
+        let attribute = dbf_lesen(&lesen("dbf")?)?;
+
+        if shapes.len() != attribute.len() {
+            return Err(format!(
+                "Shapefile ist fehlerhaft: {} Geometrien, aber {} Datensätze in der .dbf-Datei",
+                shapes.len(),
+                attribute.len()
+            ));
+        }
+
+        let mut geometrien = BTreeMap::new();
+        for (geometrie, eigenschaften) in shapes.into_iter().zip(attribute.iter()) {
+            if let (Some(g), Some(k)) = (geometrie, geojson_kennzeichen(eigenschaften)) {
+                geometrien.insert(k, g);
+            }
+        }
+
+        let crs = lesen("prj")
+            .ok()
+            .and_then(|prj| prj_epsg(&String::from_utf8_lossy(&prj)))
+            .map(|epsg| {
+                serde_json::json!({
+                    "type": "name",
+                    "properties": { "name": format!("urn:ogc:def:crs:EPSG::{}", epsg) },
+                })
+            });
+
+        Ok(FlurstueckGeometrien { geometrien, crs })
+    }
+}
+
+// In ALKIS übliche Bezugssysteme (ETRS89 / UTM) aus der .prj-Datei erkennen
+fn prj_epsg(prj: &str) -> Option<usize> {
+    let prj = prj.to_lowercase().replace(' ', "_");
+    if !prj.contains("etrs") {
+        return None;
+    }
+    if prj.contains("utm_zone_33") {
+        Some(25833)
+    } else if prj.contains("utm_zone_32") {
+        Some(25832)
+    } else {
+        None
+    }
+}
+
+fn shp_i32_be(bytes: &[u8], offset: usize) -> Result<i32, String> {
+    bytes
+        .get(offset..offset + 4)
+        .map(|b| i32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| format!("Shapefile ist unvollständig (Offset {})", offset))
+}
+
+fn shp_i32_le(bytes: &[u8], offset: usize) -> Result<i32, String> {
+    bytes
+        .get(offset..offset + 4)
+        .map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| format!("Shapefile ist unvollständig (Offset {})", offset))
+}
+
+fn shp_f64_le(bytes: &[u8], offset: usize) -> Result<f64, String> {
+    let mut b = [0; 8];
+    b.copy_from_slice(
+        bytes
+            .get(offset..offset + 8)
+            .ok_or_else(|| format!("Shapefile ist unvollständig (Offset {})", offset))?,
+    );
+    Ok(f64::from_le_bytes(b))
+}
+
+// Liest die Geometrien einer .shp-Datei (nur Polygone), Null-Shapes ergeben None
+fn shp_lesen(bytes: &[u8]) -> Result<Vec<Option<Value>>, String> {
+    if shp_i32_be(bytes, 0)? != 9994 {
+        return Err("Datei ist keine gültige .shp-Datei".to_string());
+    }
+
+    let mut shapes = Vec::new();
+    let mut offset = 100;
+
+    while offset + 8 <= bytes.len() {
+        // Länge in 16-Bit-Worten
+        let laenge = shp_i32_be(bytes, offset + 4)?.max(0) as usize * 2;
+        let inhalt = offset + 8;
+
+        shapes.push(match shp_i32_le(bytes, inhalt)? {
+            0 => None,
+            // Polygon, PolygonZ, PolygonM - Z / M werden ignoriert
+            5 | 15 | 25 => Some(shp_polygon_lesen(bytes, inhalt)?),
+            typ => {
+                return Err(format!(
+                    "Shape-Typ {} wird nicht unterstützt, nur Polygone",
+                    typ
+                ))
+            }
+        });
+
+        offset = inhalt + laenge;
+    }
+
+    Ok(shapes)
+}
+
+fn shp_polygon_lesen(bytes: &[u8], inhalt: usize) -> Result<Value, String> {
+    let anzahl_teile = shp_i32_le(bytes, inhalt + 36)?.max(0) as usize;
+    let anzahl_punkte = shp_i32_le(bytes, inhalt + 40)?.max(0) as usize;
+    let punkte_start = inhalt + 44 + 4 * anzahl_teile;
+
+    let mut teile = Vec::new();
+    for i in 0..anzahl_teile {
+        teile.push(shp_i32_le(bytes, inhalt + 44 + 4 * i)?.max(0) as usize);
+    }
+    teile.push(anzahl_punkte);
+
+    // Im Shapefile sind äußere Ringe im Uhrzeigersinn, Löcher gegen den
+    // Uhrzeigersinn orientiert - in GeoJSON genau umgekehrt
+    let mut polygone: Vec<Vec<Vec<[f64; 2]>>> = Vec::new();
+    for t in teile.windows(2) {
+        let mut ring = Vec::new();
+        for p in t[0]..t[1].min(anzahl_punkte) {
+            let x = shp_f64_le(bytes, punkte_start + 16 * p)?;
+            let y = shp_f64_le(bytes, punkte_start + 16 * p + 8)?;
+            ring.push([x, y]);
+        }
+
+        let flaeche_doppelt = ring
+            .windows(2)
+            .map(|w| w[0][0] * w[1][1] - w[1][0] * w[0][1])
+            .sum::<f64>();
+
+        ring.reverse();
+
+        match polygone.last_mut() {
+            Some(polygon) if flaeche_doppelt > 0.0 => polygon.push(ring),
+            _ => polygone.push(vec![ring]),
+        }
+    }
+
+    Ok(if polygone.len() == 1 {
+        serde_json::json!({ "type": "Polygon", "coordinates": polygone[0] })
+    } else {
+        serde_json::json!({ "type": "MultiPolygon", "coordinates": polygone })
+    })
+}
+
+// Liest die Attribute einer .dbf-Datei als Text, gelöschte Datensätze werden
+// übersprungen
+fn dbf_lesen(bytes: &[u8]) -> Result<Vec<serde_json::Map<String, Value>>, String> {
+    let unvollstaendig = || "Die .dbf-Datei ist unvollständig".to_string();
+
+    let kopf = bytes.get(0..32).ok_or_else(unvollstaendig)?;
+    let anzahl = u32::from_le_bytes([kopf[4], kopf[5], kopf[6], kopf[7]]) as usize;
+    let kopf_laenge = u16::from_le_bytes([kopf[8], kopf[9]]) as usize;
+    let satz_laenge = u16::from_le_bytes([kopf[10], kopf[11]]) as usize;
+
+    // Felder: je 32 Byte, Ende mit 0x0D
+    let mut felder = Vec::new();
+    let mut offset = 32;
+    while bytes.get(offset).map_or(false, |b| *b != 0x0D) {
+        let feld = bytes.get(offset..offset + 32).ok_or_else(unvollstaendig)?;
+        let name = feld[..11]
+            .iter()
+            .take_while(|b| **b != 0)
+            .map(|b| *b as char)
+            .collect::<String>();
+        felder.push((name, feld[16] as usize));
+        offset += 32;
+    }
+
+    let mut saetze = Vec::new();
+    for i in 0..anzahl {
+        let start = kopf_laenge + i * satz_laenge;
+        let satz = bytes
+            .get(start..start + satz_laenge)
+            .ok_or_else(unvollstaendig)?;
+
+        let mut eigenschaften = serde_json::Map::new();
+        let mut pos = 1;
+        for (name, laenge) in felder.iter() {
+            let wert = satz.get(pos..pos + laenge).ok_or_else(unvollstaendig)?;
+            // UTF-8, sonst ISO-8859-1
+            let wert = match std::str::from_utf8(wert) {
+                Ok(s) => s.to_string(),
+                Err(_) => wert.iter().map(|b| *b as char).collect(),
+            };
+            eigenschaften.insert(name.clone(), Value::String(wert.trim().to_string()));
+            pos += laenge;
+        }
+
+        // Gelöschte Datensätze behalten ihren Platz in der .shp-Datei
+        if satz.first() == Some(&b'*') {
+            eigenschaften.clear();
+        }
+
+        saetze.push(eigenschaften);
+    }
+
+    Ok(saetze)
+}
+
+/// Belastete Flurstücke als GeoJSON-FeatureCollection: je Blatt und Flurstück
+/// ein Feature mit allen Rechten aus Abt. 2 / Abt. 3, die darauf lasten.
+/// Gibt außerdem die Flurstücke zurück, die nicht exportiert werden konnten.
+pub fn belastungen_geojson(
+    blaetter: &[GrundbuchAnalysiert],
+    katalog: &[Gemarkung],
+    geometrien: &FlurstueckGeometrien,
+) -> (Value, Vec<String>) {
+    let mut flurstuecke = BTreeMap::new();
+    let mut fehler = Vec::new();
+
+    for gb in blaetter {
+        let grundbuch_von = &gb.titelblatt.grundbuch_von;
+        let blatt = &gb.titelblatt.blatt;
+
+        let rechte = gb
+            .abt2
+            .iter()
+            .map(|a2| {
+                let recht = serde_json::json!({
+                    "abteilung": 2,
+                    "lfd_nr": a2.lfd_nr,
+                    "rechteart": serde_json::to_value(&a2.rechteart).unwrap_or_default(),
+                    "kurztext": a2.text_kurz,
+                    "rechtsinhaber": a2.rechtsinhaber,
+                    "ordnungsnummer": a2.nebenbeteiligter.ordnungsnummer,
+                });
+                (&a2.belastete_flurstuecke, recht)
+            })
+            .chain(gb.abt3.iter().map(|a3| {
+                let recht = serde_json::json!({
+                    "abteilung": 3,
+                    "lfd_nr": a3.lfd_nr,
+                    "schuldenart": a3.schuldenart.to_string(),
+                    "betrag": format!(
+                        "{} {}",
+                        crate::kurztext::formatiere_betrag(&a3.betrag),
+                        a3.betrag.waehrung.to_string()
+                    ),
+                    "kurztext": a3.text_kurz,
+                    "rechtsinhaber": a3.rechtsinhaber,
+                    "ordnungsnummer": a3.nebenbeteiligter.ordnungsnummer,
+                });
+                (&a3.belastete_flurstuecke, recht)
+            }));
+
+        for (belastet, recht) in rechte {
+            for bv in belastet {
+                if !matches!(bv, BvEintrag::Flurstueck(_)) {
+                    continue;
+                }
+
+                let kennzeichen = match bv_flurstueckskennzeichen(bv, grundbuch_von, katalog) {
+                    Ok(o) => o,
+                    Err(e) => {
+                        fehler.push(format!("{} Blatt {}: {}", grundbuch_von, blatt, e));
+                        continue;
+                    }
+                };
+
+                flurstuecke
+                    .entry((grundbuch_von.clone(), blatt.clone(), kennzeichen))
+                    .or_insert_with(|| (bv.clone(), Vec::new()))
+                    .1
+                    .push(recht.clone());
+            }
+        }
+    }
+
+    let mut features = Vec::new();
+    for ((grundbuch_von, blatt, kennzeichen), (bv, rechte)) in flurstuecke {
+        let geometrie = match geometrien.geometrien.get(&kennzeichen) {
+            Some(s) => s.clone(),
+            None => {
+                fehler.push(format!(
+                    "{} Blatt {}: keine Geometrie für Flurstück {} (Flur {} Flst. {})",
+                    grundbuch_von,
+                    blatt,
+                    kennzeichen,
+                    bv.get_flur(),
+                    bv.get_flurstueck()
+                ));
+                continue;
+            }
+        };
+
+        features.push(serde_json::json!({
+            "type": "Feature",
+            "geometry": geometrie,
+            "properties": {
+                "flurstueckskennzeichen": kennzeichen,
+                "gemarkung": gemarkung_normalisieren(
+                    katalog,
+                    &bv.get_gemarkung().unwrap_or(grundbuch_von.clone())
+                ),
+                "flur": bv.get_flur(),
+                "flurstueck": bv.get_flurstueck(),
+                "grundbuch_von": grundbuch_von,
+                "blatt": blatt,
+                "bv_nr": bv.get_lfd_nr(),
+                "anzahl_rechte": rechte.len(),
+                "rechte": rechte,
+            },
+        }));
+    }
+
+    fehler.sort();
+    fehler.dedup();
+
+    let mut collection = serde_json::json!({
+        "type": "FeatureCollection",
+        "features": features,
+    });
+    if let Some(crs) = geometrien.crs.clone() {
+        collection["crs"] = crs;
+    }
+
+    (collection, fehler)
+}
+
+#[test]
+fn test_flurstueckskennzeichen() {
+    let katalog = gemarkungen_lesen(
+        "LAND\tGEMARKUNGSNUMMER\tGEMARKUNG\tALIASE\n\
+         12\t3456\tGroß Köris\tGr. Köris; Gross Koeris\n\
+         12\t3457\tKlein Köris\t\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        gemarkung_finden(&katalog, "Gross-Köris").map(|g| g.nummer),
+        Some(3456)
+    );
+    assert_eq!(
+        gemarkung_finden(&katalog, "Gr. Köris").map(|g| g.nummer),
+        Some(3456)
+    );
+    // OCR-Fehler: nur als Vorschlag, nie als Zuordnung
+    assert_eq!(gemarkung_finden(&katalog, "Kiein Köris"), None);
+    assert_eq!(
+        gemarkung_vorschlagen(&katalog, "Kiein Köris").map(|g| g.nummer),
+        Some(3457)
+    );
+    assert_eq!(gemarkung_finden(&katalog, "Teupitz"), None);
+    assert_eq!(gemarkung_vorschlagen(&katalog, "Teupitz"), None);
+
+    let flst = FlurstueckNummer::parse(" 017 / 3").unwrap();
+    assert_eq!(
+        flst,
+        FlurstueckNummer {
+            zaehler: 17,
+            nenner: Some(3)
+        }
+    );
+    assert_eq!(format!("{}", flst), "17/3");
+    assert_eq!(FlurstueckNummer::parse("17/3a"), None);
+
+    assert_eq!(
+        flurstueckskennzeichen(&katalog[0], 2, &flst),
+        Ok("123456002000170003__".to_string())
+    );
+    assert_eq!(
+        flurstueckskennzeichen(&katalog[1], 2, &FlurstueckNummer::parse("117").unwrap()),
+        Ok("12345700200117______".to_string())
+    );
+}
+
+#[test]
+fn test_gemarkung_beinahe_gleich() {
+    let katalog = gemarkungen_lesen(
+        "LAND\tGEMARKUNGSNUMMER\tGEMARKUNG\tALIASE\n\
+         13\t1001\tGatow\t\n\
+         13\t1002\tSatow\t\n\
+         13\t1003\tKarow\t\n",
+    )
+    .unwrap();
+
+    // Unterschied von einem Buchstaben, aber verschiedene Gemarkungen
+    assert_eq!(gemarkung_normalisieren(&katalog, "Gatow"), "Gatow");
+    assert_eq!(gemarkung_normalisieren(&katalog, "Satow"), "Satow");
+    assert_eq!(
+        gemarkung_finden(&katalog, "Satow").map(|g| g.nummer),
+        Some(1002)
+    );
+
+    // Nur "Gatow" im Katalog: "Satow" wird nicht auf "Gatow" abgebildet
+    let nur_gatow = vec![katalog[0].clone()];
+    assert_eq!(gemarkung_normalisieren(&nur_gatow, "Satow"), "Satow");
+    assert_eq!(
+        gemarkung_vorschlagen(&nur_gatow, "Satow").map(|g| g.nummer),
+        Some(1001)
+    );
+
+    // Mehrdeutiger Vorschlag ("Xatow" -> Gatow / Satow / Karow?)
+    assert_eq!(gemarkung_vorschlagen(&katalog, "Xatow"), None);
+}
+
+#[test]
+fn test_alkis_nas_lesen() {
+    let nas = "<AX_Bestandsdatenauszug>\n\
+        <enthaelt><wfs:FeatureCollection>\n\
+        <gml:featureMember><AX_Flurstueck gml:id=\"DEBBAL01\">\n\
+            <flurstueckskennzeichen>123456002000170003__</flurstueckskennzeichen>\n\
+            <amtlicheFlaeche uom=\"m2\">1250</amtlicheFlaeche>\n\
+        </AX_Flurstueck></gml:featureMember>\n\
+        <gml:featureMember><AX_Flurstueck gml:id=\"DEBBAL02\">\n\
+            <flurstueckskennzeichen>12345600200018000000</flurstueckskennzeichen>\n\
+            <amtlicheFlaeche uom=\"m2\">310</amtlicheFlaeche>\n\
+        </AX_Flurstueck></gml:featureMember>\n\
+        <gml:featureMember><AX_HistorischesFlurstueckOhneRaumbezug gml:id=\"DEBBAL03\">\n\
+            <flurstueckskennzeichen>123456002000190000__</flurstueckskennzeichen>\n\
+        </AX_HistorischesFlurstueckOhneRaumbezug></gml:featureMember>\n\
+        </wfs:FeatureCollection></enthaelt></AX_Bestandsdatenauszug>";
+
+    let bestand = AlkisBestand::nas_lesen(nas).unwrap();
+    assert_eq!(bestand.flurstuecke.len(), 3);
+    // Stückweise gelesen, Tags und Flurstücke über Stückgrenzen hinweg
+    for groesse in [1, 7, 64] {
+        assert_eq!(
+            AlkisBestand::nas_stueckweise_lesen(nas.as_bytes(), groesse),
+            Ok(bestand.clone())
+        );
+    }
+    assert!(AlkisBestand::nas_lesen("<xml></xml>").is_err());
+    assert_eq!(
+        bestand.flurstuecke["123456002000170003__"].amtliche_flaeche_qm,
+        Some(1250.0)
+    );
+    assert!(bestand.flurstuecke.contains_key("12345600200018______"));
+    assert!(bestand.flurstuecke["12345600200019______"].historisch);
+    assert_eq!(
+        bestand.aehnliche_flurstuecke("123456002000170008__"),
+        vec!["17/3".to_string()]
+    );
+}
+
+#[test]
+fn test_shapefile_lesen() {
+    // Quadrat (im Uhrzeigersinn) mit Loch (gegen den Uhrzeigersinn)
+    let ringe: [&[[f64; 2]]; 2] = [
+        &[[0.0, 0.0], [0.0, 4.0], [4.0, 4.0], [4.0, 0.0], [0.0, 0.0]],
+        &[[1.0, 1.0], [2.0, 1.0], [2.0, 2.0], [1.0, 1.0]],
+    ];
+    let mut inhalt = Vec::new();
+    inhalt.extend(5_i32.to_le_bytes());
+    inhalt.extend([0; 32]);
+    inhalt.extend(2_i32.to_le_bytes());
+    inhalt.extend(9_i32.to_le_bytes());
+    inhalt.extend(0_i32.to_le_bytes());
+    inhalt.extend(5_i32.to_le_bytes());
+    for [x, y] in ringe.iter().flat_map(|r| r.iter()) {
+        inhalt.extend(x.to_le_bytes());
+        inhalt.extend(y.to_le_bytes());
+    }
+    let mut shp = vec![0; 100];
+    shp[..4].copy_from_slice(&9994_i32.to_be_bytes());
+    shp.extend(1_i32.to_be_bytes());
+    shp.extend((inhalt.len() as i32 / 2).to_be_bytes());
+    shp.extend(inhalt);
+
+    let shapes = shp_lesen(&shp).unwrap();
+    assert_eq!(shapes.len(), 1);
+    let polygon = shapes[0].as_ref().unwrap();
+    assert_eq!(polygon["type"], "Polygon");
+    assert_eq!(polygon["coordinates"].as_array().unwrap().len(), 2);
+    assert_eq!(polygon["coordinates"][0][1], serde_json::json!([4.0, 0.0]));
+
+    let mut dbf = vec![0; 32];
+    dbf[4] = 1;
+    dbf[8] = 65;
+    dbf[10] = 21;
+    let mut feld = vec![0; 32];
+    feld[..9].copy_from_slice(b"FLSTKENNZ");
+    feld[11] = b'C';
+    feld[16] = 20;
+    dbf.extend(feld);
+    dbf.push(0x0D);
+    dbf.push(b' ');
+    dbf.extend(b"123456002000170003__");
+
+    let attribute = dbf_lesen(&dbf).unwrap();
+    assert_eq!(
+        geojson_kennzeichen(&attribute[0]),
+        Some("123456002000170003__".to_string())
+    );
+}
@@ -1,48 +1,1211 @@
-use crate::digital::{self, Abt2Eintrag, BvEintrag, Nebenbeteiligter};
+use crate::alkis;
+use crate::cmd::Cmd;
+use crate::digital::{
+    self, flurstueck_gleich, Abt1Eintrag, Abt1GrundEintragung, Abt2Eintrag, Abteilung1,
+    Bestandsverzeichnis, BvEintrag, ErbbauGrundstueck, Nebenbeteiligter, StringOrLines,
+    Zustimmungsvorbehalt,
+};
 use crate::get_or_insert_regex;
-use crate::python::{Betrag, PyVm, RechteArt, SchuldenArt, Spalte1Eintrag, Waehrung};
+use crate::pruefung;
+use crate::python::{
+    Betrag, Eigentuemer, EintragPruefen, FlurFlurstueck, PyVm, RechteArt, SchuldenArt,
+    Spalte1Eintrag, Waehrung,
+};
 use crate::{Abt3Eintrag, Grundbuch, Konfiguration, NebenbeteiligterExtra, Titelblatt};
 use serde_derive::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
 use std::sync::{Arc, Mutex};
+use wry::application::event_loop::EventLoopProxy;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GrundbuchAnalysiert {
     #[serde(skip, default)]
     pub titelblatt: Titelblatt,
+    #[serde(default)]
+    pub abt1: Vec<Abt1Analysiert>,
     pub abt2: Vec<Abt2Analysiert>,
     pub abt3: Vec<Abt3Analysiert>,
+    #[serde(default)]
+    pub erbbaurechte: Vec<ErbbaurechtAnalysiert>,
+    // Löschungen Abt. 2 / Abt. 3, nicht Teil des LEFIS-Exports
+    #[serde(skip, default)]
+    pub loeschungen: LoeschungenAnalysiert,
+    #[serde(default)]
+    pub rang: RangAnalysiert,
+    // Abgleich der BV-Flurstücke mit dem ALKIS-Auszug, nicht Teil des LEFIS-Exports
+    #[serde(skip, default)]
+    pub alkis: alkis::AlkisAbgleich,
+    // Plausibilitätsprüfung des gesamten Grundbuchs, nicht Teil des LEFIS-Exports
+    #[serde(skip, default)]
+    pub pruefung: PruefungAnalysiert,
+    // Abgleich mit den übrigen geladenen Blättern, wird erst beim Anzeigen
+    // bzw. Exportieren gesetzt, nicht Teil des LEFIS-Exports
+    #[serde(skip, default)]
+    pub blaetter: Vec<AnalyseWarnung>,
+}
+
+impl GrundbuchAnalysiert {
+    /// Warnungen und Fehler aller Einträge in Abt. 1 bis Abt. 3 sowie
+    /// des gesamten Grundbuchs (Abteilung `GRUNDBUCH_BEFUND`)
+    pub fn befunde(&self) -> Vec<RechtBefund> {
+        let abt1 = self.abt1.iter().flat_map(|e| {
+            e.warnungen
+                .iter()
+                .map(|w| (&w.code, &w.quittierung))
+                .chain(e.fehler.iter().map(|f| (&f.code, &f.quittierung)))
+                .map(move |(code, quittierung)| RechtBefund {
+                    abteilung: 1,
+                    lfd_nr: e.lfd_nr,
+                    id: e.id.clone(),
+                    code: code.clone(),
+                    quittiert: quittierung.is_some(),
+                })
+        });
+
+        let abt2 = self.abt2.iter().flat_map(|e| {
+            e.warnungen
+                .iter()
+                .map(|w| (&w.code, &w.quittierung))
+                .chain(e.fehler.iter().map(|f| (&f.code, &f.quittierung)))
+                .map(move |(code, quittierung)| RechtBefund {
+                    abteilung: 2,
+                    lfd_nr: e.lfd_nr,
+                    id: e.id.clone(),
+                    code: code.clone(),
+                    quittiert: quittierung.is_some(),
+                })
+        });
+
+        let abt3 = self.abt3.iter().flat_map(|e| {
+            e.warnungen
+                .iter()
+                .map(|w| (&w.code, &w.quittierung))
+                .chain(e.fehler.iter().map(|f| (&f.code, &f.quittierung)))
+                .map(move |(code, quittierung)| RechtBefund {
+                    abteilung: 3,
+                    lfd_nr: e.lfd_nr,
+                    id: e.id.clone(),
+                    code: code.clone(),
+                    quittiert: quittierung.is_some(),
+                })
+        });
+
+        let grundbuch = self
+            .grundbuch_befunde()
+            .map(|(code, quittierung)| RechtBefund {
+                abteilung: GRUNDBUCH_BEFUND,
+                lfd_nr: 0,
+                id: String::new(),
+                code: code.clone(),
+                quittiert: quittierung.is_some(),
+            });
+
+        abt1.chain(abt2).chain(abt3).chain(grundbuch).collect()
+    }
+
+    // Warnungen und Fehler, die keinem einzelnen Recht in Abt. 1 bis Abt. 3
+    // zugeordnet sind (Erbbaurechte, Löschungen, Rang, Prüfung, ALKIS, Blätter)
+    fn grundbuch_befunde(&self) -> impl Iterator<Item = (&BefundCode, &Option<Quittierung>)> {
+        let warnungen = self
+            .erbbaurechte
+            .iter()
+            .flat_map(|e| e.warnungen.iter())
+            .chain(
+                self.loeschungen
+                    .eintraege
+                    .iter()
+                    .flat_map(|l| l.warnungen.iter()),
+            )
+            .chain(self.loeschungen.warnungen.iter())
+            .chain(self.rang.warnungen.iter())
+            .chain(self.pruefung.warnungen.iter())
+            .chain(self.alkis.warnungen.iter())
+            .chain(self.blaetter.iter())
+            .map(|w| (&w.code, &w.quittierung));
+
+        let fehler = self
+            .erbbaurechte
+            .iter()
+            .flat_map(|e| e.fehler.iter())
+            .chain(
+                self.loeschungen
+                    .eintraege
+                    .iter()
+                    .flat_map(|l| l.fehler.iter()),
+            )
+            .chain(self.rang.fehler.iter())
+            .chain(self.pruefung.fehler.iter())
+            .map(|f| (&f.code, &f.quittierung));
+
+        warnungen.chain(fehler)
+    }
+
+    fn grundbuch_befunde_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (&BefundCode, &mut Option<Quittierung>)> {
+        let GrundbuchAnalysiert {
+            erbbaurechte,
+            loeschungen,
+            rang,
+            alkis,
+            pruefung,
+            blaetter,
+            ..
+        } = self;
+
+        let warnungen = erbbaurechte
+            .iter_mut()
+            .flat_map(|e| e.warnungen.iter_mut())
+            .chain(
+                loeschungen
+                    .eintraege
+                    .iter_mut()
+                    .flat_map(|l| l.warnungen.iter_mut()),
+            )
+            .chain(loeschungen.warnungen.iter_mut())
+            .chain(rang.warnungen.iter_mut())
+            .chain(pruefung.warnungen.iter_mut())
+            .chain(alkis.warnungen.iter_mut())
+            .chain(blaetter.iter_mut())
+            .map(|w| (&w.code, &mut w.quittierung));
+
+        let fehler = erbbaurechte
+            .iter_mut()
+            .flat_map(|e| e.fehler.iter_mut())
+            .chain(
+                loeschungen
+                    .eintraege
+                    .iter_mut()
+                    .flat_map(|l| l.fehler.iter_mut()),
+            )
+            .chain(rang.fehler.iter_mut())
+            .chain(pruefung.fehler.iter_mut())
+            .map(|f| (&f.code, &mut f.quittierung));
+
+        warnungen.chain(fehler)
+    }
+
+    /// Befunde ohne gültige Quittierung
+    pub fn offene_befunde(&self) -> Vec<RechtBefund> {
+        self.befunde()
+            .into_iter()
+            .filter(|b| !b.quittiert)
+            .collect()
+    }
+
+    /// JSON für den LEFIS-Export, Warnungen und Fehler werden darin nur als
+    /// Text der Meldung ausgegeben
+    pub fn lefis_json(&self) -> serde_json::Value {
+        let mut json = serde_json::to_value(self).unwrap_or_default();
+        for (i, e) in self.abt1.iter().enumerate() {
+            befunde_als_text(&mut json["abt1"][i], &e.warnungen, &e.fehler);
+        }
+        for (i, e) in self.abt2.iter().enumerate() {
+            befunde_als_text(&mut json["abt2"][i], &e.warnungen, &e.fehler);
+        }
+        for (i, e) in self.abt3.iter().enumerate() {
+            befunde_als_text(&mut json["abt3"][i], &e.warnungen, &e.fehler);
+        }
+        for (i, e) in self.erbbaurechte.iter().enumerate() {
+            befunde_als_text(&mut json["erbbaurechte"][i], &e.warnungen, &e.fehler);
+        }
+        befunde_als_text(&mut json["rang"], &self.rang.warnungen, &self.rang.fehler);
+        json
+    }
+}
+
+fn befunde_als_text(
+    json: &mut serde_json::Value,
+    warnungen: &[AnalyseWarnung],
+    fehler: &[AnalyseFehler],
+) {
+    json["warnungen"] = warnungen.iter().map(|w| w.text()).collect();
+    json["fehler"] = fehler.iter().map(|f| f.text()).collect();
+}
+
+// Art eines Befunds bei der Analyse des Grundbuchs. Schwere, betroffenes Feld
+// und Meldung ergeben sich aus dem Code, so dass Anzeige, Quittierung und Export
+// nicht auf den Text der Meldung angewiesen sind
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum BefundCode {
+    // Rechtsinhaber ist keinem Nebenbeteiligten zugeordnet
+    KeineOrdnungsnummer,
+    RangvermerkNichtImKurztext,
+    KeineFlurstueckeZugeordnet,
+    // Text der Veränderung
+    VeraenderungBeachten(String),
+    VeraenderungNichtLesbar {
+        abteilung: usize,
+        lfd_nr: String,
+        grund: String,
+    },
+    // Text der Löschung
+    TeilweiseGeloescht(String),
+    KeinFlurstueckskennzeichen(String),
+    // Skript "eigentuemer_auslesen_abt1" hat keine Eigentümer erkannt
+    KeineEigentuemerErkannt,
+    // Mehrere Grundlagen der Eintragung in Abt. 1, keine über die Position zugeordnet
+    GrundlageNichtZugeordnet,
+    // Eintrag wird noch im Hintergrund analysiert
+    WirdGeladen,
+
+    // Fehler beim Ausführen eines Python-Skripts
+    Skript {
+        skript: String,
+        meldung: String,
+    },
+    // Python-Skript hat einen falschen Typ zurückgegeben
+    SkriptRueckgabe {
+        skript: String,
+        erwartet: String,
+        erhalten: String,
+    },
+    // Meldungen der Skripte "pruefe_eintrag", "eigentuemer_auslesen_abt1"
+    // und "flurstuecke_auslesen"
+    PruefeEintragWarnung(String),
+    PruefeEintragFehler(String),
+    EigentuemerAuslesen(String),
+    FlurstueckeAuslesen(String),
+
+    // Abt. 3: Abtretungen, Teillöschungen und Umstellungen, Beträge als Text
+    TeilabtretungUnklar {
+        betrag: String,
+        glaeubiger: String,
+    },
+    TeilloeschungUnpassend {
+        geloescht: String,
+        betrag: String,
+    },
+    GlaeubigerNachAbtretung {
+        aktuell: String,
+        eingetragen: String,
+    },
+    BetragNachVeraenderungen {
+        aktuell: String,
+        eingetragen: String,
+    },
+
+    // Fortführungen im BV, über die eine Belastung übertragen wurde
+    UebertragenNachBlatt {
+        bv_nr: usize,
+        blatt: String,
+    },
+    FortfuehrungZielFehlt {
+        beschreibung: String,
+        nach: Vec<usize>,
+    },
+    BelastungFortgefuehrt {
+        bv_nr: usize,
+        beschreibung: String,
+    },
+    Verschmelzung {
+        bv_nr: usize,
+        nach: Vec<usize>,
+    },
+    AutomatischFortgefuehrt {
+        flur: usize,
+        flurstueck: String,
+        von: usize,
+        nach: usize,
+    },
+    FortfuehrungNichtEindeutig {
+        bv_nr: usize,
+        kandidaten: Vec<usize>,
+    },
+
+    // Erbbaurecht im BV eines Erbbaugrundbuchs, `blatt` = "Grundbuch von" + Blatt
+    ErbbauBlattFehlt {
+        bv_nr: usize,
+    },
+    ErbbauGrundstueckOhneBvNr {
+        bv_nr: usize,
+    },
+    ErbbauOhneDauer {
+        bv_nr: usize,
+    },
+    ErbbauGrundstueckFehlt {
+        bv_nr: usize,
+        grundstueck: usize,
+        blatt: String,
+    },
+    ErbbauAbt2KeinErbbaurecht {
+        bv_nr: usize,
+        abt2_lfd_nr: usize,
+        blatt: String,
+    },
+    ErbbauAbt2Fehlt {
+        bv_nr: usize,
+        abt2_lfd_nr: usize,
+        blatt: String,
+    },
+    ErbbauNichtGefunden {
+        bv_nr: usize,
+        blatt: String,
+    },
+    ErbbauMehrdeutig {
+        bv_nr: usize,
+        blatt: String,
+    },
+    ErbbauOhneVerweis {
+        abt2_lfd_nr: usize,
+        blatt: String,
+        erbbau_blatt: String,
+    },
+    ErbbauBvAbweichend {
+        bv_nr: usize,
+        laut_abt2: Vec<usize>,
+        laut_erbbaugrundbuch: Vec<usize>,
+    },
+    ErbbauNichtErsteRangstelle {
+        abt2_lfd_nr: usize,
+        blatt: String,
+    },
+    ErbbauDauerAbweichend {
+        bv_nr: usize,
+        dauer: String,
+        abt2_lfd_nr: usize,
+        blatt: String,
+    },
+
+    // Löschungen in Abt. 2 / Abt. 3, `loeschung` = Spalte 1 der Löschung
+    GeroetetOhneLoeschung {
+        abteilung: usize,
+        lfd_nr: usize,
+    },
+    LoeschungNichtLesbar {
+        abteilung: usize,
+        loeschung: String,
+        grund: String,
+    },
+    TeilloeschungUnklar {
+        abteilung: usize,
+        loeschung: String,
+    },
+    LoeschungOhneEintrag {
+        abteilung: usize,
+        loeschung: String,
+        lfd_nr: usize,
+    },
+    GeloeschtNichtGeroetet {
+        abteilung: usize,
+        lfd_nr: usize,
+    },
+    TeilweiseGeloeschtVollGeroetet {
+        abteilung: usize,
+        lfd_nr: usize,
+    },
+
+    // Rangverhältnisse, Rechte als "A2/1"
+    RangvermerkOhneRecht {
+        recht: String,
+        zu: String,
+    },
+    RangWiderspruch(Vec<String>),
+
+    // Plausibilitätsprüfung (pruefung.rs), `eintrag` z.B. "Abt. 2 lfd. Nr. 3"
+    LfdNrLuecke {
+        abschnitt: String,
+        lfd_nr: usize,
+    },
+    LfdNrDoppelt {
+        abschnitt: String,
+        lfd_nr: usize,
+        anzahl: usize,
+    },
+    BvVerweisFehlt {
+        eintrag: String,
+        bv_nr: usize,
+    },
+    BvVerweisGeroetet {
+        eintrag: String,
+        bv_nr: usize,
+    },
+    FlurstueckDoppelt {
+        bv_nr: usize,
+        flur: usize,
+        flurstueck: String,
+        auch_unter: Vec<usize>,
+    },
+    AbschreibungNichtGeroetet {
+        zeile: usize,
+        bv_nr: usize,
+    },
+    EigentuemerOhneGrundlage {
+        lfd_nr: usize,
+    },
+
+    // Abgleich mit dem ALKIS-Auszug, `flurstueck` = "BV-Nr. 1: Flur 2 Flst. 3"
+    AlkisKeinAbgleich {
+        flurstueck: String,
+        grund: String,
+    },
+    AlkisGemarkungFehlt {
+        gemarkung: String,
+    },
+    AlkisFlurstueckFehlt {
+        flurstueck: String,
+        aehnlich: Vec<String>,
+    },
+    AlkisFlurstueckHistorisch {
+        flurstueck: String,
+    },
+    AlkisFlaecheAbweichend {
+        flurstueck: String,
+        flaeche_qm: u64,
+        amtlich_qm: String,
+    },
+
+    // Abgleich mit den übrigen geladenen Blättern, Blätter als "Musterdorf Blatt 12"
+    HerrschvermerkMehrereBlaetter {
+        bv_nr: usize,
+        blaetter: Vec<String>,
+    },
+    HerrschvermerkOhneRueckverweis {
+        bv_nr: usize,
+        abt2_lfd_nr: usize,
+        dienend: String,
+        herrschend: String,
+    },
+    HerrschvermerkAbt2Fehlt {
+        bv_nr: usize,
+        abt2_lfd_nr: usize,
+        dienend: String,
+    },
+    HerrschvermerkOhneRecht {
+        bv_nr: usize,
+        dienend: String,
+        herrschend: String,
+    },
+    HerrschvermerkMehrdeutig {
+        bv_nr: usize,
+        dienend: String,
+    },
+    RechtOhneHerrschvermerk {
+        lfd_nr: usize,
+        herrschend: String,
+    },
+    GesamtrechtOhneMithaft {
+        abteilung: usize,
+        lfd_nr: usize,
+        blatt: String,
+        fuer: String,
+    },
+    GesamtrechtAbweichend {
+        abteilung: usize,
+        lfd_nr: usize,
+        blatt: String,
+        lfd_nr_dort: usize,
+        abweichung: String,
+    },
+    FlurstueckInMehrerenBlaettern {
+        bv_nr: usize,
+        flur: usize,
+        flurstueck: String,
+        auch_in: Vec<String>,
+    },
+}
+
+// Feld des Rechts, auf das sich ein Befund bezieht
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum BefundFeld {
+    Kurztext,
+    Rechtsinhaber,
+    BelasteteFlurstuecke,
+    Veraenderungen,
+    Loeschungen,
+    Eigentuemer,
+    Grundlagen,
+}
+
+fn nummern_text(n: &[usize]) -> String {
+    n.iter()
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn betrag_text(b: &Betrag) -> String {
+    format!(
+        "{} {}",
+        crate::kurztext::formatiere_betrag(b),
+        b.waehrung.to_string()
+    )
+}
+
+impl BefundCode {
+    pub fn ist_fehler(&self) -> bool {
+        use BefundCode::*;
+        match self {
+            RangvermerkNichtImKurztext
+            | KeineFlurstueckeZugeordnet
+            | VeraenderungNichtLesbar { .. }
+            | KeineEigentuemerErkannt
+            | WirdGeladen
+            | Skript { .. }
+            | SkriptRueckgabe { .. }
+            | PruefeEintragFehler(_)
+            | FlurstueckeAuslesen(_)
+            | TeilloeschungUnpassend { .. }
+            | FortfuehrungZielFehlt { .. }
+            | FortfuehrungNichtEindeutig { .. }
+            | ErbbauBlattFehlt { .. }
+            | ErbbauGrundstueckFehlt { .. }
+            | ErbbauAbt2KeinErbbaurecht { .. }
+            | ErbbauAbt2Fehlt { .. }
+            | ErbbauNichtGefunden { .. }
+            | LoeschungNichtLesbar { .. }
+            | LoeschungOhneEintrag { .. }
+            | RangWiderspruch(_)
+            | LfdNrDoppelt { .. }
+            | BvVerweisFehlt { .. }
+            | FlurstueckDoppelt { .. } => true,
+            KeineOrdnungsnummer
+            | VeraenderungBeachten(_)
+            | TeilweiseGeloescht(_)
+            | KeinFlurstueckskennzeichen(_)
+            | GrundlageNichtZugeordnet
+            | PruefeEintragWarnung(_)
+            | EigentuemerAuslesen(_)
+            | TeilabtretungUnklar { .. }
+            | GlaeubigerNachAbtretung { .. }
+            | BetragNachVeraenderungen { .. }
+            | UebertragenNachBlatt { .. }
+            | BelastungFortgefuehrt { .. }
+            | Verschmelzung { .. }
+            | AutomatischFortgefuehrt { .. }
+            | ErbbauGrundstueckOhneBvNr { .. }
+            | ErbbauOhneDauer { .. }
+            | ErbbauMehrdeutig { .. }
+            | ErbbauOhneVerweis { .. }
+            | ErbbauBvAbweichend { .. }
+            | ErbbauNichtErsteRangstelle { .. }
+            | ErbbauDauerAbweichend { .. }
+            | GeroetetOhneLoeschung { .. }
+            | TeilloeschungUnklar { .. }
+            | GeloeschtNichtGeroetet { .. }
+            | TeilweiseGeloeschtVollGeroetet { .. }
+            | RangvermerkOhneRecht { .. }
+            | LfdNrLuecke { .. }
+            | BvVerweisGeroetet { .. }
+            | AbschreibungNichtGeroetet { .. }
+            | EigentuemerOhneGrundlage { .. }
+            | AlkisKeinAbgleich { .. }
+            | AlkisGemarkungFehlt { .. }
+            | AlkisFlurstueckFehlt { .. }
+            | AlkisFlurstueckHistorisch { .. }
+            | AlkisFlaecheAbweichend { .. }
+            | HerrschvermerkMehrereBlaetter { .. }
+            | HerrschvermerkOhneRueckverweis { .. }
+            | HerrschvermerkAbt2Fehlt { .. }
+            | HerrschvermerkOhneRecht { .. }
+            | HerrschvermerkMehrdeutig { .. }
+            | RechtOhneHerrschvermerk { .. }
+            | GesamtrechtOhneMithaft { .. }
+            | GesamtrechtAbweichend { .. }
+            | FlurstueckInMehrerenBlaettern { .. } => false,
+        }
+    }
+
+    pub fn feld(&self) -> Option<BefundFeld> {
+        use BefundCode::*;
+        match self {
+            KeineOrdnungsnummer | GlaeubigerNachAbtretung { .. } => Some(BefundFeld::Rechtsinhaber),
+            RangvermerkNichtImKurztext => Some(BefundFeld::Kurztext),
+            KeineFlurstueckeZugeordnet
+            | KeinFlurstueckskennzeichen(_)
+            | FlurstueckeAuslesen(_)
+            | UebertragenNachBlatt { .. }
+            | FortfuehrungZielFehlt { .. }
+            | BelastungFortgefuehrt { .. }
+            | Verschmelzung { .. }
+            | AutomatischFortgefuehrt { .. }
+            | FortfuehrungNichtEindeutig { .. } => Some(BefundFeld::BelasteteFlurstuecke),
+            VeraenderungBeachten(_)
+            | VeraenderungNichtLesbar { .. }
+            | TeilabtretungUnklar { .. }
+            | BetragNachVeraenderungen { .. } => Some(BefundFeld::Veraenderungen),
+            TeilweiseGeloescht(_) | TeilloeschungUnpassend { .. } => Some(BefundFeld::Loeschungen),
+            KeineEigentuemerErkannt | EigentuemerAuslesen(_) => Some(BefundFeld::Eigentuemer),
+            GrundlageNichtZugeordnet => Some(BefundFeld::Grundlagen),
+            _ => None,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        use BefundCode::*;
+        match self {
+            KeineOrdnungsnummer => "Konnte keine Ordnungsnummer finden".to_string(),
+            RangvermerkNichtImKurztext => {
+                "Rangvermerk vorhanden, aber nicht in Kurztext vermerkt".to_string()
+            }
+            KeineFlurstueckeZugeordnet => "Konnte keine Flurstücke zuordnen!".to_string(),
+            VeraenderungBeachten(v) => format!("Veränderungsmitteilung beachten:<br/>{}", v),
+            VeraenderungNichtLesbar {
+                abteilung,
+                lfd_nr,
+                grund,
+            } => format!(
+                "Konnte Abt. {} Veränderung nicht lesen: {}: {}",
+                abteilung, lfd_nr, grund
+            ),
+            TeilweiseGeloescht(l) => format!("Teilweise gelöscht:<br/>{}", l),
+            KeinFlurstueckskennzeichen(e) => format!("Kein Flurstückskennzeichen: {}", e),
+            KeineEigentuemerErkannt => "Konnte keine Eigentümer auslesen!".to_string(),
+            GrundlageNichtZugeordnet => {
+                "Grundlage der Eintragung nicht eindeutig zuzuordnen".to_string()
+            }
+            WirdGeladen => "Wird geladen...".to_string(),
+
+            Skript { skript, meldung } => format!("{skript}: {meldung}"),
+            SkriptRueckgabe {
+                skript,
+                erwartet,
+                erhalten,
+            } => format!("{skript}: Erwartete {erwartet}, erhielt {erhalten}"),
+            PruefeEintragWarnung(t)
+            | PruefeEintragFehler(t)
+            | EigentuemerAuslesen(t)
+            | FlurstueckeAuslesen(t) => t.clone(),
+
+            TeilabtretungUnklar { betrag, glaeubiger } => {
+                format!("Teilabtretung über {betrag} an {glaeubiger}: Gläubiger nicht eindeutig")
+            }
+            TeilloeschungUnpassend { geloescht, betrag } => {
+                format!("Teillöschung über {geloescht} passt nicht zum Betrag {betrag}")
+            }
+            GlaeubigerNachAbtretung {
+                aktuell,
+                eingetragen,
+            } => format!("Gläubiger nach Abtretung: {aktuell} (eingetragen: {eingetragen})"),
+            BetragNachVeraenderungen {
+                aktuell,
+                eingetragen,
+            } => format!("Betrag nach Veränderungen: {aktuell} (eingetragen: {eingetragen})"),
+
+            UebertragenNachBlatt { bv_nr, blatt } => {
+                format!("BV-Nr. {bv_nr} wurde nach Blatt {blatt} übertragen, Belastung dort prüfen")
+            }
+            FortfuehrungZielFehlt { beschreibung, nach } => format!(
+                "{beschreibung}, aber BV-Nr. {} ist nicht im Bestandsverzeichnis vorhanden",
+                nummern_text(nach)
+            ),
+            BelastungFortgefuehrt {
+                bv_nr,
+                beschreibung,
+            } => format!("Belastung von BV-Nr. {bv_nr} fortgeführt: {beschreibung}"),
+            Verschmelzung { bv_nr, nach } => format!(
+                "BV-Nr. {bv_nr} ist in BV-Nr. {} aufgegangen, Belastung betrifft ggf. nur eine Teilfläche",
+                nummern_text(nach)
+            ),
+            AutomatischFortgefuehrt {
+                flur,
+                flurstueck,
+                von,
+                nach,
+            } => format!(
+                "Flur {flur} Flst. {flurstueck} wird automatisch fortgeführt von BV-Nr. {von} auf BV-Nr. {nach}"
+            ),
+            FortfuehrungNichtEindeutig { bv_nr, kandidaten } => format!(
+                "BV-Nr. {bv_nr} wurde fortgeführt, kann aber nicht eindeutig zugeordnet werden (Zerlegung?): Fortgeführt als eins von {kandidaten:?}"
+            ),
+
+            ErbbauBlattFehlt { bv_nr } => {
+                format!("Erbbaurecht BV-Nr. {bv_nr}: Grundstücks-Blatt nicht angegeben")
+            }
+            ErbbauGrundstueckOhneBvNr { bv_nr } => format!(
+                "Erbbaurecht BV-Nr. {bv_nr}: Lfd. Nr. des Grundstücks im Grundstücks-Blatt nicht angegeben"
+            ),
+            ErbbauOhneDauer { bv_nr } => format!("Erbbaurecht BV-Nr. {bv_nr}: Keine Dauer angegeben"),
+            ErbbauGrundstueckFehlt {
+                bv_nr,
+                grundstueck,
+                blatt,
+            } => format!(
+                "Erbbaurecht BV-Nr. {bv_nr}: Grundstück BV-Nr. {grundstueck} ist in {blatt} nicht (mehr) vorhanden"
+            ),
+            ErbbauAbt2KeinErbbaurecht {
+                bv_nr,
+                abt2_lfd_nr,
+                blatt,
+            } => format!(
+                "Erbbaurecht BV-Nr. {bv_nr}: Abt. 2 lfd. Nr. {abt2_lfd_nr} in {blatt} ist kein Erbbaurecht"
+            ),
+            ErbbauAbt2Fehlt {
+                bv_nr,
+                abt2_lfd_nr,
+                blatt,
+            } => format!(
+                "Erbbaurecht BV-Nr. {bv_nr}: Abt. 2 lfd. Nr. {abt2_lfd_nr} ist in {blatt} nicht vorhanden oder gelöscht"
+            ),
+            ErbbauNichtGefunden { bv_nr, blatt } => format!(
+                "Erbbaurecht BV-Nr. {bv_nr}: Kein Erbbaurecht in Abt. 2 von {blatt} gefunden"
+            ),
+            ErbbauMehrdeutig { bv_nr, blatt } => format!(
+                "Erbbaurecht BV-Nr. {bv_nr}: Mehrere Erbbaurechte in Abt. 2 von {blatt}, bitte lfd. Nr. in Abt. 2 angeben"
+            ),
+            ErbbauOhneVerweis {
+                abt2_lfd_nr,
+                blatt,
+                erbbau_blatt,
+            } => format!(
+                "Abt. 2 lfd. Nr. {abt2_lfd_nr} in {blatt} verweist nicht auf das Erbbaugrundbuch Blatt {erbbau_blatt}"
+            ),
+            ErbbauBvAbweichend {
+                bv_nr,
+                laut_abt2,
+                laut_erbbaugrundbuch,
+            } => format!(
+                "Erbbaurecht BV-Nr. {bv_nr}: lastet laut Abt. 2 auf BV-Nr. {}, laut Erbbaugrundbuch auf BV-Nr. {}",
+                nummern_text(laut_abt2),
+                nummern_text(laut_erbbaugrundbuch)
+            ),
+            ErbbauNichtErsteRangstelle { abt2_lfd_nr, blatt } => format!(
+                "Abt. 2 lfd. Nr. {abt2_lfd_nr} in {blatt}: Erbbaurecht steht nicht an erster Rangstelle (§ 10 ErbbauRG)"
+            ),
+            ErbbauDauerAbweichend {
+                bv_nr,
+                dauer,
+                abt2_lfd_nr,
+                blatt,
+            } => format!(
+                "Erbbaurecht BV-Nr. {bv_nr}: Dauer \"{dauer}\" stimmt nicht mit Abt. 2 lfd. Nr. {abt2_lfd_nr} in {blatt} überein"
+            ),
+
+            GeroetetOhneLoeschung { abteilung, lfd_nr } => format!(
+                "Abt. {abteilung} lfd. Nr. {lfd_nr} ist gerötet, aber es gibt keine Löschung dazu"
+            ),
+            LoeschungNichtLesbar {
+                abteilung,
+                loeschung,
+                grund,
+            } => format!("Konnte Abt. {abteilung} Löschung nicht lesen: {loeschung}: {grund}"),
+            TeilloeschungUnklar {
+                abteilung,
+                loeschung,
+            } => format!(
+                "Abt. {abteilung} Löschung {loeschung}: Teillöschung, aber gelöschte BV-Nr. / Flurstücke nicht erkannt"
+            ),
+            LoeschungOhneEintrag {
+                abteilung,
+                loeschung,
+                lfd_nr,
+            } => format!(
+                "Abt. {abteilung} Löschung {loeschung}: lfd. Nr. {lfd_nr} ist nicht vorhanden"
+            ),
+            GeloeschtNichtGeroetet { abteilung, lfd_nr } => {
+                format!("Abt. {abteilung} lfd. Nr. {lfd_nr} ist gelöscht, aber nicht gerötet")
+            }
+            TeilweiseGeloeschtVollGeroetet { abteilung, lfd_nr } => format!(
+                "Abt. {abteilung} lfd. Nr. {lfd_nr} ist nur teilweise gelöscht, aber vollständig gerötet"
+            ),
+
+            RangvermerkOhneRecht { recht, zu } => {
+                format!("{recht}: Rangvermerk verweist auf {zu}, das nicht eingetragen ist")
+            }
+            RangWiderspruch(w) => format!("Widersprüchliche Rangverhältnisse: {}", w.join("; ")),
+
+            LfdNrLuecke { abschnitt, lfd_nr } => format!(
+                "{abschnitt} lfd. Nr. {lfd_nr}: lfd. Nr. fehlt (Lücke in der Nummerierung)"
+            ),
+            LfdNrDoppelt {
+                abschnitt,
+                lfd_nr,
+                anzahl,
+            } => format!("{abschnitt} lfd. Nr. {lfd_nr}: lfd. Nr. ist {anzahl} mal vergeben"),
+            BvVerweisFehlt { eintrag, bv_nr } => {
+                format!("{eintrag}: BV-Nr. {bv_nr} existiert nicht im Bestandsverzeichnis")
+            }
+            BvVerweisGeroetet { eintrag, bv_nr } => {
+                format!("{eintrag}: BV-Nr. {bv_nr} ist gerötet")
+            }
+            FlurstueckDoppelt {
+                bv_nr,
+                flur,
+                flurstueck,
+                auch_unter,
+            } => format!(
+                "BV lfd. Nr. {bv_nr}: Flur {flur} Flst. {flurstueck} steht auch unter lfd. Nr. {}",
+                nummern_text(auch_unter)
+            ),
+            AbschreibungNichtGeroetet { zeile, bv_nr } => format!(
+                "BV Abschreibung Zeile {zeile}: BV-Nr. {bv_nr} ist abgeschrieben, aber nicht gerötet"
+            ),
+            EigentuemerOhneGrundlage { lfd_nr } => format!(
+                "Abt. 1 lfd. Nr. {lfd_nr}: Keine Grundlage der Eintragung für den Eigentümer"
+            ),
+
+            AlkisKeinAbgleich { flurstueck, grund } => {
+                format!("{flurstueck}: kein Abgleich mit ALKIS möglich: {grund}")
+            }
+            AlkisGemarkungFehlt { gemarkung } => format!(
+                "Gemarkung {gemarkung} ist nicht im ALKIS-Auszug enthalten, Flurstücke wurden nicht geprüft"
+            ),
+            AlkisFlurstueckFehlt {
+                flurstueck,
+                aehnlich,
+            } => {
+                if aehnlich.is_empty() {
+                    format!("{flurstueck}: Flurstück existiert nicht in ALKIS")
+                } else {
+                    format!(
+                        "{flurstueck}: Flurstück existiert nicht in ALKIS (Lesefehler? In ALKIS vorhanden: Flst. {})",
+                        aehnlich.join(", ")
+                    )
+                }
+            }
+            AlkisFlurstueckHistorisch { flurstueck } => {
+                format!("{flurstueck}: Flurstück ist in ALKIS historisch")
+            }
+            AlkisFlaecheAbweichend {
+                flurstueck,
+                flaeche_qm,
+                amtlich_qm,
+            } => format!(
+                "{flurstueck}: Fläche {flaeche_qm} m² weicht von der amtlichen Fläche {amtlich_qm} m² ab"
+            ),
+
+            HerrschvermerkMehrereBlaetter { bv_nr, blaetter } => format!(
+                "Herrschvermerk BV-Nr. {bv_nr}: verweist auf mehrere geladene Blätter ({})",
+                blaetter.join(", ")
+            ),
+            HerrschvermerkOhneRueckverweis {
+                bv_nr,
+                abt2_lfd_nr,
+                dienend,
+                herrschend,
+            } => format!(
+                "Herrschvermerk BV-Nr. {bv_nr}: Abt. 2 lfd. Nr. {abt2_lfd_nr} in {dienend} verweist nicht auf {herrschend}"
+            ),
+            HerrschvermerkAbt2Fehlt {
+                bv_nr,
+                abt2_lfd_nr,
+                dienend,
+            } => format!(
+                "Herrschvermerk BV-Nr. {bv_nr}: Abt. 2 lfd. Nr. {abt2_lfd_nr} ist in {dienend} nicht vorhanden oder gelöscht"
+            ),
+            HerrschvermerkOhneRecht {
+                bv_nr,
+                dienend,
+                herrschend,
+            } => format!(
+                "Herrschvermerk BV-Nr. {bv_nr}: kein Recht in Abt. 2 von {dienend} verweist auf {herrschend}"
+            ),
+            HerrschvermerkMehrdeutig { bv_nr, dienend } => format!(
+                "Herrschvermerk BV-Nr. {bv_nr}: mehrere Rechte in Abt. 2 von {dienend} kommen in Frage, bitte lfd. Nr. in Abt. 2 angeben"
+            ),
+            RechtOhneHerrschvermerk { lfd_nr, herrschend } => format!(
+                "Abt. 2 lfd. Nr. {lfd_nr}: Recht zugunsten {herrschend}, dort ist kein Herrschvermerk eingetragen"
+            ),
+            GesamtrechtOhneMithaft {
+                abteilung,
+                lfd_nr,
+                blatt,
+                fuer,
+            } => format!(
+                "Abt. {abteilung} lfd. Nr. {lfd_nr}: Gesamtrecht mit {blatt}, dort ist kein Recht mit Mithaft für {fuer} eingetragen"
+            ),
+            GesamtrechtAbweichend {
+                abteilung,
+                lfd_nr,
+                blatt,
+                lfd_nr_dort,
+                abweichung,
+            } => format!(
+                "Abt. {abteilung} lfd. Nr. {lfd_nr}: Gesamtrecht weicht von {blatt} Abt. {abteilung} lfd. Nr. {lfd_nr_dort} ab: {abweichung}"
+            ),
+            FlurstueckInMehrerenBlaettern {
+                bv_nr,
+                flur,
+                flurstueck,
+                auch_in,
+            } => format!(
+                "BV-Nr. {bv_nr}: Flur {flur} Flst. {flurstueck} ist auch in {} eingetragen",
+                auch_in.join(", ")
+            ),
+        }
+    }
+}
+
+impl fmt::Display for BefundCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text())
+    }
+}
+
+// Abteilung der Befunde und Quittierungen, die keinem einzelnen Recht
+// zugeordnet sind, sondern das gesamte Grundbuch betreffen
+pub const GRUNDBUCH_BEFUND: usize = 0;
+
+// Befund mit Verweis auf das betroffene Recht
+#[derive(Debug, Clone, PartialEq)]
+pub struct RechtBefund {
+    // 1, 2, 3 oder GRUNDBUCH_BEFUND
+    pub abteilung: usize,
+    pub lfd_nr: usize,
+    // ID des Eintrags im Grundbuch
+    pub id: String,
+    pub code: BefundCode,
+    pub quittiert: bool,
+}
+
+impl RechtBefund {
+    pub fn ist_fehler(&self) -> bool {
+        self.code.ist_fehler()
+    }
+}
+
+// Quittierung eines erwarteten Befunds (z.B. bewusst nicht zugeordneter
+// Rechtsinhaber), wird in der .gbx gespeichert. Gilt nur, solange der Text
+// des Eintrags unverändert ist. Quittierungen des gesamten Grundbuchs
+// (GRUNDBUCH_BEFUND) gelten, solange derselbe Befund auftritt
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Quittierung {
+    // 1, 2, 3 oder GRUNDBUCH_BEFUND
+    pub abteilung: usize,
+    pub lfd_nr: usize,
+    // ID des Eintrags im Grundbuch
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub id: String,
+    pub code: BefundCode,
+    pub begruendung: String,
+    // Konfiguration.server_email des Bearbeiters
+    pub email: String,
+    pub datum: crate::DateTime,
+    // Hash des Eintragstexts zum Zeitpunkt der Quittierung
+    pub text_hash: String,
+}
+
+impl Quittierung {
+    pub fn new(
+        grundbuch: &Grundbuch,
+        abteilung: usize,
+        lfd_nr: usize,
+        code: BefundCode,
+        begruendung: String,
+        email: String,
+    ) -> Option<Self> {
+        let (id, text_hash) = if abteilung == GRUNDBUCH_BEFUND {
+            (String::new(), String::new())
+        } else {
+            let (id, text) = eintrag_text(grundbuch, abteilung, lfd_nr)?;
+            (id, digital::hash_str(&text))
+        };
+        Some(Self {
+            abteilung,
+            lfd_nr,
+            id,
+            code,
+            begruendung,
+            email,
+            datum: chrono::Local::now(),
+            text_hash,
+        })
+    }
+
+    fn gilt_fuer(&self, abteilung: usize, lfd_nr: usize, id: &str, text_hash: &str) -> bool {
+        let gleicher_eintrag = if self.id.is_empty() || id.is_empty() {
+            self.lfd_nr == lfd_nr
+        } else {
+            self.id == id
+        };
+
+        self.abteilung == abteilung && gleicher_eintrag && self.text_hash == text_hash
+    }
+}
+
+// ID und Text des (nicht geröteten) Eintrags in Abt. 1 bis Abt. 3
+fn eintrag_text(
+    grundbuch: &Grundbuch,
+    abteilung: usize,
+    lfd_nr: usize,
+) -> Option<(String, String)> {
+    match abteilung {
+        1 => grundbuch
+            .abt1
+            .eintraege
+            .iter()
+            .find(|e| e.get_lfd_nr() == lfd_nr && !e.ist_geroetet())
+            .map(|e| (e.get_id(), e.get_eigentuemer())),
+        2 => grundbuch
+            .abt2
+            .eintraege
+            .iter()
+            .find(|e| e.lfd_nr == lfd_nr && !e.ist_geroetet())
+            .map(|e| (e.id.clone(), e.text.text())),
+        3 => grundbuch
+            .abt3
+            .eintraege
+            .iter()
+            .find(|e| e.lfd_nr == lfd_nr && !e.ist_geroetet())
+            .map(|e| (e.id.clone(), e.text.text())),
+        _ => None,
+    }
+}
+
+// Übernimmt die gespeicherten Quittierungen in die Warnungen / Fehler der
+// Analyse, Quittierungen zu geänderten Einträgen werden ignoriert. Muss
+// erneut aufgerufen werden, wenn Befunde nachträglich ergänzt werden
+// (Erbbaurechte / Blätter im Abgleich mit den übrigen Dateien)
+pub fn quittierungen_anwenden(grundbuch: &Grundbuch, analyse: &mut GrundbuchAnalysiert) {
+    let quittierung_suchen =
+        |abteilung: usize, lfd_nr: usize, id: &str, text_hash: &str, code: &BefundCode| {
+            grundbuch
+                .quittierungen
+                .iter()
+                .find(|q| &q.code == code && q.gilt_fuer(abteilung, lfd_nr, id, text_hash))
+                .cloned()
+        };
+
+    for e in analyse.abt1.iter_mut() {
+        let text_hash = match eintrag_text(grundbuch, 1, e.lfd_nr) {
+            Some((_, text)) => digital::hash_str(&text),
+            None => continue,
+        };
+        for w in e.warnungen.iter_mut() {
+            w.quittierung = quittierung_suchen(1, e.lfd_nr, &e.id, &text_hash, &w.code);
+        }
+        for f in e.fehler.iter_mut() {
+            f.quittierung = quittierung_suchen(1, e.lfd_nr, &e.id, &text_hash, &f.code);
+        }
+    }
+
+    for e in analyse.abt2.iter_mut() {
+        let text_hash = match eintrag_text(grundbuch, 2, e.lfd_nr) {
+            Some((_, text)) => digital::hash_str(&text),
+            None => continue,
+        };
+        for w in e.warnungen.iter_mut() {
+            w.quittierung = quittierung_suchen(2, e.lfd_nr, &e.id, &text_hash, &w.code);
+        }
+        for f in e.fehler.iter_mut() {
+            f.quittierung = quittierung_suchen(2, e.lfd_nr, &e.id, &text_hash, &f.code);
+        }
+    }
+
+    for e in analyse.abt3.iter_mut() {
+        let text_hash = match eintrag_text(grundbuch, 3, e.lfd_nr) {
+            Some((_, text)) => digital::hash_str(&text),
+            None => continue,
+        };
+        for w in e.warnungen.iter_mut() {
+            w.quittierung = quittierung_suchen(3, e.lfd_nr, &e.id, &text_hash, &w.code);
+        }
+        for f in e.fehler.iter_mut() {
+            f.quittierung = quittierung_suchen(3, e.lfd_nr, &e.id, &text_hash, &f.code);
+        }
+    }
+
+    for (code, quittierung) in analyse.grundbuch_befunde_mut() {
+        *quittierung = quittierung_suchen(GRUNDBUCH_BEFUND, 0, "", "", code);
+    }
+}
+
+// Warnung zu einem Recht, wird im LEFIS-Export nur als Text ausgegeben
+// (siehe `GrundbuchAnalysiert::lefis_json`)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct AnalyseWarnung {
+    pub code: BefundCode,
+    pub quittierung: Option<Quittierung>,
+}
+
+impl AnalyseWarnung {
+    pub fn text(&self) -> String {
+        self.code.text()
+    }
+}
+
+impl From<BefundCode> for AnalyseWarnung {
+    fn from(code: BefundCode) -> AnalyseWarnung {
+        AnalyseWarnung {
+            code,
+            quittierung: None,
+        }
+    }
+}
+
+impl fmt::Display for AnalyseWarnung {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
-#[serde(from = "String", into = "String")]
 pub struct AnalyseFehler {
-    pub text: String,
-    #[serde(skip, default)]
+    pub code: BefundCode,
+    pub quittierung: Option<Quittierung>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub traceback: Option<Vec<String>>,
-    #[serde(skip, default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub py_script: Option<Vec<String>>,
 }
 
-impl From<String> for AnalyseFehler {
-    fn from(s: String) -> AnalyseFehler {
+impl AnalyseFehler {
+    pub fn text(&self) -> String {
+        self.code.text()
+    }
+}
+
+impl From<BefundCode> for AnalyseFehler {
+    fn from(code: BefundCode) -> AnalyseFehler {
         AnalyseFehler {
-            text: s,
+            code,
+            quittierung: None,
             traceback: None,
             py_script: None,
         }
     }
 }
 
-impl From<AnalyseFehler> for String {
-    fn from(s: AnalyseFehler) -> String {
-        s.text.clone()
+impl fmt::Display for AnalyseFehler {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Abt1Analysiert {
+    pub lfd_nr: usize,
+    // ID des Eintrags im Grundbuch
+    #[serde(default)]
+    pub id: String,
+    pub eigentuemer: Vec<Eigentuemer>,
+    // Grundlagen der Eintragung, auf denen das Eigentum aller Eigentümer
+    // dieses Eintrags beruht
+    pub grundlagen: Vec<Abt1GrundlageAnalysiert>,
+    pub text_original: String,
+    pub warnungen: Vec<AnalyseWarnung>,
+    pub fehler: Vec<AnalyseFehler>,
+    #[serde(skip, default)]
+    pub fertig_analysiert: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Abt1GrundlageAnalysiert {
+    // ID der Grundlage im Grundbuch
+    #[serde(default)]
+    pub id: String,
+    pub bv_nr: String,
+    pub text: String,
+}
+
+impl From<&Abt1GrundEintragung> for Abt1GrundlageAnalysiert {
+    fn from(g: &Abt1GrundEintragung) -> Self {
+        Self {
+            id: g.id.clone(),
+            bv_nr: g.bv_nr.text(),
+            text: g.text.text(),
+        }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Abt2Analysiert {
     pub lfd_nr: usize,
+    // ID des Eintrags im Grundbuch
+    #[serde(default)]
+    pub id: String,
     pub text_kurz: String,
     pub rechteart: RechteArt,
     pub rechtsinhaber: String,
@@ -50,11 +1213,18 @@ pub struct Abt2Analysiert {
     pub spalte_2: String,
     // Flur, Flurstück
     pub belastete_flurstuecke: Vec<BvEintrag>,
+    // Flurstückskennzeichen (ALKIS) der belasteten Flurstücke, nur vorhanden,
+    // wenn ein Gemarkungskatalog importiert wurde
+    #[serde(default)]
+    pub flurstueckskennzeichen: Vec<String>,
     #[serde(default)]
     pub lastend_an: Vec<Spalte1Eintrag>,
+    // Fortführungen im BV, über die die Belastung übertragen wurde
+    #[serde(default)]
+    pub fortfuehrungen: Vec<BvFortfuehrung>,
     pub text_original: String,
     pub nebenbeteiligter: Nebenbeteiligter,
-    pub warnungen: Vec<String>,
+    pub warnungen: Vec<AnalyseWarnung>,
     pub fehler: Vec<AnalyseFehler>,
     #[serde(skip, default)]
     pub geroetet: bool,
@@ -62,21 +1232,54 @@ pub struct Abt2Analysiert {
     pub fertig_analysiert: bool,
 }
 
+// Erbbaurecht aus dem BV eines Erbbaugrundbuchs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErbbaurechtAnalysiert {
+    pub lfd_nr: usize,
+    pub grundstueck: ErbbauGrundstueck,
+    pub dauer: String,
+    pub zustimmungsvorbehalte: Vec<Zustimmungsvorbehalt>,
+    // lfd. Nr. des passenden Eintrags in Abt. 2 des Grundstücks-Blatts,
+    // nur gesetzt, wenn das Grundstücks-Blatt geladen ist
+    pub abt2_lfd_nr: Option<usize>,
+    pub warnungen: Vec<AnalyseWarnung>,
+    pub fehler: Vec<AnalyseFehler>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Abt3Analysiert {
     pub lfd_nr: usize,
+    // ID des Eintrags im Grundbuch
+    #[serde(default)]
+    pub id: String,
     pub text_kurz: String,
+    // Aktueller Betrag nach Teillöschungen / Umstellungen
     pub betrag: Betrag,
     pub schuldenart: SchuldenArt,
+    // Aktueller Gläubiger nach Abtretungen
     pub rechtsinhaber: String,
+    // Betrag und Gläubiger laut Eintragung
+    #[serde(default)]
+    pub betrag_eingetragen: Betrag,
+    #[serde(default)]
+    pub rechtsinhaber_eingetragen: String,
+    #[serde(default)]
+    pub historie: Vec<Abt3HistorieEintrag>,
     pub spalte_2: String,
     // Flur, Flurstück
     pub belastete_flurstuecke: Vec<BvEintrag>,
+    // Flurstückskennzeichen (ALKIS) der belasteten Flurstücke, nur vorhanden,
+    // wenn ein Gemarkungskatalog importiert wurde
+    #[serde(default)]
+    pub flurstueckskennzeichen: Vec<String>,
     #[serde(default)]
     pub lastend_an: Vec<Spalte1Eintrag>,
+    // Fortführungen im BV, über die die Belastung übertragen wurde
+    #[serde(default)]
+    pub fortfuehrungen: Vec<BvFortfuehrung>,
     pub text_original: String,
     pub nebenbeteiligter: Nebenbeteiligter,
-    pub warnungen: Vec<String>,
+    pub warnungen: Vec<AnalyseWarnung>,
     pub fehler: Vec<AnalyseFehler>,
     #[serde(skip, default)]
     pub geroetet: bool,
@@ -84,6 +1287,212 @@ pub struct Abt3Analysiert {
     pub fertig_analysiert: bool,
 }
 
+// Löschung aus Abt. 2 / Abt. 3, aufgelöst auf die gelöschten Einträge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoeschungAnalysiert {
+    // 2 oder 3
+    pub abteilung: usize,
+    // lfd. Nr. der gelöschten Einträge
+    pub lfd_nr: Vec<usize>,
+    pub text: String,
+    // "gelöscht hinsichtlich ...": Eintrag bleibt bestehen,
+    // gelöscht sind nur die angegebenen BV-Nr. / Flurstücke
+    pub teilweise: bool,
+    pub nur_geloescht_an_bv_nr: Vec<usize>,
+    pub nur_geloescht_an: Vec<FlurFlurstueck>,
+    // Teillöschung eines Betrags in Abt. 3
+    pub geloeschter_betrag: Option<Betrag>,
+    pub warnungen: Vec<AnalyseWarnung>,
+    pub fehler: Vec<AnalyseFehler>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoeschungenAnalysiert {
+    pub eintraege: Vec<LoeschungAnalysiert>,
+    // Gerötete Einträge ohne Löschung
+    pub warnungen: Vec<AnalyseWarnung>,
+}
+
+impl LoeschungenAnalysiert {
+    // lfd. Nr. der vollständig gelöschten Einträge
+    pub fn vollstaendig_geloescht(&self, abteilung: usize) -> BTreeSet<usize> {
+        self.eintraege
+            .iter()
+            .filter(|l| l.abteilung == abteilung && !l.teilweise)
+            .flat_map(|l| l.lfd_nr.iter().copied())
+            .collect()
+    }
+
+    pub fn teilloeschungen(&self, abteilung: usize, lfd_nr: usize) -> Vec<&LoeschungAnalysiert> {
+        self.eintraege
+            .iter()
+            .filter(|l| l.abteilung == abteilung && l.teilweise && l.lfd_nr.contains(&lfd_nr))
+            .collect()
+    }
+}
+
+// Veränderung / Löschung in Abt. 3, bezogen auf eine lfd. Nr.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Abt3HistorieEintrag {
+    // true = aus den Löschungen, false = aus den Veränderungen
+    pub loeschung: bool,
+    pub geroetet: bool,
+    pub text: String,
+    pub ereignis: Abt3Ereignis,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "typ")]
+pub enum Abt3Ereignis {
+    // Betrag = None: vollständige Abtretung
+    Abtretung {
+        glaeubiger: String,
+        betrag: Option<Betrag>,
+    },
+    Teilloeschung {
+        betrag: Betrag,
+    },
+    // Währungsumstellung, z.B. "umgestellt auf 5.112,92 EUR"
+    Umstellung {
+        betrag: Betrag,
+    },
+    Zinsaenderung,
+    Sonstige,
+}
+
+// Recht in Abt. 2 oder Abt. 3
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct RechtRef {
+    pub abteilung: usize,
+    pub lfd_nr: usize,
+}
+
+impl std::fmt::Display for RechtRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "A{}/{}", self.abteilung, self.lfd_nr)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum RangBeziehung {
+    Vor,
+    Nach,
+    Gleich,
+}
+
+impl RangBeziehung {
+    fn umkehren(&self) -> Self {
+        match self {
+            RangBeziehung::Vor => RangBeziehung::Nach,
+            RangBeziehung::Nach => RangBeziehung::Vor,
+            RangBeziehung::Gleich => RangBeziehung::Gleich,
+        }
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            RangBeziehung::Vor => "vor",
+            RangBeziehung::Nach => "nach",
+            RangBeziehung::Gleich => "gleich",
+        }
+    }
+}
+
+// "A2/1 im Rang vor A3/2", gelesen aus Eintrag oder Veränderung
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rangverhaeltnis {
+    pub recht: RechtRef,
+    pub beziehung: RangBeziehung,
+    pub zu: RechtRef,
+    pub quelle: String,
+}
+
+// Rangfolge der Rechte an einem Flurstück, gleichrangige Rechte
+// stehen in derselben Rangstelle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rangfolge {
+    pub bv_nr: usize,
+    pub flur: usize,
+    pub flurstueck: String,
+    pub rangstellen: Vec<Vec<RechtRef>>,
+    // false = Reihenfolge zwischen Abt. 2 und Abt. 3 teilweise
+    // nur nach lfd. Nr. angenommen, da Rangvermerke fehlen
+    pub eindeutig: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RangAnalysiert {
+    pub verhaeltnisse: Vec<Rangverhaeltnis>,
+    pub rangfolgen: Vec<Rangfolge>,
+    pub warnungen: Vec<AnalyseWarnung>,
+    pub fehler: Vec<AnalyseFehler>,
+}
+
+// Ergebnis der regelbasierten Plausibilitätsprüfung (siehe pruefung.rs)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PruefungAnalysiert {
+    pub warnungen: Vec<AnalyseWarnung>,
+    pub fehler: Vec<AnalyseFehler>,
+}
+
+// Fortführung im BV laut Zu- / Abschreibung
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BvFortfuehrung {
+    pub art: BvFortfuehrungArt,
+    pub von: Vec<usize>,
+    pub nach: Vec<usize>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "typ")]
+pub enum BvFortfuehrungArt {
+    // "Nr. 3 zerlegt in Nr. 5 und 6", "verselbstständigt"
+    Teilung,
+    // "Nr. 1 und 2 verschmolzen zu Nr. 7"
+    Verschmelzung,
+    // "Nr. 4 als Nr. 8 eingetragen"
+    Fortfuehrung,
+    UebertragenAus { blatt: String },
+    UebertragenNach { blatt: String },
+}
+
+impl BvFortfuehrung {
+    pub fn beschreibung(&self) -> String {
+        let nummern = |n: &[usize]| {
+            n.iter()
+                .map(|n| n.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match &self.art {
+            BvFortfuehrungArt::Teilung => format!(
+                "BV-Nr. {} geteilt in BV-Nr. {}",
+                nummern(&self.von),
+                nummern(&self.nach)
+            ),
+            BvFortfuehrungArt::Verschmelzung => format!(
+                "BV-Nr. {} verschmolzen zu BV-Nr. {}",
+                nummern(&self.von),
+                nummern(&self.nach)
+            ),
+            BvFortfuehrungArt::Fortfuehrung => format!(
+                "BV-Nr. {} fortgeführt als BV-Nr. {}",
+                nummern(&self.von),
+                nummern(&self.nach)
+            ),
+            BvFortfuehrungArt::UebertragenAus { blatt } => format!(
+                "BV-Nr. {} übertragen aus Blatt {blatt}",
+                nummern(&self.nach)
+            ),
+            BvFortfuehrungArt::UebertragenNach { blatt } => format!(
+                "BV-Nr. {} übertragen nach Blatt {blatt}",
+                nummern(&self.von)
+            ),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct GrundbuchAnalysiertCache {
     inner: Arc<Mutex<GrundbuchAnalysiertCacheInternal>>,
@@ -97,9 +1506,37 @@ impl Default for GrundbuchAnalysiertCache {
     }
 }
 
-#[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq, Hash, Serialize)]
+struct Abt1Hash {
+    abt1_eintrag_hash: String,
+    // Zuordnung der Grundlagen der Eintragung
+    abt1_grundlagen_hash: String,
+    konfiguration_hash: String,
+}
+
+impl Abt1Hash {
+    pub fn new(
+        eintrag: &Abt1Eintrag,
+        grundbuch: &Grundbuch,
+        konfiguration: &Konfiguration,
+    ) -> Self {
+        Self {
+            abt1_eintrag_hash: digital::hash_str(
+                &serde_json::to_string(eintrag).unwrap_or_default(),
+            ),
+            abt1_grundlagen_hash: digital::hash_str(
+                &serde_json::to_string(&grundbuch.abt1.grundlagen_eintragungen).unwrap_or_default(),
+            ),
+            konfiguration_hash: konfiguration.get_analyse_hash(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq, Hash, Serialize)]
 struct Abt2Hash {
     abt2_eintrag_hash: String,
+    // Veränderungen und Löschungen wirken sich auf den Eintrag aus
+    abt2_veraenderungen_hash: String,
     bv_verzeichnis_hash: String,
     konfiguration_hash: String,
     nb_hash: String,
@@ -116,16 +1553,25 @@ impl Abt2Hash {
             abt2_eintrag_hash: digital::hash_str(
                 &serde_json::to_string(eintrag).unwrap_or_default(),
             ),
+            abt2_veraenderungen_hash: digital::hash_str(
+                &serde_json::to_string(&(
+                    &grundbuch.abt2.veraenderungen,
+                    &grundbuch.abt2.loeschungen,
+                ))
+                .unwrap_or_default(),
+            ),
             bv_verzeichnis_hash: grundbuch.bestandsverzeichnis.get_eintraege_hash(),
-            konfiguration_hash: konfiguration.get_hash(),
+            konfiguration_hash: konfiguration.get_analyse_hash(),
             nb_hash: Nebenbeteiligter::get_hash(nb),
         }
     }
 }
 
-#[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq, Hash, Serialize)]
 struct Abt3Hash {
     abt3_eintrag_hash: String,
+    // Veränderungen und Löschungen wirken sich auf den Eintrag aus
+    abt3_veraenderungen_hash: String,
     bv_verzeichnis_hash: String,
     konfiguration_hash: String,
     nb_hash: String,
@@ -142,22 +1588,287 @@ impl Abt3Hash {
             abt3_eintrag_hash: digital::hash_str(
                 &serde_json::to_string(eintrag).unwrap_or_default(),
             ),
+            abt3_veraenderungen_hash: digital::hash_str(
+                &serde_json::to_string(&(
+                    &grundbuch.abt3.veraenderungen,
+                    &grundbuch.abt3.loeschungen,
+                ))
+                .unwrap_or_default(),
+            ),
             bv_verzeichnis_hash: grundbuch.bestandsverzeichnis.get_eintraege_hash(),
-            konfiguration_hash: konfiguration.get_hash(),
+            konfiguration_hash: konfiguration.get_analyse_hash(),
             nb_hash: Nebenbeteiligter::get_hash(nb),
         }
     }
 }
 
+// Schlüssel der Datei im Analyse-Cache auf der Festplatte. Enthält die
+// Programmversion, damit nach einem Update neu analysiert wird
+fn cache_schluessel<T: Serialize>(abteilung: usize, hash: &T) -> String {
+    format!(
+        "abt{abteilung}-{}",
+        digital::hash_str(&format!(
+            "{}{}",
+            env!("CARGO_PKG_VERSION"),
+            serde_json::to_string(hash).unwrap_or_default()
+        ))
+    )
+}
+
+impl Abt1Hash {
+    fn schluessel(&self) -> String {
+        cache_schluessel(1, self)
+    }
+}
+
+impl Abt2Hash {
+    fn schluessel(&self) -> String {
+        cache_schluessel(2, self)
+    }
+}
+
+impl Abt3Hash {
+    fn schluessel(&self) -> String {
+        cache_schluessel(3, self)
+    }
+}
+
+// Analysierte Einträge werden zusätzlich unter {backup_dir}/cache/analyse/
+// gespeichert, damit nach einem Neustart nicht jeder Eintrag erneut durch die
+// Python-VM muss. Ist der Ordner größer als ANALYSE_CACHE_MAX_BYTES, werden
+// die am längsten nicht gelesenen oder geschriebenen Dateien gelöscht
+const ANALYSE_CACHE_MAX_BYTES: u64 = 256 * 1024 * 1024;
+// Größe des Ordners nur bei jedem n-ten Speichern prüfen
+const ANALYSE_CACHE_AUFRAEUMEN_ALLE: usize = 50;
+
+static ANALYSE_CACHE_GESPEICHERT: std::sync::atomic::AtomicUsize =
+    std::sync::atomic::AtomicUsize::new(0);
+
+fn analyse_cache_dir() -> std::path::PathBuf {
+    std::path::Path::new(&Konfiguration::backup_dir())
+        .join("cache")
+        .join("analyse")
+}
+
+// Analysierter Eintrag, der im Cache auf der Festplatte gespeichert werden kann
+trait CacheEintrag: Clone + Serialize + serde::de::DeserializeOwned {
+    fn geroetet(&self) -> bool;
+    // Setzt die Felder, die beim Serialisieren übersprungen werden
+    fn nach_laden(&mut self, geroetet: bool);
+}
+
+impl CacheEintrag for Abt1Analysiert {
+    fn geroetet(&self) -> bool {
+        false
+    }
+    fn nach_laden(&mut self, _: bool) {
+        self.fertig_analysiert = true;
+    }
+}
+
+impl CacheEintrag for Abt2Analysiert {
+    fn geroetet(&self) -> bool {
+        self.geroetet
+    }
+    fn nach_laden(&mut self, geroetet: bool) {
+        self.geroetet = geroetet;
+        self.fertig_analysiert = true;
+    }
+}
+
+impl CacheEintrag for Abt3Analysiert {
+    fn geroetet(&self) -> bool {
+        self.geroetet
+    }
+    fn nach_laden(&mut self, geroetet: bool) {
+        self.geroetet = geroetet;
+        self.fertig_analysiert = true;
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheDatei<T> {
+    eintrag: T,
+    #[serde(default)]
+    geroetet: bool,
+}
+
+fn cache_laden<T: CacheEintrag>(schluessel: &str) -> Option<T> {
+    let pfad = analyse_cache_dir().join(format!("{schluessel}.json"));
+    let datei = std::fs::read_to_string(&pfad).ok()?;
+    let datei = serde_json::from_str::<CacheDatei<T>>(&datei).ok()?;
+    cache_verwendet(&pfad);
+
+    let mut eintrag = datei.eintrag;
+    eintrag.nach_laden(datei.geroetet);
+
+    Some(eintrag)
+}
+
+fn cache_speichern<T: CacheEintrag>(schluessel: &str, eintrag: &T) {
+    let json = match serde_json::to_string(&CacheDatei {
+        eintrag,
+        geroetet: eintrag.geroetet(),
+    }) {
+        Ok(o) => o,
+        Err(_) => return,
+    };
+
+    let dir = analyse_cache_dir();
+    if std::fs::create_dir_all(&dir).is_err() {
+        return;
+    }
+
+    // Erst in temporäre Datei schreiben, damit parallele Leser keine halbe Datei sehen
+    let tmp = dir.join(format!("{schluessel}.{}.tmp", rand::random::<u32>()));
+    if std::fs::write(&tmp, json.as_bytes()).is_err()
+        || std::fs::rename(&tmp, dir.join(format!("{schluessel}.json"))).is_err()
+    {
+        let _ = std::fs::remove_file(&tmp);
+        return;
+    }
+
+    let gespeichert = ANALYSE_CACHE_GESPEICHERT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    if gespeichert % ANALYSE_CACHE_AUFRAEUMEN_ALLE == 0 {
+        cache_aufraeumen(&dir, ANALYSE_CACHE_MAX_BYTES);
+    }
+}
+
+// Setzt die Änderungszeit auf jetzt, damit cache_aufraeumen zuletzt gelesene
+// Dateien als zuletzt verwendet behandelt
+fn cache_verwendet(pfad: &std::path::Path) {
+    if let Ok(f) = std::fs::File::options().write(true).open(pfad) {
+        let _ = f.set_modified(std::time::SystemTime::now());
+    }
+}
+
+// Löscht die am längsten nicht verwendeten Dateien, bis der Ordner nicht
+// mehr als max_bytes belegt
+fn cache_aufraeumen(dir: &std::path::Path, max_bytes: u64) {
+    let mut dateien = match std::fs::read_dir(dir) {
+        Ok(o) => o
+            .filter_map(|e| {
+                let e = e.ok()?;
+                let metadata = e.metadata().ok()?;
+                if !metadata.is_file() {
+                    return None;
+                }
+                Some((metadata.modified().ok()?, metadata.len(), e.path()))
+            })
+            .collect::<Vec<_>>(),
+        Err(_) => return,
+    };
+
+    let mut gesamt = dateien.iter().map(|(_, len, _)| len).sum::<u64>();
+    if gesamt <= max_bytes {
+        return;
+    }
+
+    dateien.sort();
+    for (_, len, pfad) in dateien {
+        if gesamt <= max_bytes {
+            break;
+        }
+        if std::fs::remove_file(&pfad).is_ok() {
+            gesamt -= len;
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 struct GrundbuchAnalysiertCacheInternal {
+    abt1: BTreeMap<Abt1Hash, Abt1Analysiert>,
+    abt1_in_progress: BTreeSet<Abt1Hash>,
     abt2: BTreeMap<Abt2Hash, Abt2Analysiert>,
     abt2_in_progress: BTreeSet<Abt2Hash>,
     abt3: BTreeMap<Abt3Hash, Abt3Analysiert>,
     abt3_in_progress: BTreeSet<Abt3Hash>,
 }
 
+impl GrundbuchAnalysiertCacheInternal {
+    // Anzahl der Einträge, die gerade im Hintergrund analysiert werden
+    fn offen(&self) -> usize {
+        self.abt1_in_progress
+            .iter()
+            .filter(|h| !self.abt1.contains_key(h))
+            .count()
+            + self
+                .abt2_in_progress
+                .iter()
+                .filter(|h| !self.abt2.contains_key(h))
+                .count()
+            + self
+                .abt3_in_progress
+                .iter()
+                .filter(|h| !self.abt3.contains_key(h))
+                .count()
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref ANALYSE_FORTSCHRITT: Mutex<Option<EventLoopProxy<Cmd>>> = Mutex::new(None);
+}
+
+// Nach der Analyse eines Eintrags im Hintergrund wird über den EventLoopProxy
+// ein Cmd::AnalyseEintragFertig gesendet, damit die Oberfläche nur diesen
+// Eintrag aktualisiert, anstatt die Analyse regelmäßig abzufragen
+pub fn analyse_fortschritt_senden_an(proxy: EventLoopProxy<Cmd>) {
+    if let Ok(mut p) = ANALYSE_FORTSCHRITT.lock() {
+        *p = Some(proxy);
+    }
+}
+
+fn analyse_fortschritt_melden(datei: String, abteilung: usize, lfd_nr: usize) {
+    ereignis_senden(Cmd::AnalyseEintragFertig {
+        datei,
+        abteilung,
+        lfd_nr,
+    });
+}
+
+// Sendet ein Cmd aus einem Hintergrund-Thread an die Oberfläche
+pub fn ereignis_senden(cmd: Cmd) {
+    if let Some(proxy) = ANALYSE_FORTSCHRITT.lock().ok().and_then(|p| p.clone()) {
+        let _ = proxy.send_event(cmd);
+    }
+}
+
 impl GrundbuchAnalysiertCache {
+    pub fn offen(&self) -> usize {
+        self.inner.lock().map(|i| i.offen()).unwrap_or_default()
+    }
+
+    // Übernimmt einen auf der Festplatte gespeicherten Eintrag in den Cache im Speicher
+    fn von_festplatte_laden_abt1(&self, hash: &Abt1Hash) -> Option<Abt1Analysiert> {
+        let eintrag = cache_laden::<Abt1Analysiert>(&hash.schluessel())?;
+        self.inner
+            .lock()
+            .ok()?
+            .abt1
+            .insert(hash.clone(), eintrag.clone());
+        Some(eintrag)
+    }
+
+    fn von_festplatte_laden_abt2(&self, hash: &Abt2Hash) -> Option<Abt2Analysiert> {
+        let eintrag = cache_laden::<Abt2Analysiert>(&hash.schluessel())?;
+        self.inner
+            .lock()
+            .ok()?
+            .abt2
+            .insert(hash.clone(), eintrag.clone());
+        Some(eintrag)
+    }
+
+    fn von_festplatte_laden_abt3(&self, hash: &Abt3Hash) -> Option<Abt3Analysiert> {
+        let eintrag = cache_laden::<Abt3Analysiert>(&hash.schluessel())?;
+        self.inner
+            .lock()
+            .ok()?
+            .abt3
+            .insert(hash.clone(), eintrag.clone());
+        Some(eintrag)
+    }
+
     pub fn start_analyzing(
         &self,
         grundbuch: &Grundbuch,
@@ -171,27 +1882,105 @@ impl GrundbuchAnalysiertCache {
             .map(|state| state.clone())
             .unwrap_or_default();
 
+        let datei = format!(
+            "{}_{}",
+            grundbuch.titelblatt.grundbuch_von, grundbuch.titelblatt.blatt
+        );
+        let mut abt1_analysiert = Vec::new();
         let mut abt2_analysiert = Vec::new();
         let mut abt3_analysiert = Vec::new();
 
+        // Löschungen nur einmal je Blatt auflösen, nicht in jedem Task
+        let loeschungen = loeschungen_aufloesen(grundbuch);
+        let loeschungen_geteilt = Arc::new(loeschungen.clone());
+        let abt2_geloescht = loeschungen.vollstaendig_geloescht(2);
+        let abt3_geloescht = loeschungen.vollstaendig_geloescht(3);
+
+        for eintrag in abt1_eintraege(grundbuch, konfiguration) {
+            let abt1_hash = Abt1Hash::new(eintrag, grundbuch, konfiguration);
+            let default_eintrag = Abt1Analysiert {
+                lfd_nr: eintrag.get_lfd_nr(),
+                id: eintrag.get_id(),
+                eigentuemer: Vec::new(),
+                grundlagen: Vec::new(),
+                text_original: eintrag.get_eigentuemer(),
+                warnungen: Vec::new(),
+                fehler: vec![BefundCode::WirdGeladen.into()],
+                fertig_analysiert: false,
+            };
+            let abt1_result = match current_state_cloned.abt1.get(&abt1_hash).cloned() {
+                Some(s) => s,
+                None => {
+                    if !self
+                        .inner
+                        .lock()
+                        .unwrap()
+                        .abt1_in_progress
+                        .contains(&abt1_hash)
+                    {
+                        self.inner
+                            .lock()
+                            .unwrap()
+                            .abt1_in_progress
+                            .insert(abt1_hash.clone());
+                        let selfinner_clone = self.inner.clone();
+                        let grundbuch_clone = grundbuch.clone();
+                        let abt1_hash_clone = abt1_hash.clone();
+                        let vm_clone = vm.clone();
+                        let konfiguration_clone = konfiguration.clone();
+                        let eintrag_clone = eintrag.clone();
+                        let datei_clone = datei.clone();
+                        rayon::spawn(move || {
+                            // Auch das Lesen aus dem Cache auf der Festplatte
+                            // nicht im Thread der Oberfläche
+                            let schluessel = abt1_hash_clone.schluessel();
+                            let result = cache_laden(&schluessel).unwrap_or_else(|| {
+                                let result = rayon_task_analyze_abt1(
+                                    &eintrag_clone,
+                                    &grundbuch_clone,
+                                    &vm_clone,
+                                    &konfiguration_clone,
+                                );
+                                cache_speichern(&schluessel, &result);
+                                result
+                            });
+                            let lfd_nr = result.lfd_nr;
+                            selfinner_clone
+                                .lock()
+                                .unwrap()
+                                .abt1
+                                .insert(abt1_hash_clone, result);
+                            analyse_fortschritt_melden(datei_clone, 1, lfd_nr);
+                        });
+                    }
+
+                    default_eintrag
+                }
+            };
+            abt1_analysiert.push(abt1_result);
+        }
+
         for eintrag in grundbuch.abt2.eintraege.iter() {
-            if eintrag.ist_geroetet() {
+            if eintrag.ist_geroetet() || abt2_geloescht.contains(&eintrag.lfd_nr) {
                 continue;
             }
             let abt2_hash = Abt2Hash::new(eintrag, grundbuch, konfiguration, nb);
             let default_eintrag = Abt2Analysiert {
                 lfd_nr: eintrag.lfd_nr,
+                id: eintrag.id.clone(),
                 text_kurz: eintrag.text.text(),
                 rechteart: RechteArt::SonstigeDabagrechteart,
                 rechtsinhaber: String::new(),
                 rangvermerk: None,
                 spalte_2: String::new(),
                 belastete_flurstuecke: Vec::new(),
+                flurstueckskennzeichen: Vec::new(),
                 lastend_an: Vec::new(),
+                fortfuehrungen: Vec::new(),
                 text_original: eintrag.text.text(),
                 nebenbeteiligter: Nebenbeteiligter::default(),
                 warnungen: Vec::new(),
-                fehler: vec!["Wird geladen...".to_string().into()],
+                fehler: vec![BefundCode::WirdGeladen.into()],
                 geroetet: eintrag.ist_geroetet(),
                 fertig_analysiert: false,
             };
@@ -217,19 +2006,29 @@ impl GrundbuchAnalysiertCache {
                         let vm_clone = vm.clone();
                         let konfiguration_clone = konfiguration.clone();
                         let eintrag_clone = eintrag.clone();
+                        let datei_clone = datei.clone();
+                        let loeschungen_clone = loeschungen_geteilt.clone();
                         rayon::spawn(move || {
-                            let result = rayon_task_analyze_abt2(
-                                &eintrag_clone,
-                                &grundbuch_clone,
-                                &vm_clone,
-                                &nb_clone,
-                                &konfiguration_clone,
-                            );
+                            let schluessel = abt2_hash_clone.schluessel();
+                            let result = cache_laden(&schluessel).unwrap_or_else(|| {
+                                let result = rayon_task_analyze_abt2(
+                                    &eintrag_clone,
+                                    &grundbuch_clone,
+                                    &loeschungen_clone,
+                                    &vm_clone,
+                                    &nb_clone,
+                                    &konfiguration_clone,
+                                );
+                                cache_speichern(&schluessel, &result);
+                                result
+                            });
+                            let lfd_nr = result.lfd_nr;
                             selfinner_clone
                                 .lock()
                                 .unwrap()
                                 .abt2
                                 .insert(abt2_hash_clone, result);
+                            analyse_fortschritt_melden(datei_clone, 2, lfd_nr);
                         });
                     }
 
@@ -240,12 +2039,13 @@ impl GrundbuchAnalysiertCache {
         }
 
         for eintrag in grundbuch.abt3.eintraege.iter() {
-            if eintrag.ist_geroetet() {
+            if eintrag.ist_geroetet() || abt3_geloescht.contains(&eintrag.lfd_nr) {
                 continue;
             }
             let abt3_hash = Abt3Hash::new(eintrag, grundbuch, konfiguration, nb);
             let default_eintrag = Abt3Analysiert {
                 lfd_nr: eintrag.lfd_nr,
+                id: eintrag.id.clone(),
                 text_kurz: eintrag.text.text(),
                 betrag: Betrag {
                     wert: 0,
@@ -254,13 +2054,18 @@ impl GrundbuchAnalysiertCache {
                 },
                 schuldenart: SchuldenArt::Grundschuld,
                 rechtsinhaber: String::new(),
+                betrag_eingetragen: Betrag::default(),
+                rechtsinhaber_eingetragen: String::new(),
+                historie: Vec::new(),
                 spalte_2: String::new(),
                 belastete_flurstuecke: Vec::new(),
+                flurstueckskennzeichen: Vec::new(),
                 lastend_an: Vec::new(),
+                fortfuehrungen: Vec::new(),
                 text_original: eintrag.text.text(),
                 nebenbeteiligter: Nebenbeteiligter::default(),
                 warnungen: Vec::new(),
-                fehler: vec!["Wird geladen...".to_string().into()],
+                fehler: vec![BefundCode::WirdGeladen.into()],
                 geroetet: eintrag.ist_geroetet(),
                 fertig_analysiert: false,
             };
@@ -286,120 +2091,1283 @@ impl GrundbuchAnalysiertCache {
                         let vm_clone = vm.clone();
                         let konfiguration_clone = konfiguration.clone();
                         let eintrag_clone = eintrag.clone();
+                        let datei_clone = datei.clone();
+                        let loeschungen_clone = loeschungen_geteilt.clone();
                         rayon::spawn(move || {
-                            let result = rayon_task_analyze_abt3(
-                                &eintrag_clone,
-                                &grundbuch_clone,
-                                &vm_clone,
-                                &nb_clone,
-                                &konfiguration_clone,
-                            );
+                            let schluessel = abt3_hash_clone.schluessel();
+                            let result = cache_laden(&schluessel).unwrap_or_else(|| {
+                                let result = rayon_task_analyze_abt3(
+                                    &eintrag_clone,
+                                    &grundbuch_clone,
+                                    &loeschungen_clone,
+                                    &vm_clone,
+                                    &nb_clone,
+                                    &konfiguration_clone,
+                                );
+                                cache_speichern(&schluessel, &result);
+                                result
+                            });
+                            let lfd_nr = result.lfd_nr;
                             selfinner_clone
                                 .lock()
                                 .unwrap()
                                 .abt3
                                 .insert(abt3_hash_clone, result);
+                            analyse_fortschritt_melden(datei_clone, 3, lfd_nr);
                         });
                     }
 
-                    default_eintrag
+                    default_eintrag
+                }
+            };
+            abt3_analysiert.push(abt3_result);
+        }
+
+        let rang = rang_analysieren(grundbuch, &loeschungen, &abt2_analysiert, &abt3_analysiert);
+
+        let mut analyse = GrundbuchAnalysiert {
+            titelblatt: grundbuch.titelblatt.clone(),
+            abt1: abt1_analysiert,
+            abt2: abt2_analysiert,
+            abt3: abt3_analysiert,
+            erbbaurechte: analysiere_erbbaurechte(grundbuch),
+            loeschungen,
+            rang,
+            alkis: alkis::bv_abgleichen(
+                grundbuch,
+                &konfiguration.gemarkungen,
+                &konfiguration.alkis,
+            ),
+            pruefung: pruefung::grundbuch_pruefen(grundbuch),
+            blaetter: Vec::new(),
+        };
+
+        quittierungen_anwenden(grundbuch, &mut analyse);
+        analyse
+    }
+
+    pub fn start_and_block_until_finished(
+        &self,
+        grundbuch: &Grundbuch,
+        vm: &PyVm,
+        nb: &[Nebenbeteiligter],
+        konfiguration: &Konfiguration,
+    ) -> GrundbuchAnalysiert {
+        let current_state_cloned = self
+            .inner
+            .lock()
+            .map(|state| state.clone())
+            .unwrap_or_default();
+
+        let mut abt1_analysiert = Vec::new();
+        let mut abt2_analysiert = Vec::new();
+        let mut abt3_analysiert = Vec::new();
+
+        let loeschungen = loeschungen_aufloesen(grundbuch);
+        let abt2_geloescht = loeschungen.vollstaendig_geloescht(2);
+        let abt3_geloescht = loeschungen.vollstaendig_geloescht(3);
+
+        for eintrag in abt1_eintraege(grundbuch, konfiguration) {
+            let abt1_hash = Abt1Hash::new(eintrag, grundbuch, konfiguration);
+            abt1_analysiert.push(
+                match current_state_cloned
+                    .abt1
+                    .get(&abt1_hash)
+                    .cloned()
+                    .or_else(|| self.von_festplatte_laden_abt1(&abt1_hash))
+                {
+                    Some(s) => s,
+                    None => {
+                        let result = rayon_task_analyze_abt1(eintrag, grundbuch, vm, konfiguration);
+                        cache_speichern(&abt1_hash.schluessel(), &result);
+                        if let Ok(mut inner) = self.inner.lock() {
+                            inner.abt1.insert(abt1_hash.clone(), result.clone());
+                        }
+                        result
+                    }
+                },
+            );
+        }
+
+        for eintrag in grundbuch.abt2.eintraege.iter() {
+            if eintrag.ist_geroetet() || abt2_geloescht.contains(&eintrag.lfd_nr) {
+                continue;
+            }
+            let abt2_hash = Abt2Hash::new(eintrag, grundbuch, konfiguration, nb);
+            abt2_analysiert.push(
+                match current_state_cloned
+                    .abt2
+                    .get(&abt2_hash)
+                    .cloned()
+                    .or_else(|| self.von_festplatte_laden_abt2(&abt2_hash))
+                {
+                    Some(s) => s,
+                    None => {
+                        let result = rayon_task_analyze_abt2(
+                            eintrag,
+                            &grundbuch,
+                            &loeschungen,
+                            vm,
+                            nb,
+                            konfiguration,
+                        );
+                        cache_speichern(&abt2_hash.schluessel(), &result);
+                        if let Ok(mut inner) = self.inner.lock() {
+                            inner.abt2.insert(abt2_hash.clone(), result.clone());
+                        }
+                        result
+                    }
+                },
+            );
+        }
+
+        for eintrag in grundbuch.abt3.eintraege.iter() {
+            if eintrag.ist_geroetet() || abt3_geloescht.contains(&eintrag.lfd_nr) {
+                continue;
+            }
+            let abt3_hash = Abt3Hash::new(eintrag, grundbuch, konfiguration, nb);
+            abt3_analysiert.push(
+                match current_state_cloned
+                    .abt3
+                    .get(&abt3_hash)
+                    .cloned()
+                    .or_else(|| self.von_festplatte_laden_abt3(&abt3_hash))
+                {
+                    Some(s) => s,
+                    None => {
+                        let result = rayon_task_analyze_abt3(
+                            eintrag,
+                            &grundbuch,
+                            &loeschungen,
+                            vm,
+                            nb,
+                            konfiguration,
+                        );
+                        cache_speichern(&abt3_hash.schluessel(), &result);
+                        if let Ok(mut inner) = self.inner.lock() {
+                            inner.abt3.insert(abt3_hash.clone(), result.clone());
+                        }
+                        result
+                    }
+                },
+            );
+        }
+
+        let rang = rang_analysieren(grundbuch, &loeschungen, &abt2_analysiert, &abt3_analysiert);
+
+        let mut analyse = GrundbuchAnalysiert {
+            titelblatt: grundbuch.titelblatt.clone(),
+            abt1: abt1_analysiert,
+            abt2: abt2_analysiert,
+            abt3: abt3_analysiert,
+            erbbaurechte: analysiere_erbbaurechte(grundbuch),
+            loeschungen,
+            rang,
+            alkis: alkis::bv_abgleichen(
+                grundbuch,
+                &konfiguration.gemarkungen,
+                &konfiguration.alkis,
+            ),
+            pruefung: pruefung::grundbuch_pruefen(grundbuch),
+            blaetter: Vec::new(),
+        };
+
+        quittierungen_anwenden(grundbuch, &mut analyse);
+        analyse
+    }
+}
+
+fn analysiere_erbbaurechte(grundbuch: &Grundbuch) -> Vec<ErbbaurechtAnalysiert> {
+    grundbuch
+        .bestandsverzeichnis
+        .eintraege
+        .iter()
+        .filter(|bv| !bv.ist_geroetet())
+        .filter_map(|bv| match bv {
+            BvEintrag::Erbbaurecht(e) => Some(e),
+            _ => None,
+        })
+        .map(|e| {
+            let mut warnungen = Vec::new();
+            let mut fehler = Vec::new();
+
+            if e.grundstueck.grundbuch_von.trim().is_empty()
+                || e.grundstueck.blatt.trim().is_empty()
+            {
+                fehler.push(BefundCode::ErbbauBlattFehlt { bv_nr: e.lfd_nr }.into());
+            }
+
+            if e.grundstueck.get_bv_nr().is_empty() {
+                warnungen.push(BefundCode::ErbbauGrundstueckOhneBvNr { bv_nr: e.lfd_nr }.into());
+            }
+
+            if e.dauer.is_empty() {
+                warnungen.push(BefundCode::ErbbauOhneDauer { bv_nr: e.lfd_nr }.into());
+            }
+
+            ErbbaurechtAnalysiert {
+                lfd_nr: e.lfd_nr,
+                grundstueck: e.grundstueck.clone(),
+                dauer: e.dauer.text(),
+                zustimmungsvorbehalte: e.zustimmungsvorbehalte.clone(),
+                abt2_lfd_nr: None,
+                warnungen,
+                fehler,
+            }
+        })
+        .collect()
+}
+
+/// Gleicht die Erbbaurechte eines Erbbaugrundbuchs mit Abt. 2 des
+/// (geladenen) Grundstücks-Blatts ab, auf das sie verweisen
+pub fn erbbaurechte_abgleichen(
+    erbbaugrundbuch: &mut GrundbuchAnalysiert,
+    grundstueck_blatt: &Grundbuch,
+    grundstueck_abt2: &[Abt2Analysiert],
+) {
+    let erbbau_blatt = erbbaugrundbuch.titelblatt.blatt.trim().to_string();
+    let gb_von = grundstueck_blatt.titelblatt.grundbuch_von.clone();
+    let gb_blatt = grundstueck_blatt.titelblatt.blatt.clone();
+    let blatt = format!("{gb_von} Blatt {gb_blatt}");
+    let abt2_geloescht = loeschungen_aufloesen(grundstueck_blatt).vollstaendig_geloescht(2);
+
+    let ist_erbbaurecht = |eintrag: &Abt2Eintrag| -> bool {
+        match grundstueck_abt2
+            .iter()
+            .find(|a2| a2.lfd_nr == eintrag.lfd_nr && a2.fertig_analysiert)
+        {
+            Some(a2) => matches!(
+                a2.rechteart,
+                RechteArt::Erbbaurecht | RechteArt::Untererbbaurecht
+            ),
+            None => eintrag.text.text().to_lowercase().contains("erbbaurecht"),
+        }
+    };
+
+    let verweist_auf_erbbaugrundbuch = |eintrag: &Abt2Eintrag| -> bool {
+        let text = eintrag.text.lines().join(" ");
+        text.split_whitespace()
+            .collect::<Vec<_>>()
+            .windows(2)
+            .any(|w| {
+                w[0] == "Blatt"
+                    && w[1].trim_end_matches(|c| c == ',' || c == '.' || c == ';') == erbbau_blatt
+            })
+    };
+
+    for e in erbbaugrundbuch.erbbaurechte.iter_mut() {
+        if !e
+            .grundstueck
+            .grundbuch_von
+            .trim()
+            .eq_ignore_ascii_case(gb_von.trim())
+            || e.grundstueck.blatt.trim() != gb_blatt.trim()
+        {
+            continue;
+        }
+
+        for bv_nr in e.grundstueck.get_bv_nr() {
+            let vorhanden = grundstueck_blatt
+                .bestandsverzeichnis
+                .eintraege
+                .iter()
+                .any(|bv| bv.get_lfd_nr() == bv_nr && !bv.ist_flurstueck_geroetet());
+            if !vorhanden {
+                e.fehler.push(
+                    BefundCode::ErbbauGrundstueckFehlt {
+                        bv_nr: e.lfd_nr,
+                        grundstueck: bv_nr,
+                        blatt: blatt.clone(),
+                    }
+                    .into(),
+                );
+            }
+        }
+
+        let abt2_eintraege = grundstueck_blatt
+            .abt2
+            .eintraege
+            .iter()
+            .filter(|a2| !a2.ist_geroetet() && !abt2_geloescht.contains(&a2.lfd_nr))
+            .collect::<Vec<_>>();
+
+        let abt2_eintrag = match e.grundstueck.abt2_lfd_nr {
+            Some(abt2_lfd_nr) => {
+                match abt2_eintraege
+                    .iter()
+                    .copied()
+                    .find(|a2| a2.lfd_nr == abt2_lfd_nr)
+                {
+                    Some(a2) if ist_erbbaurecht(a2) => Some(a2),
+                    Some(_) => {
+                        e.fehler.push(
+                            BefundCode::ErbbauAbt2KeinErbbaurecht {
+                                bv_nr: e.lfd_nr,
+                                abt2_lfd_nr,
+                                blatt: blatt.clone(),
+                            }
+                            .into(),
+                        );
+                        None
+                    }
+                    None => {
+                        e.fehler.push(
+                            BefundCode::ErbbauAbt2Fehlt {
+                                bv_nr: e.lfd_nr,
+                                abt2_lfd_nr,
+                                blatt: blatt.clone(),
+                            }
+                            .into(),
+                        );
+                        None
+                    }
+                }
+            }
+            None => {
+                let kandidaten = abt2_eintraege
+                    .iter()
+                    .copied()
+                    .filter(|a2| ist_erbbaurecht(*a2))
+                    .collect::<Vec<_>>();
+                let mit_verweis = kandidaten
+                    .iter()
+                    .copied()
+                    .filter(|a2| verweist_auf_erbbaugrundbuch(*a2))
+                    .collect::<Vec<_>>();
+
+                match (kandidaten.as_slice(), mit_verweis.as_slice()) {
+                    (_, [a2]) => Some(*a2),
+                    ([a2], []) => Some(*a2),
+                    ([], _) => {
+                        e.fehler.push(
+                            BefundCode::ErbbauNichtGefunden {
+                                bv_nr: e.lfd_nr,
+                                blatt: blatt.clone(),
+                            }
+                            .into(),
+                        );
+                        None
+                    }
+                    _ => {
+                        e.warnungen.push(
+                            BefundCode::ErbbauMehrdeutig {
+                                bv_nr: e.lfd_nr,
+                                blatt: blatt.clone(),
+                            }
+                            .into(),
+                        );
+                        None
+                    }
+                }
+            }
+        };
+
+        let abt2_eintrag = match abt2_eintrag {
+            Some(s) => s,
+            None => continue,
+        };
+
+        let abt2_lfd_nr = abt2_eintrag.lfd_nr;
+        e.abt2_lfd_nr = Some(abt2_lfd_nr);
+
+        if !verweist_auf_erbbaugrundbuch(abt2_eintrag) {
+            e.warnungen.push(
+                BefundCode::ErbbauOhneVerweis {
+                    abt2_lfd_nr,
+                    blatt: blatt.clone(),
+                    erbbau_blatt: erbbau_blatt.clone(),
+                }
+                .into(),
+            );
+        }
+
+        // Belastete Grundstücke laut Abt. 2 des Grundstücks-Blatts
+        let a2_analysiert = grundstueck_abt2
+            .iter()
+            .find(|a2| a2.lfd_nr == abt2_lfd_nr && a2.fertig_analysiert);
+
+        if let Some(a2) = a2_analysiert {
+            let laut_abt2 = a2
+                .belastete_flurstuecke
+                .iter()
+                .map(|bv| bv.get_lfd_nr())
+                .collect::<BTreeSet<_>>();
+            let laut_erbbaugrundbuch = e
+                .grundstueck
+                .get_bv_nr()
+                .into_iter()
+                .collect::<BTreeSet<_>>();
+            if !laut_abt2.is_empty()
+                && !laut_erbbaugrundbuch.is_empty()
+                && laut_abt2 != laut_erbbaugrundbuch
+            {
+                e.warnungen.push(
+                    BefundCode::ErbbauBvAbweichend {
+                        bv_nr: e.lfd_nr,
+                        laut_abt2: laut_abt2.into_iter().collect(),
+                        laut_erbbaugrundbuch: laut_erbbaugrundbuch.into_iter().collect(),
+                    }
+                    .into(),
+                );
+            }
+
+            // § 10 ErbbauRG: Erbbaurecht nur zur ausschließlich ersten Rangstelle
+            let hat_vorrangige_rechte = abt2_eintraege.iter().any(|a| a.lfd_nr < abt2_lfd_nr);
+            if hat_vorrangige_rechte && a2.rangvermerk.is_none() {
+                e.warnungen.push(
+                    BefundCode::ErbbauNichtErsteRangstelle {
+                        abt2_lfd_nr,
+                        blatt: blatt.clone(),
+                    }
+                    .into(),
+                );
+            }
+        }
+
+        // Dauer: Datums- und Jahresangaben müssen sich im Text von Abt. 2 wiederfinden
+        let abt2_text = abt2_eintrag
+            .text
+            .lines()
+            .join(" ")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let dauer = e.dauer.split_whitespace().collect::<Vec<_>>().join(" ");
+        let abt2_zahlen = zahlen_lesen(&abt2_text);
+        if zahlen_lesen(&dauer)
+            .iter()
+            .any(|z| !abt2_zahlen.contains(z))
+        {
+            e.warnungen.push(
+                BefundCode::ErbbauDauerAbweichend {
+                    bv_nr: e.lfd_nr,
+                    dauer,
+                    abt2_lfd_nr,
+                    blatt: blatt.clone(),
+                }
+                .into(),
+            );
+        }
+    }
+}
+
+// Zahlen eines Textes als ganze Token, "bis zum 31. 12. 2099" -> [31, 12, 2099],
+// damit "99" nicht in "1999" gefunden wird
+fn zahlen_lesen(text: &str) -> Vec<u64> {
+    text.split(|c: char| !c.is_ascii_digit())
+        .filter_map(|s| s.parse().ok())
+        .collect()
+}
+
+/// Ordnet die Löschungen aus Abt. 2 und Abt. 3 den gelöschten Einträgen zu
+/// und gleicht das Ergebnis mit der Rötung der Einträge ab
+pub fn loeschungen_aufloesen(grundbuch: &Grundbuch) -> LoeschungenAnalysiert {
+    let abt2_eintraege = grundbuch
+        .abt2
+        .eintraege
+        .iter()
+        .map(|e| (e.lfd_nr, e.ist_geroetet()))
+        .collect::<Vec<_>>();
+    let abt3_eintraege = grundbuch
+        .abt3
+        .eintraege
+        .iter()
+        .map(|e| (e.lfd_nr, e.ist_geroetet()))
+        .collect::<Vec<_>>();
+
+    let mut eintraege = Vec::new();
+    for l in grundbuch.abt2.loeschungen.iter() {
+        if l.ist_geroetet() {
+            continue;
+        }
+        eintraege.extend(loeschung_aufloesen(
+            2,
+            &l.lfd_nr.text(),
+            "",
+            &l.text.text(),
+            &abt2_eintraege,
+        ));
+    }
+    for l in grundbuch.abt3.loeschungen.iter() {
+        if l.ist_geroetet() {
+            continue;
+        }
+        eintraege.extend(loeschung_aufloesen(
+            3,
+            &l.lfd_nr.text(),
+            &l.betrag.text(),
+            &l.text.text(),
+            &abt3_eintraege,
+        ));
+    }
+
+    // Ohne digitalisierte Löschungen wäre jeder gerötete Eintrag eine Warnung
+    let mut warnungen = Vec::new();
+    for (abteilung, abt_eintraege, hat_loeschungen) in [
+        (2, &abt2_eintraege, !grundbuch.abt2.loeschungen.is_empty()),
+        (3, &abt3_eintraege, !grundbuch.abt3.loeschungen.is_empty()),
+    ] {
+        if !hat_loeschungen {
+            continue;
+        }
+        let geloescht = eintraege
+            .iter()
+            .filter(|l| l.abteilung == abteilung)
+            .flat_map(|l| l.lfd_nr.iter().copied())
+            .collect::<BTreeSet<_>>();
+        let geroetet = abt_eintraege
+            .iter()
+            .filter(|(lfd_nr, geroetet)| *lfd_nr != 0 && *geroetet)
+            .map(|(lfd_nr, _)| *lfd_nr)
+            .collect::<BTreeSet<_>>();
+        for lfd_nr in geroetet.difference(&geloescht) {
+            warnungen.push(
+                BefundCode::GeroetetOhneLoeschung {
+                    abteilung,
+                    lfd_nr: *lfd_nr,
+                }
+                .into(),
+            );
+        }
+    }
+
+    LoeschungenAnalysiert {
+        eintraege,
+        warnungen,
+    }
+}
+
+fn loeschung_aufloesen(
+    abteilung: usize,
+    spalte_1: &str,
+    betrag_spalte: &str,
+    text: &str,
+    abt_eintraege: &[(usize, bool)],
+) -> Option<LoeschungAnalysiert> {
+    let mut warnungen = Vec::new();
+    let mut fehler = Vec::new();
+
+    let lfd_nr = match parse_spalte_1_veraenderung(spalte_1) {
+        Ok(o) => o,
+        Err(e) => {
+            fehler.push(
+                BefundCode::LoeschungNichtLesbar {
+                    abteilung,
+                    loeschung: spalte_1.to_string(),
+                    grund: e.to_string(),
+                }
+                .into(),
+            );
+            Vec::new()
+        }
+    };
+
+    if lfd_nr.is_empty() && fehler.is_empty() {
+        return None;
+    }
+
+    let (teilweise, nur_geloescht_an_bv_nr, nur_geloescht_an) = teilloeschung_lesen(text);
+
+    // Abt. 3: "gelöscht in Höhe von 10.000 DM"
+    let geloeschter_betrag = if teilweise {
+        betrag_lesen(betrag_spalte).or_else(|| betrag_lesen(text))
+    } else {
+        None
+    };
+
+    if teilweise
+        && nur_geloescht_an_bv_nr.is_empty()
+        && nur_geloescht_an.is_empty()
+        && geloeschter_betrag.is_none()
+    {
+        warnungen.push(
+            BefundCode::TeilloeschungUnklar {
+                abteilung,
+                loeschung: spalte_1.to_string(),
+            }
+            .into(),
+        );
+    }
+
+    for nr in lfd_nr.iter() {
+        let geroetet = abt_eintraege
+            .iter()
+            .filter(|(l, _)| l == nr)
+            .map(|(_, geroetet)| *geroetet)
+            .collect::<Vec<_>>();
+
+        if geroetet.is_empty() {
+            fehler.push(
+                BefundCode::LoeschungOhneEintrag {
+                    abteilung,
+                    loeschung: spalte_1.to_string(),
+                    lfd_nr: *nr,
                 }
-            };
-            abt3_analysiert.push(abt3_result);
+                .into(),
+            );
+        } else if !teilweise && !geroetet.iter().all(|g| *g) {
+            warnungen.push(
+                BefundCode::GeloeschtNichtGeroetet {
+                    abteilung,
+                    lfd_nr: *nr,
+                }
+                .into(),
+            );
+        } else if teilweise && geroetet.iter().all(|g| *g) {
+            warnungen.push(
+                BefundCode::TeilweiseGeloeschtVollGeroetet {
+                    abteilung,
+                    lfd_nr: *nr,
+                }
+                .into(),
+            );
         }
+    }
 
-        GrundbuchAnalysiert {
-            titelblatt: grundbuch.titelblatt.clone(),
-            abt2: abt2_analysiert,
-            abt3: abt3_analysiert,
-        }
+    Some(LoeschungAnalysiert {
+        abteilung,
+        lfd_nr,
+        text: text.to_string(),
+        teilweise,
+        nur_geloescht_an_bv_nr,
+        nur_geloescht_an,
+        geloeschter_betrag,
+        warnungen,
+        fehler,
+    })
+}
+
+// "Gelöscht hinsichtlich Flur 2 Flurstück 17/3" / "bezüglich lfd. Nr. 4 des BV"
+fn teilloeschung_lesen(text: &str) -> (bool, Vec<usize>, Vec<FlurFlurstueck>) {
+    use lazy_static::lazy_static;
+    use regex::Regex;
+
+    lazy_static! {
+        static ref REGEX_FLURSTUECK: Regex = Regex::new(
+            r"(?:Flur\s+(\d+)\s*,?\s*)?(?:Flurstücke?|Flst\.)\s*(\d+(?:/\d+)?(?:\s*(?:,|und)\s*\d+(?:/\d+)?)*)"
+        )
+        .unwrap();
     }
 
-    pub fn start_and_block_until_finished(
-        &self,
-        grundbuch: &Grundbuch,
-        vm: &PyVm,
-        nb: &[Nebenbeteiligter],
-        konfiguration: &Konfiguration,
-    ) -> GrundbuchAnalysiert {
-        let current_state_cloned = self
-            .inner
-            .lock()
-            .map(|state| state.clone())
-            .unwrap_or_default();
+    // "soweit" und "lastend an" allein stehen auch in vollständigen Löschungen
+    // ("Gelöscht, soweit nicht übertragen", "lastend an Nr. 1 und 2, gelöscht am"),
+    // nur zusammen bedeuten sie eine Teillöschung
+    const TEILWEISE: &[&str] = &[
+        "hinsichtlich",
+        "bezüglich",
+        "soweit lastend",
+        "soweit es lastet",
+        "soweit es sich auf",
+        "teilweise",
+        "in ansehung",
+        "nur noch lastend",
+        "in höhe von",
+        "teilbetrag",
+    ];
 
-        let mut abt2_analysiert = Vec::new();
-        let mut abt3_analysiert = Vec::new();
+    let text = text.lines().collect::<Vec<_>>().join(" ");
+    let text_lower = text.to_lowercase();
+    if !TEILWEISE.iter().any(|t| text_lower.contains(t)) {
+        return (false, Vec::new(), Vec::new());
+    }
 
-        for eintrag in grundbuch.abt2.eintraege.iter() {
-            if eintrag.ist_geroetet() {
+    let mut flurstuecke = Vec::new();
+    for c in REGEX_FLURSTUECK.captures_iter(&text) {
+        let flur = c
+            .get(1)
+            .and_then(|f| f.as_str().parse::<usize>().ok())
+            .unwrap_or(0);
+        let liste = c.get(2).map(|f| f.as_str()).unwrap_or_default();
+        for flst in liste.split(',').flat_map(|s| s.split(" und ")) {
+            let flst = flst.trim();
+            if flst.is_empty() {
                 continue;
             }
-            let abt2_hash = Abt2Hash::new(eintrag, grundbuch, konfiguration, nb);
-            abt2_analysiert.push(match current_state_cloned.abt2.get(&abt2_hash).cloned() {
-                Some(s) => s,
-                None => rayon_task_analyze_abt2(eintrag, &grundbuch, vm, nb, konfiguration),
+            flurstuecke.push(FlurFlurstueck {
+                flur,
+                flurstueck: flst.to_string(),
+                gemarkung: None,
+                teilflaeche_qm: None,
             });
         }
+    }
 
-        for eintrag in grundbuch.abt3.eintraege.iter() {
-            if eintrag.ist_geroetet() {
-                continue;
-            }
-            let abt3_hash = Abt3Hash::new(eintrag, grundbuch, konfiguration, nb);
-            abt3_analysiert.push(match current_state_cloned.abt3.get(&abt3_hash).cloned() {
-                Some(s) => s,
-                None => rayon_task_analyze_abt3(eintrag, &grundbuch, vm, nb, konfiguration),
+    let bv_nr = bv_nummern_lesen(&text)
+        .into_iter()
+        .flat_map(|(_, nr)| nr)
+        .collect::<BTreeSet<_>>();
+
+    (true, bv_nr.into_iter().collect(), flurstuecke)
+}
+
+// "lfd. Nr. 3 und 4", "BV-Nr. 2", "Nrn. 5-7": (Position im Text, Nummern)
+fn bv_nummern_lesen(text: &str) -> Vec<(usize, Vec<usize>)> {
+    use lazy_static::lazy_static;
+    use regex::Regex;
+
+    lazy_static! {
+        static ref REGEX_BV_NR: Regex = Regex::new(
+            r"(?:lfd\.\s*Nr\.|BV-Nr\.|Nrn?\.)\s*(\d+(?:\s?[a-z]\b)?(?:\s*(?:,|und|bis|-)\s*\d+(?:\s?[a-z]\b)?)*)"
+        )
+        .unwrap();
+    }
+
+    REGEX_BV_NR
+        .captures_iter(text)
+        .filter_map(|c| {
+            let m = c.get(0)?;
+            let nr = parse_spalte_1_veraenderung(c.get(1)?.as_str()).ok()?;
+            Some((m.start(), nr))
+        })
+        .collect()
+}
+
+// Flurstückskennzeichen der belasteten Flurstücke, Rechte und Erbbaurechte im BV
+// haben kein Kennzeichen
+fn flurstueckskennzeichen_bilden(
+    belastete_flurstuecke: &[BvEintrag],
+    titelblatt: &Titelblatt,
+    konfiguration: &Konfiguration,
+    warnungen: &mut Vec<AnalyseWarnung>,
+) -> Vec<String> {
+    if konfiguration.gemarkungen.is_empty() {
+        return Vec::new();
+    }
+
+    let mut kennzeichen = Vec::new();
+    for bv in belastete_flurstuecke {
+        if !matches!(bv, BvEintrag::Flurstueck(_)) {
+            continue;
+        }
+        match alkis::bv_flurstueckskennzeichen(
+            bv,
+            &titelblatt.grundbuch_von,
+            &konfiguration.gemarkungen,
+        ) {
+            Ok(o) => kennzeichen.push(o),
+            Err(e) => warnungen.push(BefundCode::KeinFlurstueckskennzeichen(e).into()),
+        }
+    }
+
+    kennzeichen.sort();
+    kennzeichen.dedup();
+    kennzeichen
+}
+
+// Belastete Flurstücke als Eingabe für das Skript "pruefe_eintrag", ohne
+// Gemarkung im BV gilt die Gemarkung des Grundbuchs
+fn flurstuecke_fuer_pruefung(
+    belastete_flurstuecke: &[BvEintrag],
+    titelblatt: &Titelblatt,
+) -> Vec<FlurFlurstueck> {
+    belastete_flurstuecke
+        .iter()
+        .filter(|bv| matches!(bv, BvEintrag::Flurstueck(_)))
+        .map(|bv| FlurFlurstueck {
+            flur: bv.get_flur(),
+            flurstueck: bv.get_flurstueck(),
+            gemarkung: Some(
+                bv.get_gemarkung()
+                    .unwrap_or_else(|| titelblatt.grundbuch_von.clone()),
+            ),
+            teilflaeche_qm: None,
+        })
+        .collect()
+}
+
+// Projektspezifische Regeln (Skript "pruefe_eintrag"), die gemeldeten Fehler und
+// Warnungen werden an die des Eintrags angehängt
+fn eintrag_pruefen(
+    vm: &PyVm,
+    eintrag: EintragPruefen,
+    konfiguration: &Konfiguration,
+    warnungen: &mut Vec<AnalyseWarnung>,
+    fehler: &mut Vec<AnalyseFehler>,
+) {
+    match crate::python::pruefe_eintrag(vm.clone(), eintrag, konfiguration) {
+        Ok(o) => {
+            warnungen.extend(
+                o.warnungen
+                    .into_iter()
+                    .map(|w| BefundCode::PruefeEintragWarnung(w).into()),
+            );
+            fehler.extend(
+                o.fehler
+                    .into_iter()
+                    .map(|f| BefundCode::PruefeEintragFehler(f).into()),
+            );
+        }
+        Err(e) => fehler.push(e),
+    }
+}
+
+// Teillöschungen: gelöschte BV-Nr. / Flurstücke sind nicht mehr belastet
+fn teilloeschungen_anwenden(
+    abteilung: usize,
+    lfd_nr: usize,
+    belastete_flurstuecke: Vec<BvEintrag>,
+    loeschungen: &LoeschungenAnalysiert,
+    warnungen: &mut Vec<AnalyseWarnung>,
+) -> Vec<BvEintrag> {
+    let mut belastete_flurstuecke = belastete_flurstuecke;
+
+    for l in loeschungen.teilloeschungen(abteilung, lfd_nr) {
+        warnungen.push(BefundCode::TeilweiseGeloescht(l.text.clone()).into());
+        belastete_flurstuecke.retain(|bv| {
+            let bv_nr_geloescht = l.nur_geloescht_an_bv_nr.contains(&bv.get_lfd_nr());
+            let flst_geloescht = l.nur_geloescht_an.iter().any(|f| {
+                (f.flur == 0 || f.flur == bv.get_flur())
+                    && flurstueck_gleich(&f.flurstueck, &bv.get_flurstueck())
             });
+            !(bv_nr_geloescht || flst_geloescht)
+        });
+    }
+
+    belastete_flurstuecke
+}
+
+// Veränderungen und Teillöschungen zu einer lfd. Nr. in Abt. 3, in der
+// Reihenfolge der Eintragung
+fn abt3_historie(
+    grundbuch: &Grundbuch,
+    lfd_nr: usize,
+    loeschungen: &LoeschungenAnalysiert,
+) -> Vec<Abt3HistorieEintrag> {
+    let mut historie = Vec::new();
+
+    for v in grundbuch.abt3.veraenderungen.iter() {
+        let betrifft_eintrag = parse_spalte_1_veraenderung(&v.lfd_nr.text())
+            .map(|nr| nr.contains(&lfd_nr))
+            .unwrap_or(false);
+        if !betrifft_eintrag {
+            continue;
         }
+        let text = v.text.text();
+        historie.push(Abt3HistorieEintrag {
+            loeschung: false,
+            geroetet: v.ist_geroetet(),
+            ereignis: abt3_ereignis_lesen(&v.betrag.text(), &text),
+            text,
+        });
+    }
 
-        GrundbuchAnalysiert {
-            titelblatt: grundbuch.titelblatt.clone(),
-            abt2: abt2_analysiert,
-            abt3: abt3_analysiert,
+    let mut teilloeschungen = Vec::new();
+    for l in loeschungen.teilloeschungen(3, lfd_nr) {
+        if let Some(betrag) = l.geloeschter_betrag {
+            teilloeschungen.push(Abt3HistorieEintrag {
+                loeschung: true,
+                geroetet: false,
+                text: l.text.clone(),
+                ereignis: Abt3Ereignis::Teilloeschung { betrag },
+            });
         }
     }
+
+    // Veränderungen und Löschungen stehen in getrennten Spalten, die Reihenfolge
+    // ergibt sich erst aus dem Datum der Eintragung. Einträge ohne Datum bleiben
+    // hinter dem vorherigen Eintrag derselben Spalte.
+    let mut historie = nach_datum(historie)
+        .into_iter()
+        .chain(nach_datum(teilloeschungen))
+        .collect::<Vec<_>>();
+    historie.sort_by_key(|(datum, _)| *datum);
+    historie.into_iter().map(|(_, h)| h).collect()
 }
 
-fn rayon_task_analyze_abt2(
-    eintrag: &Abt2Eintrag,
+fn nach_datum(
+    eintraege: Vec<Abt3HistorieEintrag>,
+) -> Vec<(Option<chrono::NaiveDate>, Abt3HistorieEintrag)> {
+    let mut letztes_datum = None;
+    eintraege
+        .into_iter()
+        .map(|h| {
+            if let Some(datum) = eintragungsdatum_lesen(&h.text) {
+                letztes_datum = Some(datum);
+            }
+            (letztes_datum, h)
+        })
+        .collect()
+}
+
+// Letztes Datum im Text, meist "eingetragen am 01.02.1995"
+fn eintragungsdatum_lesen(text: &str) -> Option<chrono::NaiveDate> {
+    use lazy_static::lazy_static;
+    use regex::Regex;
+
+    lazy_static! {
+        static ref REGEX_DATUM: Regex =
+            Regex::new(r"\b(\d{1,2})\.\s*(\d{1,2})\.\s*(\d{4})\b").unwrap();
+    }
+
+    REGEX_DATUM
+        .captures_iter(text)
+        .filter_map(|c| {
+            chrono::NaiveDate::from_ymd_opt(
+                c[3].parse().ok()?,
+                c[2].parse().ok()?,
+                c[1].parse().ok()?,
+            )
+        })
+        .last()
+}
+
+fn abt3_ereignis_lesen(betrag_spalte: &str, text: &str) -> Abt3Ereignis {
+    let text = text.lines().collect::<Vec<_>>().join(" ");
+    let text_lower = text.to_lowercase();
+    let betrag = betrag_lesen(betrag_spalte).or_else(|| betrag_lesen(&text));
+
+    let umgestellt = ["umgestellt", "umgerechnet"]
+        .iter()
+        .filter_map(|k| text_lower.find(k))
+        .min();
+    if let Some(pos) = umgestellt {
+        // Betrag nach "umgestellt auf", nicht der alte Betrag davor
+        if let Some(betrag) = text.get(pos..).and_then(betrag_lesen) {
+            return Abt3Ereignis::Umstellung { betrag };
+        }
+    }
+
+    if let Some(glaeubiger) = glaeubiger_lesen(&text) {
+        let teilweise = ["teilbetrag", "teilweise", "in höhe von", "rangteil"]
+            .iter()
+            .any(|k| text_lower.contains(k));
+        return Abt3Ereignis::Abtretung {
+            glaeubiger,
+            betrag: if teilweise || !betrag_spalte.trim().is_empty() {
+                betrag
+            } else {
+                None
+            },
+        };
+    }
+
+    if text_lower.contains("gelöscht") {
+        if let Some(betrag) = betrag {
+            return Abt3Ereignis::Teilloeschung { betrag };
+        }
+    }
+
+    if text_lower.contains("zins") {
+        return Abt3Ereignis::Zinsaenderung;
+    }
+
+    Abt3Ereignis::Sonstige
+}
+
+// "Abgetreten an die Sparkasse Musterstadt; eingetragen am ..."
+fn glaeubiger_lesen(text: &str) -> Option<String> {
+    use lazy_static::lazy_static;
+    use regex::Regex;
+
+    lazy_static! {
+        static ref REGEX_ABTRETUNG: Regex =
+            Regex::new(r"(?i)(?:abgetreten|übergegangen|umgeschrieben)\s+(?:an|auf)\s*:?\s*(.+)")
+                .unwrap();
+    }
+
+    const ENDE: &[&str] = &[
+        ";",
+        " eingetragen",
+        " Eingetragen",
+        " gemäß",
+        " Gemäß",
+        " unter Bezugnahme",
+        " mit den Zinsen",
+        " mit Zinsen",
+    ];
+
+    let rest = REGEX_ABTRETUNG.captures(text)?.get(1)?.as_str();
+    let ende = ENDE
+        .iter()
+        .filter_map(|e| rest.find(e))
+        .min()
+        .unwrap_or(rest.len());
+    let glaeubiger = rest[..ende]
+        .trim()
+        .trim_end_matches(|c| c == '.' || c == ',')
+        .trim();
+    let glaeubiger = ["die ", "den ", "der ", "das "]
+        .iter()
+        .find_map(|a| glaeubiger.strip_prefix(a))
+        .unwrap_or(glaeubiger)
+        .trim();
+
+    if glaeubiger.is_empty() {
+        None
+    } else {
+        Some(glaeubiger.to_string())
+    }
+}
+
+// "10.000 DM", "5.112,92 EUR", "3000,-- Mark"
+fn betrag_lesen(text: &str) -> Option<Betrag> {
+    use lazy_static::lazy_static;
+    use regex::Regex;
+
+    lazy_static! {
+        static ref REGEX_BETRAG: Regex = Regex::new(
+            r"(\d{1,3}(?:\.\d{3})+|\d+)(?:,(\d{1,2}|-+))?\s*(EUR\b|Euro\b|€|DM\b|Deutsche\s+Mark\b|RM\b|Reichsmark\b|GM\b|Goldmark\b|Rentenmark\b|Mark\b|M\b)"
+        )
+        .unwrap();
+    }
+
+    let c = REGEX_BETRAG.captures(text)?;
+    let wert = c.get(1)?.as_str().replace('.', "").parse::<usize>().ok()?;
+    let nachkomma = match c.get(2).map(|n| n.as_str()) {
+        Some(n) if n.starts_with('-') => 0,
+        Some(n) if n.len() == 1 => n.parse::<usize>().ok()? * 10,
+        Some(n) => n.parse::<usize>().ok()?,
+        None => 0,
+    };
+    let waehrung = match c.get(3)?.as_str() {
+        "EUR" | "Euro" | "€" => Waehrung::Euro,
+        "DM" => Waehrung::DMark,
+        "RM" | "Reichsmark" => Waehrung::Reichsmark,
+        "GM" | "Goldmark" => Waehrung::Goldmark,
+        "Rentenmark" => Waehrung::Rentenmark,
+        "Mark" | "M" => Waehrung::MarkDDR,
+        _ => Waehrung::DMark, // "Deutsche Mark"
+    };
+
+    Some(Betrag {
+        wert,
+        nachkomma,
+        waehrung,
+    })
+}
+
+fn betrag_in_cent(b: &Betrag) -> usize {
+    b.wert * 100 + b.nachkomma
+}
+
+// Wendet die Historie auf Betrag und Gläubiger laut Eintragung an
+fn abt3_historie_anwenden(
+    historie: &[Abt3HistorieEintrag],
+    betrag_eingetragen: Betrag,
+    rechtsinhaber_eingetragen: &str,
+    warnungen: &mut Vec<AnalyseWarnung>,
+    fehler: &mut Vec<AnalyseFehler>,
+) -> (Betrag, String) {
+    let mut betrag = betrag_eingetragen;
+    let mut rechtsinhaber = rechtsinhaber_eingetragen.to_string();
+
+    for h in historie.iter().filter(|h| !h.geroetet) {
+        match &h.ereignis {
+            Abt3Ereignis::Abtretung {
+                glaeubiger,
+                betrag: teilbetrag,
+            } => match teilbetrag {
+                Some(t)
+                    if t.waehrung == betrag.waehrung
+                        && betrag_in_cent(t) < betrag_in_cent(&betrag) =>
+                {
+                    warnungen.push(
+                        BefundCode::TeilabtretungUnklar {
+                            betrag: betrag_text(t),
+                            glaeubiger: glaeubiger.clone(),
+                        }
+                        .into(),
+                    );
+                }
+                _ => {
+                    rechtsinhaber = glaeubiger.clone();
+                }
+            },
+            Abt3Ereignis::Teilloeschung { betrag: geloescht } => {
+                let rest = betrag_in_cent(&betrag).checked_sub(betrag_in_cent(geloescht));
+                match rest {
+                    Some(rest) if geloescht.waehrung == betrag.waehrung => {
+                        betrag.wert = rest / 100;
+                        betrag.nachkomma = rest % 100;
+                    }
+                    _ => {
+                        fehler.push(
+                            BefundCode::TeilloeschungUnpassend {
+                                geloescht: betrag_text(geloescht),
+                                betrag: betrag_text(&betrag),
+                            }
+                            .into(),
+                        );
+                    }
+                }
+            }
+            Abt3Ereignis::Umstellung { betrag: neu } => {
+                betrag = *neu;
+            }
+            Abt3Ereignis::Zinsaenderung | Abt3Ereignis::Sonstige => {}
+        }
+    }
+
+    if rechtsinhaber != rechtsinhaber_eingetragen {
+        warnungen.push(
+            BefundCode::GlaeubigerNachAbtretung {
+                aktuell: rechtsinhaber.clone(),
+                eingetragen: rechtsinhaber_eingetragen.to_string(),
+            }
+            .into(),
+        );
+    }
+
+    if betrag != betrag_eingetragen {
+        warnungen.push(
+            BefundCode::BetragNachVeraenderungen {
+                aktuell: betrag_text(&betrag),
+                eingetragen: betrag_text(&betrag_eingetragen),
+            }
+            .into(),
+        );
+    }
+
+    (betrag, rechtsinhaber)
+}
+
+// Nicht gerötete Eigentümer in Abt. 1, ohne Skript wird Abt. 1 nicht analysiert
+fn abt1_eintraege<'a>(
+    grundbuch: &'a Grundbuch,
+    konfiguration: &Konfiguration,
+) -> impl Iterator<Item = &'a Abt1Eintrag> {
+    let ohne_skript = konfiguration
+        .eigentuemer_auslesen_abt1_script
+        .iter()
+        .all(|l| l.trim().is_empty());
+
+    grundbuch
+        .abt1
+        .eintraege
+        .iter()
+        .filter(move |e| !ohne_skript && !e.ist_geroetet())
+}
+
+fn rayon_task_analyze_abt1(
+    eintrag: &Abt1Eintrag,
     grundbuch: &Grundbuch,
     vm: &PyVm,
-    nb: &[Nebenbeteiligter],
     konfiguration: &Konfiguration,
-) -> Abt2Analysiert {
+) -> Abt1Analysiert {
     let mut warnungen = Vec::new();
     let mut fehler = Vec::new();
 
-    let mut eintrag_veraenderungen = Vec::new();
-    let mut eintrag = eintrag.clone();
-
-    for v in grundbuch.abt2.veraenderungen.iter() {
-        let spalte_1_nummern = match parse_spalte_1_veraenderung(&v.lfd_nr.text()) {
-            Ok(s) => s,
+    let text = eintrag.get_eigentuemer();
+    let eigentuemer =
+        match crate::python::eigentuemer_auslesen_abt1(vm.clone(), &text, konfiguration) {
+            Ok(o) => {
+                warnungen.extend(
+                    o.warnungen
+                        .into_iter()
+                        .map(|w| BefundCode::EigentuemerAuslesen(w).into()),
+                );
+                o.eintraege
+            }
             Err(e) => {
-                fehler.push(AnalyseFehler {
-                    text: format!(
-                        "Konnte Abt. 2 Veränderung nicht lesen: {}: {}",
-                        v.lfd_nr.text(),
-                        e
-                    ),
-                    traceback: None,
-                    py_script: None,
-                });
+                fehler.push(e);
                 Vec::new()
             }
         };
 
-        if spalte_1_nummern.contains(&eintrag.lfd_nr) {
-            eintrag_veraenderungen.push(v.text.clone());
+    if eigentuemer.is_empty() && fehler.is_empty() {
+        fehler.push(BefundCode::KeineEigentuemerErkannt.into());
+    }
+
+    let grundlagen = match grundlagen_zuordnen(eintrag, &grundbuch.abt1) {
+        Some(s) => s,
+        None => {
+            let alle = grundbuch
+                .abt1
+                .grundlagen_eintragungen
+                .iter()
+                .filter(|g| !g.ist_geroetet())
+                .map(Abt1GrundlageAnalysiert::from)
+                .collect::<Vec<_>>();
+            if alle.len() > 1 {
+                warnungen.push(BefundCode::GrundlageNichtZugeordnet.into());
+            }
+            alle
+        }
+    };
+
+    Abt1Analysiert {
+        lfd_nr: eintrag.get_lfd_nr(),
+        id: eintrag.get_id(),
+        eigentuemer,
+        grundlagen,
+        text_original: text,
+        warnungen,
+        fehler,
+        fertig_analysiert: true,
+    }
+}
+
+// V1-Einträge enthalten die Grundlage der Eintragung selbst. Bei V2 steht die
+// Grundlage in derselben Zeile wie der Eigentümer, die Zuordnung erfolgt daher
+// über die Position in der PDF. None, falls keine Position vorhanden ist
+fn grundlagen_zuordnen(
+    eintrag: &Abt1Eintrag,
+    abt1: &Abteilung1,
+) -> Option<Vec<Abt1GrundlageAnalysiert>> {
+    let position = match eintrag {
+        Abt1Eintrag::V1(v1) if !v1.grundlage_der_eintragung.text().trim().is_empty() => {
+            return Some(vec![Abt1GrundlageAnalysiert {
+                id: String::new(),
+                bv_nr: v1.bv_nr.text(),
+                text: v1.grundlage_der_eintragung.text(),
+            }]);
         }
+        Abt1Eintrag::V1(_) => return None,
+        Abt1Eintrag::V2(v2) => v2.position_in_pdf.as_ref()?,
+    };
+
+    let zeilen = |p: &digital::PositionInPdf| Some((p.rect.min_y?, p.rect.max_y?));
+    let (min_y, max_y) = zeilen(position)?;
+
+    let grundlagen = abt1
+        .grundlagen_eintragungen
+        .iter()
+        .filter(|g| !g.ist_geroetet())
+        .filter(|g| {
+            g.position_in_pdf
+                .as_ref()
+                .filter(|p| p.seite == position.seite)
+                .and_then(zeilen)
+                .map(|(g_min_y, g_max_y)| g_min_y <= max_y && min_y <= g_max_y)
+                .unwrap_or(false)
+        })
+        .map(Abt1GrundlageAnalysiert::from)
+        .collect::<Vec<_>>();
+
+    if grundlagen.is_empty() {
+        None
+    } else {
+        Some(grundlagen)
     }
+}
+
+fn rayon_task_analyze_abt2(
+    eintrag: &Abt2Eintrag,
+    grundbuch: &Grundbuch,
+    loeschungen: &LoeschungenAnalysiert,
+    vm: &PyVm,
+    nb: &[Nebenbeteiligter],
+    konfiguration: &Konfiguration,
+) -> Abt2Analysiert {
+    let mut warnungen = Vec::new();
+    let mut fehler = Vec::new();
+
+    let mut eintrag = eintrag.clone();
+    let eintrag_veraenderungen = veraenderungen_zuordnen(
+        2,
+        eintrag.lfd_nr,
+        grundbuch
+            .abt2
+            .veraenderungen
+            .iter()
+            .map(|v| (&v.lfd_nr, &v.text)),
+        &mut fehler,
+    );
 
     // Veränderungen Abt. 2 einfügen (speziell Rangvermerke)
     if !eintrag_veraenderungen.is_empty() {
         for v in eintrag_veraenderungen.iter() {
-            warnungen.push(format!("Veränderungsmitteilung beachten:<br/>{}", v.text()));
+            warnungen.push(BefundCode::VeraenderungBeachten(v.text()).into());
             if eintrag.text.contains("Rang") || eintrag.text.contains("Gleichrang") {
                 eintrag.text.push_str(" ");
                 eintrag.text.push_str(&v.text());
@@ -421,16 +3389,19 @@ fn rayon_task_analyze_abt2(
         konfiguration,
     );
     let mut lastend_an = Vec::new();
+    let mut fortfuehrungen = Vec::new();
     let mut debug_log = String::new();
     let belastete_flurstuecke = match get_belastete_flurstuecke(
         vm.clone(),
-        &eintrag.bv_nr.text(),
+        &eintrag.get_bv_nr_ungeroetet(),
         &kt.text_sauber,
         &grundbuch.titelblatt,
         &grundbuch.bestandsverzeichnis.eintraege,
+        &bv_fortfuehrungen_lesen(&grundbuch.bestandsverzeichnis),
         konfiguration,
         &mut debug_log,
         &mut lastend_an,
+        &mut fortfuehrungen,
         &mut warnungen,
         &mut fehler,
     ) {
@@ -441,6 +3412,21 @@ fn rayon_task_analyze_abt2(
         }
     };
 
+    let belastete_flurstuecke = teilloeschungen_anwenden(
+        2,
+        eintrag.lfd_nr,
+        belastete_flurstuecke,
+        loeschungen,
+        &mut warnungen,
+    );
+
+    let flurstueckskennzeichen = flurstueckskennzeichen_bilden(
+        &belastete_flurstuecke,
+        &grundbuch.titelblatt,
+        konfiguration,
+        &mut warnungen,
+    );
+
     let mut rechteart = kt
         .rechteart
         .clone()
@@ -493,7 +3479,7 @@ fn rayon_task_analyze_abt2(
         Some(s) => s.clone(),
         None => {
             if rechteart.benoetigt_rechteinhaber() {
-                warnungen.push(format!("Konnte keine Ordnungsnummer finden"));
+                warnungen.push(BefundCode::KeineOrdnungsnummer.into());
             }
             Nebenbeteiligter {
                 typ: None,
@@ -506,19 +3492,46 @@ fn rayon_task_analyze_abt2(
 
     if rangvermerk.is_some() {
         if !(kt.gekuerzt.contains("Rang") || kt.gekuerzt.contains("Gleichrang")) {
-            fehler.push(format!("Rangvermerk vorhanden, aber nicht in Kurztext vermerkt").into());
+            fehler.push(BefundCode::RangvermerkNichtImKurztext.into());
         }
     }
 
+    eintrag_pruefen(
+        vm,
+        EintragPruefen {
+            abteilung: 2,
+            text: kt.text_sauber.clone(),
+            kurztext: kt.gekuerzt.clone(),
+            rechteart: match rechteart {
+                RechteArt::SpeziellVormerkung { .. } => "SpeziellVormerkung".to_string(),
+                r => format!("{:?}", r),
+            },
+            schuldenart: String::new(),
+            betrag: None,
+            flurstuecke: flurstuecke_fuer_pruefung(&belastete_flurstuecke, &grundbuch.titelblatt),
+            rechtsinhaber: rechtsinhaber.clone(),
+            ordnungsnummer: nebenbeteiligter
+                .ordnungsnummer
+                .map(|o| o.to_string())
+                .unwrap_or_default(),
+        },
+        konfiguration,
+        &mut warnungen,
+        &mut fehler,
+    );
+
     Abt2Analysiert {
         lfd_nr: eintrag.lfd_nr,
+        id: eintrag.id.clone(),
         text_kurz: kt.gekuerzt,
         rechteart,
         rechtsinhaber,
         rangvermerk,
         spalte_2: eintrag.bv_nr.clone().text(),
         belastete_flurstuecke,
+        flurstueckskennzeichen,
         lastend_an,
+        fortfuehrungen,
         text_original: kt.text_sauber,
         nebenbeteiligter,
         warnungen,
@@ -531,6 +3544,7 @@ fn rayon_task_analyze_abt2(
 fn rayon_task_analyze_abt3(
     eintrag: &Abt3Eintrag,
     grundbuch: &Grundbuch,
+    loeschungen: &LoeschungenAnalysiert,
     vm: &PyVm,
     nb: &[Nebenbeteiligter],
     konfiguration: &Konfiguration,
@@ -538,41 +3552,30 @@ fn rayon_task_analyze_abt3(
     let mut warnungen = Vec::new();
     let mut fehler = Vec::new();
 
-    let mut eintrag_veraenderungen = Vec::new();
     let mut eintrag = eintrag.clone();
-
-    for v in grundbuch.abt3.veraenderungen.iter() {
-        let spalte_1_nummern = match parse_spalte_1_veraenderung(&v.lfd_nr.text()) {
-            Ok(s) => s,
-            Err(e) => {
-                fehler.push(AnalyseFehler {
-                    text: format!(
-                        "Konnte Abt. 3 Veränderung nicht lesen: {}: {}",
-                        v.lfd_nr.text(),
-                        e
-                    ),
-                    traceback: None,
-                    py_script: None,
-                });
-                Vec::new()
-            }
-        };
-
-        if spalte_1_nummern.contains(&eintrag.lfd_nr) {
-            eintrag_veraenderungen.push(v.text.clone());
-        }
-    }
+    let eintrag_veraenderungen = veraenderungen_zuordnen(
+        3,
+        eintrag.lfd_nr,
+        grundbuch
+            .abt3
+            .veraenderungen
+            .iter()
+            .map(|v| (&v.lfd_nr, &v.text)),
+        &mut fehler,
+    );
 
     // Veränderungen Abt. 2 einfügen (speziell Rangvermerke)
     if !eintrag_veraenderungen.is_empty() {
-        warnungen.push(format!(
-            "Veränderungsmittelungen Abt.3 beachten!: {}",
-            eintrag_veraenderungen
-                .iter()
-                .map(|q| q.text())
-                .collect::<Vec<_>>()
-                .join("\r\n")
-        ));
+        warnungen.push(
+            BefundCode::VeraenderungBeachten(
+                eintrag_veraenderungen
+                    .iter()
+                    .map(|q| q.text())
+                    .collect::<Vec<_>>()
+                    .join("\r\n"),
+            )
+            .into(),
+        );
         for v in eintrag_veraenderungen.iter() {
             if eintrag.text.contains("Rang")
                 || eintrag.text.contains("Gleichrang")
@@ -600,16 +3603,19 @@ fn rayon_task_analyze_abt3(
         konfiguration,
     );
     let mut lastend_an = Vec::new();
+    let mut fortfuehrungen = Vec::new();
     let mut debug_log = String::new();
     let belastete_flurstuecke = match get_belastete_flurstuecke(
         vm.clone(),
-        &eintrag.bv_nr.text(),
+        &eintrag.get_bv_nr_ungeroetet(),
         &kt.text_sauber,
         &grundbuch.titelblatt,
         &grundbuch.bestandsverzeichnis.eintraege,
+        &bv_fortfuehrungen_lesen(&grundbuch.bestandsverzeichnis),
         konfiguration,
         &mut debug_log,
         &mut lastend_an,
+        &mut fortfuehrungen,
         &mut warnungen,
         &mut fehler,
     ) {
@@ -620,14 +3626,39 @@ fn rayon_task_analyze_abt3(
         }
     };
 
-    let rechtsinhaber = kt.rechtsinhaber.clone().unwrap_or_default();
+    let belastete_flurstuecke = teilloeschungen_anwenden(
+        3,
+        eintrag.lfd_nr,
+        belastete_flurstuecke,
+        loeschungen,
+        &mut warnungen,
+    );
+
+    let flurstueckskennzeichen = flurstueckskennzeichen_bilden(
+        &belastete_flurstuecke,
+        &grundbuch.titelblatt,
+        konfiguration,
+        &mut warnungen,
+    );
+
+    let rechtsinhaber_eingetragen = kt.rechtsinhaber.clone().unwrap_or_default();
+
+    // Abtretungen, Teillöschungen und Umstellungen nachvollziehen
+    let historie = abt3_historie(grundbuch, eintrag.lfd_nr, loeschungen);
+    let (betrag, rechtsinhaber) = abt3_historie_anwenden(
+        &historie,
+        kt.betrag,
+        &rechtsinhaber_eingetragen,
+        &mut warnungen,
+        &mut fehler,
+    );
 
     let schuldenart = kt.schuldenart.clone().unwrap_or(SchuldenArt::Grundschuld);
 
     let nebenbeteiligter = match nb.iter().find(|n| n.name == rechtsinhaber) {
         Some(s) => s.clone(),
         None => {
-            warnungen.push(format!("Konnte keine Ordnungsnummer finden"));
+            warnungen.push(BefundCode::KeineOrdnungsnummer.into());
             Nebenbeteiligter {
                 typ: None,
                 ordnungsnummer: None,
@@ -637,15 +3668,42 @@ fn rayon_task_analyze_abt3(
         }
     };
 
+    eintrag_pruefen(
+        vm,
+        EintragPruefen {
+            abteilung: 3,
+            text: kt.text_sauber.clone(),
+            kurztext: kt.gekuerzt.clone(),
+            rechteart: String::new(),
+            schuldenart: format!("{:?}", schuldenart),
+            betrag: Some(betrag),
+            flurstuecke: flurstuecke_fuer_pruefung(&belastete_flurstuecke, &grundbuch.titelblatt),
+            rechtsinhaber: rechtsinhaber.clone(),
+            ordnungsnummer: nebenbeteiligter
+                .ordnungsnummer
+                .map(|o| o.to_string())
+                .unwrap_or_default(),
+        },
+        konfiguration,
+        &mut warnungen,
+        &mut fehler,
+    );
+
     Abt3Analysiert {
         lfd_nr: eintrag.lfd_nr,
+        id: eintrag.id.clone(),
         text_kurz: kt.gekuerzt,
         schuldenart,
         rechtsinhaber,
-        betrag: kt.betrag,
+        betrag,
+        betrag_eingetragen: kt.betrag,
+        rechtsinhaber_eingetragen,
+        historie,
         spalte_2: eintrag.bv_nr.clone().text(),
         belastete_flurstuecke,
+        flurstueckskennzeichen,
         lastend_an,
+        fortfuehrungen,
         text_original: kt.text_sauber,
         nebenbeteiligter,
         warnungen,
@@ -661,10 +3719,12 @@ pub fn get_belastete_flurstuecke(
     text_sauber: &str,
     titelblatt: &Titelblatt,
     bestandsverzeichnis: &[BvEintrag],
+    bv_fortfuehrungen: &[BvFortfuehrung],
     konfiguration: &Konfiguration,
     debug_log: &mut String,
     eintraege: &mut Vec<Spalte1Eintrag>,
-    warnungen: &mut Vec<String>,
+    fortfuehrungen: &mut Vec<BvFortfuehrung>,
+    warnungen: &mut Vec<AnalyseWarnung>,
     fehler: &mut Vec<AnalyseFehler>,
 ) -> Result<Vec<BvEintrag>, AnalyseFehler> {
     let spalte1_eintraege =
@@ -676,7 +3736,7 @@ pub fn get_belastete_flurstuecke(
             .warnungen
             .clone()
             .into_iter()
-            .map(Into::into),
+            .map(|w| BefundCode::FlurstueckeAuslesen(w).into()),
     );
 
     let grundbuch_von = titelblatt.grundbuch_von.clone();
@@ -741,6 +3801,15 @@ pub fn get_belastete_flurstuecke(
     let mut belastet_bv = Vec::<BvEintrag>::new();
     let mut global_filter = Vec::new();
 
+    // Gemarkung laut Gemarkungskatalog, damit abweichende Schreibweisen im
+    // Rechtstext und im BV zusammenpassen
+    let gemarkung_von = |gemarkung: Option<String>| {
+        alkis::gemarkung_normalisieren(
+            &konfiguration.gemarkungen,
+            &gemarkung.unwrap_or(grundbuch_von.clone()),
+        )
+    };
+
     let mut nur_lastend = BTreeMap::new();
     for s1 in spalte1_eintraege.eintraege.iter() {
         for nl in s1.nur_lastend_an.iter() {
@@ -748,7 +3817,7 @@ pub fn get_belastete_flurstuecke(
                 .entry(s1.lfd_nr)
                 .or_insert_with(|| Vec::new())
                 .push((
-                    nl.gemarkung.clone().unwrap_or(grundbuch_von.clone()),
+                    gemarkung_von(nl.gemarkung.clone()),
                     nl.flur,
                     nl.flurstueck.clone(),
                     nl.gemarkung.is_some(),
@@ -764,40 +3833,40 @@ pub fn get_belastete_flurstuecke(
             continue;
         }
 
-        let mut alle_bv_eintraege = bestandsverzeichnis
-            .iter()
-            .filter(|bv| bv.get_lfd_nr() == s1.lfd_nr)
-            .cloned()
-            .collect::<Vec<BvEintrag>>();
-
-        alle_bv_eintraege.retain(|bv| *bv != BvEintrag::neu(0));
-        belastet_bv.extend(alle_bv_eintraege.into_iter());
+        belastet_bv.extend(
+            bestandsverzeichnis
+                .iter()
+                .filter(|bv| bv.get_lfd_nr() == s1.lfd_nr)
+                .cloned(),
+        );
     }
 
-    for bv in belastet_bv.iter_mut() {
+    belastet_bv.retain(|bv| {
         if let Some(nur_filter) = nur_lastend.get(&bv.get_lfd_nr()) {
             let bv_flur = bv.get_flur();
             let bv_flurstueck = bv.get_flurstueck();
-            let bv_gemarkung = bv.get_gemarkung().unwrap_or(grundbuch_von.clone());
-            if !(nur_filter.iter().any(|i| {
+            let bv_gemarkung = gemarkung_von(bv.get_gemarkung());
+            nur_filter.iter().any(|i| {
                 if i.3 {
-                    i.0 == bv_gemarkung.clone() && i.1 == bv_flur && i.2 == bv_flurstueck.clone()
+                    i.0 == bv_gemarkung.clone()
+                        && i.1 == bv_flur
+                        && flurstueck_gleich(&i.2, &bv_flurstueck)
                 } else {
-                    i.1 == bv_flur && i.2 == bv_flurstueck.clone()
+                    i.1 == bv_flur && flurstueck_gleich(&i.2, &bv_flurstueck)
                 }
             }) || nur_filter.iter().any(|i| {
                 if i.3 {
-                    i.0 == bv_gemarkung.clone() && i.1 == 0 && i.2 == bv_flurstueck.clone()
+                    i.0 == bv_gemarkung.clone()
+                        && i.1 == 0
+                        && flurstueck_gleich(&i.2, &bv_flurstueck)
                 } else {
-                    i.1 == 0 && i.2 == bv_flurstueck.clone()
+                    i.1 == 0 && flurstueck_gleich(&i.2, &bv_flurstueck)
                 }
-            })) {
-                *bv = BvEintrag::neu(0); // remove
-            }
+            })
+        } else {
+            true
         }
-    }
-
-    belastet_bv.retain(|bv| *bv != BvEintrag::neu(0));
+    });
 
     log.push(format!("<strong>BV-Einträge (ungefiltert):</strong>"));
     debug_log.push_str(&format!("BV-Einträge (ungefiltert):\r\n"));
@@ -819,85 +3888,741 @@ pub fn get_belastete_flurstuecke(
         ));
     }
 
-    log.push(format!("<strong>&nbsp;&nbsp;Filter:</strong>"));
-    debug_log.push_str(&format!("Filter:\r\n"));
+    log.push(format!("<strong>&nbsp;&nbsp;Filter:</strong>"));
+    debug_log.push_str(&format!("Filter:\r\n"));
+
+    // Nur lastend an Flur X, Flurstück Y (-> keine BV-Nr.!)
+    let mut global_nur_lastend = Vec::new();
+    for s1 in global_filter.iter() {
+        debug_log.push_str(&format!("{:#?}\r\n", s1));
+        for nl in s1.nur_lastend_an.iter() {
+            global_nur_lastend.push((
+                gemarkung_von(nl.gemarkung.clone()),
+                nl.flur,
+                nl.flurstueck.clone(),
+                nl.gemarkung.is_some(),
+            ));
+        }
+    }
+
+    if !global_nur_lastend.is_empty() {
+        belastet_bv.retain(|bv| {
+            let bv_flur = bv.get_flur();
+            let bv_flurstueck = bv.get_flurstueck();
+            let bv_gemarkung = gemarkung_von(bv.get_gemarkung());
+            global_nur_lastend.iter().any(|i| {
+                if i.3 {
+                    i.0 == bv_gemarkung.clone()
+                        && i.1 == bv_flur
+                        && flurstueck_gleich(&i.2, &bv_flurstueck)
+                } else {
+                    i.1 == bv_flur && flurstueck_gleich(&i.2, &bv_flurstueck)
+                }
+            }) || global_nur_lastend.iter().any(|i| {
+                if i.3 {
+                    i.0 == bv_gemarkung.clone()
+                        && i.1 == 0
+                        && flurstueck_gleich(&i.2, &bv_flurstueck)
+                } else {
+                    i.1 == 0 && flurstueck_gleich(&i.2, &bv_flurstueck)
+                }
+            })
+        });
+    }
+
+    // Gerötete Flurstücke erst nach der Fortführung entfernen,
+    // sonst gehen geteilte / verschmolzene Flurstücke verloren
+
+    let belastet_bv = flurstuecke_fortfuehren(
+        &belastet_bv,
+        titelblatt,
+        bestandsverzeichnis,
+        bv_fortfuehrungen,
+        fortfuehrungen,
+        warnungen,
+        fehler,
+    );
+
+    let belastet_bv = belastet_bv
+        .into_iter()
+        .filter(|bv| !bv.ist_flurstueck_geroetet())
+        .collect::<Vec<BvEintrag>>();
+
+    if belastet_bv.is_empty() {
+        fehler.push(BefundCode::KeineFlurstueckeZugeordnet.into());
+    }
+
+    // deduplicate
+    let mut belastet_bv_map = BTreeMap::<String, BvEintrag>::new();
+    for bv in belastet_bv {
+        belastet_bv_map.insert(format!("{}", bv), bv);
+    }
+
+    let belastet_bv = belastet_bv_map
+        .into_iter()
+        .map(|(k, v)| v)
+        .collect::<Vec<_>>();
+
+    Ok(belastet_bv)
+}
+
+/// Liest die Rangvermerke aus Einträgen und Veränderungen in Abt. 2 / Abt. 3
+/// und bildet daraus die Rangfolge der Rechte je belastetem Flurstück
+pub fn rang_analysieren(
+    grundbuch: &Grundbuch,
+    loeschungen: &LoeschungenAnalysiert,
+    abt2: &[Abt2Analysiert],
+    abt3: &[Abt3Analysiert],
+) -> RangAnalysiert {
+    let mut warnungen = Vec::new();
+    let mut fehler = Vec::new();
+
+    let abt2_geloescht = loeschungen.vollstaendig_geloescht(2);
+    let abt3_geloescht = loeschungen.vollstaendig_geloescht(3);
+
+    let eingetragen = grundbuch
+        .abt2
+        .eintraege
+        .iter()
+        .map(|e| RechtRef {
+            abteilung: 2,
+            lfd_nr: e.lfd_nr,
+        })
+        .chain(grundbuch.abt3.eintraege.iter().map(|e| RechtRef {
+            abteilung: 3,
+            lfd_nr: e.lfd_nr,
+        }))
+        .collect::<BTreeSet<_>>();
+
+    let bestehend = grundbuch
+        .abt2
+        .eintraege
+        .iter()
+        .filter(|e| !e.ist_geroetet() && !abt2_geloescht.contains(&e.lfd_nr))
+        .map(|e| RechtRef {
+            abteilung: 2,
+            lfd_nr: e.lfd_nr,
+        })
+        .chain(
+            grundbuch
+                .abt3
+                .eintraege
+                .iter()
+                .filter(|e| !e.ist_geroetet() && !abt3_geloescht.contains(&e.lfd_nr))
+                .map(|e| RechtRef {
+                    abteilung: 3,
+                    lfd_nr: e.lfd_nr,
+                }),
+        )
+        .collect::<BTreeSet<_>>();
+
+    let mut verhaeltnisse = Vec::new();
+    for e in grundbuch.abt2.eintraege.iter() {
+        verhaeltnisse.extend(rangvermerke_lesen(&e.text.text(), 2, &[e.lfd_nr]));
+    }
+    for e in grundbuch.abt3.eintraege.iter() {
+        verhaeltnisse.extend(rangvermerke_lesen(&e.text.text(), 3, &[e.lfd_nr]));
+    }
+    for v in grundbuch
+        .abt2
+        .veraenderungen
+        .iter()
+        .filter(|v| !v.ist_geroetet())
+    {
+        if let Ok(nr) = parse_spalte_1_veraenderung(&v.lfd_nr.text()) {
+            verhaeltnisse.extend(rangvermerke_lesen(&v.text.text(), 2, &nr));
+        }
+    }
+    for v in grundbuch
+        .abt3
+        .veraenderungen
+        .iter()
+        .filter(|v| !v.ist_geroetet())
+    {
+        if let Ok(nr) = parse_spalte_1_veraenderung(&v.lfd_nr.text()) {
+            verhaeltnisse.extend(rangvermerke_lesen(&v.text.text(), 3, &nr));
+        }
+    }
+
+    // Nur Rangverhältnisse zwischen bestehenden Rechten
+    let mut gesehen = BTreeSet::new();
+    verhaeltnisse.retain(|r| {
+        if !eingetragen.contains(&r.zu) {
+            warnungen.push(
+                BefundCode::RangvermerkOhneRecht {
+                    recht: r.recht.to_string(),
+                    zu: r.zu.to_string(),
+                }
+                .into(),
+            );
+        }
+        bestehend.contains(&r.recht)
+            && bestehend.contains(&r.zu)
+            && gesehen.insert((r.recht, r.beziehung, r.zu))
+    });
+
+    // Widersprüche: Vor / Nach zwischen gleichrangigen Rechten oder Zyklen
+    let gruppen = ranggruppen_bilden(&bestehend, &verhaeltnisse);
+    let mut kanten = BTreeMap::<usize, BTreeSet<usize>>::new();
+    let mut widersprueche = Vec::new();
+    for r in verhaeltnisse.iter() {
+        let (g_recht, g_zu) = (gruppen[&r.recht], gruppen[&r.zu]);
+        let (von, nach) = match r.beziehung {
+            RangBeziehung::Gleich => continue,
+            RangBeziehung::Vor => (g_recht, g_zu),
+            RangBeziehung::Nach => (g_zu, g_recht),
+        };
+        if von == nach || rang_erreichbar(&kanten, nach, von) {
+            widersprueche.push(format!("{} {} {}", r.recht, r.beziehung.to_str(), r.zu));
+        } else {
+            kanten.entry(von).or_default().insert(nach);
+        }
+    }
+    if !widersprueche.is_empty() {
+        fehler.push(BefundCode::RangWiderspruch(widersprueche).into());
+    }
+
+    // Rangfolge je Flurstück
+    let mut flurstuecke = BTreeMap::<(usize, usize, String), BTreeSet<RechtRef>>::new();
+    let belastet = abt2
+        .iter()
+        .map(|a| (2, a.lfd_nr, &a.belastete_flurstuecke))
+        .chain(abt3.iter().map(|a| (3, a.lfd_nr, &a.belastete_flurstuecke)));
+    for (abteilung, lfd_nr, bv) in belastet {
+        for bv in bv.iter() {
+            flurstuecke
+                .entry((bv.get_lfd_nr(), bv.get_flur(), bv.get_flurstueck()))
+                .or_default()
+                .insert(RechtRef { abteilung, lfd_nr });
+        }
+    }
+
+    let rangfolgen = flurstuecke
+        .into_iter()
+        .map(|((bv_nr, flur, flurstueck), rechte)| {
+            let (rangstellen, eindeutig) = rangfolge_bilden(&rechte, &verhaeltnisse);
+            Rangfolge {
+                bv_nr,
+                flur,
+                flurstueck,
+                rangstellen,
+                eindeutig,
+            }
+        })
+        .collect();
+
+    warnungen.sort();
+    warnungen.dedup();
+
+    RangAnalysiert {
+        verhaeltnisse,
+        rangfolgen,
+        warnungen,
+        fehler,
+    }
+}
+
+// Gleichrangige Rechte zusammenfassen: Recht => Gruppe
+fn ranggruppen_bilden(
+    rechte: &BTreeSet<RechtRef>,
+    verhaeltnisse: &[Rangverhaeltnis],
+) -> BTreeMap<RechtRef, usize> {
+    let mut gruppen = rechte
+        .iter()
+        .enumerate()
+        .map(|(i, r)| (*r, i))
+        .collect::<BTreeMap<_, _>>();
+
+    for r in verhaeltnisse
+        .iter()
+        .filter(|r| r.beziehung == RangBeziehung::Gleich)
+    {
+        let (alt, neu) = match (gruppen.get(&r.zu), gruppen.get(&r.recht)) {
+            (Some(a), Some(b)) => (*a, *b),
+            _ => continue,
+        };
+        for g in gruppen.values_mut() {
+            if *g == alt {
+                *g = neu;
+            }
+        }
+    }
+
+    gruppen
+}
+
+fn rang_erreichbar(kanten: &BTreeMap<usize, BTreeSet<usize>>, von: usize, nach: usize) -> bool {
+    let mut offen = vec![von];
+    let mut besucht = BTreeSet::new();
+    while let Some(g) = offen.pop() {
+        if g == nach {
+            return true;
+        }
+        if besucht.insert(g) {
+            offen.extend(kanten.get(&g).into_iter().flatten().copied());
+        }
+    }
+    false
+}
+
+// Rangfolge der Rechte an einem Flurstück: zuerst die Rangvermerke, danach
+// innerhalb einer Abteilung die Reihenfolge der lfd. Nr. (§ 879 BGB)
+fn rangfolge_bilden(
+    rechte: &BTreeSet<RechtRef>,
+    verhaeltnisse: &[Rangverhaeltnis],
+) -> (Vec<Vec<RechtRef>>, bool) {
+    let verhaeltnisse = verhaeltnisse
+        .iter()
+        .filter(|r| rechte.contains(&r.recht) && rechte.contains(&r.zu))
+        .cloned()
+        .collect::<Vec<_>>();
+    let gruppen = ranggruppen_bilden(rechte, &verhaeltnisse);
+
+    let mut kanten = BTreeMap::<usize, BTreeSet<usize>>::new();
+    let mut kante_einfuegen = |von: usize, nach: usize| {
+        if von != nach && !rang_erreichbar(&kanten, nach, von) {
+            kanten.entry(von).or_default().insert(nach);
+        }
+    };
+
+    for r in verhaeltnisse.iter() {
+        let (g_recht, g_zu) = (gruppen[&r.recht], gruppen[&r.zu]);
+        match r.beziehung {
+            RangBeziehung::Gleich => {}
+            RangBeziehung::Vor => kante_einfuegen(g_recht, g_zu),
+            RangBeziehung::Nach => kante_einfuegen(g_zu, g_recht),
+        }
+    }
+
+    for a in rechte.iter() {
+        for b in rechte.iter() {
+            if a.abteilung == b.abteilung && a.lfd_nr < b.lfd_nr {
+                kante_einfuegen(gruppen[a], gruppen[b]);
+            }
+        }
+    }
+
+    let mut offen = gruppen.values().copied().collect::<BTreeSet<_>>();
+    let mut rangstellen = Vec::new();
+    let mut eindeutig = true;
+
+    while !offen.is_empty() {
+        let kandidaten = offen
+            .iter()
+            .copied()
+            .filter(|g| {
+                !offen
+                    .iter()
+                    .any(|v| kanten.get(v).map(|k| k.contains(g)).unwrap_or(false))
+            })
+            .collect::<Vec<_>>();
+
+        if kandidaten.len() > 1 {
+            eindeutig = false;
+        }
+
+        let naechste = match kandidaten
+            .iter()
+            .min_by_key(|g| gruppen.iter().find(|(_, v)| v == g).map(|(r, _)| *r))
+        {
+            Some(s) => *s,
+            None => break,
+        };
+
+        offen.remove(&naechste);
+        rangstellen.push(
+            gruppen
+                .iter()
+                .filter(|(_, g)| **g == naechste)
+                .map(|(r, _)| *r)
+                .collect(),
+        );
+    }
+
+    (rangstellen, eindeutig)
+}
+
+// "im Rang vor Abt. III Nr. 2", "Gleichrang mit II/4", "Rangrücktritt hinter Nr. 3"
+fn rangvermerke_lesen(text: &str, abteilung: usize, subjekte: &[usize]) -> Vec<Rangverhaeltnis> {
+    use lazy_static::lazy_static;
+    use regex::Regex;
+
+    lazy_static! {
+        static ref REGEX_RANG: Regex = Regex::new(
+            r"gleichrangig\s+mit|gleichrang\s+(?:mit|zu)|im\s+gleichrang|gleichem\s+rang\s+mit|rangrücktritt\s+hinter|zurückgetreten\s+hinter|rang\s+hinter|im\s+rang\s+nach|rang\s+nach|nachrang(?:ig)?\s+(?:zu|nach|gegenüber)|im\s+rang\s+vor|vorrang\s+vor|rang\s+vor"
+        )
+        .unwrap();
+    }
+
+    const FENSTER_ENDE: &[&str] = &[
+        ";",
+        "eingetragen",
+        "bewilligung",
+        "gemäß",
+        "unter bezugnahme",
+    ];
+
+    let quelle = text.lines().collect::<Vec<_>>().join(" ");
+    let text = quelle.to_lowercase();
+    let treffer = REGEX_RANG.find_iter(&text).collect::<Vec<_>>();
+
+    let mut ergebnis = Vec::new();
+    for (i, m) in treffer.iter().enumerate() {
+        let k = m.as_str();
+        let beziehung = if k.contains("gleich") {
+            RangBeziehung::Gleich
+        } else if k.contains("nach") || k.contains("hinter") {
+            RangBeziehung::Nach
+        } else {
+            RangBeziehung::Vor
+        };
+
+        let danach_ende = treffer.get(i + 1).map(|n| n.start()).unwrap_or(text.len());
+        let danach = &text[m.end()..danach_ende];
+        let danach = &danach[..FENSTER_ENDE
+            .iter()
+            .filter_map(|e| danach.find(e))
+            .min()
+            .unwrap_or(danach.len())];
+
+        let davor_start = if i == 0 { 0 } else { treffer[i - 1].end() };
+        let davor = &text[davor_start..m.start()];
+        let davor = &davor[davor.rfind(';').map(|p| p + 1).unwrap_or(0)..];
+
+        let mut zu = rechte_referenzen_lesen(danach, abteilung);
+        let mut beziehung = beziehung;
+        let mut recht_nr = subjekte.to_vec();
+
+        if zu.is_empty() {
+            // "Abt. III Nr. 3 ist der Vorrang vor diesem Recht eingeräumt"
+            let danach = danach.trim_start();
+            if danach.starts_with("diesem") || danach.starts_with("dem ") {
+                zu = rechte_referenzen_lesen(davor, abteilung);
+                beziehung = beziehung.umkehren();
+            }
+        } else if subjekte.len() > 1 {
+            // Veränderung zu mehreren lfd. Nr.: "Nr. 2 hat Vorrang vor Nr. 1"
+            let davor_nr = rechte_referenzen_lesen(davor, abteilung)
+                .into_iter()
+                .filter(|r| r.abteilung == abteilung && subjekte.contains(&r.lfd_nr))
+                .map(|r| r.lfd_nr)
+                .collect::<Vec<_>>();
+            if !davor_nr.is_empty() {
+                recht_nr = davor_nr;
+            }
+        }
+
+        for lfd_nr in recht_nr {
+            let recht = RechtRef { abteilung, lfd_nr };
+            for z in zu.iter().filter(|z| **z != recht) {
+                ergebnis.push(Rangverhaeltnis {
+                    recht,
+                    beziehung,
+                    zu: *z,
+                    quelle: quelle.clone(),
+                });
+            }
+        }
+    }
+
+    ergebnis
+}
+
+// "abt. iii nr. 2", "iii/2", ohne Abteilung: "nr. 3" (gleiche Abteilung)
+fn rechte_referenzen_lesen(text: &str, abteilung: usize) -> Vec<RechtRef> {
+    use lazy_static::lazy_static;
+    use regex::Regex;
+
+    lazy_static! {
+        static ref REGEX_ABT: Regex = Regex::new(
+            r"(?:abt\.?|abteilung)\s*(iii|ii|3|2)\b\.?\s*,?\s*(?:lfd\.\s*)?(?:nr\.?|nummer)\s*(\d+(?:\s*(?:,|und|bis|-)\s*\d+)*)"
+        )
+        .unwrap();
+        static ref REGEX_KURZ: Regex = Regex::new(r"\b(iii|ii)\s*/\s*(\d+)").unwrap();
+        static ref REGEX_NR: Regex =
+            Regex::new(r"(?:lfd\.\s*)?(?:nr\.?|nummer)\s*(\d+(?:\s*(?:,|und|bis|-)\s*\d+)*)")
+                .unwrap();
+    }
+
+    let abteilung_lesen = |a: &str| match a {
+        "ii" | "2" => 2,
+        _ => 3,
+    };
+
+    let mut refs = Vec::new();
+    for c in REGEX_ABT
+        .captures_iter(text)
+        .chain(REGEX_KURZ.captures_iter(text))
+    {
+        let (a, nr) = match (c.get(1), c.get(2)) {
+            (Some(a), Some(nr)) => (abteilung_lesen(a.as_str()), nr.as_str()),
+            _ => continue,
+        };
+        for lfd_nr in parse_spalte_1_veraenderung(nr).unwrap_or_default() {
+            refs.push(RechtRef {
+                abteilung: a,
+                lfd_nr,
+            });
+        }
+    }
+
+    if refs.is_empty() {
+        for c in REGEX_NR.captures_iter(text) {
+            let nr = c.get(1).map(|n| n.as_str()).unwrap_or_default();
+            for lfd_nr in parse_spalte_1_veraenderung(nr).unwrap_or_default() {
+                refs.push(RechtRef { abteilung, lfd_nr });
+            }
+        }
+    }
+
+    refs
+}
+
+/// Liest Teilungen, Verschmelzungen und Übertragungen aus den Zu- und
+/// Abschreibungen des Bestandsverzeichnisses
+pub fn bv_fortfuehrungen_lesen(bv: &Bestandsverzeichnis) -> Vec<BvFortfuehrung> {
+    let mut fortfuehrungen = Vec::new();
+
+    for z in bv.zuschreibungen.iter().filter(|z| !z.ist_geroetet()) {
+        fortfuehrungen.extend(bv_fortfuehrung_lesen(&z.bv_nr.text(), &z.text.text(), true));
+    }
+
+    for a in bv.abschreibungen.iter().filter(|a| !a.ist_geroetet()) {
+        fortfuehrungen.extend(bv_fortfuehrung_lesen(
+            &a.bv_nr.text(),
+            &a.text.text(),
+            false,
+        ));
+    }
+
+    fortfuehrungen
+}
+
+fn bv_fortfuehrung_lesen(bv_nr: &str, text: &str, zuschreibung: bool) -> Option<BvFortfuehrung> {
+    use lazy_static::lazy_static;
+    use regex::Regex;
+
+    lazy_static! {
+        static ref REGEX_BLATT: Regex = Regex::new(r"Blatt\s+(\d+[a-zA-Z]?)").unwrap();
+    }
+
+    const VERSCHMELZUNG: &[&str] = &[
+        "verschmolzen",
+        "vereinigt",
+        "zusammengefasst",
+        "als bestandteil zugeschrieben",
+    ];
+    const TEILUNG: &[&str] = &[
+        "zerlegt",
+        "geteilt",
+        "verselbständigt",
+        "verselbstständigt",
+        "abgetrennt",
+    ];
+    const FORTFUEHRUNG: &[&str] = &[
+        "fortgeführt",
+        "als lfd. nr",
+        "als nr",
+        "neu vorgetragen",
+        "umgeschrieben",
+    ];
+
+    let text = text.lines().collect::<Vec<_>>().join(" ");
+    let text_lower = text.to_lowercase();
+    let spalte_1 = parse_spalte_1_veraenderung(bv_nr).unwrap_or_default();
+    let nummern = bv_nummern_lesen(&text);
+
+    let blatt = REGEX_BLATT
+        .captures(&text)
+        .and_then(|c| c.get(1))
+        .map(|b| b.as_str().to_string());
+
+    if let Some(blatt) = blatt {
+        if text_lower.contains("übertragen") || text_lower.contains("aus blatt") {
+            let im_text = nummern
+                .iter()
+                .flat_map(|(_, n)| n.iter().copied())
+                .collect::<Vec<_>>();
+            let nr = if spalte_1.is_empty() {
+                im_text
+            } else {
+                spalte_1
+            };
+            if nr.is_empty() {
+                return None;
+            }
+            return Some(if zuschreibung {
+                BvFortfuehrung {
+                    art: BvFortfuehrungArt::UebertragenAus { blatt },
+                    von: Vec::new(),
+                    nach: nr,
+                    text,
+                }
+            } else {
+                BvFortfuehrung {
+                    art: BvFortfuehrungArt::UebertragenNach { blatt },
+                    von: nr,
+                    nach: Vec::new(),
+                    text,
+                }
+            });
+        }
+    }
+
+    let (art, pos) = [
+        (BvFortfuehrungArt::Verschmelzung, VERSCHMELZUNG),
+        (BvFortfuehrungArt::Teilung, TEILUNG),
+        (BvFortfuehrungArt::Fortfuehrung, FORTFUEHRUNG),
+    ]
+    .into_iter()
+    .find_map(|(art, woerter)| {
+        woerter
+            .iter()
+            .filter_map(|w| text_lower.find(w))
+            .min()
+            .map(|pos| (art, pos))
+    })?;
+
+    // Nummern vor dem Verb: alte BV-Nr., danach: neue BV-Nr.
+    let mut von = nummern
+        .iter()
+        .filter(|(p, _)| *p < pos)
+        .flat_map(|(_, n)| n.iter().copied())
+        .collect::<Vec<_>>();
+    let mut nach = nummern
+        .iter()
+        .filter(|(p, _)| *p >= pos)
+        .flat_map(|(_, n)| n.iter().copied())
+        .collect::<Vec<_>>();
+
+    if nach.is_empty() && zuschreibung {
+        nach = spalte_1.into_iter().filter(|n| !von.contains(n)).collect();
+    }
+
+    // "Nr. 5 dem Grundstück Nr. 3 als Bestandteil zugeschrieben"
+    if nach.is_empty() && art == BvFortfuehrungArt::Verschmelzung && von.len() > 1 {
+        nach = von.pop().into_iter().collect();
+    }
+
+    if von.is_empty() || nach.is_empty() {
+        return None;
+    }
+
+    Some(BvFortfuehrung {
+        art,
+        von,
+        nach,
+        text,
+    })
+}
+
+// Flurstücke automatisch so weit wie möglich automatisch fortführen:
+// zuerst entlang der Zu- und Abschreibungen, für Flurstücke ohne
+// passende Zu- / Abschreibung anhand gleicher Flur / Flurstücksnummer
+fn flurstuecke_fortfuehren(
+    bv_eintraege: &[BvEintrag],
+    titelblatt: &Titelblatt,
+    bestandsverzeichnis: &[BvEintrag],
+    bv_fortfuehrungen: &[BvFortfuehrung],
+    fortfuehrungen: &mut Vec<BvFortfuehrung>,
+    warnungen: &mut Vec<AnalyseWarnung>,
+    fehler: &mut Vec<AnalyseFehler>,
+) -> Vec<BvEintrag> {
+    let mut bv_belastet = Vec::new();
+    let mut ohne_fortfuehrung = Vec::new();
+    let mut offen = bv_eintraege.to_vec();
+    let mut gefolgt = BTreeSet::new();
+
+    while let Some(bv) = offen.pop() {
+        let lfd_nr = bv.get_lfd_nr();
+        let naechste = bv_fortfuehrungen
+            .iter()
+            .filter(|f| f.von.contains(&lfd_nr))
+            .collect::<Vec<_>>();
+
+        if naechste.is_empty() {
+            ohne_fortfuehrung.push(bv);
+            continue;
+        }
+
+        // Nicht gerötet = besteht (z.B. nach Abtrennung einer Teilfläche) weiter
+        bv_belastet.push(bv);
 
-    // Nur lastend an Flur X, Flurstück Y (-> keine BV-Nr.!)
-    let mut global_nur_lastend = Vec::new();
-    for s1 in global_filter.iter() {
-        debug_log.push_str(&format!("{:#?}\r\n", s1));
-        for nl in s1.nur_lastend_an.iter() {
-            global_nur_lastend.push((
-                nl.gemarkung.clone().unwrap_or(grundbuch_von.clone()),
-                nl.flur,
-                nl.flurstueck.clone(),
-                nl.gemarkung.is_some(),
-            ));
+        if !gefolgt.insert(lfd_nr) {
+            continue;
         }
-    }
 
-    if !global_nur_lastend.is_empty() {
-        for bv in belastet_bv.iter_mut() {
-            let bv_flur = bv.get_flur();
-            let bv_flurstueck = bv.get_flurstueck();
-            let bv_gemarkung = bv.get_gemarkung().unwrap_or(grundbuch_von.clone());
-            if !(global_nur_lastend.iter().any(|i| {
-                if i.3 {
-                    i.0 == bv_gemarkung.clone() && i.1 == bv_flur && i.2 == bv_flurstueck.clone()
-                } else {
-                    i.1 == bv_flur && i.2 == bv_flurstueck.clone()
-                }
-            }) || global_nur_lastend.iter().any(|i| {
-                if i.3 {
-                    i.0 == bv_gemarkung.clone() && i.1 == 0 && i.2 == bv_flurstueck.clone()
-                } else {
-                    i.1 == 0 && i.2 == bv_flurstueck.clone()
-                }
-            })) {
-                *bv = BvEintrag::neu(0); // remove
+        for f in naechste {
+            if !fortfuehrungen.contains(f) {
+                fortfuehrungen.push(f.clone());
             }
-        }
-    }
 
-    belastet_bv.retain(|bv| *bv != BvEintrag::neu(0) && !bv.ist_geroetet());
+            if let BvFortfuehrungArt::UebertragenNach { blatt } = &f.art {
+                warnungen.push(
+                    BefundCode::UebertragenNachBlatt {
+                        bv_nr: lfd_nr,
+                        blatt: blatt.clone(),
+                    }
+                    .into(),
+                );
+                continue;
+            }
 
-    if belastet_bv.is_empty() {
-        fehler.push(format!("Konnte keine Flurstücke zuordnen!").into());
+            let nach = bestandsverzeichnis
+                .iter()
+                .filter(|b| f.nach.contains(&b.get_lfd_nr()) && b.get_lfd_nr() != lfd_nr)
+                .cloned()
+                .collect::<Vec<_>>();
+
+            if nach.is_empty() {
+                fehler.push(
+                    BefundCode::FortfuehrungZielFehlt {
+                        beschreibung: f.beschreibung(),
+                        nach: f.nach.clone(),
+                    }
+                    .into(),
+                );
+                continue;
+            }
+
+            warnungen.push(
+                BefundCode::BelastungFortgefuehrt {
+                    bv_nr: lfd_nr,
+                    beschreibung: f.beschreibung(),
+                }
+                .into(),
+            );
+
+            if f.art == BvFortfuehrungArt::Verschmelzung {
+                warnungen.push(
+                    BefundCode::Verschmelzung {
+                        bv_nr: lfd_nr,
+                        nach: f.nach.clone(),
+                    }
+                    .into(),
+                );
+            }
+
+            offen.extend(nach);
+        }
     }
 
-    let belastet_bv = flurstuecke_fortfuehren(
-        &belastet_bv,
+    bv_belastet.extend(flurstuecke_nach_flurstueck_fortfuehren(
+        &ohne_fortfuehrung,
         titelblatt,
         bestandsverzeichnis,
         warnungen,
         fehler,
-    );
-
-    let belastet_bv = belastet_bv
-        .into_iter()
-        .filter(|bv| !bv.ist_geroetet())
-        .collect::<Vec<BvEintrag>>();
-
-    // deduplicate
-    let mut belastet_bv_map = BTreeMap::<String, BvEintrag>::new();
-    for bv in belastet_bv {
-        belastet_bv_map.insert(format!("{}", bv), bv);
-    }
-
-    let belastet_bv = belastet_bv_map
-        .into_iter()
-        .map(|(k, v)| v)
-        .collect::<Vec<_>>();
+    ));
 
-    Ok(belastet_bv)
+    bv_belastet
 }
 
-// Flurstücke automatisch so weit wie möglich automatisch fortführen
-fn flurstuecke_fortfuehren(
+fn flurstuecke_nach_flurstueck_fortfuehren(
     bv_eintraege: &[BvEintrag],
     titelblatt: &Titelblatt,
     bestandsverzeichnis: &[BvEintrag],
-    warnungen: &mut Vec<String>,
+    warnungen: &mut Vec<AnalyseWarnung>,
     fehler: &mut Vec<AnalyseFehler>,
 ) -> Vec<BvEintrag> {
     let mut bv_belastet = bv_eintraege.to_vec();
@@ -911,14 +4636,14 @@ fn flurstuecke_fortfuehren(
             let mut fortgeführt_als = bestandsverzeichnis
                 .iter()
                 .filter(|b| {
-                    !b.ist_geroetet()
+                    !b.ist_flurstueck_geroetet()
                         && b.get_gemarkung()
                             .unwrap_or(titelblatt.grundbuch_von.clone())
                             == bv
                                 .get_gemarkung()
                                 .unwrap_or(titelblatt.grundbuch_von.clone())
                         && b.get_flur() == bv.get_flur()
-                        && b.get_flurstueck() == bv.get_flurstueck()
+                        && flurstueck_gleich(&b.get_flurstueck(), &bv.get_flurstueck())
                         && b.get_lfd_nr() > bv.get_lfd_nr()
                 })
                 .cloned()
@@ -931,42 +4656,41 @@ fn flurstuecke_fortfuehren(
                 continue;
             }
 
-            // BV-Änderungen benutzen:
-
-            // "Flurstück X ist zerlegt in die Flurstücke Y und Z"
-            // => bv_zerlegt_belastet.insert();
-
-            // "Nummer X als Y eingetragen"
-
-            // "Aus lfd. Nr. X ein Flurstück verselbstständigt und als lfd. Nr. Y eingetragen"
-            // gucken, ob irgendein Fl. / Flst. doppelt eingetragen ist unter einer höheren lfd. Nr.
-            // wenn ja, höhere Nr. nehmen
-
-            // Versuche, automatisch nach Flur / Flurstücksnummern zu matchen
+            // Zerlegungen, Verschmelzungen und "Nr. X als Nr. Y eingetragen" werden
+            // vorher über die Zu- und Abschreibungen aufgelöst (bv_fortfuehrungen_lesen),
+            // hier nur noch nach gleicher Flur / Flurstücksnummer zuordnen
             // let mut nicht_fortgeführt = Vec::new();
 
             if fortgeführt_als.len() == 1 {
-                warnungen.push(format!(
-                    "Flur {} Flst. {} wird automatisch fortgeführt von BV-Nr. {} auf BV-Nr. {}",
-                    fortgeführt_als[0].get_flur(),
-                    fortgeführt_als[0].get_flurstueck(),
-                    bv.get_lfd_nr(),
-                    fortgeführt_als[0].get_lfd_nr(),
-                ));
+                warnungen.push(
+                    BefundCode::AutomatischFortgefuehrt {
+                        flur: fortgeführt_als[0].get_flur(),
+                        flurstueck: fortgeführt_als[0].get_flurstueck(),
+                        von: bv.get_lfd_nr(),
+                        nach: fortgeführt_als[0].get_lfd_nr(),
+                    }
+                    .into(),
+                );
                 alle_fortgefuehrt = false; // nochmal prüfen
                 bv_belastet[bv_idx] = fortgeführt_als[0].clone(); // Fortführung ausführen
             } else if fortgeführt_als.len() == 2 {
                 // TODO: Zerlegung?
                 // if bv_analyse.contains(geteilt == )
-                fehler.push(format!("BV-Nr. {} wurde fortgeführt, kann aber nicht eindeutig zugeordnet werden (Zerlegung?): Fortgeführt als eins von {:?}",
-                    bv.get_lfd_nr(),
-                    fortgeführt_als.iter().map(|l| l.get_lfd_nr()).collect::<Vec<_>>(),
-                ).into());
+                fehler.push(
+                    BefundCode::FortfuehrungNichtEindeutig {
+                        bv_nr: bv.get_lfd_nr(),
+                        kandidaten: fortgeführt_als.iter().map(|l| l.get_lfd_nr()).collect(),
+                    }
+                    .into(),
+                );
             } else {
-                fehler.push(format!("BV-Nr. {} wurde fortgeführt, kann aber nicht eindeutig zugeordnet werden (Zerlegung?): Fortgeführt als eins von {:?}",
-                    bv.get_lfd_nr(),
-                    fortgeführt_als.iter().map(|l| l.get_lfd_nr()).collect::<Vec<_>>(),
-                ).into());
+                fehler.push(
+                    BefundCode::FortfuehrungNichtEindeutig {
+                        bv_nr: bv.get_lfd_nr(),
+                        kandidaten: fortgeführt_als.iter().map(|l| l.get_lfd_nr()).collect(),
+                    }
+                    .into(),
+                );
             }
         }
 
@@ -976,6 +4700,800 @@ fn flurstuecke_fortfuehren(
     bv_belastet
 }
 
-fn parse_spalte_1_veraenderung(spalte_1: &str) -> Result<Vec<usize>, String> {
-    Ok(Vec::new()) // TODO
+// Größere Bereiche ("1-1000") sind meist Lesefehler und werden nicht aufgezählt
+const MAX_NUMMERN_BEREICH: usize = 100;
+
+/// Liest die laufenden Nummern aus der Spalte 1 einer Veränderung oder Löschung
+/// in Abt. 2 / Abt. 3, z.B. "1", "2, 3", "4-6", "zu 7" oder "lfd. Nr. 3 a".
+///
+/// Buchstaben-Zusätze ("3 a") werden der Hauptnummer zugeordnet, das Ergebnis
+/// ist aufsteigend sortiert und enthält keine doppelten Nummern.
+pub(crate) fn parse_spalte_1_veraenderung(spalte_1: &str) -> Result<Vec<usize>, String> {
+    let mut nummern = BTreeSet::new();
+
+    let normalisiert = spalte_1
+        .replace('\n', ",")
+        .replace(';', ",")
+        .replace(" und ", ",")
+        .replace(" u. ", ",")
+        .replace('–', "-")
+        .replace(" bis ", "-");
+
+    for teil in normalisiert.split(',') {
+        let teil = spalte_1_praefix_entfernen(teil);
+        if teil.is_empty() {
+            continue;
+        }
+
+        match teil.split_once('-') {
+            Some((von, bis)) => {
+                let von = parse_spalte_1_nummer(von)?;
+                let bis = parse_spalte_1_nummer(spalte_1_praefix_entfernen(bis))?;
+                if von > bis {
+                    return Err(format!("Ungültiger Bereich \"{}\"", teil));
+                }
+                if bis - von > MAX_NUMMERN_BEREICH {
+                    return Err(format!("Unplausibler Bereich \"{}\"", teil));
+                }
+                nummern.extend(von..=bis);
+            }
+            None => {
+                nummern.insert(parse_spalte_1_nummer(teil)?);
+            }
+        }
+    }
+
+    Ok(nummern.into_iter().collect())
+}
+
+/// Liest alle Nummern aus einem freien Text wie "1, 2", "3-5", "4 bis 6" oder
+/// "Teil von 7". Bereiche wie bei `parse_spalte_1_veraenderung`, von
+/// unlesbaren Bereichen ("6-4", "1-1000") zählt nur die erste Nummer.
+pub(crate) fn nummern_lesen(text: &str) -> BTreeSet<usize> {
+    use lazy_static::lazy_static;
+    use regex::Regex;
+
+    lazy_static! {
+        static ref REGEX_NUMMERN: Regex = Regex::new(r"\d+(?:\s*(?:-|–|bis)\s*\d+)?").unwrap();
+    }
+
+    let mut nummern = BTreeSet::new();
+    for m in REGEX_NUMMERN.find_iter(text) {
+        match parse_spalte_1_veraenderung(m.as_str()) {
+            Ok(o) => nummern.extend(o),
+            Err(_) => {
+                let von = m
+                    .as_str()
+                    .split(|c: char| !c.is_ascii_digit())
+                    .next()
+                    .and_then(|s| s.parse::<usize>().ok());
+                nummern.extend(von);
+            }
+        }
+    }
+    nummern
+}
+
+// Veränderungen, die den Eintrag lfd_nr betreffen. Ist Spalte 1 nicht lesbar,
+// wird der Fehler nur bei den Einträgen gemeldet, deren Nummer darin vorkommt
+fn veraenderungen_zuordnen<'a>(
+    abteilung: usize,
+    lfd_nr: usize,
+    veraenderungen: impl Iterator<Item = (&'a StringOrLines, &'a StringOrLines)>,
+    fehler: &mut Vec<AnalyseFehler>,
+) -> Vec<StringOrLines> {
+    let mut eintrag_veraenderungen = Vec::new();
+
+    for (spalte_1, text) in veraenderungen {
+        let betrifft_eintrag = match parse_spalte_1_veraenderung(&spalte_1.text()) {
+            Ok(s) => s.contains(&lfd_nr),
+            Err(e) => {
+                let betrifft_eintrag = nummern_lesen(&spalte_1.text()).contains(&lfd_nr);
+                if betrifft_eintrag {
+                    fehler.push(
+                        BefundCode::VeraenderungNichtLesbar {
+                            abteilung,
+                            lfd_nr: spalte_1.text(),
+                            grund: e,
+                        }
+                        .into(),
+                    );
+                }
+                betrifft_eintrag
+            }
+        };
+
+        if betrifft_eintrag {
+            eintrag_veraenderungen.push(text.clone());
+        }
+    }
+
+    eintrag_veraenderungen
+}
+
+fn spalte_1_praefix_entfernen(teil: &str) -> &str {
+    const PRAEFIXE: &[&str] = &["zu", "lfd.", "lfd", "nr.", "nr"];
+
+    let mut teil = teil.trim();
+    'aussen: loop {
+        for p in PRAEFIXE {
+            let passt = teil
+                .get(..p.len())
+                .map(|s| s.eq_ignore_ascii_case(p))
+                .unwrap_or(false);
+            let rest = &teil[if passt { p.len() } else { 0 }..];
+            // "zu7" ist erlaubt, "nrx" nicht
+            if passt && (p.ends_with('.') || !rest.starts_with(|c: char| c.is_alphabetic())) {
+                teil = rest.trim_start();
+                continue 'aussen;
+            }
+        }
+        return teil.trim();
+    }
+}
+
+fn parse_spalte_1_nummer(nummer: &str) -> Result<usize, String> {
+    let nummer = nummer.trim();
+    let ziffern_ende = nummer
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(nummer.len());
+    let (ziffern, zusatz) = nummer.split_at(ziffern_ende);
+
+    // Buchstaben-Zusatz wie "3 a" oder "3a)" gehört zur Nummer 3
+    let zusatz = zusatz.trim().trim_end_matches(')');
+    let zusatz_ok = zusatz.is_empty()
+        || (zusatz.chars().count() == 1 && zusatz.chars().all(|c| c.is_alphabetic()));
+
+    if ziffern.is_empty() || !zusatz_ok {
+        return Err(format!("Ungültige laufende Nummer \"{}\"", nummer));
+    }
+
+    ziffern
+        .parse::<usize>()
+        .map_err(|e| format!("Ungültige laufende Nummer \"{}\": {}", nummer, e))
+}
+
+#[test]
+fn test_parse_spalte_1_veraenderung() {
+    assert_eq!(parse_spalte_1_veraenderung("1"), Ok(vec![1]));
+    assert_eq!(parse_spalte_1_veraenderung("2, 3"), Ok(vec![2, 3]));
+    assert_eq!(parse_spalte_1_veraenderung("4-6"), Ok(vec![4, 5, 6]));
+    assert_eq!(parse_spalte_1_veraenderung("4 bis 6"), Ok(vec![4, 5, 6]));
+    assert_eq!(parse_spalte_1_veraenderung("zu 7"), Ok(vec![7]));
+    assert_eq!(parse_spalte_1_veraenderung("lfd. Nr. 3 a"), Ok(vec![3]));
+    assert_eq!(
+        parse_spalte_1_veraenderung("zu Nr. 1 und 3,\n5 - 6"),
+        Ok(vec![1, 3, 5, 6])
+    );
+    assert_eq!(parse_spalte_1_veraenderung(""), Ok(Vec::new()));
+    assert!(parse_spalte_1_veraenderung("6-4").is_err());
+    assert!(parse_spalte_1_veraenderung("Nr. x").is_err());
+    assert!(parse_spalte_1_veraenderung("3 abc").is_err());
+    assert!(parse_spalte_1_veraenderung("1-1000").is_err());
+    assert_eq!(parse_spalte_1_veraenderung("1-101").unwrap().len(), 101);
+}
+
+#[test]
+fn test_nummern_lesen() {
+    let nummern = |text: &str| nummern_lesen(text).into_iter().collect::<Vec<_>>();
+    assert_eq!(nummern("1, 2"), vec![1, 2]);
+    assert_eq!(nummern("3-5"), vec![3, 4, 5]);
+    assert_eq!(nummern("4 bis 6"), vec![4, 5, 6]);
+    assert_eq!(nummern("Teil von 7"), vec![7]);
+    assert_eq!(nummern("1-100000"), vec![1]);
+    assert_eq!(nummern("6-4"), vec![6]);
+}
+
+#[test]
+fn test_veraenderungen_zuordnen() {
+    let veraenderungen = vec![
+        (
+            StringOrLines::from("1, 2".to_string()),
+            StringOrLines::from("Rang".to_string()),
+        ),
+        (
+            StringOrLines::from("2, 5-1".to_string()),
+            StringOrLines::from("Inhalt".to_string()),
+        ),
+    ];
+    let zuordnen = |lfd_nr: usize| {
+        let mut fehler = Vec::new();
+        let v = veraenderungen_zuordnen(
+            2,
+            lfd_nr,
+            veraenderungen.iter().map(|(a, b)| (a, b)),
+            &mut fehler,
+        );
+        (v.iter().map(|t| t.text()).collect::<Vec<_>>(), fehler.len())
+    };
+
+    // der unlesbare Bereich "5-1" betrifft nur Nr. 2 und 5
+    assert_eq!(zuordnen(1), (vec!["Rang".to_string()], 0));
+    assert_eq!(
+        zuordnen(2),
+        (vec!["Rang".to_string(), "Inhalt".to_string()], 1)
+    );
+    assert_eq!(zuordnen(3), (Vec::new(), 0));
+    assert_eq!(zuordnen(5), (vec!["Inhalt".to_string()], 1));
+}
+
+#[test]
+fn test_erbbaurechte_abgleichen() {
+    use crate::digital::BvEintragFlurstueck;
+
+    let mut grundstueck_blatt = Grundbuch::new(Titelblatt {
+        amtsgericht: "Prenzlau".to_string(),
+        grundbuch_von: "Ludwigsburg".to_string(),
+        blatt: "254".to_string(),
+    });
+    grundstueck_blatt.bestandsverzeichnis.eintraege =
+        vec![BvEintrag::Flurstueck(BvEintragFlurstueck::neu(1))];
+    grundstueck_blatt.abt2.eintraege = vec![Abt2Eintrag {
+        text: "Erbbaurecht für die Dauer von 99 Jahren, eingetragen im Erbbaugrundbuch Blatt 300."
+            .to_string()
+            .into(),
+        ..Abt2Eintrag::new(1)
+    }];
+
+    let erbbaugrundbuch = |dauer: &str, bv_nr: &str| GrundbuchAnalysiert {
+        titelblatt: Titelblatt {
+            amtsgericht: "Prenzlau".to_string(),
+            grundbuch_von: "Ludwigsburg".to_string(),
+            blatt: "300".to_string(),
+        },
+        abt1: Vec::new(),
+        abt2: Vec::new(),
+        abt3: Vec::new(),
+        erbbaurechte: vec![ErbbaurechtAnalysiert {
+            lfd_nr: 1,
+            grundstueck: ErbbauGrundstueck {
+                grundbuch_von: "Ludwigsburg".to_string(),
+                blatt: "254".to_string(),
+                bv_nr: bv_nr.to_string().into(),
+                abt2_lfd_nr: None,
+            },
+            dauer: dauer.to_string(),
+            zustimmungsvorbehalte: Vec::new(),
+            abt2_lfd_nr: None,
+            warnungen: Vec::new(),
+            fehler: Vec::new(),
+        }],
+        loeschungen: LoeschungenAnalysiert::default(),
+        rang: RangAnalysiert::default(),
+        alkis: alkis::AlkisAbgleich::default(),
+        pruefung: PruefungAnalysiert::default(),
+        blaetter: Vec::new(),
+    };
+
+    let mut ok = erbbaugrundbuch("99 Jahre", "1");
+    erbbaurechte_abgleichen(&mut ok, &grundstueck_blatt, &[]);
+    assert_eq!(ok.erbbaurechte[0].abt2_lfd_nr, Some(1));
+    assert!(ok.erbbaurechte[0].warnungen.is_empty());
+    assert!(ok.erbbaurechte[0].fehler.is_empty());
+
+    // "99" darf nicht als Teil von "1999" gefunden werden
+    let mut dauer_falsch = erbbaugrundbuch("99 Jahre", "1");
+    grundstueck_blatt.abt2.eintraege[0].text =
+        "Erbbaurecht bis zum 31.12.1999, eingetragen im Erbbaugrundbuch Blatt 300."
+            .to_string()
+            .into();
+    erbbaurechte_abgleichen(&mut dauer_falsch, &grundstueck_blatt, &[]);
+    assert_eq!(dauer_falsch.erbbaurechte[0].warnungen.len(), 1);
+    assert!(matches!(
+        dauer_falsch.erbbaurechte[0].warnungen[0].code,
+        BefundCode::ErbbauDauerAbweichend { .. }
+    ));
+
+    let mut bv_fehlt = erbbaugrundbuch("", "1, 2");
+    erbbaurechte_abgleichen(&mut bv_fehlt, &grundstueck_blatt, &[]);
+    assert_eq!(bv_fehlt.erbbaurechte[0].fehler.len(), 1);
+    assert!(bv_fehlt.erbbaurechte[0].fehler[0]
+        .text()
+        .contains("Grundstück BV-Nr. 2"));
+}
+
+#[test]
+fn test_teilloeschung_lesen() {
+    let (teilweise, bv_nr, flurstuecke) =
+        teilloeschung_lesen("Gelöscht hinsichtlich Flur 2 Flurstück 17/3 und 18 am 01.02.2003.");
+    assert!(teilweise);
+    assert!(bv_nr.is_empty());
+    assert_eq!(
+        flurstuecke
+            .iter()
+            .map(|f| (f.flur, f.flurstueck.as_str()))
+            .collect::<Vec<_>>(),
+        vec![(2, "17/3"), (2, "18")]
+    );
+
+    let (teilweise, bv_nr, _) =
+        teilloeschung_lesen("Bezüglich lfd. Nr. 3 und 4 des Bestandsverzeichnisses gelöscht.");
+    assert!(teilweise);
+    assert_eq!(bv_nr, vec![3, 4]);
+
+    let (teilweise, _, _) = teilloeschung_lesen("Gelöscht am 12.03.1998.");
+    assert!(!teilweise);
+
+    let (teilweise, bv_nr, _) =
+        teilloeschung_lesen("Gelöscht, soweit lastend an lfd. Nr. 2 am 01.02.2003.");
+    assert!(teilweise);
+    assert_eq!(bv_nr, vec![2]);
+
+    let (teilweise, _, _) =
+        teilloeschung_lesen("Lastend an lfd. Nr. 1 und 2, gelöscht am 12.03.1998.");
+    assert!(!teilweise);
+
+    let (teilweise, _, _) =
+        teilloeschung_lesen("Gelöscht, soweit nicht auf Blatt 254 übertragen, am 12.03.1998.");
+    assert!(!teilweise);
+}
+
+#[test]
+fn test_bv_fortfuehrung_lesen() {
+    let f = bv_fortfuehrung_lesen("5, 6", "Nr. 3 zerlegt in Nr. 5 und 6 am 01.02.2003.", true);
+    let f = f.unwrap();
+    assert_eq!(f.art, BvFortfuehrungArt::Teilung);
+    assert_eq!((f.von, f.nach), (vec![3], vec![5, 6]));
+
+    let f = bv_fortfuehrung_lesen(
+        "7",
+        "Nr. 1 und 2 vereinigt und als Nr. 7 eingetragen.",
+        true,
+    );
+    let f = f.unwrap();
+    assert_eq!(f.art, BvFortfuehrungArt::Verschmelzung);
+    assert_eq!((f.von, f.nach), (vec![1, 2], vec![7]));
+
+    let f = bv_fortfuehrung_lesen(
+        "9",
+        "Aus lfd. Nr. 3 ein Flurstück verselbstständigt und als lfd. Nr. 9 eingetragen.",
+        true,
+    );
+    let f = f.unwrap();
+    assert_eq!(f.art, BvFortfuehrungArt::Teilung);
+    assert_eq!((f.von, f.nach), (vec![3], vec![9]));
+
+    let f = bv_fortfuehrung_lesen("4", "Übertragen nach Blatt 456 am 03.04.2005.", false);
+    let f = f.unwrap();
+    assert_eq!(
+        f.art,
+        BvFortfuehrungArt::UebertragenNach {
+            blatt: "456".to_string()
+        }
+    );
+    assert_eq!(f.von, vec![4]);
+}
+
+#[test]
+fn test_abt3_historie_reihenfolge() {
+    let mut grundbuch = Grundbuch::new(Titelblatt::default());
+    grundbuch.abt3.veraenderungen = [
+        "Abgetreten an die Sparkasse Musterstadt; eingetragen am 01.02.1995.",
+        "Umgestellt auf 3.834,61 EUR am 01.01.2002.",
+    ]
+    .iter()
+    .map(|t| digital::Abt3Veraenderung {
+        lfd_nr: "1".to_string().into(),
+        text: t.to_string().into(),
+        ..Default::default()
+    })
+    .collect();
+
+    // Teillöschung in DM vor der Umstellung auf EUR
+    let loeschungen = LoeschungenAnalysiert {
+        eintraege: vec![LoeschungAnalysiert {
+            abteilung: 3,
+            lfd_nr: vec![1],
+            text: "In Höhe von 2.500,50 DM gelöscht am 03.04.1996.".to_string(),
+            teilweise: true,
+            nur_geloescht_an_bv_nr: Vec::new(),
+            nur_geloescht_an: Vec::new(),
+            geloeschter_betrag: Some(Betrag {
+                wert: 2500,
+                nachkomma: 50,
+                waehrung: Waehrung::DMark,
+            }),
+            warnungen: Vec::new(),
+            fehler: Vec::new(),
+        }],
+        warnungen: Vec::new(),
+    };
+
+    let historie = abt3_historie(&grundbuch, 1, &loeschungen);
+    assert_eq!(
+        historie.iter().map(|h| h.loeschung).collect::<Vec<_>>(),
+        vec![false, true, false]
+    );
+
+    let mut fehler = Vec::new();
+    let (betrag, _) = abt3_historie_anwenden(
+        &historie,
+        Betrag {
+            wert: 10_000,
+            nachkomma: 0,
+            waehrung: Waehrung::DMark,
+        },
+        "Max Mustermann",
+        &mut Vec::new(),
+        &mut fehler,
+    );
+    assert_eq!(betrag.waehrung, Waehrung::Euro);
+    assert!(fehler.is_empty());
+}
+
+#[test]
+fn test_abt3_historie_anwenden() {
+    let historie = [
+        "Abgetreten an die Sparkasse Musterstadt; eingetragen am 01.02.1995.",
+        "In Höhe von 2.500,50 DM gelöscht am 03.04.1996.",
+        "Umgestellt auf 3.834,61 EUR am 01.01.2002.",
+    ]
+    .iter()
+    .map(|t| Abt3HistorieEintrag {
+        loeschung: false,
+        geroetet: false,
+        text: t.to_string(),
+        ereignis: abt3_ereignis_lesen("", t),
+    })
+    .collect::<Vec<_>>();
+
+    let mut warnungen = Vec::new();
+    let mut fehler = Vec::new();
+    let eingetragen = Betrag {
+        wert: 10_000,
+        nachkomma: 0,
+        waehrung: Waehrung::DMark,
+    };
+    let (betrag, rechtsinhaber) = abt3_historie_anwenden(
+        &historie[..2],
+        eingetragen,
+        "Max Mustermann",
+        &mut warnungen,
+        &mut fehler,
+    );
+    assert_eq!(rechtsinhaber, "Sparkasse Musterstadt");
+    assert_eq!(betrag.wert, 7499);
+    assert_eq!(betrag.nachkomma, 50);
+
+    let (betrag, _) = abt3_historie_anwenden(
+        &historie,
+        eingetragen,
+        "Max Mustermann",
+        &mut warnungen,
+        &mut fehler,
+    );
+    assert_eq!(betrag.waehrung, Waehrung::Euro);
+    assert_eq!(betrag.wert, 3834);
+    assert!(fehler.is_empty());
+}
+
+#[test]
+fn test_rangvermerke_lesen() {
+    let recht = |abteilung, lfd_nr| RechtRef { abteilung, lfd_nr };
+    let kurz = |v: Vec<Rangverhaeltnis>| {
+        v.into_iter()
+            .map(|r| (r.recht, r.beziehung, r.zu))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        kurz(rangvermerke_lesen(
+            "Im Rang vor Abt. III Nr. 2 und Abt. II Nr. 1, eingetragen am 1.2.1990",
+            3,
+            &[1]
+        )),
+        vec![
+            (recht(3, 1), RangBeziehung::Vor, recht(3, 2)),
+            (recht(3, 1), RangBeziehung::Vor, recht(2, 1)),
+        ]
+    );
+    assert_eq!(
+        kurz(rangvermerke_lesen(
+            "Der Grundschuld Abt. III Nr. 3 ist der Vorrang vor diesem Recht eingeräumt.",
+            2,
+            &[1]
+        )),
+        vec![(recht(2, 1), RangBeziehung::Nach, recht(3, 3))]
+    );
+    assert_eq!(
+        kurz(rangvermerke_lesen(
+            "Nr. 2 hat Vorrang vor Nr. 1.",
+            3,
+            &[1, 2]
+        )),
+        vec![(recht(3, 2), RangBeziehung::Vor, recht(3, 1))]
+    );
+
+    let rechte = [recht(2, 1), recht(2, 2), recht(3, 1), recht(3, 2)]
+        .into_iter()
+        .collect::<BTreeSet<_>>();
+    let verhaeltnisse = rangvermerke_lesen("Im Rang vor Abt. II Nr. 1", 3, &[2]);
+    let (rangstellen, eindeutig) = rangfolge_bilden(&rechte, &verhaeltnisse);
+    assert_eq!(
+        rangstellen,
+        vec![
+            vec![recht(3, 1)],
+            vec![recht(3, 2)],
+            vec![recht(2, 1)],
+            vec![recht(2, 2)],
+        ]
+    );
+    assert!(eindeutig);
+}
+
+#[test]
+fn test_befund_code() {
+    let warnung = AnalyseWarnung::from(BefundCode::KeineOrdnungsnummer);
+    assert!(!warnung.code.ist_fehler());
+    assert_eq!(warnung.code.feld(), Some(BefundFeld::Rechtsinhaber));
+
+    let fehler = AnalyseFehler::from(BefundCode::VeraenderungNichtLesbar {
+        abteilung: 2,
+        lfd_nr: "3a".to_string(),
+        grund: "ungültige Nummer".to_string(),
+    });
+    assert!(fehler.code.ist_fehler());
+
+    // Serialisieren und Einlesen ergeben wieder denselben Befund
+    let json = serde_json::to_string(&(vec![warnung.clone()], vec![fehler.clone()])).unwrap();
+    assert_eq!(
+        serde_json::from_str::<(Vec<AnalyseWarnung>, Vec<AnalyseFehler>)>(&json).unwrap(),
+        (vec![warnung.clone()], vec![fehler.clone()])
+    );
+
+    // LEFIS-Export enthält weiterhin nur die Meldungen
+    let analysiert = GrundbuchAnalysiert {
+        titelblatt: Titelblatt::default(),
+        abt1: Vec::new(),
+        abt2: Vec::new(),
+        abt3: Vec::new(),
+        erbbaurechte: Vec::new(),
+        loeschungen: LoeschungenAnalysiert::default(),
+        rang: RangAnalysiert {
+            warnungen: vec![warnung],
+            fehler: vec![fehler],
+            ..Default::default()
+        },
+        alkis: alkis::AlkisAbgleich::default(),
+        pruefung: PruefungAnalysiert::default(),
+        blaetter: Vec::new(),
+    };
+    let json = analysiert.lefis_json();
+    assert_eq!(
+        json["rang"]["warnungen"],
+        serde_json::json!(["Konnte keine Ordnungsnummer finden"])
+    );
+    assert_eq!(
+        json["rang"]["fehler"],
+        serde_json::json!(["Konnte Abt. 2 Veränderung nicht lesen: 3a: ungültige Nummer"])
+    );
+}
+
+#[test]
+fn test_quittierung() {
+    let mut grundbuch = Grundbuch::new(Titelblatt::default());
+    grundbuch.abt2.eintraege = vec![Abt2Eintrag {
+        text: "Wegerecht für die Gemeinde Musterdorf".to_string().into(),
+        ..Abt2Eintrag::new(1)
+    }];
+    let id = grundbuch.abt2.eintraege[0].id.clone();
+    let text_hash = |g: &Grundbuch| digital::hash_str(&eintrag_text(g, 2, 1).unwrap().1);
+
+    let quittierung = Quittierung::new(
+        &grundbuch,
+        2,
+        1,
+        BefundCode::KeineOrdnungsnummer,
+        "Rechtsinhaber bewusst nicht zugeordnet".to_string(),
+        "bearbeiter@example.com".to_string(),
+    )
+    .unwrap();
+    assert!(quittierung.gilt_fuer(2, 1, &id, &text_hash(&grundbuch)));
+    assert!(!quittierung.gilt_fuer(3, 1, &id, &text_hash(&grundbuch)));
+
+    // Textänderung macht die Quittierung ungültig
+    grundbuch.abt2.eintraege[0].text = "Wegerecht für die Stadt Musterstadt".to_string().into();
+    assert!(!quittierung.gilt_fuer(2, 1, &id, &text_hash(&grundbuch)));
+
+    assert!(Quittierung::new(
+        &grundbuch,
+        2,
+        2,
+        BefundCode::KeineOrdnungsnummer,
+        String::new(),
+        String::new(),
+    )
+    .is_none());
+}
+
+#[test]
+fn test_grundlagen_zuordnen() {
+    use crate::digital::{OptRect, PositionInPdf};
+
+    let position = |min_y: f32, max_y: f32| {
+        Some(PositionInPdf {
+            seite: "3".to_string(),
+            rect: OptRect {
+                min_x: Some(0.0),
+                max_x: Some(100.0),
+                min_y: Some(min_y),
+                max_y: Some(max_y),
+            },
+        })
+    };
+    let grundlage = |text: &str, min_y: f32, max_y: f32| Abt1GrundEintragung {
+        text: text.to_string().into(),
+        position_in_pdf: position(min_y, max_y),
+        ..Abt1GrundEintragung::new()
+    };
+
+    let mut eintrag = Abt1Eintrag::new(2);
+    let abt1 = Abteilung1 {
+        eintraege: vec![eintrag.clone()],
+        grundlagen_eintragungen: vec![
+            grundlage("Aufgelassen am 1.2.1990", 100.0, 140.0),
+            grundlage("Erbfolge, eingetragen am 3.4.2005", 160.0, 200.0),
+        ],
+        ..Abteilung1::default()
+    };
+
+    // ohne Position keine Zuordnung
+    assert_eq!(grundlagen_zuordnen(&eintrag, &abt1), None);
+
+    if let Abt1Eintrag::V2(v2) = &mut eintrag {
+        v2.position_in_pdf = position(150.0, 190.0);
+    }
+    let grundlagen = grundlagen_zuordnen(&eintrag, &abt1).unwrap();
+    assert_eq!(grundlagen.len(), 1);
+    assert_eq!(grundlagen[0].text, "Erbfolge, eingetragen am 3.4.2005");
+}
+
+#[test]
+fn test_cache_aufraeumen() {
+    let dir = std::env::temp_dir().join(format!("dgb-cache-test-{}", rand::random::<u32>()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    // Änderungszeiten müssen sich unterscheiden, damit die Reihenfolge feststeht
+    for name in ["a", "b", "c"] {
+        std::fs::write(dir.join(format!("{name}.json")), [0_u8; 100]).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+
+    cache_aufraeumen(&dir, 300);
+    assert!(dir.join("a.json").exists());
+
+    // "a" wurde zuletzt gelesen, daher wird "b" zuerst gelöscht
+    cache_verwendet(&dir.join("a.json"));
+    cache_aufraeumen(&dir, 250);
+    assert!(dir.join("a.json").exists());
+    assert!(!dir.join("b.json").exists());
+    assert!(dir.join("c.json").exists());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_quittierungen_anwenden() {
+    let mut grundbuch = Grundbuch::new(Titelblatt::default());
+    let mut eintrag = Abt2Eintrag::new(1);
+    eintrag.text = StringOrLines::from("Vorkaufsrecht für Max Mustermann".to_string());
+    grundbuch.abt2.eintraege = vec![eintrag.clone()];
+    grundbuch.ids_vergeben();
+
+    let analyse_neu = |grundbuch: &Grundbuch| {
+        let eintrag = &grundbuch.abt2.eintraege[0];
+        let mut analyse = GrundbuchAnalysiert {
+            titelblatt: Titelblatt::default(),
+            abt1: Vec::new(),
+            abt2: vec![Abt2Analysiert {
+                lfd_nr: eintrag.lfd_nr,
+                id: eintrag.id.clone(),
+                text_kurz: eintrag.text.text(),
+                rechteart: RechteArt::SonstigeDabagrechteart,
+                rechtsinhaber: String::new(),
+                rangvermerk: None,
+                spalte_2: String::new(),
+                belastete_flurstuecke: Vec::new(),
+                flurstueckskennzeichen: Vec::new(),
+                lastend_an: Vec::new(),
+                fortfuehrungen: Vec::new(),
+                text_original: eintrag.text.text(),
+                nebenbeteiligter: Nebenbeteiligter::default(),
+                warnungen: vec![BefundCode::KeineOrdnungsnummer.into()],
+                fehler: vec![BefundCode::KeineFlurstueckeZugeordnet.into()],
+                geroetet: false,
+                fertig_analysiert: true,
+            }],
+            abt3: Vec::new(),
+            erbbaurechte: Vec::new(),
+            loeschungen: LoeschungenAnalysiert::default(),
+            rang: RangAnalysiert {
+                fehler: vec![BefundCode::RangWiderspruch(vec!["A2/1".to_string()]).into()],
+                ..Default::default()
+            },
+            alkis: alkis::AlkisAbgleich::default(),
+            pruefung: PruefungAnalysiert::default(),
+            blaetter: Vec::new(),
+        };
+        quittierungen_anwenden(grundbuch, &mut analyse);
+        analyse
+    };
+
+    let offen = |analyse: &GrundbuchAnalysiert| {
+        analyse
+            .offene_befunde()
+            .into_iter()
+            .map(|b| (b.abteilung, b.code))
+            .collect::<Vec<_>>()
+    };
+
+    let analyse = analyse_neu(&grundbuch);
+    assert_eq!(analyse.befunde().len(), 3);
+    assert_eq!(
+        offen(&analyse),
+        vec![
+            (2, BefundCode::KeineOrdnungsnummer),
+            (2, BefundCode::KeineFlurstueckeZugeordnet),
+            (
+                GRUNDBUCH_BEFUND,
+                BefundCode::RangWiderspruch(vec!["A2/1".to_string()])
+            ),
+        ]
+    );
+
+    let quittieren = |grundbuch: &Grundbuch, abteilung: usize, lfd_nr: usize, code: BefundCode| {
+        Quittierung::new(
+            grundbuch,
+            abteilung,
+            lfd_nr,
+            code,
+            "geprüft".to_string(),
+            "bearbeiter@example.com".to_string(),
+        )
+        .unwrap()
+    };
+    grundbuch.quittierungen = vec![
+        quittieren(&grundbuch, 2, 1, BefundCode::KeineOrdnungsnummer),
+        quittieren(
+            &grundbuch,
+            GRUNDBUCH_BEFUND,
+            0,
+            BefundCode::RangWiderspruch(vec!["A2/1".to_string()]),
+        ),
+    ];
+
+    let analyse = analyse_neu(&grundbuch);
+    assert!(analyse.abt2[0].warnungen[0].quittierung.is_some());
+    assert!(analyse.abt2[0].fehler[0].quittierung.is_none());
+    assert!(analyse.rang.fehler[0].quittierung.is_some());
+    assert_eq!(analyse.befunde().len(), 3);
+    assert_eq!(
+        offen(&analyse),
+        vec![(2, BefundCode::KeineFlurstueckeZugeordnet)]
+    );
+
+    // Text des Eintrags geändert: Quittierung des Rechts ist ungültig,
+    // die des gesamten Grundbuchs bleibt bestehen
+    grundbuch.abt2.eintraege[0].text =
+        StringOrLines::from("Vorkaufsrecht für Erika Mustermann".to_string());
+    let analyse = analyse_neu(&grundbuch);
+    assert_eq!(
+        offen(&analyse),
+        vec![
+            (2, BefundCode::KeineOrdnungsnummer),
+            (2, BefundCode::KeineFlurstueckeZugeordnet),
+        ]
+    );
+
+    // Eintrag zu einer anderen lfd. Nr. verschoben, aber gleiche ID
+    grundbuch.abt2.eintraege[0] = Abt2Eintrag {
+        lfd_nr: 2,
+        ..eintrag
+    };
+    grundbuch.abt2.eintraege[0].id = grundbuch.quittierungen[0].id.clone();
+    let analyse = analyse_neu(&grundbuch);
+    assert_eq!(
+        offen(&analyse),
+        vec![(2, BefundCode::KeineFlurstueckeZugeordnet)]
+    );
 }
@@ -0,0 +1,743 @@
+//! Blattübergreifende Analyse aller geladenen Blätter
+//!
+//! Ordnet Herrschvermerke im BV der Grunddienstbarkeit in Abt. 2 des dienenden
+//! Blatts zu, gleicht Gesamtrechte / Mithaft zwischen den Blättern ab und meldet
+//! Flurstücke, die in mehreren Blättern gleichzeitig aktiv sind. Verweise auf
+//! Blätter, die nicht geladen sind, werden nicht geprüft.
+
+use crate::alkis::{self, Gemarkung};
+use crate::analyse::{
+    loeschungen_aufloesen, Abt2Analysiert, Abt3Analysiert, AnalyseWarnung, BefundCode,
+};
+use crate::digital::{BvEintrag, FlurstueckNummer, Titelblatt};
+use crate::Grundbuch;
+use std::collections::{BTreeMap, BTreeSet};
+
+pub struct Blatt<'a> {
+    pub grundbuch: &'a Grundbuch,
+    // Analyse des Blatts, kann noch unvollständig sein
+    pub abt2: &'a [Abt2Analysiert],
+    pub abt3: &'a [Abt3Analysiert],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HerrschvermerkZuordnung {
+    // Index des herrschenden Blatts
+    pub blatt: usize,
+    pub bv_nr: usize,
+    // Index des dienenden Blatts und lfd. Nr. der Grunddienstbarkeit in Abt. 2
+    pub dienendes_blatt: Option<usize>,
+    pub abt2_lfd_nr: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BlaetterAnalysiert {
+    pub herrschvermerke: Vec<HerrschvermerkZuordnung>,
+    // Warnungen je Blatt, in der Reihenfolge der übergebenen Blätter
+    pub warnungen: Vec<Vec<AnalyseWarnung>>,
+}
+
+impl BlaetterAnalysiert {
+    pub fn get_herrschvermerk(
+        &self,
+        blatt: usize,
+        bv_nr: usize,
+    ) -> Option<&HerrschvermerkZuordnung> {
+        self.herrschvermerke
+            .iter()
+            .find(|h| h.blatt == blatt && h.bv_nr == bv_nr)
+    }
+}
+
+pub fn blaetter_abgleichen(blaetter: &[Blatt], katalog: &[Gemarkung]) -> BlaetterAnalysiert {
+    let mut ergebnis = BlaetterAnalysiert {
+        herrschvermerke: Vec::new(),
+        warnungen: vec![Vec::new(); blaetter.len()],
+    };
+
+    herrschvermerke_zuordnen(blaetter, &mut ergebnis);
+    gesamtrechte_abgleichen(blaetter, &mut ergebnis);
+    flurstuecke_abgleichen(blaetter, katalog, &mut ergebnis);
+
+    ergebnis
+}
+
+fn blatt_name(titelblatt: &Titelblatt) -> String {
+    format!("{} Blatt {}", titelblatt.grundbuch_von, titelblatt.blatt)
+}
+
+// "0123" und "123" sind dasselbe Blatt
+fn blatt_normalisieren(blatt: &str) -> String {
+    let blatt = blatt.trim().to_lowercase();
+    match blatt.trim_start_matches('0') {
+        "" => blatt,
+        s => s.to_string(),
+    }
+}
+
+// Blattnummern, auf die ein Text verweist: "Blatt 123", "Bl. 12a", "Blatt 123 und 456"
+fn blatt_nummern(text: &str) -> BTreeSet<String> {
+    use lazy_static::lazy_static;
+    use regex::Regex;
+
+    lazy_static! {
+        static ref REGEX_BLATT: Regex = Regex::new(
+            r"(?i)\bbl(?:att|\.)\s*(\d+[a-z]?(?:\s*(?:,|und|u\.|sowie)\s*\d+[a-z]?\b)*)"
+        )
+        .unwrap();
+        static ref REGEX_NR: Regex = Regex::new(r"\d+[a-zA-Z]?").unwrap();
+    }
+
+    REGEX_BLATT
+        .captures_iter(text)
+        .flat_map(|c| {
+            REGEX_NR
+                .find_iter(c.get(1).map(|m| m.as_str()).unwrap_or_default())
+                .map(|m| blatt_normalisieren(m.as_str()))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+// Verweist ein Text aus Blatt `von` auf Blatt `auf`? Bei einem anderen
+// Grundbuchbezirk muss dieser im Text genannt sein
+fn verweist_auf(text: &str, von: &Titelblatt, auf: &Titelblatt) -> bool {
+    if !blatt_nummern(text).contains(&blatt_normalisieren(&auf.blatt)) {
+        return false;
+    }
+
+    von.grundbuch_von
+        .trim()
+        .eq_ignore_ascii_case(auf.grundbuch_von.trim())
+        || text
+            .to_lowercase()
+            .contains(&auf.grundbuch_von.trim().to_lowercase())
+}
+
+// "Abt. II Nr. 3", "Abteilung 2 lfd. Nr. 3"
+fn abt2_lfd_nr_lesen(text: &str) -> Option<usize> {
+    use lazy_static::lazy_static;
+    use regex::Regex;
+
+    lazy_static! {
+        static ref REGEX_ABT2: Regex =
+            Regex::new(r"(?i)abt(?:eilung|\.)?\s*(?:ii|2)\b\.?\s*,?\s*(?:lfd\.?\s*)?nr\.?\s*(\d+)")
+                .unwrap();
+    }
+
+    REGEX_ABT2
+        .captures(text)
+        .and_then(|c| c.get(1))
+        .and_then(|m| m.as_str().parse().ok())
+}
+
+fn ist_gesamtrecht(text: &str) -> bool {
+    let text = text.to_lowercase();
+    [
+        "mithaft",
+        "gesamthaft",
+        "gesamtgrundschuld",
+        "gesamthypothek",
+        "gesamtrentenschuld",
+        "gesamtrecht",
+    ]
+    .iter()
+    .any(|s| text.contains(s))
+}
+
+// Nicht gerötete und nicht gelöschte Einträge der Abteilung als (lfd. Nr., Text)
+fn aktive_eintraege(grundbuch: &Grundbuch, abteilung: usize) -> Vec<(usize, String)> {
+    let geloescht = loeschungen_aufloesen(grundbuch).vollstaendig_geloescht(abteilung);
+    let eintraege = match abteilung {
+        2 => grundbuch
+            .abt2
+            .eintraege
+            .iter()
+            .filter(|e| !e.ist_geroetet())
+            .map(|e| (e.lfd_nr, e.text.text()))
+            .collect::<Vec<_>>(),
+        _ => grundbuch
+            .abt3
+            .eintraege
+            .iter()
+            .filter(|e| !e.ist_geroetet())
+            .map(|e| (e.lfd_nr, e.text.text()))
+            .collect::<Vec<_>>(),
+    };
+
+    eintraege
+        .into_iter()
+        .filter(|(lfd_nr, text)| !geloescht.contains(lfd_nr) && !text.trim().is_empty())
+        .collect()
+}
+
+fn herrschvermerke_zuordnen(blaetter: &[Blatt], ergebnis: &mut BlaetterAnalysiert) {
+    let abt2 = blaetter
+        .iter()
+        .map(|b| aktive_eintraege(b.grundbuch, 2))
+        .collect::<Vec<_>>();
+
+    for (i, a) in blaetter.iter().enumerate() {
+        let herrschend = &a.grundbuch.titelblatt;
+
+        for bv in a.grundbuch.bestandsverzeichnis.eintraege.iter() {
+            let recht = match bv {
+                BvEintrag::Recht(r) if !bv.ist_geroetet() => r,
+                _ => continue,
+            };
+
+            let text = recht.text.text();
+            let mut zuordnung = HerrschvermerkZuordnung {
+                blatt: i,
+                bv_nr: recht.lfd_nr,
+                dienendes_blatt: None,
+                abt2_lfd_nr: None,
+            };
+
+            let dienend = blaetter
+                .iter()
+                .enumerate()
+                .filter(|(j, b)| {
+                    *j != i && verweist_auf(&text, herrschend, &b.grundbuch.titelblatt)
+                })
+                .map(|(j, _)| j)
+                .collect::<Vec<_>>();
+
+            let j = match dienend.as_slice() {
+                [j] => *j,
+                [] => {
+                    ergebnis.herrschvermerke.push(zuordnung);
+                    continue;
+                }
+                _ => {
+                    ergebnis.warnungen[i].push(
+                        BefundCode::HerrschvermerkMehrereBlaetter {
+                            bv_nr: recht.lfd_nr,
+                            blaetter: dienend
+                                .iter()
+                                .map(|j| blatt_name(&blaetter[*j].grundbuch.titelblatt))
+                                .collect(),
+                        }
+                        .into(),
+                    );
+                    ergebnis.herrschvermerke.push(zuordnung);
+                    continue;
+                }
+            };
+
+            let dienendes_blatt = &blaetter[j].grundbuch.titelblatt;
+            let dienend_name = blatt_name(dienendes_blatt);
+            zuordnung.dienendes_blatt = Some(j);
+
+            let verweist_zurueck = |text: &str| verweist_auf(text, dienendes_blatt, herrschend);
+
+            match abt2_lfd_nr_lesen(&text) {
+                Some(lfd_nr) => match abt2[j].iter().find(|(n, _)| *n == lfd_nr) {
+                    Some((_, a2_text)) => {
+                        if !verweist_zurueck(a2_text) {
+                            ergebnis.warnungen[i].push(
+                                BefundCode::HerrschvermerkOhneRueckverweis {
+                                    bv_nr: recht.lfd_nr,
+                                    abt2_lfd_nr: lfd_nr,
+                                    dienend: dienend_name.clone(),
+                                    herrschend: blatt_name(herrschend),
+                                }
+                                .into(),
+                            );
+                        }
+                        zuordnung.abt2_lfd_nr = Some(lfd_nr);
+                    }
+                    None => {
+                        ergebnis.warnungen[i].push(
+                            BefundCode::HerrschvermerkAbt2Fehlt {
+                                bv_nr: recht.lfd_nr,
+                                abt2_lfd_nr: lfd_nr,
+                                dienend: dienend_name.clone(),
+                            }
+                            .into(),
+                        );
+                    }
+                },
+                None => {
+                    let kandidaten = abt2[j]
+                        .iter()
+                        .filter(|(_, a2_text)| verweist_zurueck(a2_text))
+                        .map(|(n, _)| *n)
+                        .collect::<Vec<_>>();
+
+                    match kandidaten.as_slice() {
+                        [lfd_nr] => zuordnung.abt2_lfd_nr = Some(*lfd_nr),
+                        [] => ergebnis.warnungen[i].push(
+                            BefundCode::HerrschvermerkOhneRecht {
+                                bv_nr: recht.lfd_nr,
+                                dienend: dienend_name.clone(),
+                                herrschend: blatt_name(herrschend),
+                            }
+                            .into(),
+                        ),
+                        _ => ergebnis.warnungen[i].push(
+                            BefundCode::HerrschvermerkMehrdeutig {
+                                bv_nr: recht.lfd_nr,
+                                dienend: dienend_name.clone(),
+                            }
+                            .into(),
+                        ),
+                    }
+                }
+            }
+
+            ergebnis.herrschvermerke.push(zuordnung);
+        }
+    }
+
+    // Grunddienstbarkeiten zugunsten eines geladenen Blatts ohne Herrschvermerk dort
+    for (j, b) in blaetter.iter().enumerate() {
+        let dienend = &b.grundbuch.titelblatt;
+
+        for (lfd_nr, text) in abt2[j].iter() {
+            if !text.to_lowercase().contains("jeweilig") {
+                continue;
+            }
+
+            for (i, a) in blaetter.iter().enumerate() {
+                let herrschend = &a.grundbuch.titelblatt;
+                if i == j || !verweist_auf(text, dienend, herrschend) {
+                    continue;
+                }
+
+                let hat_herrschvermerk = ergebnis.herrschvermerke.iter().any(|h| {
+                    h.blatt == i && h.dienendes_blatt == Some(j) && h.abt2_lfd_nr == Some(*lfd_nr)
+                });
+
+                if !hat_herrschvermerk {
+                    ergebnis.warnungen[j].push(
+                        BefundCode::RechtOhneHerrschvermerk {
+                            lfd_nr: *lfd_nr,
+                            herrschend: blatt_name(herrschend),
+                        }
+                        .into(),
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn gesamtrechte_abgleichen(blaetter: &[Blatt], ergebnis: &mut BlaetterAnalysiert) {
+    for abteilung in [2, 3] {
+        let eintraege = blaetter
+            .iter()
+            .map(|b| aktive_eintraege(b.grundbuch, abteilung))
+            .collect::<Vec<_>>();
+
+        for (i, a) in blaetter.iter().enumerate() {
+            let von = &a.grundbuch.titelblatt;
+
+            for (lfd_nr, text) in eintraege[i].iter().filter(|(_, t)| ist_gesamtrecht(t)) {
+                for (j, b) in blaetter.iter().enumerate() {
+                    let auf = &b.grundbuch.titelblatt;
+                    if i == j || !verweist_auf(text, von, auf) {
+                        continue;
+                    }
+
+                    let gegenstueck = eintraege[j]
+                        .iter()
+                        .find(|(_, t)| ist_gesamtrecht(t) && verweist_auf(t, auf, von))
+                        .map(|(n, _)| *n);
+
+                    let gegenstueck = match gegenstueck {
+                        Some(s) => s,
+                        None => {
+                            ergebnis.warnungen[i].push(
+                                BefundCode::GesamtrechtOhneMithaft {
+                                    abteilung,
+                                    lfd_nr: *lfd_nr,
+                                    blatt: blatt_name(auf),
+                                    fuer: blatt_name(von),
+                                }
+                                .into(),
+                            );
+                            continue;
+                        }
+                    };
+
+                    // Abweichungen nur einmal melden
+                    if j < i {
+                        continue;
+                    }
+
+                    if let Some(abweichung) =
+                        gesamtrecht_abweichung(abteilung, a, *lfd_nr, b, gegenstueck)
+                    {
+                        ergebnis.warnungen[i].push(
+                            BefundCode::GesamtrechtAbweichend {
+                                abteilung,
+                                lfd_nr: *lfd_nr,
+                                blatt: blatt_name(auf),
+                                lfd_nr_dort: gegenstueck,
+                                abweichung: abweichung.clone(),
+                            }
+                            .into(),
+                        );
+                        ergebnis.warnungen[j].push(
+                            BefundCode::GesamtrechtAbweichend {
+                                abteilung,
+                                lfd_nr: gegenstueck,
+                                blatt: blatt_name(von),
+                                lfd_nr_dort: *lfd_nr,
+                                abweichung,
+                            }
+                            .into(),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Vergleich nur, wenn beide Einträge fertig analysiert sind
+fn gesamtrecht_abweichung(
+    abteilung: usize,
+    a: &Blatt,
+    a_lfd_nr: usize,
+    b: &Blatt,
+    b_lfd_nr: usize,
+) -> Option<String> {
+    if abteilung == 2 {
+        let a2_a = a
+            .abt2
+            .iter()
+            .find(|e| e.lfd_nr == a_lfd_nr && e.fertig_analysiert)?;
+        let a2_b = b
+            .abt2
+            .iter()
+            .find(|e| e.lfd_nr == b_lfd_nr && e.fertig_analysiert)?;
+        if a2_a.rechteart != a2_b.rechteart {
+            return Some(format!(
+                "RechteArt {:?} / {:?}",
+                a2_a.rechteart, a2_b.rechteart
+            ));
+        }
+        return None;
+    }
+
+    let a3_a = a
+        .abt3
+        .iter()
+        .find(|e| e.lfd_nr == a_lfd_nr && e.fertig_analysiert)?;
+    let a3_b = b
+        .abt3
+        .iter()
+        .find(|e| e.lfd_nr == b_lfd_nr && e.fertig_analysiert)?;
+
+    if a3_a.betrag != a3_b.betrag {
+        return Some(format!(
+            "Betrag {} {} / {} {}",
+            crate::kurztext::formatiere_betrag(&a3_a.betrag),
+            a3_a.betrag.waehrung.to_string(),
+            crate::kurztext::formatiere_betrag(&a3_b.betrag),
+            a3_b.betrag.waehrung.to_string(),
+        ));
+    }
+
+    if a3_a.schuldenart.to_string() != a3_b.schuldenart.to_string() {
+        return Some(format!(
+            "SchuldenArt {} / {}",
+            a3_a.schuldenart.to_string(),
+            a3_b.schuldenart.to_string()
+        ));
+    }
+
+    None
+}
+
+// Ein Flurstück kann nur in einem Blatt eingetragen sein, Miteigentumsanteile
+// (Wohnungsgrundbücher) ausgenommen
+fn flurstuecke_abgleichen(
+    blaetter: &[Blatt],
+    katalog: &[Gemarkung],
+    ergebnis: &mut BlaetterAnalysiert,
+) {
+    let mut flurstuecke = BTreeMap::new();
+
+    for (i, b) in blaetter.iter().enumerate() {
+        let grundbuch_von = &b.grundbuch.titelblatt.grundbuch_von;
+
+        for bv in b.grundbuch.bestandsverzeichnis.eintraege.iter() {
+            let flst = match bv {
+                BvEintrag::Flurstueck(f) if !bv.ist_flurstueck_geroetet() => f,
+                _ => continue,
+            };
+
+            let miteigentum = flst
+                .bezeichnung
+                .as_ref()
+                .map(|b| b.text().to_lowercase().contains("miteigentum"))
+                .unwrap_or(false);
+            if miteigentum || flst.flurstueck.trim().is_empty() {
+                continue;
+            }
+
+            let gemarkung = alkis::gemarkung_normalisieren(
+                katalog,
+                flst.gemarkung.as_ref().unwrap_or(grundbuch_von),
+            )
+            .to_lowercase();
+            let nummer = match FlurstueckNummer::parse(&flst.flurstueck) {
+                Some(n) => n.to_string(),
+                None => flst.flurstueck.trim().to_string(),
+            };
+
+            flurstuecke
+                .entry((gemarkung, flst.flur, nummer))
+                .or_insert_with(Vec::new)
+                .push((i, flst.lfd_nr));
+        }
+    }
+
+    for ((_, flur, nummer), vorkommen) in flurstuecke {
+        let blaetter_mit_flst = vorkommen.iter().map(|(i, _)| *i).collect::<BTreeSet<_>>();
+        if blaetter_mit_flst.len() < 2 {
+            continue;
+        }
+
+        for (i, bv_nr) in vorkommen.iter() {
+            let auch_in = vorkommen
+                .iter()
+                .filter(|(j, _)| j != i)
+                .map(|(j, n)| {
+                    format!(
+                        "{} BV-Nr. {n}",
+                        blatt_name(&blaetter[*j].grundbuch.titelblatt)
+                    )
+                })
+                .collect();
+            ergebnis.warnungen[*i].push(
+                BefundCode::FlurstueckInMehrerenBlaettern {
+                    bv_nr: *bv_nr,
+                    flur,
+                    flurstueck: nummer.clone(),
+                    auch_in,
+                }
+                .into(),
+            );
+        }
+    }
+}
+
+#[test]
+fn test_blaetter_abgleichen() {
+    use crate::digital::{Abt2Eintrag, Abt3Eintrag, BvEintragRecht};
+
+    let grundbuch = |blatt: &str| {
+        Grundbuch::new(Titelblatt {
+            amtsgericht: "Musterstadt".to_string(),
+            grundbuch_von: "Musterdorf".to_string(),
+            blatt: blatt.to_string(),
+        })
+    };
+
+    let flurstueck = |lfd_nr: usize, flst: &str| {
+        let mut bv = BvEintrag::neu(lfd_nr);
+        if let BvEintrag::Flurstueck(f) = &mut bv {
+            f.flur = 2;
+            f.flurstueck = flst.to_string();
+        }
+        bv
+    };
+
+    // Blatt 100: herrschendes Blatt, Gesamtgrundschuld mit Blatt 200
+    let mut herrschend = grundbuch("100");
+    herrschend.bestandsverzeichnis.eintraege = vec![
+        flurstueck(1, "17/3"),
+        BvEintrag::Recht(BvEintragRecht {
+            zu_nr: "1".to_string().into(),
+            text: "Wegerecht an dem Grundstück Blatt 200 BV-Nr. 1, eingetragen in Abt. II Nr. 1"
+                .to_string()
+                .into(),
+            ..BvEintragRecht::neu(2)
+        }),
+    ];
+    herrschend.abt3.eintraege = vec![Abt3Eintrag {
+        text: "Gesamtgrundschuld, Mithaft: Blatt 200 und 300"
+            .to_string()
+            .into(),
+        ..Abt3Eintrag::new(1)
+    }];
+
+    // Blatt 200: dienendes Blatt, Flurstück 17/3 ebenfalls aktiv
+    let mut dienend = grundbuch("0200");
+    dienend.bestandsverzeichnis.eintraege = vec![flurstueck(1, "17 / 3")];
+    dienend.abt2.eintraege = vec![
+        Abt2Eintrag {
+            text: "Wegerecht für den jeweiligen Eigentümer von Blatt 100 BV-Nr. 1"
+                .to_string()
+                .into(),
+            ..Abt2Eintrag::new(1)
+        },
+        Abt2Eintrag {
+            text: "Leitungsrecht für den jeweiligen Eigentümer von Blatt 300"
+                .to_string()
+                .into(),
+            ..Abt2Eintrag::new(2)
+        },
+    ];
+
+    // Blatt 300: Mithaft fehlt
+    let mut drittes = grundbuch("300");
+    drittes.bestandsverzeichnis.eintraege = vec![flurstueck(1, "18")];
+
+    let blaetter = [&herrschend, &dienend, &drittes]
+        .iter()
+        .map(|g| Blatt {
+            grundbuch: g,
+            abt2: &[],
+            abt3: &[],
+        })
+        .collect::<Vec<_>>();
+
+    let ergebnis = blaetter_abgleichen(&blaetter, &[]);
+
+    assert_eq!(
+        ergebnis.get_herrschvermerk(0, 2),
+        Some(&HerrschvermerkZuordnung {
+            blatt: 0,
+            bv_nr: 2,
+            dienendes_blatt: Some(1),
+            abt2_lfd_nr: Some(1),
+        })
+    );
+
+    assert_eq!(
+        ergebnis
+            .warnungen
+            .iter()
+            .map(|w| w.iter().map(|w| w.text()).collect::<Vec<_>>())
+            .collect::<Vec<_>>(),
+        vec![
+            vec![
+                "Abt. 3 lfd. Nr. 1: Gesamtrecht mit Musterdorf Blatt 0200, dort ist kein Recht mit Mithaft für Musterdorf Blatt 100 eingetragen".to_string(),
+                "Abt. 3 lfd. Nr. 1: Gesamtrecht mit Musterdorf Blatt 300, dort ist kein Recht mit Mithaft für Musterdorf Blatt 100 eingetragen".to_string(),
+                "BV-Nr. 1: Flur 2 Flst. 17/3 ist auch in Musterdorf Blatt 0200 BV-Nr. 1 eingetragen".to_string(),
+            ],
+            vec![
+                "Abt. 2 lfd. Nr. 2: Recht zugunsten Musterdorf Blatt 300, dort ist kein Herrschvermerk eingetragen".to_string(),
+                "BV-Nr. 1: Flur 2 Flst. 17/3 ist auch in Musterdorf Blatt 100 BV-Nr. 1 eingetragen".to_string(),
+            ],
+            vec![],
+        ]
+    );
+}
+
+#[test]
+fn test_blatt_nummern() {
+    let nummern = |s: &str| blatt_nummern(s).into_iter().collect::<Vec<_>>();
+
+    assert_eq!(
+        nummern("Gesamtgrundschuld, Mithaft: Blatt 0123 und 456, Bl. 12a"),
+        vec!["123".to_string(), "12a".to_string(), "456".to_string()]
+    );
+    assert_eq!(nummern("Flur 2, Flurstück 123"), Vec::<String>::new());
+    assert_eq!(abt2_lfd_nr_lesen("eingetragen in Abt. II Nr. 5"), Some(5));
+    assert_eq!(abt2_lfd_nr_lesen("Abteilung 2 lfd. Nr. 12"), Some(12));
+    assert_eq!(abt2_lfd_nr_lesen("Abt. III Nr. 5"), None);
+}
+
+#[test]
+fn test_blaetter_abgleichen_herrschvermerk_unklar() {
+    use crate::digital::{Abt2Eintrag, Abt3Eintrag, BvEintragRecht};
+
+    let grundbuch = |grundbuch_von: &str, blatt: &str| {
+        Grundbuch::new(Titelblatt {
+            amtsgericht: "Musterstadt".to_string(),
+            grundbuch_von: grundbuch_von.to_string(),
+            blatt: blatt.to_string(),
+        })
+    };
+
+    // Blatt 10: ein Herrschvermerk ohne lfd. Nr., einer mit falscher lfd. Nr.
+    let mut herrschend = grundbuch("Musterdorf", "10");
+    herrschend.bestandsverzeichnis.eintraege = vec![
+        BvEintrag::Recht(BvEintragRecht {
+            zu_nr: "1".to_string().into(),
+            text: "Wegerecht an Blatt 20".to_string().into(),
+            ..BvEintragRecht::neu(1)
+        }),
+        BvEintrag::Recht(BvEintragRecht {
+            zu_nr: "1".to_string().into(),
+            text: "Leitungsrecht an Blatt 20, Abt. II Nr. 5"
+                .to_string()
+                .into(),
+            ..BvEintragRecht::neu(2)
+        }),
+    ];
+    herrschend.abt3.eintraege = vec![Abt3Eintrag {
+        text: "Gesamtgrundschuld, Mithaft: Blatt 20".to_string().into(),
+        ..Abt3Eintrag::new(1)
+    }];
+
+    // Blatt 20: zwei Rechte kommen in Frage, Mithaft ist eingetragen
+    let mut dienend = grundbuch("Musterdorf", "20");
+    dienend.abt2.eintraege = vec![
+        Abt2Eintrag {
+            text: "Wegerecht für den jeweiligen Eigentümer von Blatt 10"
+                .to_string()
+                .into(),
+            ..Abt2Eintrag::new(1)
+        },
+        Abt2Eintrag {
+            text: "Leitungsrecht für den jeweiligen Eigentümer von Blatt 10"
+                .to_string()
+                .into(),
+            ..Abt2Eintrag::new(2)
+        },
+    ];
+    dienend.abt3.eintraege = vec![Abt3Eintrag {
+        text: "Gesamtgrundschuld, Mithaft: Bl. 10".to_string().into(),
+        ..Abt3Eintrag::new(1)
+    }];
+
+    // Gleiche Blattnummer in einem anderen Grundbuchbezirk, wird nicht genannt
+    let anderer_bezirk = grundbuch("Anderdorf", "20");
+
+    let blaetter = [&herrschend, &dienend, &anderer_bezirk]
+        .iter()
+        .map(|g| Blatt {
+            grundbuch: g,
+            abt2: &[],
+            abt3: &[],
+        })
+        .collect::<Vec<_>>();
+
+    let ergebnis = blaetter_abgleichen(&blaetter, &[]);
+
+    assert_eq!(
+        ergebnis.get_herrschvermerk(0, 1),
+        Some(&HerrschvermerkZuordnung {
+            blatt: 0,
+            bv_nr: 1,
+            dienendes_blatt: Some(1),
+            abt2_lfd_nr: None,
+        })
+    );
+
+    assert_eq!(
+        ergebnis
+            .warnungen
+            .iter()
+            .map(|w| w.iter().map(|w| w.text()).collect::<Vec<_>>())
+            .collect::<Vec<_>>(),
+        vec![
+            vec![
+                "Herrschvermerk BV-Nr. 1: mehrere Rechte in Abt. 2 von Musterdorf Blatt 20 kommen in Frage, bitte lfd. Nr. in Abt. 2 angeben".to_string(),
+                "Herrschvermerk BV-Nr. 2: Abt. 2 lfd. Nr. 5 ist in Musterdorf Blatt 20 nicht vorhanden oder gelöscht".to_string(),
+            ],
+            vec![
+                "Abt. 2 lfd. Nr. 1: Recht zugunsten Musterdorf Blatt 10, dort ist kein Herrschvermerk eingetragen".to_string(),
+                "Abt. 2 lfd. Nr. 2: Recht zugunsten Musterdorf Blatt 10, dort ist kein Herrschvermerk eingetragen".to_string(),
+            ],
+            vec![],
+        ]
+    );
+}
@@ -1,3 +1,4 @@
+use crate::analyse::BefundCode;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -28,6 +29,20 @@ pub enum Cmd {
         amtsgericht: String,
         blatt: String,
     },
+    #[serde(rename = "befund_quittieren")]
+    BefundQuittieren {
+        abteilung: usize,
+        lfd_nr: usize,
+        code: BefundCode,
+    },
+    #[serde(rename = "befund_quittieren_finished")]
+    BefundQuittierenFinished { begruendung: String },
+    #[serde(rename = "quittierung_aufheben")]
+    QuittierungAufheben {
+        abteilung: usize,
+        lfd_nr: usize,
+        code: BefundCode,
+    },
     #[serde(rename = "undo")]
     Undo,
     #[serde(rename = "redo")]
@@ -38,8 +53,16 @@ pub enum Cmd {
     ImportNebenbeteiligte,
     #[serde(rename = "delete_nb")]
     DeleteNebenbeteiligte,
+    #[serde(rename = "import_gemarkungen")]
+    ImportGemarkungen,
+    #[serde(rename = "import_alkis")]
+    ImportAlkis,
     #[serde(rename = "export_lefis")]
     ExportLefis,
+    #[serde(rename = "export_rang")]
+    ExportRangverhaeltnisse,
+    #[serde(rename = "export_geojson")]
+    ExportGeoJson,
     #[serde(rename = "export_alle_rechte")]
     ExportAlleRechte,
     #[serde(rename = "export_alle_fehler")]
@@ -75,6 +98,8 @@ pub enum Cmd {
     OpenHelp,
     #[serde(rename = "open_export_pdf")]
     OpenExportPdf,
+    #[serde(rename = "konfiguration_vergleichen")]
+    KonfigurationVergleichen,
     #[serde(rename = "close_file")]
     CloseFile { file_name: String },
     #[serde(rename = "klassifiziere_seite_neu")]
@@ -86,8 +111,6 @@ pub enum Cmd {
     CheckPdfImageSichtbar,
     #[serde(rename = "toggle_lefis_analyse")]
     ToggleLefisAnalyse,
-    #[serde(rename = "check_pdf_for_errors")]
-    CheckPdfForErrors,
     #[serde(rename = "search")]
     Search { search_text: String },
     #[serde(rename = "grundbuch_abonnieren")]
@@ -100,6 +123,10 @@ pub enum Cmd {
     EditAbkuerzungenScript { script: String },
     #[serde(rename = "edit_text_saubern_script")]
     EditTextSaubernScript { script: String },
+    #[serde(rename = "text_saubern_script_testen")]
+    TextSaubernScriptTesten { text: String },
+    #[serde(rename = "abkuerzungen_script_testen")]
+    AbkuerzungenScriptTesten { text: String },
     #[serde(rename = "edit_flurstuecke_auslesen_script")]
     EditFlurstueckeAuslesenScript { script: String },
     #[serde(rename = "edit_commit_description")]
@@ -146,6 +173,14 @@ pub enum Cmd {
     EditRechtsinhaberAuslesenAbt3Script { neu: String },
     #[serde(rename = "rechtsinhaber_auslesen_abt3_script_testen")]
     RechtsinhaberAuslesenAbt3ScriptTesten { text: String },
+    #[serde(rename = "edit_pruefe_eintrag_script")]
+    EditPruefeEintragScript { neu: String },
+    #[serde(rename = "pruefe_eintrag_script_testen")]
+    PruefeEintragScriptTesten { text: String, abteilung: usize },
+    #[serde(rename = "edit_eigentuemer_auslesen_abt1_script")]
+    EditEigentuemerAuslesenAbt1Script { neu: String },
+    #[serde(rename = "eigentuemer_auslesen_abt1_script_testen")]
+    EigentuemerAuslesenAbt1ScriptTesten { text: String },
     #[serde(rename = "switch_aenderung_view")]
     SwitchAenderungView { i: usize },
 
@@ -160,6 +195,39 @@ pub enum Cmd {
     #[serde(rename = "regex_loeschen")]
     RegexLoeschen { regex_key: String },
 
+    // Testfälle der Konfiguration, ziel ist TestZiel::id
+    #[serde(rename = "testfall_speichern")]
+    TestfallSpeichern {
+        ziel: String,
+        eingabe: String,
+        #[serde(default)]
+        bv_nr: String,
+    },
+    #[serde(rename = "testfall_loeschen")]
+    TestfallLoeschen { ziel: String, index: usize },
+    #[serde(rename = "testfall_aus_eintrag")]
+    TestfallAusEintrag { abteilung: usize, lfd_nr: usize },
+    #[serde(rename = "testfaelle_ausfuehren")]
+    TestfaelleAusfuehren,
+    // Nach der Verzögerung im Anschluss an eine Änderung eines Skripts,
+    // lauf siehe RpcData::testfaelle_lauf
+    #[serde(rename = "testfaelle_starten")]
+    TestfaelleStarten { lauf: usize },
+    // Ergebnis von Cmd::TestfaelleAusfuehren, wird aus dem Thread des Testlaufs
+    // über den EventLoopProxy gesendet, lauf siehe RpcData::testfaelle_lauf
+    #[serde(rename = "testfaelle_fertig")]
+    TestfaelleFertig { lauf: usize, html: String },
+
+    // Versionsgeschichte der Konfiguration, id ist die Commit-ID
+    #[serde(rename = "historie_vergleichen")]
+    HistorieVergleichen { alt: String, neu: String },
+    #[serde(rename = "historie_wiederherstellen")]
+    HistorieWiederherstellen { id: String },
+    // Fehler beim verzögerten Ablegen einer Version, wird aus dem Hintergrund
+    // über den EventLoopProxy gesendet
+    #[serde(rename = "historie_fehler")]
+    HistorieFehler { meldung: String },
+
     // Check whether a "{file_name}".json with analyzed texts exists
     #[serde(rename = "check_for_pdf_loaded")]
     CheckForPdfLoaded {
@@ -176,6 +244,9 @@ pub enum Cmd {
     EintragRoeten { path: String },
     #[serde(rename = "eintrag_loeschen")]
     EintragLoeschen { path: String },
+    // path: "bv:0:groesse", zeile: None = ganze Zelle
+    #[serde(rename = "zelle_roeten")]
+    ZelleRoeten { path: String, zeile: Option<usize> },
     #[serde(rename = "open_context_menu")]
     OpenContextMenu { x: f32, y: f32, seite: usize },
     #[serde(rename = "close_pop_over")]
@@ -238,8 +309,20 @@ pub enum Cmd {
         image_data_base64: String,
         render_hocr: bool,
     },
-    #[serde(rename = "check_for_grundbuch_loaded")]
-    CheckForGrundbuchLoaded,
+    // Wird nicht vom Frontend gesendet, sondern nach der Analyse eines Eintrags
+    // im Hintergrund über den EventLoopProxy
+    #[serde(rename = "analyse_eintrag_fertig")]
+    AnalyseEintragFertig {
+        datei: String,
+        abteilung: usize,
+        lfd_nr: usize,
+    },
+    // Fortschritt von Cmd::KonfigurationVergleichen, wird aus dem Thread des
+    // Vergleichs über den EventLoopProxy gesendet
+    #[serde(rename = "konfiguration_vergleich_fortschritt")]
+    KonfigurationVergleichFortschritt { fertig: usize, gesamt: usize },
+    #[serde(rename = "konfiguration_vergleich_fertig")]
+    KonfigurationVergleichFertig { meldung: String },
     #[serde(rename = "open_script")]
     OpenScript { lines: Vec<String> },
 }
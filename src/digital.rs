@@ -1,4 +1,4 @@
-use crate::{python::PyVm, AnpassungSeite, Konfiguration, PdfFile, Rect};
+use crate::{analyse::Quittierung, python::PyVm, AnpassungSeite, Konfiguration, PdfFile, Rect};
 use chrono::{DateTime, Utc};
 use image::ImageError;
 use lopdf::Error as LoPdfError;
@@ -185,6 +185,8 @@ pub struct HocrSeite {
 pub struct ParsedLinie {
     pub punkte_point: Vec<Punkt>,
     pub ctm_transforms: Vec<PdfMatrix>,
+    // 0.0 für gefüllte Flächen
+    pub linienbreite_pt: f32,
     pub page_height_mm: f32,
 }
 
@@ -204,13 +206,29 @@ impl ParsedLinie {
                 }
             })
             .collect();
-        Linie { punkte }
+        // Linienbreite skaliert mit der CTM (Mittelwert der Achsen)
+        let skalierung = self
+            .ctm_transforms
+            .iter()
+            .map(|c| c.determinant().abs().sqrt())
+            .product::<f32>();
+        Linie {
+            punkte,
+            breite_mm: self.linienbreite_pt * skalierung * 0.352778,
+        }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Linie {
     pub punkte: Vec<Punkt>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "linienbreite_ist_null")]
+    pub breite_mm: f32,
+}
+
+fn linienbreite_ist_null(f: &f32) -> bool {
+    *f == 0.0
 }
 
 impl Linie {
@@ -239,11 +257,12 @@ impl Linie {
             .map(|p| p.y.round() as isize)
             .max()
             .unwrap_or(0) as f32;
+        let rand = self.breite_mm / 2.0;
         Rect {
-            min_x,
-            min_y,
-            max_x,
-            max_y,
+            min_x: min_x - rand,
+            min_y: min_y - rand,
+            max_x: max_x + rand,
+            max_y: max_y + rand,
         }
     }
 }
@@ -497,10 +516,373 @@ impl HocrSeite {
     }
 }
 
-#[derive(Default)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum PdfFarbraum {
+    Grau,
+    Rgb,
+    Cmyk,
+    // Pattern, Separation, Indexed, ...
+    Unbekannt,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum PdfFarbe {
+    Grau(f32),
+    Rgb(f32, f32, f32),
+    Cmyk(f32, f32, f32, f32),
+    Unbekannt,
+}
+
+impl PdfFarbraum {
+    fn standardfarbe(&self) -> PdfFarbe {
+        match self {
+            PdfFarbraum::Grau => PdfFarbe::Grau(0.0),
+            PdfFarbraum::Rgb => PdfFarbe::Rgb(0.0, 0.0, 0.0),
+            PdfFarbraum::Cmyk => PdfFarbe::Cmyk(0.0, 0.0, 0.0, 1.0),
+            PdfFarbraum::Unbekannt => PdfFarbe::Unbekannt,
+        }
+    }
+
+    fn aus_name(
+        pdf: &lopdf::Document,
+        ressourcen: &[&lopdf::Dictionary],
+        name: &[u8],
+    ) -> PdfFarbraum {
+        match name {
+            b"DeviceGray" | b"CalGray" | b"G" => return PdfFarbraum::Grau,
+            b"DeviceRGB" | b"CalRGB" | b"RGB" => return PdfFarbraum::Rgb,
+            b"DeviceCMYK" | b"CMYK" => return PdfFarbraum::Cmyk,
+            b"Pattern" => return PdfFarbraum::Unbekannt,
+            _ => {}
+        }
+
+        // benannter Farbraum aus /Resources /ColorSpace, z.B. [/ICCBased 12 0 R]
+        let farbraum = match finde_ressource(pdf, ressourcen, b"ColorSpace", name)
+            .and_then(|o| o.as_array().ok())
+        {
+            Some(s) => s,
+            None => return PdfFarbraum::Unbekannt,
+        };
+
+        match farbraum.get(0).and_then(|o| o.as_name().ok()) {
+            Some(b"CalGray") => PdfFarbraum::Grau,
+            Some(b"CalRGB") | Some(b"Lab") => PdfFarbraum::Rgb,
+            Some(b"ICCBased") => {
+                let n = farbraum
+                    .get(1)
+                    .and_then(|o| pdf.dereference(o).ok())
+                    .and_then(|(_, o)| o.as_stream().ok())
+                    .and_then(|s| s.dict.get(b"N").ok())
+                    .and_then(|n| n.as_i64().ok());
+                match n {
+                    Some(1) => PdfFarbraum::Grau,
+                    Some(3) => PdfFarbraum::Rgb,
+                    Some(4) => PdfFarbraum::Cmyk,
+                    _ => PdfFarbraum::Unbekannt,
+                }
+            }
+            _ => PdfFarbraum::Unbekannt,
+        }
+    }
+}
+
+impl PdfFarbe {
+    fn aus_operanden(farbraum: PdfFarbraum, operanden: &[lopdf::Object]) -> PdfFarbe {
+        let werte = operanden.iter().map(pdf_zahl).collect::<Option<Vec<_>>>();
+        match (farbraum, werte.as_deref()) {
+            (PdfFarbraum::Grau, Some([g])) => PdfFarbe::Grau(*g),
+            (PdfFarbraum::Rgb, Some([r, g, b])) => PdfFarbe::Rgb(*r, *g, *b),
+            (PdfFarbraum::Cmyk, Some([c, m, y, k])) => PdfFarbe::Cmyk(*c, *m, *y, *k),
+            _ => PdfFarbe::Unbekannt,
+        }
+    }
+
+    fn ist_rot(&self) -> bool {
+        let (r, g, b) = match *self {
+            PdfFarbe::Rgb(r, g, b) => (r, g, b),
+            PdfFarbe::Cmyk(c, m, y, k) => (
+                (1.0 - c) * (1.0 - k),
+                (1.0 - m) * (1.0 - k),
+                (1.0 - y) * (1.0 - k),
+            ),
+            PdfFarbe::Grau(_) | PdfFarbe::Unbekannt => return false,
+        };
+        r >= 0.6 && g <= 0.4 && b <= 0.4 && r - g.max(b) >= 0.35
+    }
+}
+
+#[derive(Debug, Clone)]
 struct GraphicsState {
-    ctms: Vec<PdfMatrix>,
-    children: Vec<GraphicsState>,
+    ctm: PdfMatrix,
+    farbraum_linie: PdfFarbraum,
+    farbraum_flaeche: PdfFarbraum,
+    farbe_linie: PdfFarbe,
+    farbe_flaeche: PdfFarbe,
+    linienbreite: f32,
+}
+
+impl Default for GraphicsState {
+    fn default() -> Self {
+        GraphicsState {
+            ctm: PdfMatrix::identity(),
+            farbraum_linie: PdfFarbraum::Grau,
+            farbraum_flaeche: PdfFarbraum::Grau,
+            farbe_linie: PdfFarbe::Grau(0.0),
+            farbe_flaeche: PdfFarbe::Grau(0.0),
+            linienbreite: 1.0,
+        }
+    }
+}
+
+// Form XObjects können sich gegenseitig einbinden
+const MAX_XOBJECT_TIEFE: usize = 8;
+
+fn pdf_zahl(o: &lopdf::Object) -> Option<f32> {
+    o.as_f64()
+        .ok()
+        .or_else(|| o.as_i64().ok().map(|r| r as f64))
+        .map(|f| f as f32)
+}
+
+fn pdf_matrix(operanden: &[lopdf::Object]) -> Option<PdfMatrix> {
+    let m = operanden.iter().map(pdf_zahl).collect::<Option<Vec<_>>>()?;
+    match m.as_slice() {
+        [a, b, c, d, e, f] => Some(PdfMatrix::new(*a, *b, *c, *d, *e, *f)),
+        _ => None,
+    }
+}
+
+fn finde_ressource<'a>(
+    pdf: &'a lopdf::Document,
+    ressourcen: &[&'a lopdf::Dictionary],
+    kategorie: &[u8],
+    name: &[u8],
+) -> Option<&'a lopdf::Object> {
+    ressourcen.iter().find_map(|r| {
+        let kategorie = r.get_deref(kategorie, pdf).ok()?.as_dict().ok()?;
+        kategorie.get_deref(name, pdf).ok()
+    })
+}
+
+// Läuft durch einen Content-Stream und sammelt alle rot gezeichneten
+// Linien und rot gefüllten Flächen (auch innerhalb von Form XObjects)
+fn sammle_rote_linien(
+    pdf: &lopdf::Document,
+    operationen: &[lopdf::content::Operation],
+    ressourcen: &[&lopdf::Dictionary],
+    gs_start: GraphicsState,
+    tiefe: usize,
+    page_height_mm: f32,
+    linien: &mut Vec<ParsedLinie>,
+) {
+    let mut gs = gs_start;
+    let mut gs_stack = Vec::new();
+    let mut pfade: Vec<Vec<Punkt>> = Vec::new();
+
+    for op in operationen {
+        let operanden = op.operands.as_slice();
+        match op.operator.as_str() {
+            "q" => gs_stack.push(gs.clone()),
+            "Q" => {
+                if let Some(alt) = gs_stack.pop() {
+                    gs = alt;
+                }
+            }
+            "cm" => {
+                if let Some(m) = pdf_matrix(operanden) {
+                    gs.ctm = m.then(&gs.ctm);
+                }
+            }
+            "w" => {
+                if let Some(w) = operanden.get(0).and_then(pdf_zahl) {
+                    gs.linienbreite = w;
+                }
+            }
+            "gs" => {
+                let lw = operanden
+                    .get(0)
+                    .and_then(|o| o.as_name().ok())
+                    .and_then(|name| finde_ressource(pdf, ressourcen, b"ExtGState", name))
+                    .and_then(|o| o.as_dict().ok())
+                    .and_then(|d| d.get(b"LW").ok())
+                    .and_then(pdf_zahl);
+                if let Some(lw) = lw {
+                    gs.linienbreite = lw;
+                }
+            }
+            "G" | "RG" | "K" | "g" | "rg" | "k" => {
+                let farbraum = match op.operator.as_str() {
+                    "G" | "g" => PdfFarbraum::Grau,
+                    "RG" | "rg" => PdfFarbraum::Rgb,
+                    _ => PdfFarbraum::Cmyk,
+                };
+                let farbe = PdfFarbe::aus_operanden(farbraum, operanden);
+                if op.operator.chars().all(|c| c.is_uppercase()) {
+                    gs.farbraum_linie = farbraum;
+                    gs.farbe_linie = farbe;
+                } else {
+                    gs.farbraum_flaeche = farbraum;
+                    gs.farbe_flaeche = farbe;
+                }
+            }
+            "CS" | "cs" => {
+                let farbraum = match operanden.get(0).and_then(|o| o.as_name().ok()) {
+                    Some(name) => PdfFarbraum::aus_name(pdf, ressourcen, name),
+                    None => PdfFarbraum::Unbekannt,
+                };
+                if op.operator == "CS" {
+                    gs.farbraum_linie = farbraum;
+                    gs.farbe_linie = farbraum.standardfarbe();
+                } else {
+                    gs.farbraum_flaeche = farbraum;
+                    gs.farbe_flaeche = farbraum.standardfarbe();
+                }
+            }
+            "SC" | "SCN" => {
+                gs.farbe_linie = PdfFarbe::aus_operanden(gs.farbraum_linie, operanden);
+            }
+            "sc" | "scn" => {
+                gs.farbe_flaeche = PdfFarbe::aus_operanden(gs.farbraum_flaeche, operanden);
+            }
+            "m" => {
+                if let (Some(x), Some(y)) = (
+                    operanden.get(0).and_then(pdf_zahl),
+                    operanden.get(1).and_then(pdf_zahl),
+                ) {
+                    pfade.push(vec![Punkt { x, y }]);
+                }
+            }
+            "l" | "c" | "v" | "y" => {
+                // Bei Kurven zählt nur der Endpunkt
+                let n = operanden.len();
+                if let (Some(x), Some(y), Some(pfad)) = (
+                    operanden.get(n.saturating_sub(2)).and_then(pdf_zahl),
+                    operanden.get(n.saturating_sub(1)).and_then(pdf_zahl),
+                    pfade.last_mut(),
+                ) {
+                    pfad.push(Punkt { x, y });
+                }
+            }
+            "h" => {
+                if let Some(pfad) = pfade.last_mut() {
+                    if let Some(start) = pfad.first().cloned() {
+                        pfad.push(start);
+                    }
+                }
+            }
+            "re" => {
+                let re = operanden.iter().map(pdf_zahl).collect::<Option<Vec<_>>>();
+                if let Some([x, y, w, h]) = re.as_deref() {
+                    pfade.push(vec![
+                        Punkt { x: *x, y: *y },
+                        Punkt { x: x + w, y: *y },
+                        Punkt { x: x + w, y: y + h },
+                        Punkt { x: *x, y: y + h },
+                        Punkt { x: *x, y: *y },
+                    ]);
+                }
+            }
+            OP_PATH_PAINT_STROKE
+            | OP_PATH_PAINT_STROKE_CLOSE
+            | OP_PATH_PAINT_FILL_NZ
+            | OP_PATH_PAINT_FILL_NZ_OLD
+            | OP_PATH_PAINT_FILL_EO
+            | OP_PATH_PAINT_FILL_STROKE_NZ
+            | OP_PATH_PAINT_FILL_STROKE_CLOSE_NZ
+            | OP_PATH_PAINT_FILL_STROKE_EO
+            | OP_PATH_PAINT_FILL_STROKE_CLOSE_EO
+            | OP_PATH_PAINT_END => {
+                let operator = op.operator.as_str();
+                let zeichnet_linie = matches!(operator, "S" | "s" | "B" | "B*" | "b" | "b*");
+                let zeichnet_flaeche =
+                    matches!(operator, "f" | "F" | "f*" | "B" | "B*" | "b" | "b*");
+                let linie_rot = zeichnet_linie && gs.farbe_linie.ist_rot();
+                let flaeche_rot = zeichnet_flaeche && gs.farbe_flaeche.ist_rot();
+
+                if linie_rot || flaeche_rot {
+                    let schliessen = matches!(operator, "s" | "b" | "b*");
+                    for mut pfad in pfade.drain(..) {
+                        if pfad.is_empty() {
+                            continue;
+                        }
+                        if schliessen {
+                            if let Some(start) = pfad.first().cloned() {
+                                pfad.push(start);
+                            }
+                        }
+                        linien.push(ParsedLinie {
+                            punkte_point: pfad,
+                            ctm_transforms: vec![gs.ctm.clone()],
+                            linienbreite_pt: if linie_rot { gs.linienbreite } else { 0.0 },
+                            page_height_mm,
+                        });
+                    }
+                }
+
+                pfade.clear();
+            }
+            "Do" => {
+                if tiefe >= MAX_XOBJECT_TIEFE {
+                    continue;
+                }
+
+                let xobject = match operanden
+                    .get(0)
+                    .and_then(|o| o.as_name().ok())
+                    .and_then(|name| finde_ressource(pdf, ressourcen, b"XObject", name))
+                    .and_then(|o| o.as_stream().ok())
+                {
+                    Some(s) => s,
+                    None => continue,
+                };
+
+                if xobject.dict.get(b"Subtype").and_then(|s| s.as_name()).ok() != Some(b"Form") {
+                    continue;
+                }
+
+                let content = match lopdf::content::Content::decode(
+                    &xobject
+                        .decompressed_content()
+                        .unwrap_or_else(|_| xobject.content.clone()),
+                ) {
+                    Ok(o) => o,
+                    Err(_) => continue,
+                };
+
+                let form_ressourcen = xobject
+                    .dict
+                    .get_deref(b"Resources", pdf)
+                    .and_then(|r| r.as_dict())
+                    .ok();
+                let form_ressourcen = match form_ressourcen {
+                    Some(r) => vec![r],
+                    None => ressourcen.to_vec(),
+                };
+
+                let mut gs_form = gs.clone();
+                if let Some(m) = xobject
+                    .dict
+                    .get(b"Matrix")
+                    .and_then(|m| m.as_array())
+                    .ok()
+                    .and_then(|m| pdf_matrix(m))
+                {
+                    gs_form.ctm = m.then(&gs_form.ctm);
+                }
+
+                sammle_rote_linien(
+                    pdf,
+                    &content.operations,
+                    &form_ressourcen,
+                    gs_form,
+                    tiefe + 1,
+                    page_height_mm,
+                    linien,
+                );
+            }
+            _ => {}
+        }
+    }
 }
 
 pub fn get_rote_linien(pdf_bytes: &[u8]) -> Result<BTreeMap<String, Vec<Linie>>, Fehler> {
@@ -512,142 +894,27 @@ pub fn get_rote_linien(pdf_bytes: &[u8]) -> Result<BTreeMap<String, Vec<Linie>>,
         .filter_map(|(page_num, page_obj)| {
             let (_breite_mm, hoehe_mm) = seiten_dimensionen.get(&page_num)?;
             let content = pdf.get_and_decode_page_content(page_obj).ok()?;
-            let mut operations_reverse = content.operations.clone();
-            operations_reverse.reverse();
 
-            let line_end_operation_indexes = content
-                .operations
-                .iter()
-                .enumerate()
-                .filter_map(|(idx, op)| {
-                    if op.operator == OP_PATH_PAINT_STROKE {
-                        Some(idx)
-                    } else {
-                        None
-                    }
-                })
+            let (ressourcen_direkt, ressourcen_ids) = pdf.get_page_resources(page_obj);
+            let ressourcen = ressourcen_direkt
+                .into_iter()
+                .chain(
+                    ressourcen_ids
+                        .into_iter()
+                        .filter_map(|id| pdf.get_dictionary(id).ok()),
+                )
                 .collect::<Vec<_>>();
 
-            let cm_operations = line_end_operation_indexes
-                .iter()
-                .enumerate()
-                .filter_map(|(line_idx, end_idx)| {
-                    let mut ctm = PdfMatrix::identity();
-                    let mut graphics_states = Vec::new();
-
-                    for op in &content.operations[..*end_idx] {
-                        match op.operator.as_str() {
-                            "cm" => {
-                                let cm0 = op.operands.get(0)?;
-                                let cm1 = op.operands.get(1)?;
-                                let cm2 = op.operands.get(2)?;
-                                let cm3 = op.operands.get(3)?;
-                                let cm4 = op.operands.get(4)?;
-                                let cm5 = op.operands.get(5)?;
-
-                                let cm0 = (cm0
-                                    .as_f64()
-                                    .ok()
-                                    .or_else(|| cm0.as_i64().ok().map(|r| r as f64))?)
-                                    as f32;
-                                let cm1 = (cm1
-                                    .as_f64()
-                                    .ok()
-                                    .or_else(|| cm1.as_i64().ok().map(|r| r as f64))?)
-                                    as f32;
-                                let cm2 = (cm2
-                                    .as_f64()
-                                    .ok()
-                                    .or_else(|| cm2.as_i64().ok().map(|r| r as f64))?)
-                                    as f32;
-                                let cm3 = (cm3
-                                    .as_f64()
-                                    .ok()
-                                    .or_else(|| cm3.as_i64().ok().map(|r| r as f64))?)
-                                    as f32;
-                                let cm4 = (cm4
-                                    .as_f64()
-                                    .ok()
-                                    .or_else(|| cm4.as_i64().ok().map(|r| r as f64))?)
-                                    as f32;
-                                let cm5 = (cm5
-                                    .as_f64()
-                                    .ok()
-                                    .or_else(|| cm5.as_i64().ok().map(|r| r as f64))?)
-                                    as f32;
-
-                                ctm = PdfMatrix::new(cm0, cm1, cm2, cm3, cm4, cm5).then(&ctm);
-                            }
-                            "q" => {
-                                graphics_states.push(ctm.clone());
-                            }
-                            "Q" => {
-                                ctm = graphics_states.pop().unwrap_or(PdfMatrix::identity());
-                            }
-                            _ => {}
-                        }
-                    }
-
-                    Some((line_idx, vec![ctm]))
-                })
-                .collect::<BTreeMap<_, _>>();
-
-            let linien = line_end_operation_indexes
-                .iter()
-                .enumerate()
-                .filter_map(|(line_idx, end_idx)| {
-                    let last_m_operator_idx_reverse = operations_reverse
-                        .iter()
-                        .enumerate()
-                        .skip(operations_reverse.len().saturating_sub(*end_idx))
-                        .find_map(|(op_reverse_idx, op)| {
-                            if op.operator == "m" {
-                                Some(op_reverse_idx)
-                            } else {
-                                None
-                            }
-                        })?;
-
-                    let points = content.operations[(content.operations.len()
-                        - last_m_operator_idx_reverse)
-                        .saturating_sub(1)
-                        ..*end_idx]
-                        .iter()
-                        .filter_map(|op| match op.operator.as_str() {
-                            "m" => Some((op.operands.get(0)?, op.operands.get(1)?)),
-                            "l" => Some((op.operands.get(0)?, op.operands.get(1)?)),
-                            _ => None,
-                        })
-                        .filter_map(|(x, y)| {
-                            // pt to mm
-                            let x = (x
-                                .as_f64()
-                                .ok()
-                                .or_else(|| x.as_i64().ok().map(|r| r as f64))?)
-                                as f32;
-                            let y = (y
-                                .as_f64()
-                                .ok()
-                                .or_else(|| y.as_i64().ok().map(|r| r as f64))?)
-                                as f32;
-                            Some(Punkt { x, y })
-                        })
-                        .collect::<Vec<_>>();
-
-                    if points.is_empty() {
-                        None
-                    } else {
-                        Some(ParsedLinie {
-                            punkte_point: points,
-                            ctm_transforms: cm_operations
-                                .get(&line_idx)
-                                .cloned()
-                                .unwrap_or_default(),
-                            page_height_mm: *hoehe_mm,
-                        })
-                    }
-                })
-                .collect::<Vec<_>>();
+            let mut linien = Vec::new();
+            sammle_rote_linien(
+                &pdf,
+                &content.operations,
+                &ressourcen,
+                GraphicsState::default(),
+                0,
+                *hoehe_mm,
+                &mut linien,
+            );
 
             let linien = linien
                 .into_iter()
@@ -661,6 +928,345 @@ pub fn get_rote_linien(pdf_bytes: &[u8]) -> Result<BTreeMap<String, Vec<Linie>>,
     result
 }
 
+#[test]
+fn test_pdf_farbe_ist_rot() {
+    assert!(PdfFarbe::Rgb(1.0, 0.0, 0.0).ist_rot());
+    assert!(PdfFarbe::Rgb(0.8, 0.3, 0.3).ist_rot());
+    // zu dunkel bzw. zu blass
+    assert!(!PdfFarbe::Rgb(0.5, 0.0, 0.0).ist_rot());
+    assert!(!PdfFarbe::Rgb(1.0, 0.7, 0.7).ist_rot());
+    // Orange und Magenta
+    assert!(!PdfFarbe::Rgb(1.0, 0.5, 0.0).ist_rot());
+    assert!(!PdfFarbe::Rgb(1.0, 0.0, 0.5).ist_rot());
+    // CMYK: Magenta und Gelb ergeben Rot, mit Schwarzanteil zu dunkel
+    assert!(PdfFarbe::Cmyk(0.0, 1.0, 1.0, 0.0).ist_rot());
+    assert!(!PdfFarbe::Cmyk(0.0, 1.0, 1.0, 0.6).ist_rot());
+    assert!(!PdfFarbe::Cmyk(0.0, 0.0, 0.0, 1.0).ist_rot());
+    assert!(!PdfFarbe::Grau(0.5).ist_rot());
+    assert!(!PdfFarbe::Unbekannt.ist_rot());
+}
+
+#[test]
+fn test_sammle_rote_linien() {
+    use lopdf::content::Operation;
+
+    let op = |operator: &str, werte: &[f32]| {
+        Operation::new(operator, werte.iter().map(|w| (*w).into()).collect())
+    };
+    let operationen = vec![
+        op("w", &[2.0]),
+        // rote Linie
+        op("RG", &[1.0, 0.0, 0.0]),
+        op("m", &[10.0, 10.0]),
+        op("l", &[100.0, 10.0]),
+        op("S", &[]),
+        // schwarze Linie
+        op("RG", &[0.0, 0.0, 0.0]),
+        op("m", &[10.0, 20.0]),
+        op("l", &[100.0, 20.0]),
+        op("S", &[]),
+        // rote Fläche, Farbe gilt nur bis "Q"
+        op("q", &[]),
+        op("rg", &[1.0, 0.0, 0.0]),
+        op("re", &[10.0, 30.0, 50.0, 5.0]),
+        op("f", &[]),
+        op("Q", &[]),
+        op("re", &[10.0, 40.0, 50.0, 5.0]),
+        op("f", &[]),
+    ];
+
+    let mut linien = Vec::new();
+    sammle_rote_linien(
+        &lopdf::Document::new(),
+        &operationen,
+        &[],
+        GraphicsState::default(),
+        0,
+        297.0,
+        &mut linien,
+    );
+
+    assert_eq!(linien.len(), 2);
+    assert_eq!(linien[0].linienbreite_pt, 2.0);
+    assert_eq!(
+        linien[0]
+            .punkte_point
+            .iter()
+            .map(|p| (p.x, p.y))
+            .collect::<Vec<_>>(),
+        vec![(10.0, 10.0), (100.0, 10.0)]
+    );
+    assert_eq!(linien[1].linienbreite_pt, 0.0);
+    assert_eq!(linien[1].punkte_point.len(), 5);
+}
+
+/// Rote Linien, die auf dem Bild einer (eingescannten) Seite gefunden wurden,
+/// Koordinaten und Linienbreite in Pixeln
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RoteLinienBild {
+    pub breite_px: u32,
+    pub hoehe_px: u32,
+    pub linien: Vec<RoteLinieBild>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoteLinieBild {
+    pub punkte: Vec<Punkt>,
+    pub breite_px: f32,
+}
+
+impl RoteLinienBild {
+    /// Rechnet die Linien anhand der Auflösung, mit der die Seite
+    /// (breite_mm x hoehe_mm) gerendert wurde, in Millimeter um
+    pub fn in_mm(&self, breite_mm: f32, hoehe_mm: f32) -> Vec<Linie> {
+        if self.breite_px == 0 || self.hoehe_px == 0 || breite_mm <= 0.0 || hoehe_mm <= 0.0 {
+            return Vec::new();
+        }
+
+        let px_pro_mm_x = self.breite_px as f32 / breite_mm;
+        let px_pro_mm_y = self.hoehe_px as f32 / hoehe_mm;
+
+        self.linien
+            .iter()
+            .map(|l| Linie {
+                punkte: l
+                    .punkte
+                    .iter()
+                    .map(|p| Punkt {
+                        x: p.x / px_pro_mm_x,
+                        y: p.y / px_pro_mm_y,
+                    })
+                    .collect(),
+                breite_mm: l.breite_px / px_pro_mm_x.min(px_pro_mm_y),
+            })
+            .collect()
+    }
+}
+
+fn ist_roter_pixel(pixel: &image::Rgb<u8>) -> bool {
+    let [r, g, b] = pixel.0;
+    let r = r as i32;
+    let gb = (g as i32).max(b as i32);
+    // eingescannte Rötungen sind blasser als in elektronischen PDFs
+    r >= 120 && r - gb >= 60
+}
+
+// Lücken bis zu dieser Größe (in Pixeln) gehören noch zur selben Linie
+const ROT_NACHBARSCHAFT_PX: i64 = 2;
+
+/// Sucht auf dem farbigen Seitenbild nach roten Durchstreichungen
+/// (waagerecht, senkrecht, diagonal oder als Kreuz)
+pub fn get_rote_linien_aus_bild(bild: &image::RgbImage) -> RoteLinienBild {
+    let (breite, hoehe) = bild.dimensions();
+    let mut besucht = vec![false; breite as usize * hoehe as usize];
+    let mut linien = Vec::new();
+
+    // kürzere rote Flächen sind eher Schrift oder Stempel
+    let mindestlaenge = (breite.max(hoehe) / 40).max(10) as f32;
+
+    for start_y in 0..hoehe {
+        for start_x in 0..breite {
+            let start_idx = (start_y * breite + start_x) as usize;
+            if besucht[start_idx] || !ist_roter_pixel(bild.get_pixel(start_x, start_y)) {
+                continue;
+            }
+
+            // zusammenhängende rote Fläche sammeln
+            besucht[start_idx] = true;
+            let mut stack = vec![(start_x, start_y)];
+            let mut pixel = Vec::new();
+            while let Some((x, y)) = stack.pop() {
+                pixel.push((x, y));
+                for dy in -ROT_NACHBARSCHAFT_PX..=ROT_NACHBARSCHAFT_PX {
+                    for dx in -ROT_NACHBARSCHAFT_PX..=ROT_NACHBARSCHAFT_PX {
+                        let nx = x as i64 + dx;
+                        let ny = y as i64 + dy;
+                        if nx < 0 || ny < 0 || nx >= breite as i64 || ny >= hoehe as i64 {
+                            continue;
+                        }
+                        let idx = (ny as u32 * breite + nx as u32) as usize;
+                        if besucht[idx] || !ist_roter_pixel(bild.get_pixel(nx as u32, ny as u32)) {
+                            continue;
+                        }
+                        besucht[idx] = true;
+                        stack.push((nx as u32, ny as u32));
+                    }
+                }
+            }
+
+            linien.extend(klassifiziere_rote_flaeche(&pixel, mindestlaenge));
+        }
+    }
+
+    RoteLinienBild {
+        breite_px: breite,
+        hoehe_px: hoehe,
+        linien,
+    }
+}
+
+fn klassifiziere_rote_flaeche(pixel: &[(u32, u32)], mindestlaenge: f32) -> Vec<RoteLinieBild> {
+    let min_x = pixel.iter().map(|p| p.0).min().unwrap_or(0) as f32;
+    let max_x = pixel.iter().map(|p| p.0).max().unwrap_or(0) as f32 + 1.0;
+    let min_y = pixel.iter().map(|p| p.1).min().unwrap_or(0) as f32;
+    let max_y = pixel.iter().map(|p| p.1).max().unwrap_or(0) as f32 + 1.0;
+    let breite = max_x - min_x;
+    let hoehe = max_y - min_y;
+    let laenge = breite.max(hoehe);
+
+    if laenge < mindestlaenge {
+        return Vec::new();
+    }
+
+    let dicke = pixel.len() as f32 / laenge;
+
+    // waagerechter Strich
+    if hoehe * 8.0 <= breite {
+        let y = min_y + hoehe / 2.0;
+        return vec![RoteLinieBild {
+            punkte: vec![Punkt { x: min_x, y }, Punkt { x: max_x, y }],
+            breite_px: hoehe,
+        }];
+    }
+
+    // senkrechter Strich
+    if breite * 8.0 <= hoehe {
+        let x = min_x + breite / 2.0;
+        return vec![RoteLinieBild {
+            punkte: vec![Punkt { x, y: min_y }, Punkt { x, y: max_y }],
+            breite_px: breite,
+        }];
+    }
+
+    // ausgefüllte Flächen (Stempel, Markierungen) sind keine Durchstreichungen
+    if pixel.len() as f32 > breite * hoehe * 0.5 {
+        return Vec::new();
+    }
+
+    // Diagonale oder Kreuz: Pixel müssen nahe an einer der beiden Diagonalen liegen
+    let toleranz = 0.1 + (dicke / laenge).min(0.1);
+    let mut auf_hauptdiagonale = 0;
+    let mut auf_nebendiagonale = 0;
+    let mut auf_keiner = 0;
+    for (x, y) in pixel.iter() {
+        let u = (*x as f32 - min_x) / breite;
+        let v = (*y as f32 - min_y) / hoehe;
+        let haupt = (u - v).abs() <= toleranz;
+        let neben = (u + v - 1.0).abs() <= toleranz;
+        if haupt {
+            auf_hauptdiagonale += 1;
+        }
+        if neben {
+            auf_nebendiagonale += 1;
+        }
+        if !haupt && !neben {
+            auf_keiner += 1;
+        }
+    }
+
+    if auf_keiner as f32 > pixel.len() as f32 * 0.3 {
+        return Vec::new();
+    }
+
+    let mut linien = Vec::new();
+    let mindestanteil = pixel.len() as f32 * 0.25;
+    if auf_hauptdiagonale as f32 >= mindestanteil {
+        linien.push(RoteLinieBild {
+            punkte: vec![Punkt { x: min_x, y: min_y }, Punkt { x: max_x, y: max_y }],
+            breite_px: auf_hauptdiagonale as f32 / laenge,
+        });
+    }
+    if auf_nebendiagonale as f32 >= mindestanteil {
+        linien.push(RoteLinieBild {
+            punkte: vec![Punkt { x: min_x, y: max_y }, Punkt { x: max_x, y: min_y }],
+            breite_px: auf_nebendiagonale as f32 / laenge,
+        });
+    }
+    linien
+}
+
+#[test]
+fn test_ist_roter_pixel() {
+    assert!(ist_roter_pixel(&image::Rgb([255, 0, 0])));
+    // blasse Rötung auf eingescannter Seite
+    assert!(ist_roter_pixel(&image::Rgb([200, 110, 120])));
+    assert!(!ist_roter_pixel(&image::Rgb([100, 0, 0])));
+    assert!(!ist_roter_pixel(&image::Rgb([255, 220, 220])));
+    assert!(!ist_roter_pixel(&image::Rgb([255, 200, 0])));
+    assert!(!ist_roter_pixel(&image::Rgb([0, 0, 0])));
+    assert!(!ist_roter_pixel(&image::Rgb([255, 255, 255])));
+}
+
+#[test]
+fn test_klassifiziere_rote_flaeche() {
+    // waagerechter Strich, 100 x 3 Pixel
+    let strich = (0..100)
+        .flat_map(|x| (0..3).map(move |y| (x, y + 10)))
+        .collect::<Vec<_>>();
+    let linien = klassifiziere_rote_flaeche(&strich, 10.0);
+    assert_eq!(linien.len(), 1);
+    assert_eq!(linien[0].breite_px, 3.0);
+    assert_eq!(
+        linien[0]
+            .punkte
+            .iter()
+            .map(|p| (p.x, p.y))
+            .collect::<Vec<_>>(),
+        vec![(0.0, 11.5), (100.0, 11.5)]
+    );
+
+    // zu kurz
+    assert!(klassifiziere_rote_flaeche(&strich[..15], 10.0).is_empty());
+
+    // ausgefülltes Quadrat
+    let quadrat = (0..30)
+        .flat_map(|x| (0..30).map(move |y| (x, y)))
+        .collect::<Vec<_>>();
+    assert!(klassifiziere_rote_flaeche(&quadrat, 10.0).is_empty());
+}
+
+#[test]
+fn test_get_rote_linien_aus_bild() {
+    let rot = image::Rgb([220, 30, 30]);
+    let mut bild = image::RgbImage::from_pixel(400, 400, image::Rgb([255, 255, 255]));
+
+    // waagerechte Rötung
+    for x in 20..220 {
+        for y in 50..53 {
+            bild.put_pixel(x, y, rot);
+        }
+    }
+    // schwarze Linie wird ignoriert
+    for x in 20..220 {
+        bild.put_pixel(x, 70, image::Rgb([0, 0, 0]));
+    }
+    // Kreuz
+    for i in 0..200 {
+        for t in 0..2 {
+            bild.put_pixel(100 + i + t, 100 + i, rot);
+            bild.put_pixel(300 - i - t, 100 + i, rot);
+        }
+    }
+    // Stempel
+    for x in 20..50 {
+        for y in 350..380 {
+            bild.put_pixel(x, y, rot);
+        }
+    }
+
+    let rote_linien = get_rote_linien_aus_bild(&bild);
+    assert_eq!(rote_linien.breite_px, 400);
+    assert_eq!(rote_linien.hoehe_px, 400);
+    assert_eq!(rote_linien.linien.len(), 3);
+    assert_eq!(rote_linien.linien[0].breite_px, 3.0);
+
+    // 400 px auf 200 mm Seitenbreite: 2 px pro mm
+    let in_mm = rote_linien.in_mm(200.0, 200.0);
+    assert_eq!(in_mm[0].breite_mm, 1.5);
+    assert_eq!(in_mm[0].punkte[0].x, 10.0);
+    assert_eq!(in_mm[0].punkte[1].x, 110.0);
+}
+
 // Funktion, die das Titelblatt ausliest
 pub fn lese_titelblatt(pdf_bytes: &[u8]) -> Result<Titelblatt, Fehler> {
     let doc = lopdf::Document::load_mem(pdf_bytes)?;
@@ -3563,6 +4169,10 @@ pub struct Grundbuch {
     #[serde(default)]
     #[serde(skip_serializing_if = "Abteilung3::is_empty")]
     pub abt3: Abteilung3,
+    // Quittierte Warnungen / Fehler der Analyse
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub quittierungen: Vec<Quittierung>,
 }
 
 impl Grundbuch {
@@ -3573,21 +4183,540 @@ impl Grundbuch {
             abt1: Abteilung1::default(),
             abt2: Abteilung2::default(),
             abt3: Abteilung3::default(),
+            quittierungen: Vec::new(),
         }
     }
-}
 
-#[derive(Debug, Default, Clone, PartialOrd, PartialEq, Serialize, Deserialize)]
-pub struct PositionInPdf {
-    pub seite: String,
-    #[serde(default)]
-    pub rect: OptRect,
-}
+    /// Vergibt neue IDs an alle Einträge ohne ID oder mit doppelter ID.
+    /// Die `id`-Felder der Einträge sind stabil: Sie werden bei der
+    /// Digitalisierung vergeben und bleiben bei Bearbeitung und erneuter
+    /// Analyse erhalten, so dass z.B. Quittierungen am Eintrag hängen bleiben
+    pub fn ids_vergeben(&mut self) {
+        let mut vergeben = BTreeSet::new();
+        for abschnitt in ABSCHNITTE {
+            for id in self.abschnitt_ids_mut(abschnitt) {
+                if id.is_empty() || vergeben.contains(id.as_str()) {
+                    *id = neue_id();
+                }
+                vergeben.insert(id.clone());
+            }
+        }
+    }
 
-impl PositionInPdf {
-    pub fn ist_geroetet(&self, hocr: &HocrLayout) -> bool {
-        let seite = match hocr.seiten.get(&self.seite) {
-            Some(s) => s,
+    /// Übernimmt nach einer erneuten Analyse die IDs der bisherigen Einträge.
+    /// Zugeordnet wird zuerst über lfd. Nr. (bzw. BV-Nr. / Spalte 1) und Text,
+    /// dann über den Text allein (Eintrag wurde umnummeriert) und zuletzt
+    /// über die lfd. Nr. allein (Text wurde anders erkannt)
+    pub fn ids_uebernehmen(&mut self, alt: &Grundbuch) {
+        let bv_inhalt = |e: &BvEintrag| format!("{}/{}", e.get_flur(), e.get_flurstueck());
+
+        ids_zuordnen(
+            alt.bestandsverzeichnis
+                .eintraege
+                .iter()
+                .map(|e| (e.get_lfd_nr().to_string(), bv_inhalt(e), e.get_id())),
+            self.bestandsverzeichnis
+                .eintraege
+                .iter_mut()
+                .map(|e| (e.get_lfd_nr().to_string(), bv_inhalt(e), e.id_mut())),
+        );
+        ids_zuordnen(
+            alt.bestandsverzeichnis
+                .zuschreibungen
+                .iter()
+                .map(|e| (e.bv_nr.text(), e.text.text(), e.id.clone())),
+            self.bestandsverzeichnis
+                .zuschreibungen
+                .iter_mut()
+                .map(|e| (e.bv_nr.text(), e.text.text(), &mut e.id)),
+        );
+        ids_zuordnen(
+            alt.bestandsverzeichnis
+                .abschreibungen
+                .iter()
+                .map(|e| (e.bv_nr.text(), e.text.text(), e.id.clone())),
+            self.bestandsverzeichnis
+                .abschreibungen
+                .iter_mut()
+                .map(|e| (e.bv_nr.text(), e.text.text(), &mut e.id)),
+        );
+        ids_zuordnen(
+            alt.abt1
+                .eintraege
+                .iter()
+                .map(|e| (e.get_lfd_nr().to_string(), e.get_eigentuemer(), e.get_id())),
+            self.abt1
+                .eintraege
+                .iter_mut()
+                .map(|e| (e.get_lfd_nr().to_string(), e.get_eigentuemer(), e.id_mut())),
+        );
+        ids_zuordnen(
+            alt.abt1
+                .grundlagen_eintragungen
+                .iter()
+                .map(|e| (e.bv_nr.text(), e.text.text(), e.id.clone())),
+            self.abt1
+                .grundlagen_eintragungen
+                .iter_mut()
+                .map(|e| (e.bv_nr.text(), e.text.text(), &mut e.id)),
+        );
+        ids_zuordnen(
+            alt.abt1
+                .veraenderungen
+                .iter()
+                .map(|e| (e.lfd_nr.text(), e.text.text(), e.id.clone())),
+            self.abt1
+                .veraenderungen
+                .iter_mut()
+                .map(|e| (e.lfd_nr.text(), e.text.text(), &mut e.id)),
+        );
+        ids_zuordnen(
+            alt.abt1
+                .loeschungen
+                .iter()
+                .map(|e| (e.lfd_nr.text(), e.text.text(), e.id.clone())),
+            self.abt1
+                .loeschungen
+                .iter_mut()
+                .map(|e| (e.lfd_nr.text(), e.text.text(), &mut e.id)),
+        );
+        ids_zuordnen(
+            alt.abt2
+                .eintraege
+                .iter()
+                .map(|e| (e.lfd_nr.to_string(), e.text.text(), e.id.clone())),
+            self.abt2
+                .eintraege
+                .iter_mut()
+                .map(|e| (e.lfd_nr.to_string(), e.text.text(), &mut e.id)),
+        );
+        ids_zuordnen(
+            alt.abt2
+                .veraenderungen
+                .iter()
+                .map(|e| (e.lfd_nr.text(), e.text.text(), e.id.clone())),
+            self.abt2
+                .veraenderungen
+                .iter_mut()
+                .map(|e| (e.lfd_nr.text(), e.text.text(), &mut e.id)),
+        );
+        ids_zuordnen(
+            alt.abt2
+                .loeschungen
+                .iter()
+                .map(|e| (e.lfd_nr.text(), e.text.text(), e.id.clone())),
+            self.abt2
+                .loeschungen
+                .iter_mut()
+                .map(|e| (e.lfd_nr.text(), e.text.text(), &mut e.id)),
+        );
+        ids_zuordnen(
+            alt.abt3
+                .eintraege
+                .iter()
+                .map(|e| (e.lfd_nr.to_string(), e.text.text(), e.id.clone())),
+            self.abt3
+                .eintraege
+                .iter_mut()
+                .map(|e| (e.lfd_nr.to_string(), e.text.text(), &mut e.id)),
+        );
+        ids_zuordnen(
+            alt.abt3
+                .veraenderungen
+                .iter()
+                .map(|e| (e.lfd_nr.text(), e.text.text(), e.id.clone())),
+            self.abt3
+                .veraenderungen
+                .iter_mut()
+                .map(|e| (e.lfd_nr.text(), e.text.text(), &mut e.id)),
+        );
+        ids_zuordnen(
+            alt.abt3
+                .loeschungen
+                .iter()
+                .map(|e| (e.lfd_nr.text(), e.text.text(), e.id.clone())),
+            self.abt3
+                .loeschungen
+                .iter_mut()
+                .map(|e| (e.lfd_nr.text(), e.text.text(), &mut e.id)),
+        );
+
+        self.ids_vergeben();
+    }
+
+    /// Index der Zeile mit der gegebenen ID im Abschnitt ("bv", "abt2-veraenderung", ...),
+    /// so wie er in den Pfaden von `Cmd::EditText` verwendet wird
+    pub fn zeile_nach_id(&self, abschnitt: &str, id: &str) -> Option<usize> {
+        self.abschnitt_ids(abschnitt).iter().position(|i| i == id)
+    }
+
+    /// Liest die Zeile aus einem Pfad ("bv:3:lfd-nr" oder "bv:<id>:lfd-nr")
+    pub fn zeile_aufloesen(&self, abschnitt: &str, zeile: &str) -> Option<usize> {
+        zeile
+            .parse::<usize>()
+            .ok()
+            .or_else(|| self.zeile_nach_id(abschnitt, zeile))
+    }
+
+    /// IDs der Einträge eines Abschnitts, nach Index
+    pub fn abschnitt_ids(&self, abschnitt: &str) -> Vec<String> {
+        match abschnitt {
+            "bv" => self
+                .bestandsverzeichnis
+                .eintraege
+                .iter()
+                .map(|e| e.get_id())
+                .collect(),
+            "bv-zuschreibung" => self
+                .bestandsverzeichnis
+                .zuschreibungen
+                .iter()
+                .map(|e| e.id.clone())
+                .collect(),
+            "bv-abschreibung" => self
+                .bestandsverzeichnis
+                .abschreibungen
+                .iter()
+                .map(|e| e.id.clone())
+                .collect(),
+            "abt1" => self.abt1.eintraege.iter().map(|e| e.get_id()).collect(),
+            "abt1-grundlage-eintragung" => self
+                .abt1
+                .grundlagen_eintragungen
+                .iter()
+                .map(|e| e.id.clone())
+                .collect(),
+            "abt1-veraenderung" => self
+                .abt1
+                .veraenderungen
+                .iter()
+                .map(|e| e.id.clone())
+                .collect(),
+            "abt1-loeschung" => self.abt1.loeschungen.iter().map(|e| e.id.clone()).collect(),
+            "abt2" => self.abt2.eintraege.iter().map(|e| e.id.clone()).collect(),
+            "abt2-veraenderung" => self
+                .abt2
+                .veraenderungen
+                .iter()
+                .map(|e| e.id.clone())
+                .collect(),
+            "abt2-loeschung" => self.abt2.loeschungen.iter().map(|e| e.id.clone()).collect(),
+            "abt3" => self.abt3.eintraege.iter().map(|e| e.id.clone()).collect(),
+            "abt3-veraenderung" => self
+                .abt3
+                .veraenderungen
+                .iter()
+                .map(|e| e.id.clone())
+                .collect(),
+            "abt3-loeschung" => self.abt3.loeschungen.iter().map(|e| e.id.clone()).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Einträge eines Abschnitts als JSON, nach Index
+    pub fn abschnitt_json(&self, abschnitt: &str) -> Vec<serde_json::Value> {
+        fn json<T: serde::Serialize>(eintraege: &[T]) -> Vec<serde_json::Value> {
+            eintraege
+                .iter()
+                .filter_map(|e| serde_json::to_value(e).ok())
+                .collect()
+        }
+
+        match abschnitt {
+            "bv" => json(&self.bestandsverzeichnis.eintraege),
+            "bv-zuschreibung" => json(&self.bestandsverzeichnis.zuschreibungen),
+            "bv-abschreibung" => json(&self.bestandsverzeichnis.abschreibungen),
+            "abt1" => json(&self.abt1.eintraege),
+            "abt1-grundlage-eintragung" => json(&self.abt1.grundlagen_eintragungen),
+            "abt1-veraenderung" => json(&self.abt1.veraenderungen),
+            "abt1-loeschung" => json(&self.abt1.loeschungen),
+            "abt2" => json(&self.abt2.eintraege),
+            "abt2-veraenderung" => json(&self.abt2.veraenderungen),
+            "abt2-loeschung" => json(&self.abt2.loeschungen),
+            "abt3" => json(&self.abt3.eintraege),
+            "abt3-veraenderung" => json(&self.abt3.veraenderungen),
+            "abt3-loeschung" => json(&self.abt3.loeschungen),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Vergleicht die Einträge über ihre ID mit `alt`: neue, geänderte und
+    /// entfernte Einträge, je Abschnitt in der Reihenfolge des Grundbuchs
+    pub fn eintraege_vergleichen(&self, alt: &Grundbuch) -> Vec<EintragAenderung> {
+        let mut aenderungen = Vec::new();
+
+        for abschnitt in ABSCHNITTE {
+            let alt_eintraege = alt
+                .abschnitt_ids(abschnitt)
+                .into_iter()
+                .zip(alt.abschnitt_json(abschnitt).into_iter())
+                .collect::<Vec<_>>();
+            let neu_eintraege = self
+                .abschnitt_ids(abschnitt)
+                .into_iter()
+                .zip(self.abschnitt_json(abschnitt).into_iter())
+                .collect::<Vec<_>>();
+
+            for (id, neu) in neu_eintraege.iter() {
+                let alt = alt_eintraege.iter().find(|(a, _)| a == id).map(|(_, e)| e);
+                if alt != Some(neu) {
+                    aenderungen.push(EintragAenderung {
+                        abschnitt: abschnitt.to_string(),
+                        id: id.clone(),
+                        alt: alt.cloned(),
+                        neu: Some(neu.clone()),
+                    });
+                }
+            }
+
+            for (id, alt) in alt_eintraege.iter() {
+                if !neu_eintraege.iter().any(|(n, _)| n == id) {
+                    aenderungen.push(EintragAenderung {
+                        abschnitt: abschnitt.to_string(),
+                        id: id.clone(),
+                        alt: Some(alt.clone()),
+                        neu: None,
+                    });
+                }
+            }
+        }
+
+        aenderungen
+    }
+
+    /// Setzt die IDs der Einträge eines Abschnitts, nach Index
+    pub fn abschnitt_ids_setzen(&mut self, abschnitt: &str, ids: &[String]) {
+        for (id, neu) in self
+            .abschnitt_ids_mut(abschnitt)
+            .into_iter()
+            .zip(ids.iter())
+        {
+            *id = neu.clone();
+        }
+    }
+
+    fn abschnitt_ids_mut(&mut self, abschnitt: &str) -> Vec<&mut String> {
+        match abschnitt {
+            "bv" => self
+                .bestandsverzeichnis
+                .eintraege
+                .iter_mut()
+                .map(|e| e.id_mut())
+                .collect(),
+            "bv-zuschreibung" => self
+                .bestandsverzeichnis
+                .zuschreibungen
+                .iter_mut()
+                .map(|e| &mut e.id)
+                .collect(),
+            "bv-abschreibung" => self
+                .bestandsverzeichnis
+                .abschreibungen
+                .iter_mut()
+                .map(|e| &mut e.id)
+                .collect(),
+            "abt1" => self.abt1.eintraege.iter_mut().map(|e| e.id_mut()).collect(),
+            "abt1-grundlage-eintragung" => self
+                .abt1
+                .grundlagen_eintragungen
+                .iter_mut()
+                .map(|e| &mut e.id)
+                .collect(),
+            "abt1-veraenderung" => self
+                .abt1
+                .veraenderungen
+                .iter_mut()
+                .map(|e| &mut e.id)
+                .collect(),
+            "abt1-loeschung" => self
+                .abt1
+                .loeschungen
+                .iter_mut()
+                .map(|e| &mut e.id)
+                .collect(),
+            "abt2" => self.abt2.eintraege.iter_mut().map(|e| &mut e.id).collect(),
+            "abt2-veraenderung" => self
+                .abt2
+                .veraenderungen
+                .iter_mut()
+                .map(|e| &mut e.id)
+                .collect(),
+            "abt2-loeschung" => self
+                .abt2
+                .loeschungen
+                .iter_mut()
+                .map(|e| &mut e.id)
+                .collect(),
+            "abt3" => self.abt3.eintraege.iter_mut().map(|e| &mut e.id).collect(),
+            "abt3-veraenderung" => self
+                .abt3
+                .veraenderungen
+                .iter_mut()
+                .map(|e| &mut e.id)
+                .collect(),
+            "abt3-loeschung" => self
+                .abt3
+                .loeschungen
+                .iter_mut()
+                .map(|e| &mut e.id)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Unterschied eines einzelnen Eintrags zwischen zwei Ständen eines Grundbuchs,
+/// `alt` bzw. `neu` fehlen bei neuen bzw. entfernten Einträgen
+#[derive(Debug, Clone, PartialEq)]
+pub struct EintragAenderung {
+    pub abschnitt: String,
+    pub id: String,
+    pub alt: Option<serde_json::Value>,
+    pub neu: Option<serde_json::Value>,
+}
+
+/// Abschnitte des Grundbuchs, wie sie in den Pfaden von `Cmd::EditText` stehen
+pub const ABSCHNITTE: [&str; 13] = [
+    "bv",
+    "bv-zuschreibung",
+    "bv-abschreibung",
+    "abt1",
+    "abt1-grundlage-eintragung",
+    "abt1-veraenderung",
+    "abt1-loeschung",
+    "abt2",
+    "abt2-veraenderung",
+    "abt2-loeschung",
+    "abt3",
+    "abt3-veraenderung",
+    "abt3-loeschung",
+];
+
+/// Neue zufällige ID (UUID v4) für einen Grundbucheintrag
+pub fn neue_id() -> String {
+    let mut bytes = rand::random::<[u8; 16]>();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex::encode(bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+// Ordnet den neuen Einträgen die IDs der alten Einträge zu: zuerst bei
+// gleichem Schlüssel und Inhalt, dann bei gleichem Inhalt, dann bei gleichem
+// Schlüssel. Jede alte ID wird höchstens einmal vergeben, bei mehrfach
+// vorkommenden Schlüsseln in der Reihenfolge ihres Vorkommens
+fn ids_zuordnen<'a>(
+    alt: impl Iterator<Item = (String, String, String)>,
+    neu: impl Iterator<Item = (String, String, &'a mut String)>,
+) {
+    let mut alt = alt
+        .filter(|(_, _, id)| !id.is_empty())
+        .map(|(schluessel, inhalt, id)| (schluessel, inhalt, Some(id)))
+        .collect::<Vec<_>>();
+    let mut neu = neu
+        .map(|(schluessel, inhalt, id)| {
+            id.clear();
+            (schluessel, inhalt, id)
+        })
+        .collect::<Vec<_>>();
+
+    // (Schlüssel alt, Inhalt alt, Schlüssel neu, Inhalt neu)
+    let stufen: [fn(&str, &str, &str, &str) -> bool; 3] = [
+        |a_s, a_i, n_s, n_i| a_s == n_s && a_i == n_i,
+        |_, a_i, _, n_i| !a_i.trim().is_empty() && a_i == n_i,
+        |a_s, _, n_s, _| a_s == n_s,
+    ];
+
+    for passt in stufen {
+        for (schluessel, inhalt, id) in neu.iter_mut() {
+            if !id.is_empty() {
+                continue;
+            }
+            let treffer = alt
+                .iter_mut()
+                .find(|(a_s, a_i, a_id)| a_id.is_some() && passt(a_s, a_i, schluessel, inhalt));
+            if let Some((_, _, a_id)) = treffer {
+                **id = a_id.take().unwrap_or_default();
+            }
+        }
+    }
+}
+
+#[test]
+fn test_ids_uebernehmen() {
+    let titelblatt = Titelblatt {
+        amtsgericht: "Prenzlau".to_string(),
+        grundbuch_von: "Ludwigsburg".to_string(),
+        blatt: "254".to_string(),
+    };
+
+    let mut alt = Grundbuch::new(titelblatt.clone());
+    alt.abt2.eintraege = vec![Abt2Eintrag::new(1), Abt2Eintrag::new(2)];
+
+    // Erneute Analyse: Eintrag 1 wurde nicht mehr erkannt
+    let mut neu = Grundbuch::new(titelblatt);
+    neu.abt2.eintraege = vec![Abt2Eintrag::new(2), Abt2Eintrag::new(3)];
+    neu.abt2.eintraege.push(Abt2Eintrag {
+        id: String::new(),
+        ..Abt2Eintrag::new(4)
+    });
+    neu.ids_uebernehmen(&alt);
+
+    assert_eq!(neu.abt2.eintraege[0].id, alt.abt2.eintraege[1].id);
+    assert_ne!(neu.abt2.eintraege[1].id, alt.abt2.eintraege[0].id);
+    assert!(!neu.abt2.eintraege[2].id.is_empty());
+
+    // Umnummerierter Eintrag wird über den Text zugeordnet
+    let mut alt_abt3 = Abt3Eintrag::new(1);
+    alt_abt3.text = "Grundschuld zu 10.000 DM für die Sparkasse"
+        .to_string()
+        .into();
+    alt.abt3.eintraege = vec![alt_abt3.clone()];
+    let mut neu_abt3 = Abt3Eintrag::new(2);
+    neu_abt3.text = alt_abt3.text.clone();
+    neu.abt3.eintraege = vec![Abt3Eintrag::new(1), neu_abt3];
+    neu.ids_uebernehmen(&alt);
+
+    assert_eq!(neu.abt2.eintraege[0].id, alt.abt2.eintraege[1].id);
+    assert_eq!(neu.abt3.eintraege[1].id, alt_abt3.id);
+    assert_ne!(neu.abt3.eintraege[0].id, alt_abt3.id);
+
+    let id = neu.abt2.eintraege[1].id.clone();
+    assert_eq!(neu.zeile_aufloesen("abt2", &id), Some(1));
+    assert_eq!(neu.zeile_aufloesen("abt2", "2"), Some(2));
+    assert_eq!(neu.zeile_aufloesen("abt3", &id), None);
+
+    // Vergleich über die ID, nicht über die Position
+    let mut geaendert = neu.clone();
+    geaendert.abt2.eintraege.swap(0, 1);
+    geaendert.abt2.eintraege[0].text = "Wegerecht".to_string().into();
+    geaendert.abt3.eintraege.remove(0);
+    let aenderungen = geaendert.eintraege_vergleichen(&neu);
+    assert_eq!(aenderungen.len(), 2);
+    assert_eq!(aenderungen[0].abschnitt, "abt2");
+    assert_eq!(aenderungen[0].id, neu.abt2.eintraege[1].id);
+    assert_eq!(aenderungen[1].abschnitt, "abt3");
+    assert!(aenderungen[1].neu.is_none());
+}
+
+#[derive(Debug, Default, Clone, PartialOrd, PartialEq, Serialize, Deserialize)]
+pub struct PositionInPdf {
+    pub seite: String,
+    #[serde(default)]
+    pub rect: OptRect,
+}
+
+impl PositionInPdf {
+    pub fn ist_geroetet(&self, hocr: &HocrLayout) -> bool {
+        let seite = match hocr.seiten.get(&self.seite) {
+            Some(s) => s,
             None => return false,
         };
         let rect = Rect {
@@ -3625,6 +4754,254 @@ impl OptRect {
     }
 }
 
+// Rötung einer einzelnen Zelle eines Eintrags, z.B. nur die alte Größe
+// im BV oder eine einzelne Zeile in der Spalte "lfd. Nr. der betroffenen Grundstücke"
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct ZelleGeroetet {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub automatisch_geroetet: Option<bool>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manuell_geroetet: Option<bool>,
+    // Zeilen innerhalb der Zelle (ab 0), falls nicht die ganze Zelle gerötet ist
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub automatisch_geroetete_zeilen: BTreeSet<usize>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub manuell_geroetete_zeilen: BTreeMap<usize, bool>,
+}
+
+impl ZelleGeroetet {
+    pub fn ist_geroetet(&self) -> bool {
+        self.manuell_geroetet
+            .or(self.automatisch_geroetet.clone())
+            .unwrap_or(false)
+    }
+
+    // Manuelle Rötung einer Zeile geht vor, auch wenn die ganze Zelle gerötet ist
+    pub fn ist_zeile_geroetet(&self, zeile: usize) -> bool {
+        self.manuell_geroetete_zeilen
+            .get(&zeile)
+            .copied()
+            .unwrap_or(self.ist_geroetet() || self.automatisch_geroetete_zeilen.contains(&zeile))
+    }
+
+    pub fn ist_leer(&self) -> bool {
+        !self.ist_geroetet()
+            && self.automatisch_geroetete_zeilen.is_empty()
+            && self.manuell_geroetete_zeilen.values().all(|v| !*v)
+    }
+}
+
+// Schlüssel ist die Spalten-ID wie im Bearbeitungspfad ("bv:0:groesse" => "groesse")
+pub type ZellenGeroetet = BTreeMap<String, ZelleGeroetet>;
+
+pub fn ist_zelle_geroetet(zellen: &ZellenGeroetet, spalte: &str) -> bool {
+    zellen
+        .get(spalte)
+        .map(|z| z.ist_geroetet())
+        .unwrap_or(false)
+}
+
+pub fn ist_zeile_geroetet(zellen: &ZellenGeroetet, spalte: &str, zeile: usize) -> bool {
+    zellen
+        .get(spalte)
+        .map(|z| z.ist_zeile_geroetet(zeile))
+        .unwrap_or(false)
+}
+
+// Gibt die Zeilen einer Zelle ohne die geröteten Zeilen zurück
+pub fn ungeroetete_zeilen(text: &StringOrLines, zellen: &ZellenGeroetet, spalte: &str) -> String {
+    text.lines()
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| !ist_zeile_geroetet(zellen, spalte, *i))
+        .map(|(_, l)| l)
+        .collect::<Vec<_>>()
+        .join("\r\n")
+}
+
+// Prüft beim Einlesen einer Zeile für jede Zelle (und jede Textzeile in
+// der Zelle), ob sie von einer roten Linie überdeckt wird
+pub struct RoetungsErkennung<'a> {
+    hocr: &'a HocrLayout,
+    seite: String,
+    // Eine Spalte kann aus mehreren Textblöcken bestehen (Größe: ha / a / m²)
+    zellen: BTreeMap<String, Vec<ZelleGeroetet>>,
+}
+
+impl<'a> RoetungsErkennung<'a> {
+    pub fn neu(hocr: &'a HocrLayout, seite: &str) -> Self {
+        Self {
+            hocr,
+            seite: seite.to_string(),
+            zellen: BTreeMap::new(),
+        }
+    }
+
+    pub fn pruefe(&mut self, spalte: &str, t: &Textblock) {
+        let seite = match self.hocr.seiten.get(&self.seite) {
+            Some(s) => s,
+            None => return,
+        };
+
+        let anzahl_zeilen = t.text.trim().lines().count();
+        if anzahl_zeilen == 0 {
+            return;
+        }
+
+        // Zelle in gleich hohe Streifen pro Textzeile aufteilen, leicht
+        // verkleinert, damit Linien auf der Grenze nicht beide Zeilen röten
+        let hoehe_zeile = (t.end_y - t.start_y) / anzahl_zeilen as f32;
+        let geroetete_zeilen = (0..anzahl_zeilen)
+            .filter(|i| {
+                let start_y = t.start_y + *i as f32 * hoehe_zeile;
+                seite.ist_eintrag_geroetet(&Rect {
+                    min_x: t.start_x,
+                    max_x: t.end_x,
+                    min_y: start_y + hoehe_zeile * 0.1,
+                    max_y: start_y + hoehe_zeile * 0.9,
+                })
+            })
+            .collect::<BTreeSet<_>>();
+
+        let zelle = if geroetete_zeilen.len() == anzahl_zeilen {
+            ZelleGeroetet {
+                automatisch_geroetet: Some(true),
+                ..Default::default()
+            }
+        } else {
+            ZelleGeroetet {
+                automatisch_geroetet: Some(false),
+                automatisch_geroetete_zeilen: geroetete_zeilen,
+                ..Default::default()
+            }
+        };
+
+        self.zellen
+            .entry(spalte.to_string())
+            .or_insert_with(Vec::new)
+            .push(zelle);
+    }
+
+    fn get_zellen(&self) -> ZellenGeroetet {
+        self.zellen
+            .iter()
+            .map(|(spalte, teile)| {
+                let zelle = ZelleGeroetet {
+                    automatisch_geroetet: Some(teile.iter().all(|t| t.ist_geroetet())),
+                    automatisch_geroetete_zeilen: teile
+                        .iter()
+                        .flat_map(|t| t.automatisch_geroetete_zeilen.iter().copied())
+                        .collect(),
+                    ..Default::default()
+                };
+                (spalte.clone(), zelle)
+            })
+            .collect()
+    }
+
+    // Der ganze Eintrag ist gerötet, wenn alle ausgefüllten Zellen gerötet sind.
+    // Früher reichte eine Linie irgendwo im Bereich des Eintrags - dann wäre
+    // z.B. jedes Flurstück mit geröteter alter Größe komplett gerötet und die
+    // Rötung einzelner Zellen hätte keine Wirkung.
+    pub fn eintrag_geroetet(&self) -> bool {
+        !self.zellen.is_empty() && self.get_zellen().values().all(|z| z.ist_geroetet())
+    }
+
+    // Teilweise Rötung, leer, falls der ganze Eintrag gerötet ist
+    pub fn get_zellen_geroetet(&self) -> ZellenGeroetet {
+        if self.eintrag_geroetet() {
+            return BTreeMap::new();
+        }
+
+        self.get_zellen()
+            .into_iter()
+            .filter(|(_, z)| !z.ist_leer())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+fn hocr_mit_roter_linie(von: (f32, f32), bis: (f32, f32)) -> HocrLayout {
+    let seite = HocrSeite {
+        breite_mm: 210.0,
+        hoehe_mm: 297.0,
+        rote_linien: vec![Linie {
+            punkte: vec![Punkt { x: von.0, y: von.1 }, Punkt { x: bis.0, y: bis.1 }],
+            breite_mm: 0.5,
+        }],
+        ..Default::default()
+    };
+    HocrLayout {
+        seiten: [("1".to_string(), seite)].into_iter().collect(),
+    }
+}
+
+#[cfg(test)]
+fn textblock(text: &str, start_x: f32, end_x: f32) -> Textblock {
+    Textblock {
+        text: text.to_string(),
+        start_y: 15.0,
+        end_y: 25.0,
+        start_x,
+        end_x,
+    }
+}
+
+#[test]
+fn test_roetung_einzelner_zelle() {
+    // nur die alte Größe ist durchgestrichen
+    let hocr = hocr_mit_roter_linie((100.0, 20.0), (150.0, 20.0));
+    let mut roetung = RoetungsErkennung::neu(&hocr, "1");
+    roetung.pruefe("flurstueck", &textblock("12", 10.0, 50.0));
+    roetung.pruefe("groesse", &textblock("500", 100.0, 150.0));
+
+    assert!(!roetung.eintrag_geroetet());
+    let zellen = roetung.get_zellen_geroetet();
+    assert!(ist_zelle_geroetet(&zellen, "groesse"));
+    assert!(!ist_zelle_geroetet(&zellen, "flurstueck"));
+}
+
+#[test]
+fn test_roetung_ganzer_eintrag() {
+    let hocr = hocr_mit_roter_linie((5.0, 20.0), (160.0, 20.0));
+    let mut roetung = RoetungsErkennung::neu(&hocr, "1");
+    roetung.pruefe("flurstueck", &textblock("12", 10.0, 50.0));
+    roetung.pruefe("groesse", &textblock("500", 100.0, 150.0));
+    // leere Zellen zählen nicht
+    roetung.pruefe("bezeichnung", &textblock("", 60.0, 90.0));
+
+    assert!(roetung.eintrag_geroetet());
+    assert!(roetung.get_zellen_geroetet().is_empty());
+}
+
+// Regression: Nach der alten Regel (rote Linie irgendwo im Bereich des Eintrags)
+// war das Flurstück ganz gerötet, obwohl nur die alte Größe durchgestrichen ist.
+// Jetzt muss jede ausgefüllte Zelle gerötet sein
+#[test]
+fn test_roetung_eintrag_nur_wenn_alle_zellen_geroetet() {
+    let hocr = hocr_mit_roter_linie((100.0, 20.0), (150.0, 20.0));
+    let flurstueck = textblock("12", 10.0, 50.0);
+    let groesse = textblock("500", 100.0, 150.0);
+
+    let mut position = PositionInPdf {
+        seite: "1".to_string(),
+        rect: OptRect::default(),
+    };
+    position.expand(&flurstueck);
+    position.expand(&groesse);
+    assert!(position.ist_geroetet(&hocr));
+
+    let mut roetung = RoetungsErkennung::neu(&hocr, "1");
+    roetung.pruefe("flurstueck", &flurstueck);
+    roetung.pruefe("groesse", &groesse);
+    assert!(!roetung.eintrag_geroetet());
+    assert_eq!(roetung.get_zellen_geroetet().len(), 1);
+}
+
 impl PositionInPdf {
     pub fn expand(&mut self, t: &Textblock) {
         self.rect.min_x = Some(self.rect.min_x.get_or_insert(t.start_x).min(t.start_x));
@@ -3663,13 +5040,18 @@ impl Bestandsverzeichnis {
             && self.abschreibungen.is_empty()
     }
 
+    // Zu- und Abschreibungen gehören dazu, da sie die Fortführung der Flurstücke bestimmen
     pub fn get_eintraege_hash(&self) -> String {
         use sha2::Digest;
         let mut hasher = sha2::Sha256::default();
         hasher.update(
-            serde_json::to_string_pretty(&self.eintraege)
-                .unwrap_or_default()
-                .as_bytes(),
+            serde_json::to_string_pretty(&(
+                &self.eintraege,
+                &self.zuschreibungen,
+                &self.abschreibungen,
+            ))
+            .unwrap_or_default()
+            .as_bytes(),
         );
         let hash = hasher.finalize();
         hex::encode(hash)
@@ -3680,6 +5062,8 @@ impl Bestandsverzeichnis {
 #[serde(untagged)]
 pub enum BvEintrag {
     Flurstueck(BvEintragFlurstueck),
+    // muss vor Recht stehen, da BvEintragRecht nur lfd_nr als Pflichtfeld hat
+    Erbbaurecht(BvEintragErbbaurecht),
     Recht(BvEintragRecht),
 }
 
@@ -3705,6 +5089,20 @@ impl fmt::Display for BvEintrag {
                 let zu_nr = zu_nr.text();
                 write!(f, "Grundstücksgleiches Recht {lfd_nr} (zu Nr. {zu_nr}, bisher {bisherige_lfd_nr:?})")
             }
+            BvEintrag::Erbbaurecht(BvEintragErbbaurecht {
+                lfd_nr,
+                bisherige_lfd_nr,
+                grundstueck,
+                ..
+            }) => {
+                write!(
+                    f,
+                    "Erbbaurecht {lfd_nr} an {} Blatt {} BV-Nr. {} (bisher {bisherige_lfd_nr:?})",
+                    grundstueck.grundbuch_von,
+                    grundstueck.blatt,
+                    grundstueck.bv_nr.text()
+                )
+            }
         }
     }
 }
@@ -3712,6 +5110,9 @@ impl fmt::Display for BvEintrag {
 // Eintrag für ein grundstücksgleiches Recht
 #[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct BvEintragRecht {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub id: String,
     pub lfd_nr: usize,
     #[serde(default)]
     #[serde(skip_serializing_if = "StringOrLines::is_empty")]
@@ -3729,12 +5130,121 @@ pub struct BvEintragRecht {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub manuell_geroetet: Option<bool>,
     #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub zellen_geroetet: ZellenGeroetet,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position_in_pdf: Option<PositionInPdf>,
+}
+
+// Eintrag für ein Erbbaurecht im Bestandsverzeichnis eines Erbbaugrundbuchs
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct BvEintragErbbaurecht {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub id: String,
+    pub lfd_nr: usize,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bisherige_lfd_nr: Option<usize>,
+    // Pflichtfeld, damit der Eintrag nicht als BvEintragRecht gelesen wird
+    pub grundstueck: ErbbauGrundstueck,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "StringOrLines::is_empty")]
+    pub dauer: StringOrLines,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub zustimmungsvorbehalte: Vec<Zustimmungsvorbehalt>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "StringOrLines::is_empty")]
+    pub text: StringOrLines,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub automatisch_geroetet: Option<bool>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manuell_geroetet: Option<bool>,
+    #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub position_in_pdf: Option<PositionInPdf>,
 }
 
+// Grundstück (im Grundstücks-Blatt), an dem das Erbbaurecht bestellt ist
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct ErbbauGrundstueck {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub grundbuch_von: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub blatt: String,
+    // lfd. Nr. des Grundstücks im BV des Grundstücks-Blatts
+    #[serde(default)]
+    #[serde(skip_serializing_if = "StringOrLines::is_empty")]
+    pub bv_nr: StringOrLines,
+    // lfd. Nr. des Erbbaurechts in Abt. 2 des Grundstücks-Blatts
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abt2_lfd_nr: Option<usize>,
+}
+
+impl ErbbauGrundstueck {
+    pub fn ist_leer(&self) -> bool {
+        self.grundbuch_von.is_empty()
+            && self.blatt.is_empty()
+            && self.bv_nr.is_empty()
+            && self.abt2_lfd_nr.is_none()
+    }
+
+    pub fn get_bv_nr(&self) -> Vec<usize> {
+        self.bv_nr
+            .text()
+            .split(|c: char| !c.is_ascii_digit())
+            .filter_map(|s| s.parse::<usize>().ok())
+            .collect()
+    }
+}
+
+// Zustimmungsvorbehalte des Grundstückseigentümers nach § 5 ErbbauRG
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Serialize, Deserialize)]
+pub enum Zustimmungsvorbehalt {
+    #[serde(rename = "veraeusserung")]
+    Veraeusserung,
+    #[serde(rename = "belastung")]
+    Belastung,
+}
+
+impl Zustimmungsvorbehalt {
+    pub fn get_id(&self) -> &'static str {
+        match self {
+            Zustimmungsvorbehalt::Veraeusserung => "zustimmung-veraeusserung",
+            Zustimmungsvorbehalt::Belastung => "zustimmung-belastung",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "zustimmung-veraeusserung" => Some(Zustimmungsvorbehalt::Veraeusserung),
+            "zustimmung-belastung" => Some(Zustimmungsvorbehalt::Belastung),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Zustimmungsvorbehalt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Zustimmungsvorbehalt::Veraeusserung => write!(f, "Veräußerung"),
+            Zustimmungsvorbehalt::Belastung => write!(f, "Belastung"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct BvEintragFlurstueck {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub id: String,
     pub lfd_nr: usize,
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -3759,13 +5269,85 @@ pub struct BvEintragFlurstueck {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub manuell_geroetet: Option<bool>,
     #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub zellen_geroetet: ZellenGeroetet,
+    #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub position_in_pdf: Option<PositionInPdf>,
 }
 
+/// Flurstücksnummer, aufgeteilt in Zähler und (optionalen) Nenner, z.B. "17/3"
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct FlurstueckNummer {
+    pub zaehler: usize,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nenner: Option<usize>,
+}
+
+impl FlurstueckNummer {
+    /// Liest "17/3", "17 / 3", "0017/0003" oder "17", außerdem typische
+    /// OCR-Varianten des Bruchstrichs ("17|3", "17\3") und "O" statt "0"
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s
+            .trim()
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| match c {
+                '|' | '\\' => '/',
+                'O' | 'o' => '0',
+                c => c,
+            })
+            .collect::<String>();
+
+        let mut teile = s.split('/');
+        let zaehler = teile.next()?;
+        let nenner = teile.next();
+
+        if teile.next().is_some() {
+            return None;
+        }
+
+        let zahl = |t: &str| -> Option<usize> {
+            if t.is_empty() || !t.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            t.parse().ok()
+        };
+
+        Some(FlurstueckNummer {
+            zaehler: zahl(zaehler)?,
+            nenner: match nenner {
+                Some(n) => Some(zahl(n)?),
+                None => None,
+            },
+        })
+    }
+}
+
+impl fmt::Display for FlurstueckNummer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.nenner {
+            Some(n) => write!(f, "{}/{}", self.zaehler, n),
+            None => write!(f, "{}", self.zaehler),
+        }
+    }
+}
+
+/// Vergleicht zwei Flurstücksnummern nach Zähler / Nenner, so dass "17/3",
+/// "17 / 3" und "017/3" als gleich gelten. Nicht lesbare Nummern werden
+/// als Text verglichen.
+pub fn flurstueck_gleich(a: &str, b: &str) -> bool {
+    match (FlurstueckNummer::parse(a), FlurstueckNummer::parse(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a.trim() == b.trim(),
+    }
+}
+
 impl BvEintragFlurstueck {
     pub fn neu(lfd_nr: usize) -> Self {
         BvEintragFlurstueck {
+            id: neue_id(),
             lfd_nr,
             bisherige_lfd_nr: None,
             flur: 0,
@@ -3775,6 +5357,7 @@ impl BvEintragFlurstueck {
             groesse: FlurstueckGroesse::default(),
             automatisch_geroetet: None,
             manuell_geroetet: None,
+            zellen_geroetet: BTreeMap::new(),
             position_in_pdf: None,
         }
     }
@@ -3783,15 +5366,121 @@ impl BvEintragFlurstueck {
 impl BvEintragRecht {
     pub fn neu(lfd_nr: usize) -> Self {
         BvEintragRecht {
+            id: neue_id(),
             lfd_nr,
             zu_nr: String::new().into(),
             bisherige_lfd_nr: None,
             text: String::new().into(),
             automatisch_geroetet: None,
             manuell_geroetet: None,
+            zellen_geroetet: BTreeMap::new(),
+            position_in_pdf: None,
+        }
+    }
+}
+
+impl BvEintragErbbaurecht {
+    pub fn neu(lfd_nr: usize) -> Self {
+        BvEintragErbbaurecht {
+            id: neue_id(),
+            lfd_nr,
+            bisherige_lfd_nr: None,
+            grundstueck: ErbbauGrundstueck::default(),
+            dauer: String::new().into(),
+            zustimmungsvorbehalte: Vec::new(),
+            text: String::new().into(),
+            automatisch_geroetet: None,
+            manuell_geroetet: None,
             position_in_pdf: None,
         }
     }
+
+    // Liest Grundstücks-Blatt, Dauer und Zustimmungsvorbehalte aus dem
+    // Text ("Erbbaurecht an dem Grundstück ... eingetragen in Blatt ...")
+    pub fn aus_text(lfd_nr: usize, text: &str) -> Self {
+        use lazy_static::lazy_static;
+        use regex::Regex;
+
+        lazy_static! {
+            static ref REGEX_GRUNDBUCH_VON: Regex =
+                Regex::new(r"Grundbuch\s+von\s+([^\s,;]+(?:\s+[^\s,;]+)??)\s+Blatt").unwrap();
+            static ref REGEX_BLATT: Regex = Regex::new(r"Blatt\s+(\d+[a-zA-Z]?)").unwrap();
+            static ref REGEX_BV_NR: Regex =
+                Regex::new(r"Nr\.\s+(\d+(?:\s*(?:,|und)\s*\d+)*)\s+(?:des\s+)?(?:Bestandsverzeichnis|BV)")
+                    .unwrap();
+            static ref REGEX_DAUER: Regex = Regex::new(
+                r"(?:[Ff]ür\s+die\s+Dauer\s+von\s+\d+\s+Jahren(?:\s+seit\s+dem\s+Tage\s+der\s+Eintragung)?|bis\s+zum\s+\d{1,2}\.\s?\d{1,2}\.\s?\d{4})"
+            )
+            .unwrap();
+        }
+
+        let text_einzeilig = text.lines().collect::<Vec<_>>().join(" ");
+
+        let mut zustimmungsvorbehalte = Vec::new();
+        if text_einzeilig.contains("Zustimmung") || text_einzeilig.contains("zustimmung") {
+            if text_einzeilig.contains("Veräußerung") {
+                zustimmungsvorbehalte.push(Zustimmungsvorbehalt::Veraeusserung);
+            }
+            if text_einzeilig.contains("Belastung") {
+                zustimmungsvorbehalte.push(Zustimmungsvorbehalt::Belastung);
+            }
+        }
+
+        BvEintragErbbaurecht {
+            grundstueck: ErbbauGrundstueck {
+                grundbuch_von: REGEX_GRUNDBUCH_VON
+                    .captures(&text_einzeilig)
+                    .and_then(|c| c.get(1))
+                    .map(|m| m.as_str().trim().to_string())
+                    .unwrap_or_default(),
+                blatt: REGEX_BLATT
+                    .captures(&text_einzeilig)
+                    .and_then(|c| c.get(1))
+                    .map(|m| m.as_str().to_string())
+                    .unwrap_or_default(),
+                bv_nr: REGEX_BV_NR
+                    .captures(&text_einzeilig)
+                    .and_then(|c| c.get(1))
+                    .map(|m| m.as_str().to_string())
+                    .unwrap_or_default()
+                    .into(),
+                abt2_lfd_nr: None,
+            },
+            dauer: REGEX_DAUER
+                .find(&text_einzeilig)
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_default()
+                .into(),
+            zustimmungsvorbehalte,
+            text: text.to_string().into(),
+            ..BvEintragErbbaurecht::neu(lfd_nr)
+        }
+    }
+}
+
+#[test]
+fn test_erbbaurecht_aus_text() {
+    let text = "Erbbaurecht an dem im Grundbuch von Musterdorf Blatt 123 unter Nr. 2 des \
+                Bestandsverzeichnisses verzeichneten Grundstück, eingetragen in Abteilung II Nr. 1, \
+                für die Dauer von 99 Jahren seit dem Tage der Eintragung. Der Erbbauberechtigte \
+                bedarf zur Veräußerung und Belastung des Erbbaurechts der Zustimmung des \
+                Grundstückseigentümers.";
+
+    let erbbaurecht = BvEintragErbbaurecht::aus_text(1, text);
+    assert_eq!(erbbaurecht.grundstueck.grundbuch_von, "Musterdorf");
+    assert_eq!(erbbaurecht.grundstueck.blatt, "123");
+    assert_eq!(erbbaurecht.grundstueck.get_bv_nr(), vec![2]);
+    assert_eq!(
+        erbbaurecht.dauer.text(),
+        "für die Dauer von 99 Jahren seit dem Tage der Eintragung"
+    );
+    assert_eq!(
+        erbbaurecht.zustimmungsvorbehalte,
+        vec![
+            Zustimmungsvorbehalt::Veraeusserung,
+            Zustimmungsvorbehalt::Belastung
+        ]
+    );
 }
 
 impl BvEintrag {
@@ -3803,6 +5492,23 @@ impl BvEintrag {
         match self {
             BvEintrag::Flurstueck(flst) => flst.position_in_pdf.clone(),
             BvEintrag::Recht(recht) => recht.position_in_pdf.clone(),
+            BvEintrag::Erbbaurecht(erbbaurecht) => erbbaurecht.position_in_pdf.clone(),
+        }
+    }
+
+    pub fn get_id(&self) -> String {
+        match self {
+            BvEintrag::Flurstueck(flst) => flst.id.clone(),
+            BvEintrag::Recht(recht) => recht.id.clone(),
+            BvEintrag::Erbbaurecht(erbbaurecht) => erbbaurecht.id.clone(),
+        }
+    }
+
+    pub fn id_mut(&mut self) -> &mut String {
+        match self {
+            BvEintrag::Flurstueck(flst) => &mut flst.id,
+            BvEintrag::Recht(recht) => &mut recht.id,
+            BvEintrag::Erbbaurecht(erbbaurecht) => &mut erbbaurecht.id,
         }
     }
 
@@ -3810,6 +5516,7 @@ impl BvEintrag {
         match self {
             BvEintrag::Flurstueck(flst) => flst.flur,
             BvEintrag::Recht(recht) => 0,
+            BvEintrag::Erbbaurecht(_) => 0,
         }
     }
 
@@ -3817,6 +5524,15 @@ impl BvEintrag {
         match self {
             BvEintrag::Flurstueck(flst) => flst.flurstueck.clone(),
             BvEintrag::Recht(recht) => String::new(),
+            BvEintrag::Erbbaurecht(_) => String::new(),
+        }
+    }
+
+    pub fn get_flurstueck_nummer(&self) -> Option<FlurstueckNummer> {
+        match self {
+            BvEintrag::Flurstueck(flst) => FlurstueckNummer::parse(&flst.flurstueck),
+            BvEintrag::Recht(_) => None,
+            BvEintrag::Erbbaurecht(_) => None,
         }
     }
 
@@ -3824,6 +5540,7 @@ impl BvEintrag {
         match self {
             BvEintrag::Flurstueck(flst) => flst.gemarkung.clone(),
             BvEintrag::Recht(_) => None,
+            BvEintrag::Erbbaurecht(_) => None,
         }
     }
 
@@ -3843,6 +5560,13 @@ impl BvEintrag {
                     && recht.bisherige_lfd_nr == None
                     && recht.text.is_empty()
             }
+            BvEintrag::Erbbaurecht(erbbaurecht) => {
+                (erbbaurecht.lfd_nr == 0 || erbbaurecht.lfd_nr == 1)
+                    && erbbaurecht.bisherige_lfd_nr == None
+                    && erbbaurecht.grundstueck.ist_leer()
+                    && erbbaurecht.dauer.is_empty()
+                    && erbbaurecht.text.is_empty()
+            }
         }
     }
 
@@ -3854,6 +5578,43 @@ impl BvEintrag {
             BvEintrag::Recht(recht) => recht
                 .manuell_geroetet
                 .unwrap_or(recht.automatisch_geroetet.unwrap_or(false)),
+            BvEintrag::Erbbaurecht(erbbaurecht) => erbbaurecht
+                .manuell_geroetet
+                .unwrap_or(erbbaurecht.automatisch_geroetet.unwrap_or(false)),
+        }
+    }
+
+    // Erbbaurechte haben keine Rötung einzelner Zellen
+    pub fn get_zellen_geroetet(&self) -> Option<&ZellenGeroetet> {
+        match self {
+            BvEintrag::Flurstueck(flst) => Some(&flst.zellen_geroetet),
+            BvEintrag::Recht(recht) => Some(&recht.zellen_geroetet),
+            BvEintrag::Erbbaurecht(_) => None,
+        }
+    }
+
+    pub fn get_zellen_geroetet_mut(&mut self) -> Option<&mut ZellenGeroetet> {
+        match self {
+            BvEintrag::Flurstueck(flst) => Some(&mut flst.zellen_geroetet),
+            BvEintrag::Recht(recht) => Some(&mut recht.zellen_geroetet),
+            BvEintrag::Erbbaurecht(_) => None,
+        }
+    }
+
+    pub fn ist_zelle_geroetet(&self, spalte: &str) -> bool {
+        self.ist_geroetet()
+            || self
+                .get_zellen_geroetet()
+                .map(|z| ist_zelle_geroetet(z, spalte))
+                .unwrap_or(false)
+    }
+
+    // Flurstück existiert nicht mehr, wenn der Eintrag oder die
+    // Flurstücksnummer gerötet ist (z.B. nach einer Zerlegung)
+    pub fn ist_flurstueck_geroetet(&self) -> bool {
+        match self {
+            BvEintrag::Flurstueck(_) => self.ist_zelle_geroetet("flurstueck"),
+            BvEintrag::Recht(_) | BvEintrag::Erbbaurecht(_) => self.ist_geroetet(),
         }
     }
 
@@ -3867,6 +5628,7 @@ impl BvEintrag {
                 };
             }
             BvEintrag::Recht(_) => {}
+            BvEintrag::Erbbaurecht(_) => {}
         }
     }
 
@@ -3874,6 +5636,7 @@ impl BvEintrag {
         match self {
             BvEintrag::Flurstueck(flst) => flst.bezeichnung.clone().map(|s| s.text()),
             BvEintrag::Recht(recht) => None,
+            BvEintrag::Erbbaurecht(_) => None,
         }
     }
 
@@ -3881,6 +5644,7 @@ impl BvEintrag {
         match self {
             BvEintrag::Flurstueck(flst) => Some(flst.groesse.clone()),
             BvEintrag::Recht(recht) => None,
+            BvEintrag::Erbbaurecht(_) => None,
         }
     }
 
@@ -3888,6 +5652,7 @@ impl BvEintrag {
         match self {
             BvEintrag::Flurstueck(flst) => flst.lfd_nr,
             BvEintrag::Recht(recht) => recht.lfd_nr,
+            BvEintrag::Erbbaurecht(erbbaurecht) => erbbaurecht.lfd_nr,
         }
     }
 
@@ -3895,6 +5660,7 @@ impl BvEintrag {
         match self {
             BvEintrag::Flurstueck(flst) => flst.lfd_nr = nr,
             BvEintrag::Recht(recht) => recht.lfd_nr = nr,
+            BvEintrag::Erbbaurecht(erbbaurecht) => erbbaurecht.lfd_nr = nr,
         }
     }
 
@@ -3902,6 +5668,7 @@ impl BvEintrag {
         match self {
             BvEintrag::Flurstueck(flst) => flst.bisherige_lfd_nr,
             BvEintrag::Recht(recht) => recht.bisherige_lfd_nr,
+            BvEintrag::Erbbaurecht(erbbaurecht) => erbbaurecht.bisherige_lfd_nr,
         }
     }
 
@@ -3909,12 +5676,14 @@ impl BvEintrag {
         match self {
             BvEintrag::Flurstueck(flst) => flst.bisherige_lfd_nr = nr,
             BvEintrag::Recht(recht) => recht.bisherige_lfd_nr = nr,
+            BvEintrag::Erbbaurecht(erbbaurecht) => erbbaurecht.bisherige_lfd_nr = nr,
         }
     }
 
     pub fn set_zu_nr(&mut self, val: String) {
         match self {
             BvEintrag::Flurstueck(_) => {}
+            BvEintrag::Erbbaurecht(_) => {}
             BvEintrag::Recht(recht) => {
                 recht.zu_nr = val.into();
             }
@@ -3924,6 +5693,9 @@ impl BvEintrag {
     pub fn set_recht_text(&mut self, val: String) {
         match self {
             BvEintrag::Flurstueck(_) => {}
+            BvEintrag::Erbbaurecht(erbbaurecht) => {
+                erbbaurecht.text = val.into();
+            }
             BvEintrag::Recht(recht) => {
                 recht.text = val.into();
             }
@@ -3936,6 +5708,7 @@ impl BvEintrag {
                 flst.gemarkung = val;
             }
             BvEintrag::Recht(_) => {}
+            BvEintrag::Erbbaurecht(_) => {}
         }
     }
 
@@ -3945,6 +5718,7 @@ impl BvEintrag {
                 flst.flur = val;
             }
             BvEintrag::Recht(_) => {}
+            BvEintrag::Erbbaurecht(_) => {}
         }
     }
 
@@ -3954,6 +5728,7 @@ impl BvEintrag {
                 flst.flurstueck = val;
             }
             BvEintrag::Recht(_) => {}
+            BvEintrag::Erbbaurecht(_) => {}
         }
     }
 
@@ -3963,6 +5738,7 @@ impl BvEintrag {
                 flst.groesse = val;
             }
             BvEintrag::Recht(_) => {}
+            BvEintrag::Erbbaurecht(_) => {}
         }
     }
 
@@ -3974,6 +5750,9 @@ impl BvEintrag {
             BvEintrag::Recht(recht) => {
                 recht.automatisch_geroetet = None;
             }
+            BvEintrag::Erbbaurecht(erbbaurecht) => {
+                erbbaurecht.automatisch_geroetet = None;
+            }
         }
     }
 
@@ -3981,6 +5760,7 @@ impl BvEintrag {
         match self {
             BvEintrag::Flurstueck(flst) => flst.automatisch_geroetet,
             BvEintrag::Recht(recht) => recht.automatisch_geroetet,
+            BvEintrag::Erbbaurecht(erbbaurecht) => erbbaurecht.automatisch_geroetet,
         }
     }
 
@@ -3992,6 +5772,9 @@ impl BvEintrag {
             BvEintrag::Recht(recht) => {
                 recht.automatisch_geroetet = Some(val);
             }
+            BvEintrag::Erbbaurecht(erbbaurecht) => {
+                erbbaurecht.automatisch_geroetet = Some(val);
+            }
         }
     }
 
@@ -3999,6 +5782,7 @@ impl BvEintrag {
         match self {
             BvEintrag::Flurstueck(flst) => flst.manuell_geroetet,
             BvEintrag::Recht(recht) => recht.manuell_geroetet,
+            BvEintrag::Erbbaurecht(erbbaurecht) => erbbaurecht.manuell_geroetet,
         }
     }
 
@@ -4010,6 +5794,16 @@ impl BvEintrag {
             BvEintrag::Recht(recht) => {
                 recht.manuell_geroetet = val;
             }
+            BvEintrag::Erbbaurecht(erbbaurecht) => {
+                erbbaurecht.manuell_geroetet = val;
+            }
+        }
+    }
+
+    pub fn get_erbbaurecht_mut(&mut self) -> Option<&mut BvEintragErbbaurecht> {
+        match self {
+            BvEintrag::Erbbaurecht(erbbaurecht) => Some(erbbaurecht),
+            _ => None,
         }
     }
 }
@@ -4046,17 +5840,47 @@ impl StringOrLines {
         input_type: TextInputType,
         focus_type: FocusType,
     ) -> String {
+        self.get_html_editable_textfield_mit_roetung(
+            width, geroetet, None, id, input_id, input_type, focus_type,
+        )
+    }
+
+    // wie get_html_editable_textfield, aber mit Rötung einzelner Zeilen:
+    // die Spalte ergibt sich aus dem letzten Teil der input_id ("bv:0:groesse")
+    pub fn get_html_editable_textfield_mit_roetung(
+        &self,
+        width: usize,
+        geroetet: bool,
+        zellen_geroetet: Option<&ZellenGeroetet>,
+        id: String,
+        input_id: String,
+        input_type: TextInputType,
+        focus_type: FocusType,
+    ) -> String {
+        let spalte = input_id.rsplit(':').next().unwrap_or_default();
+        let zelle = zellen_geroetet.and_then(|z| z.get(spalte));
+
         let lines = self.lines().iter()
-            .map(|l| l.replace(" ", "\u{00a0}"))
-            .map(|l| l.replace("\\", "&bsol;"))
-            .map(|l| if l.is_empty() { 
-                format!("<div style='display:block;font-family:monospace;font-size:16px;word-wrap:break-word;max-width:500px;'>&nbsp;</div>") 
-            } else {
-                format!("<div style='display:block;font-family:monospace;font-size:16px;word-wrap:break-word;max-width:500px;'>{}</div>", l) 
+            .enumerate()
+            .map(|(i, l)| {
+                let zeile_geroetet = if !geroetet && zelle.map(|z| z.ist_zeile_geroetet(i)).unwrap_or(false) {
+                    "background:rgb(255,195,195);"
+                } else {
+                    ""
+                };
+                let l = l.replace(" ", "\u{00a0}").replace("\\", "&bsol;");
+                let l = if l.is_empty() { "&nbsp;".to_string() } else { l };
+                format!("<div style='display:block;font-family:monospace;font-size:16px;word-wrap:break-word;max-width:500px;{zeile_geroetet}'>{l}</div>")
             })
             .collect::<Vec<String>>()
         .join("\r\n");
 
+        let zelle_roeten = if zellen_geroetet.is_some() {
+            format!("oncontextmenu='zelleRoeten(event, \"{input_id}\");'")
+        } else {
+            String::new()
+        };
+
         let bv_geroetet = if geroetet {
             "background:rgb(255,195,195);"
         } else {
@@ -4089,6 +5913,7 @@ impl StringOrLines {
                 style='font-size:16px;user-select: initial;-webkit-user-select: initial;flex-direction:column;{width}{bv_geroetet}' 
                 {insert_tab_at_caret}
                 {select_on_click}
+                {zelle_roeten}
                 oninput='editStringOrLines(event, \"{input_id}\");' 
                 contenteditable='true'
                 focusable='true'
@@ -4181,7 +6006,7 @@ impl FlurstueckGroesse {
         match self {
             FlurstueckGroesse::Metrisch { m2 } => m2.unwrap_or(0),
             FlurstueckGroesse::Hektar { ha, a, m2 } => {
-                ha.unwrap_or(0) * 100_000 + a.unwrap_or(0) * 100 + m2.unwrap_or(0)
+                ha.unwrap_or(0) * 10_000 + a.unwrap_or(0) * 100 + m2.unwrap_or(0)
             }
         }
     }
@@ -4226,8 +6051,28 @@ impl FlurstueckGroesse {
     }
 }
 
+#[test]
+fn test_flurstueck_groesse_m2() {
+    // 1 ha = 100 a = 10.000 m²
+    let groesse = FlurstueckGroesse::Hektar {
+        ha: Some(2),
+        a: Some(35),
+        m2: Some(7),
+    };
+    assert_eq!(groesse.get_m2(), 23_507);
+    assert_eq!(groesse.get_ha_string(), "2");
+    assert_eq!(groesse.get_a_string(), "35");
+    assert_eq!(groesse.get_m2_string(), "07");
+
+    let groesse = FlurstueckGroesse::Metrisch { m2: Some(23_507) };
+    assert_eq!(groesse.get_m2(), 23_507);
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BvZuschreibung {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub id: String,
     #[serde(default)]
     #[serde(skip_serializing_if = "StringOrLines::is_empty")]
     pub bv_nr: StringOrLines,
@@ -4258,6 +6103,9 @@ impl BvZuschreibung {
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BvAbschreibung {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub id: String,
     #[serde(default)]
     #[serde(skip_serializing_if = "StringOrLines::is_empty")]
     pub bv_nr: StringOrLines,
@@ -4326,6 +6174,7 @@ pub fn analysiere_bv(
                             seite: seitenzahl.to_string(),
                             rect: OptRect::zero(),
                         };
+                        let mut roetung = RoetungsErkennung::neu(hocr, &seitenzahl.to_string());
 
                         let lfd_nr = s
                             .texte
@@ -4333,6 +6182,7 @@ pub fn analysiere_bv(
                             .and_then(|zeilen| zeilen.get(i))
                             .and_then(|t| {
                                 position.expand(&t);
+                                roetung.pruefe("lfd-nr", &t);
                                 let numeric_chars =
                                     String::from_iter(t.text.chars().filter(|c| c.is_numeric()));
                                 numeric_chars.parse::<usize>().ok()
@@ -4345,6 +6195,7 @@ pub fn analysiere_bv(
                             .and_then(|zeilen| zeilen.get(i))
                             .and_then(|t| {
                                 position.expand(&t);
+                                roetung.pruefe("bisherige-lfd-nr", &t);
                                 let numeric_chars =
                                     String::from_iter(t.text.chars().filter(|c| c.is_numeric()));
                                 numeric_chars.parse::<usize>().ok()
@@ -4356,6 +6207,7 @@ pub fn analysiere_bv(
                             .and_then(|zeilen| zeilen.get(i))
                             .map(|t| {
                                 position.expand(&t);
+                                roetung.pruefe("gemarkung", &t);
                                 t.text.trim().to_string()
                             })
                             .unwrap_or_default();
@@ -4372,6 +6224,7 @@ pub fn analysiere_bv(
                             .and_then(|zeilen| zeilen.get(i))
                             .and_then(|t| {
                                 position.expand(&t);
+                                roetung.pruefe("flur", &t);
                                 let numeric_chars =
                                     String::from_iter(t.text.chars().filter(|c| c.is_numeric()));
                                 numeric_chars.parse::<usize>().ok()
@@ -4384,6 +6237,7 @@ pub fn analysiere_bv(
                             .and_then(|zeilen| zeilen.get(i))
                             .and_then(|t| {
                                 position.expand(&t);
+                                roetung.pruefe("flurstueck", &t);
                                 let numeric_chars = String::from_iter(
                                     t.text.chars().filter(|c| c.is_numeric() || *c == '/'),
                                 );
@@ -4397,6 +6251,7 @@ pub fn analysiere_bv(
                             .and_then(|zeilen| zeilen.get(i))
                             .map(|t| {
                                 position.expand(&t);
+                                roetung.pruefe("bezeichnung", &t);
                                 t.text.trim().to_string()
                             })
                             .unwrap_or_default();
@@ -4419,6 +6274,7 @@ pub fn analysiere_bv(
                             .and_then(|zeilen| zeilen.get(i))
                             .and_then(|t| {
                                 position.expand(&t);
+                                roetung.pruefe("groesse", &t);
                                 let numeric_chars =
                                     String::from_iter(t.text.chars().filter(|c| c.is_numeric()));
                                 numeric_chars.parse::<u64>().ok()
@@ -4430,6 +6286,7 @@ pub fn analysiere_bv(
                             .and_then(|zeilen| zeilen.get(i))
                             .and_then(|t| {
                                 position.expand(&t);
+                                roetung.pruefe("groesse", &t);
                                 let numeric_chars =
                                     String::from_iter(t.text.chars().filter(|c| c.is_numeric()));
                                 numeric_chars.parse::<u64>().ok()
@@ -4441,6 +6298,7 @@ pub fn analysiere_bv(
                             .and_then(|zeilen| zeilen.get(i))
                             .and_then(|t| {
                                 position.expand(&t);
+                                roetung.pruefe("groesse", &t);
                                 let numeric_chars =
                                     String::from_iter(t.text.chars().filter(|c| c.is_numeric()));
                                 numeric_chars.parse::<u64>().ok()
@@ -4449,6 +6307,7 @@ pub fn analysiere_bv(
                         let groesse = FlurstueckGroesse::Hektar { ha, a, m2 };
 
                         BvEintrag::Flurstueck(BvEintragFlurstueck {
+                            id: neue_id(),
                             lfd_nr,
                             bisherige_lfd_nr,
                             flur,
@@ -4456,8 +6315,9 @@ pub fn analysiere_bv(
                             gemarkung,
                             bezeichnung,
                             groesse,
-                            automatisch_geroetet: Some(position.ist_geroetet(hocr)),
+                            automatisch_geroetet: Some(roetung.eintrag_geroetet()),
                             manuell_geroetet: None,
+                            zellen_geroetet: roetung.get_zellen_geroetet(),
                             position_in_pdf: Some(position),
                         })
                     })
@@ -4469,6 +6329,7 @@ pub fn analysiere_bv(
                             seite: seitenzahl.to_string(),
                             rect: OptRect::zero(),
                         };
+                        let mut roetung = RoetungsErkennung::neu(hocr, &seitenzahl.to_string());
 
                         let lfd_nr = s
                             .texte
@@ -4476,6 +6337,7 @@ pub fn analysiere_bv(
                             .and_then(|zeilen| zeilen.get(i))
                             .and_then(|t| {
                                 position.expand(&t);
+                                roetung.pruefe("lfd-nr", &t);
                                 let numeric_chars =
                                     String::from_iter(t.text.chars().filter(|c| c.is_numeric()));
                                 numeric_chars.parse::<usize>().ok()
@@ -4488,6 +6350,7 @@ pub fn analysiere_bv(
                             .and_then(|zeilen| zeilen.get(i))
                             .and_then(|t| {
                                 position.expand(&t);
+                                roetung.pruefe("bisherige-lfd-nr", &t);
                                 let numeric_chars =
                                     String::from_iter(t.text.chars().filter(|c| c.is_numeric()));
                                 numeric_chars.parse::<usize>().ok()
@@ -4501,6 +6364,7 @@ pub fn analysiere_bv(
                             .and_then(|zeilen| zeilen.get(i))
                             .and_then(|t| {
                                 position.expand(&t);
+                                roetung.pruefe("flur", &t);
                                 // ignoriere Zusatzbemerkungen zu Gemarkung
                                 let numeric_chars =
                                     String::from_iter(t.text.chars().filter(|c| c.is_numeric()));
@@ -4526,6 +6390,7 @@ pub fn analysiere_bv(
                             .and_then(|zeilen| zeilen.get(i))
                             .and_then(|t| {
                                 position.expand(&t);
+                                roetung.pruefe("flurstueck", &t);
                                 let numeric_chars = String::from_iter(
                                     t.text.chars().filter(|c| c.is_numeric() || *c == '/'),
                                 );
@@ -4539,6 +6404,7 @@ pub fn analysiere_bv(
                             .and_then(|zeilen| zeilen.get(i))
                             .map(|t| {
                                 position.expand(&t);
+                                roetung.pruefe("bezeichnung", &t);
                                 t.text.trim().to_string()
                             })
                             .unwrap_or_default();
@@ -4555,6 +6421,7 @@ pub fn analysiere_bv(
                             .and_then(|zeilen| zeilen.get(i))
                             .and_then(|t| {
                                 position.expand(&t);
+                                roetung.pruefe("groesse", &t);
                                 let numeric_chars =
                                     String::from_iter(t.text.chars().filter(|c| c.is_numeric()));
                                 numeric_chars.parse::<u64>().ok()
@@ -4563,6 +6430,7 @@ pub fn analysiere_bv(
                         let groesse = FlurstueckGroesse::Metrisch { m2 };
 
                         BvEintrag::Flurstueck(BvEintragFlurstueck {
+                            id: neue_id(),
                             lfd_nr,
                             bisherige_lfd_nr,
                             flur,
@@ -4570,8 +6438,9 @@ pub fn analysiere_bv(
                             gemarkung,
                             bezeichnung,
                             groesse,
-                            automatisch_geroetet: Some(position.ist_geroetet(hocr)),
+                            automatisch_geroetet: Some(roetung.eintrag_geroetet()),
                             manuell_geroetet: None,
+                            zellen_geroetet: roetung.get_zellen_geroetet(),
                             position_in_pdf: Some(position),
                         })
                     })
@@ -4583,6 +6452,7 @@ pub fn analysiere_bv(
                             seite: seitenzahl.to_string(),
                             rect: OptRect::zero(),
                         };
+                        let mut roetung = RoetungsErkennung::neu(hocr, &seitenzahl.to_string());
 
                         let lfd_nr = s
                             .texte
@@ -4590,6 +6460,7 @@ pub fn analysiere_bv(
                             .and_then(|zeilen| zeilen.get(i))
                             .and_then(|t| {
                                 position.expand(&t);
+                                roetung.pruefe("lfd-nr", &t);
                                 let numeric_chars =
                                     String::from_iter(t.text.chars().filter(|c| c.is_numeric()));
                                 numeric_chars.parse::<usize>().ok()
@@ -4602,6 +6473,7 @@ pub fn analysiere_bv(
                             .and_then(|zeilen| zeilen.get(i))
                             .and_then(|t| {
                                 position.expand(&t);
+                                roetung.pruefe("bisherige-lfd-nr", &t);
                                 let numeric_chars =
                                     String::from_iter(t.text.chars().filter(|c| c.is_numeric()));
                                 numeric_chars.parse::<usize>().ok()
@@ -4614,6 +6486,7 @@ pub fn analysiere_bv(
                         if let Some(s) = s.texte.get(2).and_then(|zeilen| zeilen.get(i)) {
                             let mut split_whitespace = s.text.trim().split_whitespace().rev();
                             position.expand(&s);
+                            roetung.pruefe("flurstueck", &s);
                             flurstueck = split_whitespace
                                 .next()
                                 .map(|s| {
@@ -4648,6 +6521,7 @@ pub fn analysiere_bv(
                             .and_then(|zeilen| zeilen.get(i))
                             .map(|t| {
                                 position.expand(&t);
+                                roetung.pruefe("bezeichnung", &t);
                                 t.text.trim().to_string()
                             })
                             .unwrap_or_default();
@@ -4664,6 +6538,7 @@ pub fn analysiere_bv(
                             .and_then(|zeilen| zeilen.get(i))
                             .and_then(|t| {
                                 position.expand(&t);
+                                roetung.pruefe("groesse", &t);
                                 let numeric_chars =
                                     String::from_iter(t.text.chars().filter(|c| c.is_numeric()));
                                 numeric_chars.parse::<u64>().ok()
@@ -4672,6 +6547,7 @@ pub fn analysiere_bv(
                         let groesse = FlurstueckGroesse::Metrisch { m2 };
 
                         BvEintrag::Flurstueck(BvEintragFlurstueck {
+                            id: neue_id(),
                             lfd_nr,
                             bisherige_lfd_nr,
                             flur,
@@ -4679,8 +6555,9 @@ pub fn analysiere_bv(
                             gemarkung,
                             bezeichnung,
                             groesse,
-                            automatisch_geroetet: Some(position.ist_geroetet(hocr)),
+                            automatisch_geroetet: Some(roetung.eintrag_geroetet()),
                             manuell_geroetet: None,
+                            zellen_geroetet: roetung.get_zellen_geroetet(),
                             position_in_pdf: Some(position),
                         })
                     })
@@ -4875,6 +6752,7 @@ pub fn analysiere_bv(
                         .unwrap_or_default();
 
                     BvZuschreibung {
+                        id: neue_id(),
                         bv_nr: zur_lfd_nr.into(),
                         text: bestand_und_zuschreibungen.into(),
                         automatisch_geroetet: Some(position.ist_geroetet(hocr)),
@@ -4928,6 +6806,7 @@ pub fn analysiere_bv(
                         .unwrap_or_default();
 
                     BvAbschreibung {
+                        id: neue_id(),
                         bv_nr: zur_lfd_nr.into(),
                         text: abschreibungen.into(),
                         automatisch_geroetet: Some(position.ist_geroetet(hocr)),
@@ -4987,6 +6866,7 @@ impl Abteilung1 {
             let neu = match e.clone() {
                 Abt1Eintrag::V1(v1) => {
                     let eintragung_neu = Abt1GrundEintragung {
+                        id: neue_id(),
                         bv_nr: v1.bv_nr,
                         text: v1.grundlage_der_eintragung,
                         automatisch_geroetet: v1.automatisch_geroetet,
@@ -4996,6 +6876,7 @@ impl Abteilung1 {
 
                     grundlage_eintragungen_neu.push(eintragung_neu);
                     Abt1Eintrag::V2(Abt1EintragV2 {
+                        id: if v1.id.is_empty() { neue_id() } else { v1.id },
                         lfd_nr: v1.lfd_nr,
                         eigentuemer: v1.eigentuemer,
                         version: 2,
@@ -5019,12 +6900,19 @@ impl Abteilung1 {
 #[serde(untagged)]
 #[repr(C)]
 pub enum Abt1Eintrag {
-    V1(Abt1EintragV1),
+    // Reihenfolge ist wichtig (serde(untagged) nimmt die erste passende Variante):
+    // Ein V2-Eintrag ließe sich auch als V1 lesen (bv_nr und Grundlage sind
+    // optional, "version" würde ignoriert), daher V2 zuerst. V1-Einträge aus
+    // alten Dateien haben kein Feld "version" und passen daher nur auf V1.
     V2(Abt1EintragV2),
+    V1(Abt1EintragV1),
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Abt1EintragV2 {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub id: String,
     // lfd. Nr. der Eintragung
     pub lfd_nr: usize,
     // Rechtstext
@@ -5046,6 +6934,9 @@ pub struct Abt1EintragV2 {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Abt1EintragV1 {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub id: String,
     // lfd. Nr. der Eintragung
     pub lfd_nr: usize,
     // Rechtstext
@@ -5072,6 +6963,9 @@ pub struct Abt1EintragV1 {
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Abt1GrundEintragung {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub id: String,
     // lfd. Nr. der Eintragung
     #[serde(default)]
     #[serde(skip_serializing_if = "StringOrLines::is_empty")]
@@ -5094,6 +6988,7 @@ pub struct Abt1GrundEintragung {
 impl Abt1GrundEintragung {
     pub fn new() -> Self {
         Abt1GrundEintragung {
+            id: neue_id(),
             bv_nr: String::new().into(),
             text: String::new().into(),
 
@@ -5129,6 +7024,7 @@ impl Abt1EintragV2 {
 impl Abt1Eintrag {
     pub fn new(lfd_nr: usize) -> Self {
         Abt1Eintrag::V2(Abt1EintragV2 {
+            id: neue_id(),
             lfd_nr,
             eigentuemer: String::new().into(),
             version: 2,
@@ -5156,6 +7052,20 @@ impl Abt1Eintrag {
         }
     }
 
+    pub fn get_id(&self) -> String {
+        match self {
+            Abt1Eintrag::V1(v1) => v1.id.clone(),
+            Abt1Eintrag::V2(v2) => v2.id.clone(),
+        }
+    }
+
+    pub fn id_mut(&mut self) -> &mut String {
+        match self {
+            Abt1Eintrag::V1(v1) => &mut v1.id,
+            Abt1Eintrag::V2(v2) => &mut v2.id,
+        }
+    }
+
     pub fn set_manuell_geroetet(&mut self, m: Option<bool>) {
         match self {
             Abt1Eintrag::V1(v1) => {
@@ -5258,6 +7168,7 @@ pub fn analysiere_abt1(
                         .unwrap_or_default();
 
                     Some(Abt1Eintrag::V2(Abt1EintragV2 {
+                        id: neue_id(),
                         lfd_nr,
                         eigentuemer: crate::python::text_saubern(
                             vm.clone(),
@@ -5318,6 +7229,7 @@ pub fn analysiere_abt1(
                         .unwrap_or_default();
 
                     Some(Abt1GrundEintragung {
+                        id: neue_id(),
                         bv_nr: bv_nr.into(),
                         text: crate::python::text_saubern(
                             vm.clone(),
@@ -5369,6 +7281,9 @@ impl Abteilung2 {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Abt2Eintrag {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub id: String,
     // lfd. Nr. der Eintragung
     pub lfd_nr: usize,
     // lfd. Nr der betroffenen Grundstücke im Bestandsverzeichnis
@@ -5385,6 +7300,9 @@ pub struct Abt2Eintrag {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub manuell_geroetet: Option<bool>,
     #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub zellen_geroetet: ZellenGeroetet,
+    #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub position_in_pdf: Option<PositionInPdf>,
 }
@@ -5392,11 +7310,13 @@ pub struct Abt2Eintrag {
 impl Abt2Eintrag {
     pub fn new(lfd_nr: usize) -> Self {
         Abt2Eintrag {
+            id: neue_id(),
             lfd_nr,
             bv_nr: String::new().into(),
             text: String::new().into(),
             automatisch_geroetet: None,
             manuell_geroetet: None,
+            zellen_geroetet: BTreeMap::new(),
             position_in_pdf: None,
         }
     }
@@ -5406,6 +7326,11 @@ impl Abt2Eintrag {
             .or(self.automatisch_geroetet.clone())
             .unwrap_or(false)
     }
+
+    // lfd. Nr. der betroffenen Grundstücke ohne gerötete Zeilen
+    pub fn get_bv_nr_ungeroetet(&self) -> String {
+        ungeroetete_zeilen(&self.bv_nr, &self.zellen_geroetet, "bv-nr")
+    }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -5467,13 +7392,27 @@ impl NebenbeteiligterExtra {
     }
 
     pub fn geburtsdatum_from_str(d: &str) -> Option<DateTime<Utc>> {
-        use chrono::NaiveDateTime;
+        use chrono::NaiveDate;
         let utc_self = Utc::now();
-        let naive_date = NaiveDateTime::parse_from_str(d, "%d.%m.%Y").ok()?;
+        // "%d.%m.%Y" enthält keine Uhrzeit, daher als Datum parsen
+        let naive_date = NaiveDate::parse_from_str(d, "%d.%m.%Y")
+            .ok()?
+            .and_hms_opt(0, 0, 0)?;
         Some(DateTime::from_utc(naive_date, utc_self.offset().clone()))
     }
 }
 
+#[test]
+fn test_geburtsdatum_from_str() {
+    let d = NebenbeteiligterExtra::geburtsdatum_from_str("03.05.1961").unwrap();
+    assert_eq!(NebenbeteiligterExtra::geburtsdatum_to_str(&d), "03.05.1961");
+    assert_eq!(
+        NebenbeteiligterExtra::geburtsdatum_from_str("1961-05-03"),
+        None
+    );
+    assert_eq!(NebenbeteiligterExtra::geburtsdatum_from_str(""), None);
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum Anrede {
     Herr,
@@ -5741,6 +7680,9 @@ pub struct GemarkungFlurFlurstueck {
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Abt1Veraenderung {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub id: String,
     #[serde(default)]
     #[serde(skip_serializing_if = "StringOrLines::is_empty")]
     pub lfd_nr: StringOrLines,
@@ -5768,6 +7710,9 @@ impl Abt1Veraenderung {
 
 #[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Abt1Loeschung {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub id: String,
     #[serde(default)]
     #[serde(skip_serializing_if = "StringOrLines::is_empty")]
     pub lfd_nr: StringOrLines,
@@ -5795,6 +7740,9 @@ impl Abt1Loeschung {
 
 #[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Abt2Veraenderung {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub id: String,
     #[serde(default)]
     #[serde(skip_serializing_if = "StringOrLines::is_empty")]
     pub lfd_nr: StringOrLines,
@@ -5822,6 +7770,9 @@ impl Abt2Veraenderung {
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Abt2Loeschung {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub id: String,
     #[serde(default)]
     #[serde(skip_serializing_if = "StringOrLines::is_empty")]
     pub lfd_nr: StringOrLines,
@@ -5874,6 +7825,7 @@ pub fn analysiere_abt2(
                         seite: seitenzahl.clone(),
                         rect: OptRect::zero(),
                     };
+                    let mut roetung = RoetungsErkennung::neu(hocr, seitenzahl);
 
                     let lfd_nr = s
                         .texte
@@ -5881,6 +7833,7 @@ pub fn analysiere_abt2(
                         .and_then(|zeilen| zeilen.get(i))
                         .and_then(|t| {
                             position.expand(t);
+                            roetung.pruefe("lfd-nr", t);
                             let numeric_chars =
                                 String::from_iter(t.text.chars().filter(|c| c.is_numeric()));
                             numeric_chars.parse::<usize>().ok()
@@ -5893,6 +7846,7 @@ pub fn analysiere_abt2(
                         .and_then(|zeilen| zeilen.get(i))
                         .map(|t| {
                             position.expand(t);
+                            roetung.pruefe("bv-nr", t);
                             t.text.trim().to_string()
                         })
                         .unwrap_or_default();
@@ -5903,18 +7857,21 @@ pub fn analysiere_abt2(
                         .and_then(|zeilen| zeilen.get(i))
                         .map(|t| {
                             position.expand(t);
+                            roetung.pruefe("text", t);
                             t.text.trim().to_string()
                         })
                         .unwrap_or_default();
 
                     Some(Abt2Eintrag {
+                        id: neue_id(),
                         lfd_nr,
                         bv_nr: bv_nr.into(),
                         text: crate::python::text_saubern(vm.clone(), text.trim(), konfiguration)
                             .ok()?
                             .into(),
-                        automatisch_geroetet: Some(position.ist_geroetet(hocr)),
+                        automatisch_geroetet: Some(roetung.eintrag_geroetet()),
                         manuell_geroetet: None,
+                        zellen_geroetet: roetung.get_zellen_geroetet(),
                         position_in_pdf: Some(position),
                     })
                 })
@@ -5962,6 +7919,7 @@ pub fn analysiere_abt2(
                         .unwrap_or_default();
 
                     Some(Abt2Veraenderung {
+                        id: neue_id(),
                         lfd_nr: lfd_nr.into(),
                         text: crate::python::text_saubern(vm.clone(), text.trim(), konfiguration)
                             .ok()?
@@ -6004,6 +7962,9 @@ impl Abteilung3 {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Abt3Eintrag {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub id: String,
     // lfd. Nr. der Eintragung
     pub lfd_nr: usize,
     // lfd. Nr der betroffenen Grundstücke im Bestandsverzeichnis
@@ -6025,6 +7986,9 @@ pub struct Abt3Eintrag {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub manuell_geroetet: Option<bool>,
     #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub zellen_geroetet: ZellenGeroetet,
+    #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub position_in_pdf: Option<PositionInPdf>,
 }
@@ -6032,12 +7996,14 @@ pub struct Abt3Eintrag {
 impl Abt3Eintrag {
     pub fn new(lfd_nr: usize) -> Self {
         Abt3Eintrag {
+            id: neue_id(),
             lfd_nr,
             bv_nr: String::new().into(),
             text: String::new().into(),
             betrag: String::new().into(),
             automatisch_geroetet: None,
             manuell_geroetet: None,
+            zellen_geroetet: BTreeMap::new(),
             position_in_pdf: None,
         }
     }
@@ -6047,10 +8013,18 @@ impl Abt3Eintrag {
             .or(self.automatisch_geroetet.clone())
             .unwrap_or(false)
     }
+
+    // lfd. Nr. der betroffenen Grundstücke ohne gerötete Zeilen
+    pub fn get_bv_nr_ungeroetet(&self) -> String {
+        ungeroetete_zeilen(&self.bv_nr, &self.zellen_geroetet, "bv-nr")
+    }
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Abt3Veraenderung {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub id: String,
     #[serde(default)]
     #[serde(skip_serializing_if = "StringOrLines::is_empty")]
     pub lfd_nr: StringOrLines,
@@ -6080,6 +8054,9 @@ impl Abt3Veraenderung {
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Abt3Loeschung {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub id: String,
     #[serde(default)]
     #[serde(skip_serializing_if = "StringOrLines::is_empty")]
     pub lfd_nr: StringOrLines,
@@ -6135,6 +8112,7 @@ pub fn analysiere_abt3(
                         seite: seitenzahl.clone(),
                         rect: OptRect::zero(),
                     };
+                    let mut roetung = RoetungsErkennung::neu(hocr, seitenzahl);
 
                     let lfd_nr = s
                         .texte
@@ -6142,6 +8120,7 @@ pub fn analysiere_abt3(
                         .and_then(|zeilen| zeilen.get(i))
                         .and_then(|t| {
                             position.expand(t);
+                            roetung.pruefe("lfd-nr", t);
                             let numeric_chars =
                                 String::from_iter(t.text.chars().filter(|c| c.is_numeric()));
                             numeric_chars.parse::<usize>().ok()
@@ -6154,6 +8133,7 @@ pub fn analysiere_abt3(
                         .and_then(|zeilen| zeilen.get(i))
                         .map(|t| {
                             position.expand(t);
+                            roetung.pruefe("bv-nr", t);
                             t.text.trim().to_string()
                         })
                         .unwrap_or_default();
@@ -6164,6 +8144,7 @@ pub fn analysiere_abt3(
                         .and_then(|zeilen| zeilen.get(i))
                         .map(|t| {
                             position.expand(t);
+                            roetung.pruefe("betrag", t);
                             t.text.trim().to_string()
                         })
                         .unwrap_or_default();
@@ -6174,19 +8155,22 @@ pub fn analysiere_abt3(
                         .and_then(|zeilen| zeilen.get(i))
                         .map(|t| {
                             position.expand(t);
+                            roetung.pruefe("text", t);
                             t.text.trim().to_string()
                         })
                         .unwrap_or_default();
 
                     Some(Abt3Eintrag {
+                        id: neue_id(),
                         lfd_nr: lfd_nr.into(),
                         bv_nr: bv_nr.to_string().into(),
                         betrag: betrag.trim().to_string().into(),
                         text: crate::python::text_saubern(vm.clone(), text.trim(), konfiguration)
                             .ok()?
                             .into(),
-                        automatisch_geroetet: Some(position.ist_geroetet(hocr)),
+                        automatisch_geroetet: Some(roetung.eintrag_geroetet()),
                         manuell_geroetet: None,
+                        zellen_geroetet: roetung.get_zellen_geroetet(),
                         position_in_pdf: Some(position),
                     })
                 })
@@ -6247,6 +8231,7 @@ pub fn analysiere_abt3(
                             .unwrap_or_default();
 
                         Some(Abt3Veraenderung {
+                            id: neue_id(),
                             lfd_nr: lfd_nr.into(),
                             betrag: betrag.into(),
                             text: crate::python::text_saubern(
@@ -6326,6 +8311,7 @@ pub fn analysiere_abt3(
                             .unwrap_or_default();
 
                         Some(Abt3Loeschung {
+                            id: neue_id(),
                             lfd_nr: lfd_nr.into(),
                             betrag: betrag.into(),
                             text: crate::python::text_saubern(
@@ -0,0 +1,472 @@
+//! Versionsgeschichte der Konfiguration
+//!
+//! Jeder gespeicherte Stand der Skripte, regulären Ausdrücke und Testfälle aus
+//! Konfiguration.json wird als Commit in einem Git-Repository unter
+//! `{backup_dir}/historie.git` abgelegt. Da die Konfiguration bei jedem
+//! Tastendruck gespeichert wird, entsteht die Version erst, wenn eine Weile
+//! nichts mehr geändert wurde (bzw. sofort beim Schließen der Konfiguration).
+//! Damit lassen sich zwei Stände je Skript / regulärem Ausdruck vergleichen und
+//! die Skripte eines älteren Stands wiederherstellen, wenn eine Änderung die
+//! Kurztexte o.ä. verschlechtert hat.
+
+use crate::cmd::Cmd;
+use crate::testfaelle::{zeilen_diff, DiffZeile};
+use crate::Konfiguration;
+use chrono::{DateTime, Local, TimeZone};
+use git2::{Oid, Repository, Signature};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// Wartezeit nach der letzten Änderung, bis der Stand als Version abgelegt wird
+const VERZOEGERUNG: Duration = Duration::from_secs(10);
+
+lazy_static::lazy_static! {
+    // Noch nicht abgelegter Stand mit dem Zeitpunkt der letzten Änderung, die
+    // Sperre wird auch während des Commits gehalten
+    static ref VORGEMERKT: Mutex<Option<(Instant, String)>> = Mutex::new(None);
+}
+
+#[derive(Debug, Clone)]
+pub struct Version {
+    pub id: String,
+    pub zeit: DateTime<Local>,
+    pub beschreibung: String,
+}
+
+impl Version {
+    pub fn kurz_id(&self) -> &str {
+        self.id.get(..7).unwrap_or(&self.id)
+    }
+}
+
+// Geändertes Skript bzw. geänderter regulärer Ausdruck zwischen zwei Versionen
+#[derive(Debug, Clone, PartialEq)]
+pub struct Abschnitt {
+    pub titel: String,
+    pub diff: Vec<DiffZeile>,
+}
+
+fn historie_dir() -> PathBuf {
+    PathBuf::from(Konfiguration::backup_dir()).join("historie.git")
+}
+
+fn git_fehler(e: git2::Error) -> String {
+    format!(
+        "Fehler in Versionsgeschichte der Konfiguration: {}",
+        e.message()
+    )
+}
+
+fn repo_oeffnen(dir: &Path) -> Result<Repository, String> {
+    match Repository::open_bare(dir) {
+        Ok(o) => Ok(o),
+        Err(_) => Repository::init_bare(dir).map_err(git_fehler),
+    }
+}
+
+// Felder der Konfiguration, die als Version abgelegt werden. Server, E-Mail,
+// privater Schlüssel und Passwort-Einstellungen bleiben draußen, da sich
+// Git-Objekte nicht mehr entfernen lassen
+const VERSIONIERTE_FELDER: &[&str] = &[
+    "regex",
+    "abkuerzungen_script",
+    "text_saubern_script",
+    "flurstuecke_auslesen_script",
+    "text_kuerzen_abt2_script",
+    "text_kuerzen_abt3_script",
+    "betrag_auslesen_script",
+    "rechtsinhaber_auslesen_abt3_script",
+    "rechtsinhaber_auslesen_abt2_script",
+    "rangvermerk_auslesen_abt2_script",
+    "klassifiziere_rechteart",
+    "klassifiziere_schuldenart",
+    "pruefe_eintrag_script",
+    "eigentuemer_auslesen_abt1_script",
+    "testfaelle",
+];
+
+// Alle Felder der Konfiguration haben Standardwerte, daher lässt sich der
+// Teilstand wieder mit Konfiguration::parse_from lesen
+fn versionierter_stand(k: &Konfiguration) -> Result<String, String> {
+    let fehler = |e: serde_json::Error| format!("Fehler beim Speichern der Version: {e}");
+    let mut wert = serde_json::to_value(k).map_err(fehler)?;
+    let mut stand = serde_json::Map::new();
+    if let Some(felder) = wert.as_object_mut() {
+        for feld in VERSIONIERTE_FELDER {
+            if let Some(v) = felder.remove(*feld) {
+                stand.insert(feld.to_string(), v);
+            }
+        }
+    }
+    Ok(serde_json::to_string_pretty(&stand)
+        .map_err(fehler)?
+        .replace("\n", "\r\n"))
+}
+
+/// Legt die Skripte, regulären Ausdrücke und Testfälle sofort als neue Version
+/// ab, ohne Beschreibung wird aufgelistet, welche Skripte / regulären Ausdrücke
+/// sich geändert haben. Ein vorgemerkter Stand ist damit hinfällig.
+pub fn version_speichern(k: &Konfiguration, beschreibung: Option<&str>) -> Result<(), String> {
+    let json = versionierter_stand(k)?;
+    let mut vorgemerkt = VORGEMERKT
+        .lock()
+        .map_err(|_| "Versionsgeschichte der Konfiguration gesperrt".to_string())?;
+    *vorgemerkt = None;
+    version_speichern_in(&repo_oeffnen(&historie_dir())?, &json, beschreibung)
+}
+
+/// Merkt den gespeicherten Stand vor, die Version wird im Hintergrund angelegt,
+/// sobald VERZOEGERUNG lang nichts mehr gespeichert wurde. Fehler werden als
+/// Cmd::HistorieFehler an die Oberfläche gesendet.
+pub fn version_vormerken(k: &Konfiguration) {
+    let json = match versionierter_stand(k) {
+        Ok(o) => o,
+        Err(e) => {
+            crate::analyse::ereignis_senden(Cmd::HistorieFehler { meldung: e });
+            return;
+        }
+    };
+
+    let mut vorgemerkt = match VORGEMERKT.lock() {
+        Ok(o) => o,
+        Err(_) => return,
+    };
+    let wartet_bereits = vorgemerkt.is_some();
+    *vorgemerkt = Some((Instant::now(), json));
+    if wartet_bereits {
+        return;
+    }
+
+    std::thread::spawn(|| loop {
+        let letzte_aenderung = match VORGEMERKT.lock() {
+            Ok(o) => o.as_ref().map(|(zeit, _)| zeit.elapsed()),
+            Err(_) => return,
+        };
+        match letzte_aenderung {
+            Some(vergangen) if vergangen < VERZOEGERUNG => {
+                std::thread::sleep(VERZOEGERUNG - vergangen);
+            }
+            Some(_) => {
+                if let Err(e) = vorgemerkte_version_speichern() {
+                    crate::analyse::ereignis_senden(Cmd::HistorieFehler { meldung: e });
+                }
+                return;
+            }
+            // Bereits durch version_speichern abgelegt
+            None => return,
+        }
+    });
+}
+
+/// Legt einen vorgemerkten Stand sofort ab, z.B. beim Beenden des Programms
+pub fn vorgemerkte_version_speichern() -> Result<(), String> {
+    let mut vorgemerkt = VORGEMERKT
+        .lock()
+        .map_err(|_| "Versionsgeschichte der Konfiguration gesperrt".to_string())?;
+    match vorgemerkt.take() {
+        Some((_, json)) => version_speichern_in(&repo_oeffnen(&historie_dir())?, &json, None),
+        None => Ok(()),
+    }
+}
+
+// Ist der Stand unverändert, wird keine Version angelegt
+fn version_speichern_in(
+    repo: &Repository,
+    json: &str,
+    beschreibung: Option<&str>,
+) -> Result<(), String> {
+    let blob = repo.blob(json.as_bytes()).map_err(git_fehler)?;
+    let mut tree_builder = repo.treebuilder(None).map_err(git_fehler)?;
+    tree_builder
+        .insert(Konfiguration::FILE_NAME, blob, 0o100644)
+        .map_err(git_fehler)?;
+    let tree_id = tree_builder.write().map_err(git_fehler)?;
+
+    let letzte_version = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+    if letzte_version.as_ref().map(|c| c.tree_id()) == Some(tree_id) {
+        return Ok(());
+    }
+
+    let beschreibung = match (beschreibung, letzte_version.as_ref()) {
+        (Some(s), _) => s.to_string(),
+        (None, None) => "Erste Version".to_string(),
+        (None, Some(c)) => {
+            let alt = version_laden_aus(repo, c.id());
+            let neu = Konfiguration::parse_from(json);
+            match (alt, neu) {
+                (Ok(alt), Ok(neu)) => aenderungen_beschreiben(&alt, &neu),
+                _ => "Konfiguration gespeichert".to_string(),
+            }
+        }
+    };
+
+    let tree = repo.find_tree(tree_id).map_err(git_fehler)?;
+    let signatur = Signature::now("dgb", "dgb@localhost").map_err(git_fehler)?;
+    let eltern = letzte_version.iter().collect::<Vec<_>>();
+    repo.commit(
+        Some("HEAD"),
+        &signatur,
+        &signatur,
+        &beschreibung,
+        &tree,
+        &eltern,
+    )
+    .map_err(git_fehler)?;
+
+    Ok(())
+}
+
+fn aenderungen_beschreiben(alt: &Konfiguration, neu: &Konfiguration) -> String {
+    let titel = konfiguration_diff(alt, neu)
+        .into_iter()
+        .map(|a| a.titel)
+        .collect::<Vec<_>>();
+
+    if titel.is_empty() {
+        "Einstellungen geändert".to_string()
+    } else {
+        format!("Geändert: {}", titel.join(", "))
+    }
+}
+
+/// Versionen, neueste zuerst
+pub fn versionen(max: usize) -> Result<Vec<Version>, String> {
+    versionen_in(&repo_oeffnen(&historie_dir())?, max)
+}
+
+fn versionen_in(repo: &Repository, max: usize) -> Result<Vec<Version>, String> {
+    // Noch keine Version gespeichert
+    if repo.head().is_err() {
+        return Ok(Vec::new());
+    }
+
+    let mut revwalk = repo.revwalk().map_err(git_fehler)?;
+    revwalk.push_head().map_err(git_fehler)?;
+
+    revwalk
+        .take(max)
+        .map(|oid| {
+            let commit = repo
+                .find_commit(oid.map_err(git_fehler)?)
+                .map_err(git_fehler)?;
+            Ok(Version {
+                id: commit.id().to_string(),
+                zeit: Local
+                    .timestamp_opt(commit.time().seconds(), 0)
+                    .single()
+                    .unwrap_or_else(Local::now),
+                beschreibung: commit.summary().unwrap_or_default().to_string(),
+            })
+        })
+        .collect()
+}
+
+pub fn version_laden(id: &str) -> Result<Konfiguration, String> {
+    let repo = repo_oeffnen(&historie_dir())?;
+    let oid = Oid::from_str(id).map_err(git_fehler)?;
+    version_laden_aus(&repo, oid)
+}
+
+fn version_laden_aus(repo: &Repository, oid: Oid) -> Result<Konfiguration, String> {
+    let commit = repo.find_commit(oid).map_err(git_fehler)?;
+    let tree = commit.tree().map_err(git_fehler)?;
+    let eintrag = tree
+        .get_name(Konfiguration::FILE_NAME)
+        .ok_or_else(|| format!("Version {oid} enthält keine {}", Konfiguration::FILE_NAME))?;
+    let blob = repo.find_blob(eintrag.id()).map_err(git_fehler)?;
+    Konfiguration::parse_from(&String::from_utf8_lossy(blob.content()))
+}
+
+// Skripte und reguläre Ausdrücke der Konfiguration, in der Reihenfolge der
+// Konfigurationsansicht
+fn abschnitte(k: &Konfiguration) -> Vec<(String, String)> {
+    let skripte = [
+        ("Text säubern", &k.text_saubern_script),
+        ("Abkürzungen", &k.abkuerzungen_script),
+        ("Flurstücke auslesen", &k.flurstuecke_auslesen_script),
+        (
+            "Eigentümer auslesen (Abt. 1)",
+            &k.eigentuemer_auslesen_abt1_script,
+        ),
+        (
+            "Klassifizierung RechteArt (Abt. 2)",
+            &k.klassifiziere_rechteart,
+        ),
+        (
+            "Rechtsinhaber auslesen (Abt. 2)",
+            &k.rechtsinhaber_auslesen_abt2_script,
+        ),
+        (
+            "Rangvermerk auslesen (Abt. 2)",
+            &k.rangvermerk_auslesen_abt2_script,
+        ),
+        ("Text kürzen (Abt. 2)", &k.text_kuerzen_abt2_script),
+        ("Betrag auslesen (Abt. 3)", &k.betrag_auslesen_script),
+        (
+            "Klassifizierung SchuldenArt (Abt. 3)",
+            &k.klassifiziere_schuldenart,
+        ),
+        (
+            "Rechtsinhaber auslesen (Abt. 3)",
+            &k.rechtsinhaber_auslesen_abt3_script,
+        ),
+        ("Text kürzen (Abt. 3)", &k.text_kuerzen_abt3_script),
+        ("Einträge prüfen (Abt. 2 / 3)", &k.pruefe_eintrag_script),
+    ];
+
+    skripte
+        .iter()
+        .map(|(titel, zeilen)| (titel.to_string(), zeilen.join("\n")))
+        .chain(
+            k.regex
+                .iter()
+                .map(|(id, regex)| (format!("Regex {id}"), regex.clone())),
+        )
+        .collect()
+}
+
+/// Übernimmt die Skripte (inkl. Abkürzungen) und regulären Ausdrücke einer
+/// älteren Version, Einstellungen und Testfälle bleiben wie in `aktuell`
+pub fn version_wiederherstellen(aktuell: &Konfiguration, version: &Konfiguration) -> Konfiguration {
+    Konfiguration {
+        regex: version.regex.clone(),
+        text_saubern_script: version.text_saubern_script.clone(),
+        abkuerzungen_script: version.abkuerzungen_script.clone(),
+        flurstuecke_auslesen_script: version.flurstuecke_auslesen_script.clone(),
+        eigentuemer_auslesen_abt1_script: version.eigentuemer_auslesen_abt1_script.clone(),
+        klassifiziere_rechteart: version.klassifiziere_rechteart.clone(),
+        rechtsinhaber_auslesen_abt2_script: version.rechtsinhaber_auslesen_abt2_script.clone(),
+        rangvermerk_auslesen_abt2_script: version.rangvermerk_auslesen_abt2_script.clone(),
+        text_kuerzen_abt2_script: version.text_kuerzen_abt2_script.clone(),
+        betrag_auslesen_script: version.betrag_auslesen_script.clone(),
+        klassifiziere_schuldenart: version.klassifiziere_schuldenart.clone(),
+        rechtsinhaber_auslesen_abt3_script: version.rechtsinhaber_auslesen_abt3_script.clone(),
+        text_kuerzen_abt3_script: version.text_kuerzen_abt3_script.clone(),
+        pruefe_eintrag_script: version.pruefe_eintrag_script.clone(),
+        ..aktuell.clone()
+    }
+}
+
+/// Geänderte Skripte / reguläre Ausdrücke zwischen zwei Versionen
+pub fn konfiguration_diff(alt: &Konfiguration, neu: &Konfiguration) -> Vec<Abschnitt> {
+    let abschnitte_alt = abschnitte(alt);
+    let abschnitte_neu = abschnitte(neu);
+
+    let mut titel = abschnitte_alt.iter().map(|(t, _)| t).collect::<Vec<_>>();
+    for (t, _) in abschnitte_neu.iter() {
+        if !titel.contains(&t) {
+            titel.push(t);
+        }
+    }
+
+    let text = |abschnitte: &[(String, String)], gesucht: &str| {
+        abschnitte
+            .iter()
+            .find(|(t, _)| t == gesucht)
+            .map(|(_, s)| s.clone())
+            .unwrap_or_default()
+    };
+
+    titel
+        .into_iter()
+        .filter_map(|t| {
+            let text_alt = text(&abschnitte_alt, t);
+            let text_neu = text(&abschnitte_neu, t);
+            if text_alt == text_neu {
+                return None;
+            }
+            Some(Abschnitt {
+                titel: t.clone(),
+                diff: zeilen_diff(&text_alt, &text_neu),
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn test_konfiguration_diff() {
+    let alt = Konfiguration::parse_from(Konfiguration::DEFAULT).unwrap();
+    let mut neu = alt.clone();
+    neu.text_kuerzen_abt2_script
+        .push("return 'Wegerecht'".to_string());
+    neu.regex
+        .insert("TEST_REGEX".to_string(), "(\\d+)".to_string());
+
+    let diff = konfiguration_diff(&alt, &neu);
+
+    assert_eq!(
+        diff.iter().map(|a| a.titel.as_str()).collect::<Vec<_>>(),
+        vec!["Text kürzen (Abt. 2)", "Regex TEST_REGEX"]
+    );
+    assert_eq!(
+        diff[1].diff,
+        vec![DiffZeile::Hinzugefuegt("(\\d+)".to_string())]
+    );
+    assert!(konfiguration_diff(&alt, &alt).is_empty());
+}
+
+#[test]
+fn test_version_speichern() {
+    let dir = std::env::temp_dir().join(format!("dgb-historie-test-{}", rand::random::<u32>()));
+    let repo = repo_oeffnen(&dir).unwrap();
+    assert!(versionen_in(&repo, 10).unwrap().is_empty());
+
+    let alt = Konfiguration::parse_from(Konfiguration::DEFAULT).unwrap();
+    let mut neu = alt.clone();
+    neu.text_kuerzen_abt2_script
+        .push("return 'Wegerecht'".to_string());
+    let json = |k: &Konfiguration| versionierter_stand(k).unwrap();
+
+    version_speichern_in(&repo, &json(&alt), None).unwrap();
+    // Unveränderter Stand legt keine neue Version an
+    version_speichern_in(&repo, &json(&alt), None).unwrap();
+    version_speichern_in(&repo, &json(&neu), None).unwrap();
+
+    let versionen = versionen_in(&repo, 10).unwrap();
+    assert_eq!(
+        versionen
+            .iter()
+            .map(|v| v.beschreibung.as_str())
+            .collect::<Vec<_>>(),
+        vec!["Geändert: Text kürzen (Abt. 2)", "Erste Version"]
+    );
+
+    let geladen = version_laden_aus(&repo, Oid::from_str(&versionen[1].id).unwrap()).unwrap();
+    assert!(konfiguration_diff(&alt, &geladen).is_empty());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_version_wiederherstellen() {
+    let version = Konfiguration::parse_from(Konfiguration::DEFAULT).unwrap();
+    let mut aktuell = version.clone();
+    aktuell.abkuerzungen_script = vec!["return ['Dr.']".to_string()];
+    aktuell
+        .regex
+        .insert("TEST_REGEX".to_string(), "(\\d+)".to_string());
+    aktuell.server_email = "bearbeiter@example.com".to_string();
+
+    let wiederhergestellt = version_wiederherstellen(&aktuell, &version);
+
+    assert!(konfiguration_diff(&version, &wiederhergestellt).is_empty());
+    assert_eq!(wiederhergestellt.server_email, aktuell.server_email);
+}
+
+#[test]
+fn test_versionierter_stand() {
+    let mut k = Konfiguration::parse_from(Konfiguration::DEFAULT).unwrap();
+    k.server_email = "bearbeiter@example.com".to_string();
+    k.server_privater_schluessel_base64 = Some("GEHEIMER_SCHLUESSEL".to_string());
+    k.text_kuerzen_abt2_script
+        .push("return 'Wegerecht'".to_string());
+
+    let json = versionierter_stand(&k).unwrap();
+    assert!(!json.contains("GEHEIMER_SCHLUESSEL"));
+    assert!(!json.contains("bearbeiter@example.com"));
+
+    let geladen = Konfiguration::parse_from(&json).unwrap();
+    assert!(konfiguration_diff(&k, &geladen).is_empty());
+    assert_eq!(geladen.server_privater_schluessel_base64, None);
+}
@@ -6,6 +6,7 @@ use std::collections::BTreeSet;
 use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::sync::Mutex;
 
 use crate::analyse::GrundbuchAnalysiert;
@@ -16,11 +17,12 @@ use crate::digital::{
     Abt3Veraenderung, Anrede, BvAbschreibung, BvEintrag, BvEintragFlurstueck, BvEintragRecht,
     BvZuschreibung, Grundbuch, HocrArea, HocrLine, HocrParagraph, HocrWord, Linie,
     Nebenbeteiligter, NebenbeteiligterExport, NebenbeteiligterExtra, NebenbeteiligterTyp, Punkt,
-    SeitenTyp, Titelblatt,
+    RoteLinienBild, SeitenTyp, Titelblatt,
 };
 use crate::digital::{Abteilung1, Abteilung2, Abteilung3, Bestandsverzeichnis};
-use crate::python::{Betrag, PyVm, RechteArt, SchuldenArt};
-use analyse::{AnalyseFehler, GrundbuchAnalysiertCache};
+use crate::python::PyVm;
+use crate::testfaelle::TestZiel;
+use analyse::{AnalyseFehler, BefundCode, GrundbuchAnalysiertCache};
 use digital::FlurstueckGroesse;
 use digital::HocrSeite;
 use digital::ParsedHocr;
@@ -35,13 +37,20 @@ const GTK_OVERLAY_SCROLLING: &str = "GTK_OVERLAY_SCROLLING";
 
 type FileName = String;
 
+pub mod alkis;
 pub mod analyse;
+pub mod blaetter;
 pub mod cmd;
 pub mod digital;
+pub mod historie;
 pub mod kurztext;
+pub mod migration;
 pub mod pdf;
+pub mod pruefung;
 pub mod python;
+pub mod testfaelle;
 pub mod ui;
+pub mod vergleich;
 
 use crate::cmd::Cmd;
 
@@ -56,12 +65,16 @@ pub struct RpcData {
     pub commit_msg: String,
 
     pub loaded_files: BTreeMap<FileName, PdfFile>,
-    pub loaded_remote_files: BTreeMap<FileName, gbx::PdfFile>,
+    pub loaded_remote_files: BTreeMap<FileName, GbxDatei>,
     pub loaded_nb: Vec<Nebenbeteiligter>,
     pub loaded_nb_paths: Vec<String>,
 
     pub konfiguration: Konfiguration,
     pub vm: PyVm,
+
+    // Zählt die Testläufe der Konfiguration mit, Ergebnisse eines veralteten
+    // Testlaufs aus dem Hintergrund werden verworfen
+    pub testfaelle_lauf: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -81,10 +94,308 @@ pub struct PgpSignatur {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UploadChangesetData {
-    pub neu: Vec<gbx::PdfFile>,
+    pub neu: Vec<GbxDatei>,
     pub geaendert: Vec<GbxAenderung>,
 }
 
+// Datei im .gbx-Format des Servers. Was gbx::PdfFile (noch) nicht abbilden kann,
+// wird unter "erweiterung" mit übertragen, damit nach dem Herunterladen
+// dieselbe Datei entsteht wie vor dem Hochladen
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GbxDatei {
+    #[serde(flatten)]
+    pub datei: gbx::PdfFile,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "GbxErweiterung::ist_leer")]
+    pub erweiterung: GbxErweiterung,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GbxErweiterung {
+    // Erbbaurechte im BV, nach Index in bestandsverzeichnis.eintraege -
+    // in gbx::PdfFile nur als grundstücksgleiches Recht enthalten
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub erbbaurechte: BTreeMap<usize, digital::BvEintragErbbaurecht>,
+    // Rötung einzelner Zellen / Zeilen, je Abschnitt ("bv", "abt2", "abt3")
+    // nach Index des Eintrags
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub zellen_geroetet: BTreeMap<String, BTreeMap<usize, digital::ZellenGeroetet>>,
+    // IDs der Einträge je Abschnitt ("bv", "abt2-veraenderung", ...) nach Index,
+    // damit Quittierungen und Bearbeitungspfade nach dem Herunterladen gültig bleiben
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub ids: BTreeMap<String, Vec<String>>,
+    // Quittierte Befunde, in gbx::PdfFile nicht enthalten
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub quittierungen: Vec<analyse::Quittierung>,
+}
+
+impl GbxErweiterung {
+    pub fn ist_leer(&self) -> bool {
+        self.erbbaurechte.is_empty()
+            && self.zellen_geroetet.is_empty()
+            && self.ids.is_empty()
+            && self.quittierungen.is_empty()
+    }
+
+    fn aus_grundbuch(grundbuch: &Grundbuch) -> Self {
+        let bv = grundbuch
+            .bestandsverzeichnis
+            .eintraege
+            .iter()
+            .map(|bv| bv.get_zellen_geroetet().cloned().unwrap_or_default())
+            .collect::<Vec<_>>();
+        let abt2 = grundbuch
+            .abt2
+            .eintraege
+            .iter()
+            .map(|e| e.zellen_geroetet.clone())
+            .collect::<Vec<_>>();
+        let abt3 = grundbuch
+            .abt3
+            .eintraege
+            .iter()
+            .map(|e| e.zellen_geroetet.clone())
+            .collect::<Vec<_>>();
+
+        let zellen_geroetet = [("bv", bv), ("abt2", abt2), ("abt3", abt3)]
+            .into_iter()
+            .map(|(abschnitt, zellen)| {
+                let zellen = zellen
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(_, z)| !z.is_empty())
+                    .collect::<BTreeMap<_, _>>();
+                (abschnitt.to_string(), zellen)
+            })
+            .filter(|(_, zellen)| !zellen.is_empty())
+            .collect();
+
+        let ids = digital::ABSCHNITTE
+            .iter()
+            .map(|abschnitt| (abschnitt.to_string(), grundbuch.abschnitt_ids(abschnitt)))
+            .filter(|(_, ids)| !ids.is_empty())
+            .collect();
+
+        GbxErweiterung {
+            zellen_geroetet,
+            ids,
+            quittierungen: grundbuch.quittierungen.clone(),
+            erbbaurechte: grundbuch
+                .bestandsverzeichnis
+                .eintraege
+                .iter()
+                .enumerate()
+                .filter_map(|(i, bv)| match bv {
+                    BvEintrag::Erbbaurecht(e) => Some((i, e.clone())),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+
+    fn anwenden(&self, grundbuch: &mut Grundbuch) {
+        for (i, erbbaurecht) in self.erbbaurechte.iter() {
+            let bv = match grundbuch.bestandsverzeichnis.eintraege.get_mut(*i) {
+                Some(s) => s,
+                None => continue,
+            };
+            // nur übernehmen, wenn der Eintrag auf dem Server nicht verschoben wurde
+            if matches!(bv, BvEintrag::Recht(r) if r.lfd_nr == erbbaurecht.lfd_nr) {
+                *bv = BvEintrag::Erbbaurecht(erbbaurecht.clone());
+            }
+        }
+
+        for (abschnitt, zellen) in self.zellen_geroetet.iter() {
+            for (i, z) in zellen.iter() {
+                let ziel = match abschnitt.as_str() {
+                    "bv" => grundbuch
+                        .bestandsverzeichnis
+                        .eintraege
+                        .get_mut(*i)
+                        .and_then(|bv| bv.get_zellen_geroetet_mut()),
+                    "abt2" => grundbuch
+                        .abt2
+                        .eintraege
+                        .get_mut(*i)
+                        .map(|e| &mut e.zellen_geroetet),
+                    "abt3" => grundbuch
+                        .abt3
+                        .eintraege
+                        .get_mut(*i)
+                        .map(|e| &mut e.zellen_geroetet),
+                    _ => None,
+                };
+                if let Some(ziel) = ziel {
+                    *ziel = z.clone();
+                }
+            }
+        }
+
+        for (abschnitt, ids) in self.ids.iter() {
+            // nur übernehmen, wenn auf dem Server keine Einträge hinzugekommen
+            // oder entfernt wurden, sonst passen die Indizes nicht mehr
+            if grundbuch.abschnitt_ids(abschnitt).len() == ids.len() {
+                grundbuch.abschnitt_ids_setzen(abschnitt, ids);
+            }
+        }
+
+        grundbuch.ids_vergeben();
+        grundbuch.quittierungen = self.quittierungen.clone();
+    }
+}
+
+#[test]
+fn test_gbx_erweiterung_erbbaurecht() {
+    let mut grundbuch = Grundbuch::new(Titelblatt::default());
+    let erbbaurecht = digital::BvEintragErbbaurecht {
+        dauer: "99 Jahre".to_string().into(),
+        ..digital::BvEintragErbbaurecht::aus_text(
+            2,
+            "Erbbaurecht an dem Grundstück Grundbuch von Ludwigsburg Blatt 254 Nr. 1 des Bestandsverzeichnisses",
+        )
+    };
+    grundbuch.bestandsverzeichnis.eintraege = vec![
+        BvEintrag::neu(1),
+        BvEintrag::Erbbaurecht(erbbaurecht.clone()),
+    ];
+
+    let erweiterung: GbxErweiterung = serde_json::from_str(
+        &serde_json::to_string(&GbxErweiterung::aus_grundbuch(&grundbuch)).unwrap(),
+    )
+    .unwrap();
+
+    // So kommt der Eintrag vom Server zurück
+    let mut vom_server = grundbuch.clone();
+    vom_server.bestandsverzeichnis.eintraege[1] = BvEintrag::Recht(BvEintragRecht {
+        text: erbbaurecht.text.clone(),
+        ..BvEintragRecht::neu(2)
+    });
+    erweiterung.anwenden(&mut vom_server);
+
+    assert_eq!(
+        vom_server.bestandsverzeichnis.eintraege[1],
+        BvEintrag::Erbbaurecht(erbbaurecht)
+    );
+}
+
+#[test]
+fn test_gbx_erweiterung_zellen_geroetet() {
+    let zellen = [(
+        "groesse".to_string(),
+        digital::ZelleGeroetet {
+            automatisch_geroetet: Some(false),
+            automatisch_geroetete_zeilen: [0].into_iter().collect(),
+            ..Default::default()
+        },
+    )]
+    .into_iter()
+    .collect::<digital::ZellenGeroetet>();
+
+    let mut grundbuch = Grundbuch::new(Titelblatt::default());
+    grundbuch.bestandsverzeichnis.eintraege = vec![BvEintrag::neu(1), BvEintrag::neu(2)];
+    if let Some(z) = grundbuch.bestandsverzeichnis.eintraege[1].get_zellen_geroetet_mut() {
+        *z = zellen.clone();
+    }
+    let mut abt2 = digital::Abt2Eintrag::new(1);
+    abt2.zellen_geroetet = zellen.clone();
+    grundbuch.abt2.eintraege = vec![abt2];
+
+    let erweiterung: GbxErweiterung = serde_json::from_str(
+        &serde_json::to_string(&GbxErweiterung::aus_grundbuch(&grundbuch)).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        erweiterung.zellen_geroetet.keys().collect::<Vec<_>>(),
+        vec!["abt2", "bv"]
+    );
+
+    // gbx::PdfFile kennt keine Rötung einzelner Zellen
+    let mut vom_server = grundbuch.clone();
+    for bv in vom_server.bestandsverzeichnis.eintraege.iter_mut() {
+        if let Some(z) = bv.get_zellen_geroetet_mut() {
+            z.clear();
+        }
+    }
+    vom_server.abt2.eintraege[0].zellen_geroetet.clear();
+    erweiterung.anwenden(&mut vom_server);
+
+    assert_eq!(vom_server, grundbuch);
+}
+
+#[test]
+fn test_gbx_erweiterung_ids() {
+    let mut grundbuch = Grundbuch::new(Titelblatt::default());
+    grundbuch.bestandsverzeichnis.eintraege = vec![BvEintrag::neu(1), BvEintrag::neu(2)];
+    grundbuch.abt2.eintraege = vec![digital::Abt2Eintrag::new(1)];
+    grundbuch.abt3.veraenderungen = vec![digital::Abt3Veraenderung::default()];
+    grundbuch.ids_vergeben();
+
+    let erweiterung: GbxErweiterung = serde_json::from_str(
+        &serde_json::to_string(&GbxErweiterung::aus_grundbuch(&grundbuch)).unwrap(),
+    )
+    .unwrap();
+
+    // gbx::PdfFile kennt keine IDs
+    let mut vom_server = grundbuch.clone();
+    for abschnitt in digital::ABSCHNITTE {
+        let leer = vec![String::new(); vom_server.abschnitt_ids(abschnitt).len()];
+        vom_server.abschnitt_ids_setzen(abschnitt, &leer);
+    }
+    erweiterung.anwenden(&mut vom_server);
+    assert_eq!(vom_server, grundbuch);
+
+    // Auf dem Server wurde ein Eintrag hinzugefügt: neue IDs statt falscher Zuordnung
+    let mut vom_server = grundbuch.clone();
+    vom_server
+        .abt2
+        .eintraege
+        .insert(0, digital::Abt2Eintrag::new(1));
+    vom_server.abschnitt_ids_setzen("abt2", &[String::new(), String::new()]);
+    erweiterung.anwenden(&mut vom_server);
+    let ids = vom_server.abschnitt_ids("abt2");
+    assert!(ids
+        .iter()
+        .all(|id| !id.is_empty() && *id != grundbuch.abt2.eintraege[0].id));
+    assert_eq!(
+        vom_server.abschnitt_ids("bv"),
+        grundbuch.abschnitt_ids("bv")
+    );
+}
+
+#[test]
+fn test_gbx_erweiterung_quittierungen() {
+    let mut grundbuch = Grundbuch::new(Titelblatt::default());
+    let mut abt2 = digital::Abt2Eintrag::new(1);
+    abt2.text = "Vorkaufsrecht für Max Mustermann".to_string().into();
+    grundbuch.abt2.eintraege = vec![abt2];
+    grundbuch.ids_vergeben();
+    grundbuch.quittierungen = vec![analyse::Quittierung::new(
+        &grundbuch,
+        2,
+        1,
+        BefundCode::KeineOrdnungsnummer,
+        "Rechtsinhaber ist kein Nebenbeteiligter".to_string(),
+        "bearbeiter@example.com".to_string(),
+    )
+    .unwrap()];
+
+    let erweiterung: GbxErweiterung = serde_json::from_str(
+        &serde_json::to_string(&GbxErweiterung::aus_grundbuch(&grundbuch)).unwrap(),
+    )
+    .unwrap();
+    assert!(!erweiterung.ist_leer());
+
+    // gbx::PdfFile kennt keine Quittierungen
+    let mut vom_server = grundbuch.clone();
+    vom_server.quittierungen.clear();
+    erweiterung.anwenden(&mut vom_server);
+    assert_eq!(vom_server, grundbuch);
+}
+
 fn translate_rect(r: &Rect) -> gbx::Rect {
     gbx::Rect {
         min_x: r.min_x,
@@ -145,8 +456,15 @@ fn untranslate_stringorlines(s: &gbx::StringOrLines) -> StringOrLines {
     }
 }
 
-fn untranslate_gbx(f: &gbx::PdfFile) -> PdfFile {
+fn untranslate_gbx(gbx_datei: &GbxDatei) -> PdfFile {
+    let mut datei = untranslate_gbx_datei(&gbx_datei.datei);
+    gbx_datei.erweiterung.anwenden(&mut datei.analysiert);
+    datei
+}
+
+fn untranslate_gbx_datei(f: &gbx::PdfFile) -> PdfFile {
     PdfFile {
+        schema_version: migration::SCHEMA_VERSION,
         cache: GrundbuchAnalysiertCache::default(),
         icon: None,
         next_state: None,
@@ -230,6 +548,8 @@ fn untranslate_gbx(f: &gbx::PdfFile) -> PdfFile {
                                         .iter()
                                         .map(|p| Punkt { x: p.x, y: p.y })
                                         .collect(),
+                                    // gbx speichert keine Linienbreite
+                                    breite_mm: 0.0,
                                 })
                                 .collect(),
                         },
@@ -332,6 +652,7 @@ fn untranslate_gbx(f: &gbx::PdfFile) -> PdfFile {
                     .map(|k| match k {
                         gbx::BvEintrag::Flurstueck(f) => {
                             BvEintrag::Flurstueck(BvEintragFlurstueck {
+                                id: String::new(),
                                 lfd_nr: f.lfd_nr.clone(),
                                 bisherige_lfd_nr: f.bisherige_lfd_nr.clone(),
                                 flur: f.flur.clone(),
@@ -348,16 +669,19 @@ fn untranslate_gbx(f: &gbx::PdfFile) -> PdfFile {
                                 },
                                 automatisch_geroetet: f.automatisch_geroetet.clone(),
                                 manuell_geroetet: f.manuell_geroetet.clone(),
+                                zellen_geroetet: BTreeMap::new(),
                                 position_in_pdf: untranslate_position_in_seite(&f.position_in_pdf),
                             })
                         }
                         gbx::BvEintrag::Recht(r) => BvEintrag::Recht(BvEintragRecht {
+                            id: String::new(),
                             lfd_nr: r.lfd_nr.clone(),
                             zu_nr: untranslate_stringorlines(&r.zu_nr),
                             bisherige_lfd_nr: r.bisherige_lfd_nr.clone(),
                             text: untranslate_stringorlines(&r.text),
                             automatisch_geroetet: r.automatisch_geroetet.clone(),
                             manuell_geroetet: r.manuell_geroetet.clone(),
+                            zellen_geroetet: BTreeMap::new(),
                             position_in_pdf: untranslate_position_in_seite(&r.position_in_pdf),
                         }),
                     })
@@ -368,6 +692,7 @@ fn untranslate_gbx(f: &gbx::PdfFile) -> PdfFile {
                     .zuschreibungen
                     .iter()
                     .map(|k| BvZuschreibung {
+                        id: String::new(),
                         bv_nr: untranslate_stringorlines(&k.bv_nr),
                         text: untranslate_stringorlines(&k.text),
                         automatisch_geroetet: k.automatisch_geroetet.clone(),
@@ -381,6 +706,7 @@ fn untranslate_gbx(f: &gbx::PdfFile) -> PdfFile {
                     .abschreibungen
                     .iter()
                     .map(|k| BvAbschreibung {
+                        id: String::new(),
                         bv_nr: untranslate_stringorlines(&k.bv_nr),
                         text: untranslate_stringorlines(&k.text),
                         automatisch_geroetet: k.automatisch_geroetet.clone(),
@@ -397,6 +723,7 @@ fn untranslate_gbx(f: &gbx::PdfFile) -> PdfFile {
                     .iter()
                     .map(|k| match k {
                         gbx::Abt1Eintrag::V1(v) => Abt1Eintrag::V1(Abt1EintragV1 {
+                            id: String::new(),
                             lfd_nr: v.lfd_nr.clone(),
                             eigentuemer: untranslate_stringorlines(&v.eigentuemer),
                             grundlage_der_eintragung: untranslate_stringorlines(
@@ -408,6 +735,7 @@ fn untranslate_gbx(f: &gbx::PdfFile) -> PdfFile {
                             position_in_pdf: untranslate_position_in_seite(&v.position_in_pdf),
                         }),
                         gbx::Abt1Eintrag::V2(v) => Abt1Eintrag::V2(Abt1EintragV2 {
+                            id: String::new(),
                             lfd_nr: v.lfd_nr.clone(),
                             version: v.version.clone(),
                             eigentuemer: untranslate_stringorlines(&v.eigentuemer),
@@ -423,6 +751,7 @@ fn untranslate_gbx(f: &gbx::PdfFile) -> PdfFile {
                     .grundlagen_eintragungen
                     .iter()
                     .map(|k| Abt1GrundEintragung {
+                        id: String::new(),
                         bv_nr: untranslate_stringorlines(&k.bv_nr),
                         text: untranslate_stringorlines(&k.text),
                         automatisch_geroetet: k.automatisch_geroetet.clone(),
@@ -436,6 +765,7 @@ fn untranslate_gbx(f: &gbx::PdfFile) -> PdfFile {
                     .veraenderungen
                     .iter()
                     .map(|k| Abt1Veraenderung {
+                        id: String::new(),
                         lfd_nr: untranslate_stringorlines(&k.lfd_nr),
                         text: untranslate_stringorlines(&k.text),
                         automatisch_geroetet: k.automatisch_geroetet.clone(),
@@ -449,6 +779,7 @@ fn untranslate_gbx(f: &gbx::PdfFile) -> PdfFile {
                     .loeschungen
                     .iter()
                     .map(|k| Abt1Loeschung {
+                        id: String::new(),
                         lfd_nr: untranslate_stringorlines(&k.lfd_nr),
                         text: untranslate_stringorlines(&k.text),
                         automatisch_geroetet: k.automatisch_geroetet.clone(),
@@ -464,11 +795,13 @@ fn untranslate_gbx(f: &gbx::PdfFile) -> PdfFile {
                     .eintraege
                     .iter()
                     .map(|k| Abt2Eintrag {
+                        id: String::new(),
                         lfd_nr: k.lfd_nr.clone(),
                         bv_nr: untranslate_stringorlines(&k.bv_nr),
                         text: untranslate_stringorlines(&k.text),
                         automatisch_geroetet: k.automatisch_geroetet.clone(),
                         manuell_geroetet: k.manuell_geroetet.clone(),
+                        zellen_geroetet: BTreeMap::new(),
                         position_in_pdf: untranslate_position_in_seite(&k.position_in_pdf),
                     })
                     .collect(),
@@ -478,6 +811,7 @@ fn untranslate_gbx(f: &gbx::PdfFile) -> PdfFile {
                     .veraenderungen
                     .iter()
                     .map(|k| Abt2Veraenderung {
+                        id: String::new(),
                         lfd_nr: untranslate_stringorlines(&k.lfd_nr),
                         text: untranslate_stringorlines(&k.text),
                         automatisch_geroetet: k.automatisch_geroetet.clone(),
@@ -491,6 +825,7 @@ fn untranslate_gbx(f: &gbx::PdfFile) -> PdfFile {
                     .loeschungen
                     .iter()
                     .map(|k| Abt2Loeschung {
+                        id: String::new(),
                         lfd_nr: untranslate_stringorlines(&k.lfd_nr),
                         text: untranslate_stringorlines(&k.text),
                         automatisch_geroetet: k.automatisch_geroetet.clone(),
@@ -506,11 +841,13 @@ fn untranslate_gbx(f: &gbx::PdfFile) -> PdfFile {
                     .eintraege
                     .iter()
                     .map(|k| Abt3Eintrag {
+                        id: String::new(),
                         lfd_nr: k.lfd_nr.clone(),
                         bv_nr: untranslate_stringorlines(&k.bv_nr),
                         text: untranslate_stringorlines(&k.text),
                         automatisch_geroetet: k.automatisch_geroetet.clone(),
                         manuell_geroetet: k.manuell_geroetet.clone(),
+                        zellen_geroetet: BTreeMap::new(),
                         position_in_pdf: untranslate_position_in_seite(&k.position_in_pdf),
                         betrag: untranslate_stringorlines(&k.betrag),
                     })
@@ -521,6 +858,7 @@ fn untranslate_gbx(f: &gbx::PdfFile) -> PdfFile {
                     .veraenderungen
                     .iter()
                     .map(|k| Abt3Veraenderung {
+                        id: String::new(),
                         lfd_nr: untranslate_stringorlines(&k.lfd_nr),
                         text: untranslate_stringorlines(&k.text),
                         automatisch_geroetet: k.automatisch_geroetet.clone(),
@@ -535,6 +873,7 @@ fn untranslate_gbx(f: &gbx::PdfFile) -> PdfFile {
                     .loeschungen
                     .iter()
                     .map(|k| Abt3Loeschung {
+                        id: String::new(),
                         lfd_nr: untranslate_stringorlines(&k.lfd_nr),
                         text: untranslate_stringorlines(&k.text),
                         automatisch_geroetet: k.automatisch_geroetet.clone(),
@@ -544,11 +883,19 @@ fn untranslate_gbx(f: &gbx::PdfFile) -> PdfFile {
                     })
                     .collect(),
             },
+            quittierungen: Vec::new(),
         },
     }
 }
 
-fn translate_gbx(f: &PdfFile) -> gbx::PdfFile {
+fn translate_gbx(f: &PdfFile) -> GbxDatei {
+    GbxDatei {
+        datei: translate_gbx_datei(f),
+        erweiterung: GbxErweiterung::aus_grundbuch(&f.analysiert),
+    }
+}
+
+fn translate_gbx_datei(f: &PdfFile) -> gbx::PdfFile {
     gbx::PdfFile {
         digitalisiert: f.datei.is_some(),
         hocr: gbx::HocrLayout {
@@ -750,6 +1097,18 @@ fn translate_gbx(f: &PdfFile) -> gbx::PdfFile {
                             manuell_geroetet: r.manuell_geroetet.clone(),
                             position_in_pdf: translate_position_in_seite(&r.position_in_pdf),
                         }),
+                        // Das .gbx-Format des Servers kennt noch keine Erbbaurechte im BV,
+                        // der Eintrag wird daher als grundstücksgleiches Recht übertragen,
+                        // die übrigen Felder in GbxErweiterung::erbbaurechte
+                        BvEintrag::Erbbaurecht(e) => gbx::BvEintrag::Recht(gbx::BvEintragRecht {
+                            lfd_nr: e.lfd_nr.clone(),
+                            zu_nr: translate_stringorlines(&StringOrLines::default()),
+                            bisherige_lfd_nr: e.bisherige_lfd_nr.clone(),
+                            text: translate_stringorlines(&e.text),
+                            automatisch_geroetet: e.automatisch_geroetet.clone(),
+                            manuell_geroetet: e.manuell_geroetet.clone(),
+                            position_in_pdf: translate_position_in_seite(&e.position_in_pdf),
+                        }),
                     })
                     .collect(),
                 zuschreibungen: f
@@ -973,7 +1332,7 @@ impl RpcData {
     pub fn get_current_remote_state_of_files(
         local: &BTreeMap<String, PdfFile>,
         konfiguration: &Konfiguration,
-    ) -> Result<BTreeMap<String, gbx::PdfFile>, Option<String>> {
+    ) -> Result<BTreeMap<String, GbxDatei>, Option<String>> {
         let mut map = BTreeMap::new();
 
         for (k, d) in local {
@@ -996,7 +1355,7 @@ impl RpcData {
 
     pub fn get_aenderungen(
         local: &BTreeMap<String, PdfFile>,
-        remote: &BTreeMap<String, gbx::PdfFile>,
+        remote: &BTreeMap<String, GbxDatei>,
     ) -> Result<GbxAenderungen, Vec<String>> {
         let mut neue_dateien = BTreeMap::new();
         let mut geaenderte_dateien = BTreeMap::new();
@@ -1011,14 +1370,24 @@ impl RpcData {
                     continue;
                 }
             };
-            let local_json = serde_json::to_string_pretty(&local).unwrap_or_default();
-            let remote_json = serde_json::to_string_pretty(remote).unwrap_or_default();
-            if local_json != remote_json {
+
+            // Einträge über ihre ID vergleichen, beide Seiten in der Form,
+            // in der sie vom Server zurückkommen
+            let local_grundbuch = untranslate_gbx(&local).analysiert;
+            let mut remote_grundbuch = untranslate_gbx(remote).analysiert;
+            if remote.erweiterung.ids.is_empty() {
+                // vor der Einführung der IDs hochgeladen
+                remote_grundbuch.ids_uebernehmen(&local_grundbuch);
+            }
+            let eintraege = local_grundbuch.eintraege_vergleichen(&remote_grundbuch);
+
+            if !eintraege.is_empty() || ohne_eintraege(&local) != ohne_eintraege(remote) {
                 geaenderte_dateien.insert(
                     file_name.clone(),
                     GbxAenderung {
                         alt: remote.clone(),
                         neu: local,
+                        eintraege,
                     },
                 );
             }
@@ -1057,14 +1426,30 @@ impl RpcData {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GbxAenderungen {
-    pub neue_dateien: BTreeMap<FileName, gbx::PdfFile>,
+    pub neue_dateien: BTreeMap<FileName, GbxDatei>,
     pub geaenderte_dateien: BTreeMap<FileName, GbxAenderung>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GbxAenderung {
-    pub alt: gbx::PdfFile,
-    pub neu: gbx::PdfFile,
+    pub alt: GbxDatei,
+    pub neu: GbxDatei,
+    // Geänderte Einträge, zugeordnet über ihre ID - nur für die Anzeige,
+    // wird nicht mit hochgeladen
+    #[serde(skip)]
+    pub eintraege: Vec<digital::EintragAenderung>,
+}
+
+// Datei ohne die Einträge des Grundbuchs (Titelblatt, HOCR, ...), die Einträge
+// selbst werden über ihre ID verglichen
+fn ohne_eintraege(datei: &GbxDatei) -> serde_json::Value {
+    let mut json = serde_json::to_value(&datei.datei).unwrap_or_default();
+    if let Some(analysiert) = json.get_mut("analysiert").and_then(|a| a.as_object_mut()) {
+        for abschnitt in ["bestandsverzeichnis", "abt1", "abt2", "abt3"] {
+            analysiert.remove(abschnitt);
+        }
+    }
+    json
 }
 
 impl GbxAenderungen {
@@ -1084,6 +1469,11 @@ pub enum PopoverState {
         amtsgericht: String,
         blatt: String,
     },
+    BefundQuittieren {
+        abteilung: usize,
+        lfd_nr: usize,
+        code: BefundCode,
+    },
     GrundbuchSuchenDialog,
     GrundbuchUploadDialog(usize),
     Configuration(ConfigurationView),
@@ -1105,6 +1495,9 @@ pub enum ConfigurationView {
     KlassifizierungSchuldenArtAbt3,
     RechtsinhaberAuslesenAbt3,
     TextKuerzenAbt3,
+    PruefeEintrag,
+    EigentuemerAuslesenAbt1,
+    Historie,
 }
 
 #[derive(Debug, Copy, PartialEq, PartialOrd, Clone)]
@@ -1169,6 +1562,7 @@ impl Default for RpcData {
             konfiguration: Konfiguration::neu_laden()
                 .unwrap_or(Konfiguration::parse_from(Konfiguration::DEFAULT).unwrap()),
             vm: PyVm::new().unwrap(),
+            testfaelle_lauf: 0,
         }
     }
 }
@@ -1182,6 +1576,9 @@ fn default_server_email() -> String {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PdfFile {
+    // Version des .gbx-Formats, siehe migration::SCHEMA_VERSION
+    #[serde(default)]
+    schema_version: usize,
     // Pfad der zugehörigen .pdf-Datei
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
@@ -1211,6 +1608,18 @@ pub struct PdfFile {
 }
 
 impl PdfFile {
+    // Ersetzt eine bestehende Quittierung desselben Befunds
+    fn quittierung_setzen(&mut self, quittierung: analyse::Quittierung) {
+        self.quittierung_entfernen(quittierung.abteilung, quittierung.lfd_nr, &quittierung.code);
+        self.analysiert.quittierungen.push(quittierung);
+    }
+
+    fn quittierung_entfernen(&mut self, abteilung: usize, lfd_nr: usize, code: &BefundCode) {
+        self.analysiert
+            .quittierungen
+            .retain(|q| !(q.abteilung == abteilung && q.lfd_nr == lfd_nr && q.code == *code));
+    }
+
     pub fn get_seitenzahlen(&self) -> Vec<u32> {
         self.datei
             .clone()
@@ -1220,7 +1629,7 @@ impl PdfFile {
     }
 }
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PdfFileIcon {
     // Gelbes Warn-Icon
     HatFehler,
@@ -1437,9 +1846,7 @@ impl PdfFile {
             .cache
             .start_analyzing(&self.analysiert, &vm, nb, konfiguration);
 
-        self.ist_geladen()
-            && analysiert.abt2.iter().all(|e| e.fehler.is_empty())
-            && analysiert.abt3.iter().all(|e| e.fehler.is_empty())
+        self.ist_geladen() && !analysiert.offene_befunde().iter().any(|b| b.ist_fehler())
     }
 
     pub fn alle_ordnungsnummern_zugewiesen(
@@ -1452,19 +1859,11 @@ impl PdfFile {
             .cache
             .start_analyzing(&self.analysiert, &vm, nb, konfiguration);
 
-        let any_abt2 = analysiert.abt2.iter().any(|e| {
-            e.warnungen
-                .iter()
-                .any(|w| w == "Konnte keine Ordnungsnummer finden")
-        });
-
-        let any_abt3 = analysiert.abt3.iter().any(|e| {
-            e.warnungen
+        self.ist_geladen()
+            && !analysiert
+                .offene_befunde()
                 .iter()
-                .any(|w| w == "Konnte keine Ordnungsnummer finden")
-        });
-
-        self.ist_geladen() && !any_abt2 && !any_abt3
+                .any(|b| b.code == BefundCode::KeineOrdnungsnummer)
     }
 
     pub fn get_nebenbeteiligte(
@@ -1478,6 +1877,47 @@ impl PdfFile {
             self.cache
                 .start_and_block_until_finished(&self.analysiert, &vm, &[], konfiguration);
 
+        for abt1 in &analysiert.abt1 {
+            for e in &abt1.eigentuemer {
+                use crate::python::EigentuemerArt;
+
+                let name = e.to_string();
+                v.push(NebenbeteiligterExport {
+                    ordnungsnummer: None,
+                    recht: format!(
+                        "{} Blatt {}, Abt. 1/{}",
+                        self.analysiert.titelblatt.grundbuch_von,
+                        self.analysiert.titelblatt.blatt,
+                        abt1.lfd_nr
+                    ),
+                    typ: match e.art {
+                        EigentuemerArt::Firma => NebenbeteiligterTyp::from_str(&name),
+                        // Herr / Frau ist aus dem Grundbuch nicht ersichtlich
+                        EigentuemerArt::Person if abt1.eigentuemer.len() > 1 => {
+                            Some(NebenbeteiligterTyp::PrivateigentuemerMehrere)
+                        }
+                        EigentuemerArt::Person => None,
+                    },
+                    name,
+                    extra: NebenbeteiligterExtra {
+                        anrede: match e.art {
+                            EigentuemerArt::Firma => Some(Anrede::Firma),
+                            EigentuemerArt::Person => None,
+                        },
+                        titel: None,
+                        vorname: e.vorname.clone(),
+                        nachname_oder_firma: Some(e.name.clone()),
+                        geburtsname: e.geburtsname.clone(),
+                        geburtsdatum: e
+                            .geburtsdatum
+                            .as_deref()
+                            .and_then(NebenbeteiligterExtra::geburtsdatum_from_str),
+                        wohnort: e.wohnort.clone(),
+                    },
+                });
+            }
+        }
+
         for abt2 in &analysiert.abt2 {
             if !abt2.rechtsinhaber.is_empty() {
                 v.push(NebenbeteiligterExport {
@@ -1517,10 +1957,33 @@ impl PdfFile {
 }
 
 impl Konfiguration {
-    pub fn get_hash(&self) -> String {
+    // Hash der Teile der Konfiguration, die sich auf die Analyse der Einträge
+    // auswirken. Schlüssel des Analyse-Caches im Speicher und auf der Festplatte,
+    // Ansichtseinstellungen, Server-Zugang, Testfälle oder ALKIS-Auszüge (der
+    // ALKIS-Abgleich läuft bei jeder Analyse neu) machen ihn nicht ungültig
+    pub fn get_analyse_hash(&self) -> String {
         use sha2::Digest;
 
-        let arr = serde_json::to_string(&self).unwrap_or_default();
+        let json = (
+            &self.regex,
+            [
+                &self.abkuerzungen_script,
+                &self.text_saubern_script,
+                &self.flurstuecke_auslesen_script,
+                &self.text_kuerzen_abt2_script,
+                &self.text_kuerzen_abt3_script,
+                &self.betrag_auslesen_script,
+                &self.rechtsinhaber_auslesen_abt3_script,
+                &self.rechtsinhaber_auslesen_abt2_script,
+                &self.rangvermerk_auslesen_abt2_script,
+                &self.klassifiziere_rechteart,
+                &self.klassifiziere_schuldenart,
+                &self.pruefe_eintrag_script,
+                &self.eigentuemer_auslesen_abt1_script,
+            ],
+            &self.gemarkungen,
+        );
+        let arr = serde_json::to_string(&json).unwrap_or_default();
         let mut hasher = sha2::Sha256::default();
         hasher.update(arr.as_bytes());
         let hash = hasher.finalize();
@@ -1528,6 +1991,39 @@ impl Konfiguration {
     }
 }
 
+#[test]
+fn test_konfiguration_analyse_hash() {
+    let konfiguration = Konfiguration::parse_from(Konfiguration::DEFAULT).unwrap();
+    let hash = konfiguration.get_analyse_hash();
+
+    let ansicht = Konfiguration {
+        spalten_ausblenden: !konfiguration.spalten_ausblenden,
+        server_email: "bearbeiter@example.com".to_string(),
+        alkis_dateien: vec!["auszug.xml".to_string()],
+        ..konfiguration.clone()
+    };
+    assert_eq!(ansicht.get_analyse_hash(), hash);
+
+    // Testfälle ändern nichts an der Analyse
+    let mut testfaelle = konfiguration.clone();
+    testfaelle.testfaelle.insert(
+        TestZiel::TextSaubern.id(),
+        vec![testfaelle::Testfall {
+            name: "Testfall 1".to_string(),
+            eingabe: "Wegerecht".to_string(),
+            bv_nr: String::new(),
+            erwartet: "Wegerecht".to_string(),
+        }],
+    );
+    assert_eq!(testfaelle.get_analyse_hash(), hash);
+
+    let skript = Konfiguration {
+        text_saubern_script: vec!["return recht.upper()".to_string()],
+        ..konfiguration.clone()
+    };
+    assert_ne!(skript.get_analyse_hash(), hash);
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Konfiguration {
     #[serde(skip, default)]
@@ -1574,6 +2070,22 @@ pub struct Konfiguration {
     pub klassifiziere_rechteart: Vec<String>,
     #[serde(default)]
     pub klassifiziere_schuldenart: Vec<String>,
+    // Projektspezifische Prüfung der analysierten Einträge in Abt. 2 / Abt. 3
+    #[serde(default)]
+    pub pruefe_eintrag_script: Vec<String>,
+    // Aufteilung der Eigentümer in Abt. 1 in Personen / Firmen
+    #[serde(default)]
+    pub eigentuemer_auslesen_abt1_script: Vec<String>,
+    #[serde(default)]
+    pub gemarkungen: Vec<alkis::Gemarkung>,
+    // Pfade der importierten ALKIS-Auszüge, werden beim Start neu geladen
+    #[serde(default)]
+    pub alkis_dateien: Vec<String>,
+    // Testfälle je Skript / regulärem Ausdruck, Schlüssel siehe TestZiel::id
+    #[serde(default)]
+    pub testfaelle: BTreeMap<String, Vec<testfaelle::Testfall>>,
+    #[serde(skip, default)]
+    pub alkis: Arc<alkis::AlkisBestand>,
 }
 
 fn default_passwort_speichern() -> bool {
@@ -1855,11 +2367,20 @@ impl Konfiguration {
             .unwrap_or(format!("./dgb/{}", Self::FILE_NAME))
     }
 
+    /// Speichert die Konfiguration, die Version in der Versionsgeschichte
+    /// entsteht verzögert, siehe historie::version_vormerken
     pub fn speichern(&self) {
         let _ = serde_json::to_string_pretty(self).ok().and_then(|s| {
             let s = s.replace("\n", "\r\n");
             std::fs::write(&Self::konfiguration_pfad(), &s.as_bytes()).ok()
         });
+        historie::version_vormerken(self);
+    }
+
+    /// Legt den Stand sofort in der Versionsgeschichte ab: beim Schließen der
+    /// Konfiguration, beim Öffnen der Historie und beim Wiederherstellen
+    pub fn version_speichern(&self, beschreibung: Option<&str>) -> Result<(), String> {
+        historie::version_speichern(self, beschreibung)
     }
 
     pub fn neu_laden() -> Result<Self, String> {
@@ -1867,7 +2388,7 @@ impl Konfiguration {
             let _ = std::fs::write(&Self::konfiguration_pfad(), &Self::DEFAULT.as_bytes()).ok();
         }
 
-        let konfig = match std::fs::read_to_string(&Self::konfiguration_pfad()) {
+        let mut konfig = match std::fs::read_to_string(&Self::konfiguration_pfad()) {
             Ok(o) => Self::parse_from(&o)?,
             Err(e) => {
                 return Err(format!(
@@ -1878,8 +2399,27 @@ impl Konfiguration {
             }
         };
 
+        // Nicht mehr vorhandene ALKIS-Auszüge werden beim Start ignoriert
+        let _ = konfig.alkis_laden();
+
         Ok(konfig)
     }
+
+    /// Lädt alle ALKIS-Auszüge aus `alkis_dateien`, gibt die Fehler zurück
+    pub fn alkis_laden(&mut self) -> Vec<String> {
+        let mut bestand = alkis::AlkisBestand::default();
+        let mut fehler = Vec::new();
+
+        for datei in self.alkis_dateien.iter() {
+            match alkis::AlkisBestand::datei_laden(Path::new(datei)) {
+                Ok(o) => bestand.zusammenfuehren(o),
+                Err(e) => fehler.push(e),
+            }
+        }
+
+        self.alkis = Arc::new(bestand);
+        fehler
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -2026,9 +2566,194 @@ pub struct LefisDateiExport {
     pub rechte: GrundbuchAnalysiert,
 }
 
+impl LefisDateiExport {
+    // Inhalt der .lefis-Datei, Warnungen und Fehler nur als Text
+    pub fn lefis_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "titelblatt": self.titelblatt,
+            "rechte": self.rechte.lefis_json(),
+        })
+    }
+}
+
+// Setzt das Icon der Datei zurück und startet die Analyse. Sind keine Einträge
+// mehr offen (z.B. weil nur Quittierungen geändert wurden), wird das Icon
+// sofort neu berechnet, sonst nach Cmd::AnalyseEintragFertig
+fn icon_neu_berechnen(data: &mut RpcData, datei: &str) -> Option<PdfFileIcon> {
+    let s = data.loaded_files.get_mut(datei)?;
+    s.icon = None;
+    let icon = s
+        .get_icon(data.vm.clone(), &data.loaded_nb, &data.konfiguration)
+        .filter(|_| s.cache.offen() == 0)?;
+    s.icon = Some(icon);
+    Some(icon)
+}
+
+fn icon_aktualisieren(webview: &WebView, data: &mut RpcData, datei: &str) {
+    if let Some(icon) = icon_neu_berechnen(data, datei) {
+        let _ = webview.evaluate_script(&format!(
+            "replaceIcon(`{}`, `{}`)",
+            datei,
+            icon.get_base64()
+        ));
+    }
+}
+
+#[test]
+fn test_icon_nach_quittierung() {
+    let mut data = RpcData {
+        konfiguration: Konfiguration::parse_from(Konfiguration::DEFAULT).unwrap(),
+        ..RpcData::default()
+    };
+    let mut grundbuch = Grundbuch::new(Titelblatt {
+        grundbuch_von: "Icontest".to_string(),
+        ..Titelblatt::default()
+    });
+    let mut abt2 = digital::Abt2Eintrag::new(1);
+    abt2.text = "Vorkaufsrecht für Max Mustermann".to_string().into();
+    grundbuch.abt2.eintraege = vec![abt2];
+    grundbuch.ids_vergeben();
+    data.loaded_files.insert(
+        "Icontest".to_string(),
+        PdfFile {
+            schema_version: migration::SCHEMA_VERSION,
+            datei: None,
+            gbx_datei_pfad: None,
+            icon: None,
+            hocr: HocrLayout::default(),
+            analysiert: grundbuch,
+            cache: GrundbuchAnalysiertCache::default(),
+            nebenbeteiligte_dateipfade: Vec::new(),
+            anpassungen_seite: BTreeMap::new(),
+            previous_state: None,
+            next_state: None,
+        },
+    );
+
+    // Analyse blockierend ausführen, damit das Icon ohne Analyse im Hintergrund
+    // (und Cmd::AnalyseEintragFertig) sofort berechnet werden kann
+    let datei = &data.loaded_files["Icontest"];
+    datei.cache.start_and_block_until_finished(
+        &datei.analysiert,
+        &data.vm,
+        &data.loaded_nb,
+        &data.konfiguration,
+    );
+    let vorher = icon_neu_berechnen(&mut data, "Icontest");
+    assert_ne!(vorher, None);
+    assert_ne!(vorher, Some(PdfFileIcon::AllesOkay));
+
+    let datei = data.loaded_files.get_mut("Icontest").unwrap();
+    let befunde = datei
+        .cache
+        .start_analyzing(&datei.analysiert, &data.vm, &[], &data.konfiguration)
+        .offene_befunde();
+    assert!(!befunde.is_empty());
+    for b in befunde {
+        let quittierung = analyse::Quittierung::new(
+            &datei.analysiert,
+            b.abteilung,
+            b.lfd_nr,
+            b.code,
+            "geprüft".to_string(),
+            data.konfiguration.server_email.clone(),
+        )
+        .unwrap();
+        datei.quittierung_setzen(quittierung);
+    }
+
+    // Quittierungen ändern keinen Eintrag, es wird also nichts neu analysiert
+    // und kein Cmd::AnalyseEintragFertig gesendet: Icon muss sofort gesetzt werden
+    assert_eq!(
+        icon_neu_berechnen(&mut data, "Icontest"),
+        Some(PdfFileIcon::AllesOkay)
+    );
+    assert_eq!(
+        data.loaded_files["Icontest"].icon,
+        Some(PdfFileIcon::AllesOkay)
+    );
+}
+
+// Testfeld in der Konfiguration: Ausgabe des Skripts mit Laufzeit
+fn script_testen(
+    webview: &WebView,
+    data: &RpcData,
+    ziel: &TestZiel,
+    text: &str,
+    js_funktion: &str,
+) {
+    let start = std::time::Instant::now();
+    let result = testfaelle::ausgabe(ziel, text, "", data.vm.clone(), &data.konfiguration);
+    let time = std::time::Instant::now() - start;
+    let result = match result {
+        Ok(o) => format!("{}\r\nAusgabe berechnet in {:?}", o, time),
+        Err(e) => e,
+    };
+    let _ = webview.evaluate_script(&format!("{}(`{}`);", js_funktion, result));
+}
+
+// Wartezeit nach der letzten Änderung eines Skripts, bis die Testfälle laufen
+const TESTFAELLE_VERZOEGERUNG: std::time::Duration = std::time::Duration::from_millis(500);
+
+// Testfälle der aktuellen Konfigurationsansicht nach einer Änderung erneut
+// ausführen, erst wenn TESTFAELLE_VERZOEGERUNG lang nichts mehr geändert wurde.
+// Das Ergebnis eines noch laufenden Tests wird verworfen.
+fn testfaelle_nach_aenderung(webview: &WebView, data: &mut RpcData) {
+    let ansicht = match data.popover_state.as_ref() {
+        Some(PopoverState::Configuration(cw)) => *cw,
+        _ => return,
+    };
+    data.testfaelle_lauf += 1;
+    let lauf = data.testfaelle_lauf;
+    let ziele = testfaelle::ziele_fuer_ansicht(&ansicht, &data.konfiguration);
+    let ergebnisse = testfaelle::testfaelle_auflisten(&ziele, &data.konfiguration);
+    if ergebnisse.is_empty() {
+        return;
+    }
+    let _ = webview.evaluate_script(&format!(
+        "replaceTestfaelle(`{}`);",
+        ui::render_testfaelle(&ansicht, &ergebnisse, true)
+    ));
+
+    std::thread::spawn(move || {
+        std::thread::sleep(TESTFAELLE_VERZOEGERUNG);
+        analyse::ereignis_senden(Cmd::TestfaelleStarten { lauf });
+    });
+}
+
+// Testfälle der aktuellen Konfigurationsansicht im Hintergrund ausführen,
+// das Ergebnis kommt als Cmd::TestfaelleFertig zurück
+fn testfaelle_ausfuehren(webview: &WebView, data: &mut RpcData) {
+    let ansicht = match data.popover_state.as_ref() {
+        Some(PopoverState::Configuration(cw)) => *cw,
+        _ => return,
+    };
+    data.testfaelle_lauf += 1;
+    let lauf = data.testfaelle_lauf;
+    let ziele = testfaelle::ziele_fuer_ansicht(&ansicht, &data.konfiguration);
+    let ergebnisse = testfaelle::testfaelle_auflisten(&ziele, &data.konfiguration);
+    let _ = webview.evaluate_script(&format!(
+        "replaceTestfaelle(`{}`);",
+        ui::render_testfaelle(&ansicht, &ergebnisse, true)
+    ));
+
+    let vm = data.vm.clone();
+    let konfiguration = data.konfiguration.clone();
+    std::thread::spawn(move || {
+        let ergebnisse = testfaelle::testfaelle_ausfuehren(&ziele, vm, &konfiguration);
+        analyse::ereignis_senden(Cmd::TestfaelleFertig {
+            lauf,
+            html: ui::render_testfaelle(&ansicht, &ergebnisse, false),
+        });
+    });
+}
+
 fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
 
-    println!("webview_cb {arg:#?}");
+    // Ergebnis der Testfälle enthält das gesamte HTML
+    if !matches!(arg, Cmd::TestfaelleFertig { .. }) {
+        println!("webview_cb {arg:#?}");
+    }
 
     match &arg {
         Cmd::OpenScript { lines } => {
@@ -2057,53 +2782,46 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
             };
             url_open::open(&url);
         }
-        Cmd::CheckForGrundbuchLoaded => {
-            // Icons
-            let mut new_icons = BTreeMap::new();
-            let mut icon_count = 0;
+        Cmd::AnalyseEintragFertig {
+            datei,
+            abteilung,
+            lfd_nr,
+        } => {
+            let offen = match data.loaded_files.get(datei) {
+                Some(s) => s.cache.offen(),
+                None => return,
+            };
 
-            for (k, v) in data.loaded_files.iter() {
-                if icon_count >= 1 {
-                    break;
-                }
+            if offen == 0 {
+                icon_aktualisieren(webview, data, datei);
+            }
 
-                if v.ist_geladen() {
-                    let icon =
-                        match v.get_icon(data.vm.clone(), &data.loaded_nb, &data.konfiguration) {
-                            Some(s) => s,
-                            None => {
-                                return;
-                            }
-                        };
-                    new_icons.insert(k.clone(), icon);
-                    icon_count += 1;
-                }
+            if !data.konfiguration.lefis_analyse_einblenden {
+                return;
             }
 
-            for (k, v) in new_icons {
-                if let Some(s) = data.loaded_files.get_mut(&k.clone()) {
-                    s.icon = Some(v);
-                    let _ = webview.evaluate_script(&format!(
-                        "replaceIcon(`{}`, `{}`)",
-                        k,
-                        v.get_base64()
-                    ));
+            let ist_geoeffnet = data.open_page.as_ref().map(|(file, _)| file) == Some(datei);
+
+            // Löschungen, Rangverhältnisse usw. ändern sich erst, wenn
+            // alle Einträge analysiert sind, dann einmal alles neu rendern.
+            // Ist ein anderes Blatt fertig, ändert sich der Abgleich zwischen
+            // den Blättern auch für das geöffnete Blatt
+            if offen == 0 {
+                if ist_geoeffnet || data.loaded_files.len() > 1 {
+                    analyse_grundbuch_ersetzen(webview, data);
                 }
+                return;
             }
 
-            let open_file = match data
-                .open_page
-                .clone()
-                .and_then(|(file, _)| data.loaded_files.get(&file))
-            {
+            if !ist_geoeffnet {
+                return;
+            }
+
+            let open_file = match data.loaded_files.get(datei) {
                 Some(s) => s,
                 None => return,
             };
 
-            if !open_file.ist_geladen() {
-                return;
-            }
-
             let analyse = open_file.cache.start_analyzing(
                 &open_file.analysiert,
                 &data.vm,
@@ -2111,12 +2829,16 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 &data.konfiguration,
             );
 
-            if data.konfiguration.lefis_analyse_einblenden {
+            if let Some(eintrag) = ui::render_analyse_eintrag(&analyse, *abteilung, *lfd_nr) {
                 let _ = webview.evaluate_script(&format!(
-                    "replaceAnalyseGrundbuch(`{}`);",
-                    ui::render_analyse_grundbuch(&analyse, false, false)
+                    "replaceAnalyseEintrag({}, {}, `{}`);",
+                    abteilung, lfd_nr, eintrag
                 ));
             }
+            let _ = webview.evaluate_script(&format!(
+                "replaceAnalyseFortschritt(`{}`);",
+                ui::render_analyse_fortschritt(offen)
+            ));
         }
         Cmd::Init => {
             let _ = webview.evaluate_script(&format!(
@@ -2141,7 +2863,9 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 .iter()
                 .filter_map(|dateipfad| {
                     let dateiendung = Path::new(dateipfad).extension()?;
-                    if dateiendung == "pdf" || dateiendung == "gbx" {
+                    if dateiendung.eq_ignore_ascii_case("pdf")
+                        || dateiendung.eq_ignore_ascii_case("gbx")
+                    {
                         Some(dateipfad)
                     } else {
                         None
@@ -2159,11 +2883,31 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                     }
                 };
 
+                // Nach Inhalt, nicht nach Dateiendung unterscheiden: alles, was
+                // kein PDF ist, wird als .gbx gelesen und dabei migriert
+                let ist_pdf = datei_bytes.starts_with(b"%PDF");
+
+                let gbx_geladen = if !ist_pdf {
+                    match migration::gbx_datei_laden(Path::new(d)) {
+                        Ok(o) => {
+                            gbx_migration_melden(d, &o);
+                            Some(o.pdf)
+                        }
+                        Err(e) => {
+                            tinyfiledialogs::message_box_ok(
+                                &format!("Fehler beim Laden von {}", d),
+                                &format!("{}", e).replace("\"", "").replace("'", ""),
+                                MessageBoxIcon::Error,
+                            );
+                            continue;
+                        }
+                    }
+                } else {
+                    None
+                };
+
                 let mut force_hocr_load = true;
-                if let Some(mut grundbuch_json_parsed) = String::from_utf8(datei_bytes.clone())
-                    .ok()
-                    .and_then(|s| serde_json::from_str::<PdfFile>(&s).ok())
-                {
+                if let Some(mut grundbuch_json_parsed) = gbx_geladen {
                     let file_name = format!(
                         "{}_{}",
                         grundbuch_json_parsed.analysiert.titelblatt.grundbuch_von,
@@ -2228,6 +2972,7 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
 
                     // Lösche Titelblattseite von Seiten, die gerendert werden müssen
                     let mut pdf_parsed = PdfFile {
+                        schema_version: migration::SCHEMA_VERSION,
                         datei: Some(d.to_string()),
                         gbx_datei_pfad: None,
                         icon: None,
@@ -2242,19 +2987,33 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
 
                     if let Some(cached_pdf) = std::fs::read_to_string(&cache_output_path)
                         .ok()
-                        .and_then(|s| serde_json::from_str(&s).ok())
+                        .and_then(|s| migration::gbx_lesen(&s).ok())
                     {
-                        pdf_parsed = cached_pdf;
+                        pdf_parsed = cached_pdf.pdf;
                     }
 
-                    if let Some(mut target_pdf) = std::fs::read_to_string(&target_output_path)
-                        .ok()
-                        .and_then(|s| serde_json::from_str::<PdfFile>(&s).ok())
-                    {
-                        let json = match serde_json::to_string_pretty(&target_pdf) {
-                            Ok(o) => o,
-                            Err(_) => continue,
-                        };
+                    let target_pdf = match migration::gbx_datei_laden(&target_output_path) {
+                        Ok(o) => {
+                            gbx_migration_melden(&target_output_path.display().to_string(), &o);
+                            Some(o.pdf)
+                        }
+                        Err(e @ migration::MigrationFehler::ZuNeu { .. }) => {
+                            // Datei nicht überschreiben
+                            tinyfiledialogs::message_box_ok(
+                                &format!("Fehler beim Laden von {}", target_output_path.display()),
+                                &format!("{}", e).replace("\"", "").replace("'", ""),
+                                MessageBoxIcon::Error,
+                            );
+                            continue;
+                        }
+                        Err(_) => None,
+                    };
+
+                    if let Some(target_pdf) = target_pdf {
+                        let json = match serde_json::to_string_pretty(&target_pdf) {
+                            Ok(o) => o,
+                            Err(_) => continue,
+                        };
                         let _ = std::fs::write(&target_output_path, json.as_bytes());
                         pdf_parsed = target_pdf.clone();
                         data.create_diff_save_point(&file_name, target_pdf.clone());
@@ -2293,7 +3052,6 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
 
             let html_inner = ui::render_entire_screen(data);
             let _ = webview.evaluate_script(&format!("replaceEntireScreen(`{}`)", html_inner));
-            let _ = webview.evaluate_script("startCheckingForPdfErrors()");
 
             for (pdf_parsed, _) in &pdf_zu_laden {
                 let output_parent = pdf_parsed.get_gbx_datei_parent();
@@ -2302,6 +3060,7 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                     pdf_parsed.analysiert.titelblatt.grundbuch_von,
                     pdf_parsed.analysiert.titelblatt.blatt
                 );
+                icon_aktualisieren(webview, data, &file_name);
                 let cache_output_path = output_parent
                     .clone()
                     .join(&format!("{}.cache.gbx", file_name));
@@ -2355,6 +3114,80 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 "replaceEntireScreen(`{}`)",
                 ui::render_entire_screen(data)
             ));
+        }
+        Cmd::BefundQuittieren {
+            abteilung,
+            lfd_nr,
+            code,
+        } => {
+            data.popover_state = Some(PopoverState::BefundQuittieren {
+                abteilung: *abteilung,
+                lfd_nr: *lfd_nr,
+                code: code.clone(),
+            });
+            let _ = webview.evaluate_script(&format!(
+                "replacePopOver(`{}`)",
+                ui::render_popover_content(data)
+            ));
+        }
+        Cmd::BefundQuittierenFinished { begruendung } => {
+            let (abteilung, lfd_nr, code) = match data.popover_state.clone() {
+                Some(PopoverState::BefundQuittieren {
+                    abteilung,
+                    lfd_nr,
+                    code,
+                }) => (abteilung, lfd_nr, code),
+                _ => return,
+            };
+            data.popover_state = None;
+            let _ = webview.evaluate_script(&format!(
+                "replacePopOver(`{}`)",
+                ui::render_popover_content(data)
+            ));
+            let of = match data.open_page.clone().map(|s| s.0.clone()) {
+                Some(s) => s,
+                None => return,
+            };
+            let email = data.konfiguration.server_email.clone();
+            if let Some(s) = data.loaded_files.get_mut(&of) {
+                let quittierung = match analyse::Quittierung::new(
+                    &s.analysiert,
+                    abteilung,
+                    lfd_nr,
+                    code,
+                    begruendung.trim().to_string(),
+                    email,
+                ) {
+                    Some(s) => s,
+                    None => return,
+                };
+                s.quittierung_setzen(quittierung);
+                s.speichern();
+            }
+            icon_aktualisieren(webview, data, &of);
+            let _ = webview.evaluate_script(&format!(
+                "replaceEntireScreen(`{}`)",
+                ui::render_entire_screen(data)
+            ));
+        }
+        Cmd::QuittierungAufheben {
+            abteilung,
+            lfd_nr,
+            code,
+        } => {
+            let of = match data.open_page.clone().map(|s| s.0.clone()) {
+                Some(s) => s,
+                None => return,
+            };
+            if let Some(s) = data.loaded_files.get_mut(&of) {
+                s.quittierung_entfernen(*abteilung, *lfd_nr, code);
+                s.speichern();
+            }
+            icon_aktualisieren(webview, data, &of);
+            let _ = webview.evaluate_script(&format!(
+                "replaceEntireScreen(`{}`)",
+                ui::render_entire_screen(data)
+            ));
         },
         Cmd::CreateNewGrundbuch => {
             data.popover_state = Some(PopoverState::CreateNewGrundbuch);
@@ -2452,6 +3285,7 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
             let file_name = format!("{}_{}", grundbuch_von, blatt);
 
             let pdf_parsed = PdfFile {
+                schema_version: migration::SCHEMA_VERSION,
                 datei: None,
                 gbx_datei_pfad: Some(gbx_folder),
                 icon: None,
@@ -2466,6 +3300,7 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                     abt1: Abteilung1::default(),
                     abt2: Abteilung2::default(),
                     abt3: Abteilung3::default(),
+                    quittierungen: Vec::new(),
                 },
                 cache: GrundbuchAnalysiertCache::default(),
                 nebenbeteiligte_dateipfade: Vec::new(),
@@ -2491,7 +3326,7 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 "replaceEntireScreen(`{}`)",
                 ui::render_entire_screen(data)
             ));
-            let _ = webview.evaluate_script("startCheckingForPdfErrors()");
+            icon_aktualisieren(webview, data, &file_name);
         }
         Cmd::Search { search_text } => {
             let authtoken = match data.konfiguration.get_authtoken() {
@@ -2743,7 +3578,7 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                         "replaceEntireScreen(`{}`)",
                         ui::render_entire_screen(data)
                     ));
-                    let _ = webview.evaluate_script("startCheckingForPdfErrors()");
+                    icon_aktualisieren(webview, data, &file_name);
                 }
                 PdfFileOrEmpty::NichtVorhanden(err) => {
                     tinyfiledialogs::message_box_ok(
@@ -3005,16 +3840,25 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 };
 
                 let flipped = decoded.flipv();
+
+                let tempdir = std::env::temp_dir()
+                    .join(&pdf_grundbuch_von)
+                    .join(&pdf_blatt);
+                let _ = std::fs::create_dir_all(&tempdir);
+
+                // Rötungen auf eingescannten Seiten: nur im Farbbild erkennbar
+                let rote_linien = crate::digital::get_rote_linien_aus_bild(&flipped.to_rgb8());
+                let _ = std::fs::write(
+                    tempdir.join(format!("{seite}.rote_linien.json")),
+                    serde_json::to_string_pretty(&rote_linien).unwrap_or_default(),
+                );
+
                 let grayscale = flipped.grayscale();
 
                 let mut bytes: Vec<u8> = Vec::new();
                 let _ =
                     grayscale.write_to(&mut Cursor::new(&mut bytes), image::ImageOutputFormat::Png);
 
-                let tempdir = std::env::temp_dir()
-                    .join(&pdf_grundbuch_von)
-                    .join(&pdf_blatt);
-                let _ = std::fs::create_dir_all(&tempdir);
                 let _ = std::fs::write(tempdir.join(&image_filename), &bytes);
                 let pnm_bytes = match crate::digital::read_png_and_convert_to_bmp(
                     &tempdir.join(&image_filename),
@@ -3064,9 +3908,9 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
 
             let mut pdf_parsed: PdfFile = match std::fs::read_to_string(&cache_output_path)
                 .ok()
-                .and_then(|s| serde_json::from_str(&s).ok())
+                .and_then(|s| migration::gbx_lesen(&s).ok())
             {
-                Some(s) => s,
+                Some(s) => s.pdf,
                 None => {
                     let _ = webview
                         .evaluate_script(&format!("stopCheckingForPageLoaded(`{}`)", file_name));
@@ -3099,6 +3943,7 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
             }
 
             let _ = std::fs::remove_file(&cache_output_path);
+            icon_aktualisieren(webview, data, file_name);
             if data.open_page.is_none() {
                 data.open_page = Some((file_name.clone(), 2));
                 let _ = webview.evaluate_script(&format!(
@@ -3171,7 +4016,7 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 None => return,
             };
 
-            let row = match split.get(1).and_then(|s| s.parse::<usize>().ok()) {
+            let zeile_pfad = match split.get(1) {
                 Some(s) => s,
                 None => return,
             };
@@ -3190,6 +4035,11 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 None => return,
             };
 
+            let row = match open_file.analysiert.zeile_aufloesen(section, zeile_pfad) {
+                Some(s) => s,
+                None => return,
+            };
+
             match (*section, *cell) {
                 ("bv", "lfd-nr") => {
                     let new_value = match new_value.parse::<usize>().ok() {
@@ -3223,6 +4073,62 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                     );
                     bv_eintrag.set_zu_nr(new_value.clone().into());
                 }
+                ("bv", "erbbau-grundbuch-von") => {
+                    if let Some(erbbaurecht) = open_file
+                        .analysiert
+                        .bestandsverzeichnis
+                        .eintraege
+                        .get_mut(row)
+                        .and_then(|bv| bv.get_erbbaurecht_mut())
+                    {
+                        erbbaurecht.grundstueck.grundbuch_von = new_value.trim().to_string();
+                    }
+                }
+                ("bv", "erbbau-blatt") => {
+                    if let Some(erbbaurecht) = open_file
+                        .analysiert
+                        .bestandsverzeichnis
+                        .eintraege
+                        .get_mut(row)
+                        .and_then(|bv| bv.get_erbbaurecht_mut())
+                    {
+                        erbbaurecht.grundstueck.blatt = new_value.trim().to_string();
+                    }
+                }
+                ("bv", "erbbau-bv-nr") => {
+                    if let Some(erbbaurecht) = open_file
+                        .analysiert
+                        .bestandsverzeichnis
+                        .eintraege
+                        .get_mut(row)
+                        .and_then(|bv| bv.get_erbbaurecht_mut())
+                    {
+                        erbbaurecht.grundstueck.bv_nr = new_value.clone().into();
+                    }
+                }
+                ("bv", "erbbau-abt2-lfd-nr") => {
+                    if let Some(erbbaurecht) = open_file
+                        .analysiert
+                        .bestandsverzeichnis
+                        .eintraege
+                        .get_mut(row)
+                        .and_then(|bv| bv.get_erbbaurecht_mut())
+                    {
+                        erbbaurecht.grundstueck.abt2_lfd_nr =
+                            new_value.trim().parse::<usize>().ok();
+                    }
+                }
+                ("bv", "erbbau-dauer") => {
+                    if let Some(erbbaurecht) = open_file
+                        .analysiert
+                        .bestandsverzeichnis
+                        .eintraege
+                        .get_mut(row)
+                        .and_then(|bv| bv.get_erbbaurecht_mut())
+                    {
+                        erbbaurecht.dauer = new_value.clone().into();
+                    }
+                }
                 ("bv", "recht-text") => {
                     let mut bv_eintrag = get_mut_or_insert_last(
                         &mut open_file.analysiert.bestandsverzeichnis.eintraege,
@@ -3537,24 +4443,18 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 }
             }
 
+            open_file.analysiert.ids_vergeben();
             open_file.speichern();
             let _ = webview.evaluate_script("saveState();");
-            open_file.icon = None;
             if data.konfiguration.lefis_analyse_einblenden {
-                let analyse = open_file.cache.start_analyzing(
-                    &open_file.analysiert,
-                    &data.vm,
-                    &data.loaded_nb,
-                    &data.konfiguration,
-                );
-                let _ = webview.evaluate_script(&format!(
-                    "replaceAnalyseGrundbuch(`{}`);",
-                    ui::render_analyse_grundbuch(&analyse, false, false)
-                ));
+                analyse_grundbuch_ersetzen(webview, data);
+            }
+            if let Some((file, _)) = data.open_page.clone() {
+                icon_aktualisieren(webview, data, &file);
             }
         }
         Cmd::BvEintragTypAendern { path, value } => {
-            use crate::digital::{BvEintragFlurstueck, BvEintragRecht};
+            use crate::digital::{BvEintragErbbaurecht, BvEintragFlurstueck, BvEintragRecht};
 
             let open_file = match data
                 .open_page
@@ -3577,56 +4477,72 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 None => return,
             };
 
-            let row = match split.get(1).and_then(|s| s.parse::<usize>().ok()) {
+            let row = match split
+                .get(1)
+                .and_then(|s| open_file.analysiert.zeile_aufloesen("bv", s))
+            {
+                Some(s) => s,
+                None => return,
+            };
+
+            let bv_eintrag = match open_file
+                .analysiert
+                .bestandsverzeichnis
+                .eintraege
+                .get(row)
+                .cloned()
+            {
                 Some(s) => s,
                 None => return,
             };
 
-            match value.as_str() {
-                "flst" => {
-                    if let Some(BvEintrag::Recht(BvEintragRecht {
+            let id = bv_eintrag.get_id();
+            let lfd_nr = bv_eintrag.get_lfd_nr();
+            let bisherige_lfd_nr = bv_eintrag.get_bisherige_lfd_nr();
+
+            let bv_eintrag_neu = match (value.as_str(), bv_eintrag) {
+                ("flst", BvEintrag::Flurstueck(_))
+                | ("recht", BvEintrag::Recht(_))
+                | ("erbbaurecht", BvEintrag::Erbbaurecht(_)) => return,
+                ("flst", _) => BvEintrag::Flurstueck(BvEintragFlurstueck {
+                    id,
+                    lfd_nr,
+                    bisherige_lfd_nr,
+                    ..BvEintragFlurstueck::neu(0)
+                }),
+                ("recht", BvEintrag::Erbbaurecht(erbbaurecht)) => {
+                    BvEintrag::Recht(BvEintragRecht {
+                        id,
                         lfd_nr,
                         bisherige_lfd_nr,
-                        ..
-                    })) = open_file
-                        .analysiert
-                        .bestandsverzeichnis
-                        .eintraege
-                        .get(row)
-                        .cloned()
-                    {
-                        open_file.analysiert.bestandsverzeichnis.eintraege[row] =
-                            BvEintrag::Flurstueck(BvEintragFlurstueck {
-                                lfd_nr,
-                                bisherige_lfd_nr,
-                                ..BvEintragFlurstueck::neu(0)
-                            });
-                    }
+                        text: erbbaurecht.text,
+                        ..BvEintragRecht::neu(0)
+                    })
                 }
-                "recht" => {
-                    if let Some(BvEintrag::Flurstueck(BvEintragFlurstueck {
+                ("recht", _) => BvEintrag::Recht(BvEintragRecht {
+                    id,
+                    lfd_nr,
+                    bisherige_lfd_nr,
+                    ..BvEintragRecht::neu(0)
+                }),
+                ("erbbaurecht", BvEintrag::Recht(recht)) => {
+                    BvEintrag::Erbbaurecht(BvEintragErbbaurecht {
+                        id,
                         lfd_nr,
                         bisherige_lfd_nr,
-                        ..
-                    })) = open_file
-                        .analysiert
-                        .bestandsverzeichnis
-                        .eintraege
-                        .get(row)
-                        .cloned()
-                    {
-                        open_file.analysiert.bestandsverzeichnis.eintraege[row] =
-                            BvEintrag::Recht(BvEintragRecht {
-                                lfd_nr,
-                                bisherige_lfd_nr,
-                                ..BvEintragRecht::neu(0)
-                            });
-                    }
-                }
-                _ => {
-                    return;
+                        ..BvEintragErbbaurecht::aus_text(0, &recht.text.text())
+                    })
                 }
-            }
+                ("erbbaurecht", _) => BvEintrag::Erbbaurecht(BvEintragErbbaurecht {
+                    id,
+                    lfd_nr,
+                    bisherige_lfd_nr,
+                    ..BvEintragErbbaurecht::neu(0)
+                }),
+                _ => return,
+            };
+
+            open_file.analysiert.bestandsverzeichnis.eintraege[row] = bv_eintrag_neu;
 
             // speichern
             open_file.speichern();
@@ -3681,16 +4597,7 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 "replaceAbt3Loeschungen(`{}`);",
                 ui::render_abt_3_loeschungen(open_file)
             ));
-            let analyse = open_file.cache.start_analyzing(
-                &open_file.analysiert,
-                &data.vm,
-                &data.loaded_nb,
-                &data.konfiguration,
-            );
-            let _ = webview.evaluate_script(&format!(
-                "replaceAnalyseGrundbuch(`{}`);",
-                ui::render_analyse_grundbuch(&analyse, false, false)
-            ));
+            analyse_grundbuch_ersetzen(webview, data);
             let _ = webview.evaluate_script(&format!(
                 "replacePageList(`{}`);",
                 ui::render_page_list(data)
@@ -3704,7 +4611,7 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 None => return,
             };
 
-            let row = match split.get(1).and_then(|s| s.parse::<usize>().ok()) {
+            let zeile_pfad = match split.get(1) {
                 Some(s) => s,
                 None => return,
             };
@@ -3718,6 +4625,11 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 None => return,
             };
 
+            let row = match open_file.analysiert.zeile_aufloesen(section, zeile_pfad) {
+                Some(s) => s,
+                None => return,
+            };
+
             fn insert_after<T: Clone>(vec: &mut Vec<T>, index: usize, new: T) {
                 if vec.is_empty() {
                     vec.push(new.clone());
@@ -3823,6 +4735,8 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 _ => return,
             };
 
+            open_file.analysiert.ids_vergeben();
+
             // speichern
             open_file.speichern();
             let _ = webview.evaluate_script("saveState();");
@@ -3876,16 +4790,7 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 "replaceAbt3Loeschungen(`{}`);",
                 ui::render_abt_3_loeschungen(open_file)
             ));
-            let analyse = open_file.cache.start_analyzing(
-                &open_file.analysiert,
-                &data.vm,
-                &data.loaded_nb,
-                &data.konfiguration,
-            );
-            let _ = webview.evaluate_script(&format!(
-                "replaceAnalyseGrundbuch(`{}`);",
-                ui::render_analyse_grundbuch(&analyse, false, false)
-            ));
+            analyse_grundbuch_ersetzen(webview, data);
             let _ = webview.evaluate_script(&format!(
                 "replacePageList(`{}`);",
                 ui::render_page_list(data)
@@ -3896,6 +4801,95 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 next_focus
             ));
         }
+        Cmd::ZelleRoeten { path, zeile } => {
+            let split = path.split(":").collect::<Vec<_>>();
+
+            let (section, zeile_pfad, spalte) = match (split.get(0), split.get(1), split.get(2)) {
+                (Some(section), Some(zeile_pfad), Some(spalte)) => (*section, *zeile_pfad, *spalte),
+                _ => return,
+            };
+
+            let open_file = match data
+                .open_page
+                .clone()
+                .and_then(|(file, _)| data.loaded_files.get_mut(&file))
+            {
+                Some(s) => s,
+                None => return,
+            };
+
+            let row = match open_file.analysiert.zeile_aufloesen(section, zeile_pfad) {
+                Some(s) => s,
+                None => return,
+            };
+
+            let zellen = match section {
+                "bv" => open_file
+                    .analysiert
+                    .bestandsverzeichnis
+                    .eintraege
+                    .get_mut(row)
+                    .and_then(|e| e.get_zellen_geroetet_mut()),
+                "abt2" => open_file
+                    .analysiert
+                    .abt2
+                    .eintraege
+                    .get_mut(row)
+                    .map(|e| &mut e.zellen_geroetet),
+                "abt3" => open_file
+                    .analysiert
+                    .abt3
+                    .eintraege
+                    .get_mut(row)
+                    .map(|e| &mut e.zellen_geroetet),
+                _ => None,
+            };
+
+            let zellen = match zellen {
+                Some(s) => s,
+                None => return,
+            };
+
+            let zelle = zellen.entry(spalte.to_string()).or_default();
+            match zeile {
+                Some(z) => {
+                    let cur = zelle.ist_zeile_geroetet(*z);
+                    zelle.manuell_geroetete_zeilen.insert(*z, !cur);
+                }
+                None => {
+                    let cur = zelle.ist_geroetet();
+                    zelle.manuell_geroetet = Some(!cur);
+                    zelle.manuell_geroetete_zeilen.clear();
+                }
+            }
+
+            if zelle.ist_leer() {
+                zellen.remove(spalte);
+            }
+
+            open_file.speichern();
+            let _ = webview.evaluate_script("saveState();");
+
+            let _ = match section {
+                "bv" => webview.evaluate_script(&format!(
+                    "replaceBestandsverzeichnis(`{}`);",
+                    ui::render_bestandsverzeichnis(open_file, &data.konfiguration)
+                )),
+                "abt2" => webview
+                    .evaluate_script(&format!("replaceAbt2(`{}`);", ui::render_abt_2(open_file))),
+                _ => webview.evaluate_script(&format!(
+                    "replaceAbt3(`{}`);",
+                    ui::render_abt_3(open_file, data.konfiguration.lefis_analyse_einblenden)
+                )),
+            };
+
+            if data.konfiguration.lefis_analyse_einblenden {
+                analyse_grundbuch_ersetzen(webview, data);
+            }
+            if let Some((file, _)) = data.open_page.clone() {
+                icon_aktualisieren(webview, data, &file);
+            }
+        }
         Cmd::EintragLoeschen { path } | Cmd::EintragRoeten { path } => {
             let eintrag_roeten = match arg {
                 Cmd::EintragLoeschen { .. } => false,
@@ -3909,7 +4903,7 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 None => return,
             };
 
-            let row = match split.get(1).and_then(|s| s.parse::<usize>().ok()) {
+            let zeile_pfad = match split.get(1) {
                 Some(s) => s,
                 None => return,
             };
@@ -3923,6 +4917,11 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 None => return,
             };
 
+            let row = match open_file.analysiert.zeile_aufloesen(section, zeile_pfad) {
+                Some(s) => s,
+                None => return,
+            };
+
             match (*section, eintrag_roeten) {
                 ("bv", false) => {
                     if !open_file
@@ -4340,16 +5339,7 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 "replaceAbt3Loeschungen(`{}`);",
                 ui::render_abt_3_loeschungen(open_file)
             ));
-            let analyse = open_file.cache.start_analyzing(
-                &open_file.analysiert,
-                &data.vm,
-                &data.loaded_nb,
-                &data.konfiguration,
-            );
-            let _ = webview.evaluate_script(&format!(
-                "replaceAnalyseGrundbuch(`{}`);",
-                ui::render_analyse_grundbuch(&analyse, false, false)
-            ));
+            analyse_grundbuch_ersetzen(webview, data);
             let _ = webview.evaluate_script(&format!(
                 "replacePageList(`{}`);",
                 ui::render_page_list(data)
@@ -4439,10 +5429,27 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 }
                 "rechtsinhaber-auslesen-abt3" => ConfigurationView::RechtsinhaberAuslesenAbt3,
                 "text-kuerzen-abt3" => ConfigurationView::TextKuerzenAbt3,
+                "pruefe-eintrag" => ConfigurationView::PruefeEintrag,
+                "eigentuemer-auslesen-abt1" => ConfigurationView::EigentuemerAuslesenAbt1,
+                "historie" => ConfigurationView::Historie,
                 _ => {
                     return;
                 }
             }));
+            // Ergebnis eines laufenden Tests gehört zur vorherigen Ansicht
+            data.testfaelle_lauf += 1;
+            // Bisherige Änderungen erscheinen in der Historie als eigene Version
+            if let Some(PopoverState::Configuration(ConfigurationView::Historie)) =
+                data.popover_state
+            {
+                if let Err(e) = data.konfiguration.version_speichern(None) {
+                    tinyfiledialogs::message_box_ok(
+                        "Version der Konfiguration nicht gespeichert",
+                        &e,
+                        MessageBoxIcon::Warning,
+                    );
+                }
+            }
             let _ = webview.evaluate_script(&format!(
                 "replacePopOver(`{}`)",
                 ui::render_popover_content(data)
@@ -4481,7 +5488,6 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 ui::render_entire_screen(data)
             ));
         }
-        Cmd::CheckPdfForErrors => {}
         Cmd::ToggleLefisAnalyse => {
             data.konfiguration.lefis_analyse_einblenden =
                 !data.konfiguration.lefis_analyse_einblenden;
@@ -4506,98 +5512,123 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
             data.konfiguration.text_kuerzen_abt2_script =
                 script.lines().map(|l| l.replace("\u{00a0}", " ")).collect();
             data.konfiguration.speichern();
+            testfaelle_nach_aenderung(webview, data);
         }
         Cmd::EditTextKuerzenAbt3Script { script } => {
             data.konfiguration.text_kuerzen_abt3_script =
                 script.lines().map(|l| l.replace("\u{00a0}", " ")).collect();
             data.konfiguration.speichern();
+            testfaelle_nach_aenderung(webview, data);
         }
         Cmd::EditAbkuerzungenScript { script } => {
             data.konfiguration.abkuerzungen_script =
                 script.lines().map(|l| l.replace("\u{00a0}", " ")).collect();
             data.konfiguration.speichern();
+            testfaelle_nach_aenderung(webview, data);
         }
         Cmd::EditTextSaubernScript { script } => {
             data.konfiguration.text_saubern_script =
                 script.lines().map(|l| l.replace("\u{00a0}", " ")).collect();
             data.konfiguration.speichern();
+            testfaelle_nach_aenderung(webview, data);
+        }
+        Cmd::TextSaubernScriptTesten { text } => {
+            script_testen(
+                webview,
+                data,
+                &TestZiel::TextSaubern,
+                text,
+                "replaceTextSaubernTestOutput",
+            );
+        }
+        Cmd::AbkuerzungenScriptTesten { text } => {
+            script_testen(
+                webview,
+                data,
+                &TestZiel::Abkuerzungen,
+                text,
+                "replaceAbkuerzungenTestOutput",
+            );
         }
         Cmd::EditFlurstueckeAuslesenScript { script } => {
             data.konfiguration.flurstuecke_auslesen_script =
                 script.lines().map(|l| l.replace("\u{00a0}", " ")).collect();
             data.konfiguration.speichern();
+            testfaelle_nach_aenderung(webview, data);
         }
         Cmd::EditRechteArtScript { neu } => {
             data.konfiguration.klassifiziere_rechteart =
                 neu.lines().map(|l| l.replace("\u{00a0}", " ")).collect();
             data.konfiguration.speichern();
+            testfaelle_nach_aenderung(webview, data);
         }
         Cmd::EditRangvermerkAuslesenAbt2Script { neu } => {
             data.konfiguration.rangvermerk_auslesen_abt2_script =
                 neu.lines().map(|l| l.replace("\u{00a0}", " ")).collect();
             data.konfiguration.speichern();
+            testfaelle_nach_aenderung(webview, data);
         }
         Cmd::EditRechtsinhaberAuslesenAbt2Script { neu } => {
             data.konfiguration.rechtsinhaber_auslesen_abt2_script =
                 neu.lines().map(|l| l.replace("\u{00a0}", " ")).collect();
             data.konfiguration.speichern();
+            testfaelle_nach_aenderung(webview, data);
         }
         Cmd::EditRechtsinhaberAuslesenAbt3Script { neu } => {
             data.konfiguration.rechtsinhaber_auslesen_abt3_script =
                 neu.lines().map(|l| l.replace("\u{00a0}", " ")).collect();
             data.konfiguration.speichern();
+            testfaelle_nach_aenderung(webview, data);
         }
         Cmd::EditSchuldenArtScript { neu } => {
             data.konfiguration.klassifiziere_schuldenart =
                 neu.lines().map(|l| l.replace("\u{00a0}", " ")).collect();
             data.konfiguration.speichern();
+            testfaelle_nach_aenderung(webview, data);
         }
         Cmd::EditBetragAuslesenScript { neu } => {
             data.konfiguration.betrag_auslesen_script =
                 neu.lines().map(|l| l.replace("\u{00a0}", " ")).collect();
             data.konfiguration.speichern();
+            testfaelle_nach_aenderung(webview, data);
+        }
+        Cmd::EditPruefeEintragScript { neu } => {
+            data.konfiguration.pruefe_eintrag_script =
+                neu.lines().map(|l| l.replace("\u{00a0}", " ")).collect();
+            data.konfiguration.speichern();
+            testfaelle_nach_aenderung(webview, data);
+        }
+        Cmd::PruefeEintragScriptTesten { text, abteilung } => {
+            let ziel = match abteilung {
+                2 => TestZiel::PruefeEintragAbt2,
+                _ => TestZiel::PruefeEintragAbt3,
+            };
+            script_testen(webview, data, &ziel, text, "replacePruefeEintragTestOutput");
+        }
+        Cmd::EditEigentuemerAuslesenAbt1Script { neu } => {
+            data.konfiguration.eigentuemer_auslesen_abt1_script =
+                neu.lines().map(|l| l.replace("\u{00a0}", " ")).collect();
+            data.konfiguration.speichern();
+            testfaelle_nach_aenderung(webview, data);
         }
         Cmd::FlurstueckAuslesenScriptTesten { text, bv_nr } => {
             let start = std::time::Instant::now();
             let mut debug_log = String::new();
-            let result: Result<String, String> = Err(String::new()).or_else(|_| {
-                let (text_sauber, saetze_clean) =
-                    crate::kurztext::text_saubern(data.vm.clone(), &*text, &data.konfiguration)?;
-
-                let mut fehler = Vec::new();
-                let mut warnungen = Vec::new();
-                let mut spalte1_eintraege = Vec::new();
-
-                let default_bv = Vec::new();
-                let open_file = data
-                    .open_page
-                    .clone()
-                    .and_then(|(file, _)| data.loaded_files.get_mut(&file));
-
-                let bv_eintraege = crate::analyse::get_belastete_flurstuecke(
-                    data.vm.clone(),
-                    bv_nr,
-                    &text_sauber,
-                    &Titelblatt {
-                        amtsgericht: "XXX".to_string(),
-                        grundbuch_von: "Unbekannt".to_string(),
-                        blatt: "0".to_string(),
-                    },
-                    open_file
-                        .map(|of| &of.analysiert.bestandsverzeichnis.eintraege)
-                        .unwrap_or(&default_bv),
-                    &data.konfiguration,
-                    &mut debug_log,
-                    &mut spalte1_eintraege,
-                    &mut warnungen,
-                    &mut fehler,
-                )?;
-                Ok(spalte1_eintraege
-                    .iter()
-                    .map(|e| format!("{e:#?}"))
-                    .collect::<Vec<_>>()
-                    .join("\r\n"))
-            });
+            let default_bv = Bestandsverzeichnis::default();
+            let bv = data
+                .open_page
+                .as_ref()
+                .and_then(|(file, _)| data.loaded_files.get(file))
+                .map(|of| &of.analysiert.bestandsverzeichnis)
+                .unwrap_or(&default_bv);
+            let result = testfaelle::flurstuecke_auslesen(
+                data.vm.clone(),
+                text,
+                bv_nr,
+                bv,
+                &data.konfiguration,
+                &mut debug_log,
+            );
 
             let time = std::time::Instant::now() - start;
             let result: String = match result {
@@ -4617,283 +5648,85 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
             ));
         }
         Cmd::RangvermerkAuslesenAbt2ScriptTesten { text } => {
-            let start = std::time::Instant::now();
-            let result: Result<String, String> = Err(String::new()).or_else(|_| {
-                let (text_sauber, saetze_clean) =
-                    crate::kurztext::text_saubern(data.vm.clone(), &*text, &data.konfiguration)?;
-
-                crate::python::get_rangvermerk_abt2(
-                    data.vm.clone(),
-                    "",
-                    &text_sauber,
-                    &saetze_clean,
-                    &data.konfiguration,
-                )
-                .map_err(|e| e.text)
-            });
-
-            let time = std::time::Instant::now() - start;
-            let result: String = match result {
-                Ok(o) => {
-                    format!("{}\r\nAusgabe berechnet in {:?}", o, time)
-                }
-                Err(e) => {
-                    format!("{}", e)
-                }
-            };
-            let _ = webview.evaluate_script(&format!(
-                "replaceRangvermerkAuslesenAbt2TestOutput(`{}`);",
-                result
-            ));
+            script_testen(
+                webview,
+                data,
+                &TestZiel::RangvermerkAbt2,
+                text,
+                "replaceRangvermerkAuslesenAbt2TestOutput",
+            );
         }
         Cmd::RechtsinhaberAuslesenAbt2ScriptTesten { text } => {
-            let start = std::time::Instant::now();
-            let result: Result<String, String> = Err(String::new()).or_else(|_| {
-                let (text_sauber, saetze_clean) =
-                    crate::kurztext::text_saubern(data.vm.clone(), &*text, &data.konfiguration)?;
-
-                crate::python::get_rechtsinhaber_abt2(
-                    data.vm.clone(),
-                    "",
-                    &text_sauber,
-                    &saetze_clean,
-                    &data.konfiguration,
-                )
-                .map_err(|e| e.text)
-            });
-            let time = std::time::Instant::now() - start;
-            let result: String = match result {
-                Ok(o) => {
-                    format!("{}\r\nAusgabe berechnet in {:?}", o, time)
-                }
-                Err(e) => {
-                    format!("{}", e)
-                }
-            };
-            let _ = webview.evaluate_script(&format!(
-                "replaceRechtsinhaberAbt2TestOutput(`{}`);",
-                result
-            ));
+            script_testen(
+                webview,
+                data,
+                &TestZiel::RechtsinhaberAbt2,
+                text,
+                "replaceRechtsinhaberAbt2TestOutput",
+            );
         }
         Cmd::RechtsinhaberAuslesenAbt3ScriptTesten { text } => {
-            let start = std::time::Instant::now();
-            let result: Result<String, String> = Err(String::new()).or_else(|_| {
-                let (text_sauber, saetze_clean) =
-                    crate::kurztext::text_saubern(data.vm.clone(), &*text, &data.konfiguration)?;
-
-                crate::python::get_rechtsinhaber_abt3(
-                    data.vm.clone(),
-                    "",
-                    &text_sauber,
-                    &saetze_clean,
-                    &data.konfiguration,
-                )
-                .map_err(|e| e.text)
-            });
-            let time = std::time::Instant::now() - start;
-            let result = match result {
-                Ok(o) => {
-                    format!("{}\r\nAusgabe berechnet in {:?}", o, time)
-                }
-                Err(e) => {
-                    format!("{}", e)
-                }
-            };
-            let _ = webview.evaluate_script(&format!(
-                "replaceRechtsinhaberAbt3TestOutput(`{}`);",
-                result
-            ));
+            script_testen(
+                webview,
+                data,
+                &TestZiel::RechtsinhaberAbt3,
+                text,
+                "replaceRechtsinhaberAbt3TestOutput",
+            );
+        }
+        Cmd::EigentuemerAuslesenAbt1ScriptTesten { text } => {
+            script_testen(
+                webview,
+                data,
+                &TestZiel::EigentuemerAbt1,
+                text,
+                "replaceEigentuemerAbt1TestOutput",
+            );
         }
         Cmd::BetragAuslesenScriptTesten { text } => {
-            let start = std::time::Instant::now();
-            let result: Result<Betrag, String> = Err(String::new()).or_else(|_| {
-                let (text_sauber, saetze_clean) =
-                    crate::kurztext::text_saubern(data.vm.clone(), &*text, &data.konfiguration)?;
-
-                crate::python::get_betrag_abt3(
-                    data.vm.clone(),
-                    "",
-                    &text_sauber,
-                    &saetze_clean,
-                    &data.konfiguration,
-                )
-                .map_err(|e| e.text)
-            });
-            let time = std::time::Instant::now() - start;
-            let result = match result {
-                Ok(o) => {
-                    format!("{:#?}\r\nAusgabe berechnet in {:?}", o, time)
-                }
-                Err(e) => {
-                    format!("{}", e)
-                }
-            };
-            let _ =
-                webview.evaluate_script(&format!("replaceBetragAuslesenTestOutput(`{}`);", result));
+            script_testen(
+                webview,
+                data,
+                &TestZiel::BetragAbt3,
+                text,
+                "replaceBetragAuslesenTestOutput",
+            );
         }
         Cmd::KurzTextAbt2ScriptTesten { text } => {
-            let start = std::time::Instant::now();
-            let result: Result<String, String> = Err(String::new()).or_else(|_| {
-                let (text_sauber, saetze_clean) =
-                    crate::kurztext::text_saubern(data.vm.clone(), &*text, &data.konfiguration)?;
-
-                let rechtsinhaber = crate::python::get_rechtsinhaber_abt2(
-                    data.vm.clone(),
-                    "",
-                    &text_sauber,
-                    &saetze_clean,
-                    &data.konfiguration,
-                )
-                .ok();
-
-                let rangvermerk = crate::python::get_rangvermerk_abt2(
-                    data.vm.clone(),
-                    "",
-                    &text_sauber,
-                    &saetze_clean,
-                    &data.konfiguration,
-                )
-                .ok();
-
-                crate::python::get_kurztext_abt2(
-                    data.vm.clone(),
-                    "",
-                    &text_sauber,
-                    rechtsinhaber,
-                    rangvermerk,
-                    &saetze_clean,
-                    &data.konfiguration,
-                )
-                .map_err(|e| e.text)
-            });
-
-            let time = std::time::Instant::now() - start;
-            let result = match result {
-                Ok(o) => {
-                    format!("{}\r\nAusgabe berechnet in {:?}", o, time)
-                }
-                Err(e) => {
-                    format!("{}", e)
-                }
-            };
-            let _ = webview
-                .evaluate_script(&format!("replaceTextKuerzenAbt2TestOutput(`{}`);", result));
+            script_testen(
+                webview,
+                data,
+                &TestZiel::KurzTextAbt2,
+                text,
+                "replaceTextKuerzenAbt2TestOutput",
+            );
         }
         Cmd::KurzTextAbt3ScriptTesten { text } => {
-            let start = std::time::Instant::now();
-
-            let result: Result<String, String> = Err(String::new()).or_else(|_| {
-                let (text_sauber, saetze_clean) =
-                    crate::kurztext::text_saubern(data.vm.clone(), &*text, &data.konfiguration)?;
-
-                let schuldenart = crate::python::get_schulden_art_abt3(
-                    data.vm.clone(),
-                    "",
-                    &text_sauber,
-                    &saetze_clean,
-                    &data.konfiguration,
-                )?;
-
-                let betrag = crate::python::get_betrag_abt3(
-                    data.vm.clone(),
-                    "",
-                    &format!("100.000,00 EUR"),
-                    &[format!("100.000,00 EUR")],
-                    &data.konfiguration,
-                )?;
-
-                let rechtsinhaber = crate::python::get_rechtsinhaber_abt3(
-                    data.vm.clone(),
-                    "",
-                    &text_sauber,
-                    &saetze_clean,
-                    &data.konfiguration,
-                )?;
-
-                let betrag = format!(
-                    "{} {}",
-                    crate::kurztext::formatiere_betrag(&betrag),
-                    betrag.waehrung.to_string()
-                );
-                crate::python::get_kurztext_abt3(
-                    data.vm.clone(),
-                    "",
-                    &text_sauber,
-                    Some(betrag),
-                    Some(schuldenart.to_string().to_string()),
-                    Some(rechtsinhaber),
-                    &saetze_clean,
-                    &data.konfiguration,
-                )
-                .map_err(|e| e.text)
-            });
-
-            let time = std::time::Instant::now() - start;
-            let result = match result {
-                Ok(o) => {
-                    format!("{}\r\nAusgabe berechnet in {:?}", o, time)
-                }
-                Err(e) => {
-                    format!("{}", e)
-                }
-            };
-            let _ = webview
-                .evaluate_script(&format!("replaceTextKuerzenAbt3TestOutput(`{}`);", result));
+            script_testen(
+                webview,
+                data,
+                &TestZiel::KurzTextAbt3,
+                text,
+                "replaceTextKuerzenAbt3TestOutput",
+            );
         }
-
         Cmd::RechteArtScriptTesten { text } => {
-            let start = std::time::Instant::now();
-            let result: Result<RechteArt, String> = Err(String::new()).or_else(|_| {
-                let (text_sauber, saetze_clean) =
-                    crate::kurztext::text_saubern(data.vm.clone(), &*text, &data.konfiguration)?;
-
-                crate::python::get_rechte_art_abt2(
-                    data.vm.clone(),
-                    "",
-                    &text_sauber,
-                    &saetze_clean,
-                    &data.konfiguration,
-                )
-                .map_err(|e| e.text)
-            });
-            let time = std::time::Instant::now() - start;
-            let result = match result {
-                Ok(o) => {
-                    format!("{:?}\r\nAusgabe berechnet in {:?}", o, time)
-                }
-                Err(e) => {
-                    format!("{}", e)
-                }
-            };
-
-            let _ = webview.evaluate_script(&format!("replaceRechteArtTestOutput(`{}`);", result));
+            script_testen(
+                webview,
+                data,
+                &TestZiel::RechteArtAbt2,
+                text,
+                "replaceRechteArtTestOutput",
+            );
         }
         Cmd::SchuldenArtScriptTesten { text } => {
-            let start = std::time::Instant::now();
-            let result: Result<SchuldenArt, String> = Err(String::new()).or_else(|_| {
-                let (text_sauber, saetze_clean) =
-                    crate::kurztext::text_saubern(data.vm.clone(), &*text, &data.konfiguration)?;
-
-                crate::python::get_schulden_art_abt3(
-                    data.vm.clone(),
-                    "",
-                    &text_sauber,
-                    &saetze_clean,
-                    &data.konfiguration,
-                )
-                .map_err(|e| e.text)
-            });
-            let time = std::time::Instant::now() - start;
-            let result = match result {
-                Ok(o) => {
-                    format!("{:?}\r\nAusgabe berechnet in {:?}", o, time)
-                }
-                Err(e) => {
-                    format!("{}", e)
-                }
-            };
-            let _ =
-                webview.evaluate_script(&format!("replaceSchuldenArtTestOutput(`{}`);", result));
+            script_testen(
+                webview,
+                data,
+                &TestZiel::SchuldenArtAbt3,
+                text,
+                "replaceSchuldenArtTestOutput",
+            );
         }
         Cmd::DeleteNebenbeteiligte => {
             use tinyfiledialogs::YesNo;
@@ -4994,8 +5827,16 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
         }
         Cmd::ClosePopOver {} => {
             if let Some(PopoverState::Configuration(_)) = data.popover_state {
-                for (k, v) in data.loaded_files.iter_mut() {
-                    v.icon = None;
+                if let Err(e) = data.konfiguration.version_speichern(None) {
+                    tinyfiledialogs::message_box_ok(
+                        "Version der Konfiguration nicht gespeichert",
+                        &e,
+                        MessageBoxIcon::Warning,
+                    );
+                }
+                let dateien = data.loaded_files.keys().cloned().collect::<Vec<_>>();
+                for datei in dateien.iter() {
+                    icon_neu_berechnen(data, datei);
                 }
                 let _ = webview.evaluate_script(&format!(
                     "replaceFileList(`{}`)",
@@ -5135,7 +5976,7 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                         .map(|s| s.0);
                 match result {
                     Ok(o) => o,
-                    Err(e) => e.text,
+                    Err(e) => e.text(),
                 }
             };
             let _ = webview.evaluate_script(&format!("copyTextToClipboard(`{}`)", text));
@@ -5158,7 +5999,7 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 None => return,
             };
 
-            if !translate_gbx(open_file).digitalisiert {
+            if !translate_gbx_datei(open_file).digitalisiert {
                 tinyfiledialogs::message_box_ok(
                     "Grundbuch kann nicht neu geladen werden",
                     "Dieses Grundbuch wurde digital angelegt und kann nicht neu digitalisiert werden.",
@@ -5217,12 +6058,13 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 .clone()
                 .join(&format!("{}.cache.gbx", file_name));
 
-            let grundbuch_neu =
+            let mut grundbuch_neu =
                 match analyse_grundbuch(data.vm.clone(), &open_file, &data.konfiguration) {
                     Some(s) => s,
                     None => return,
                 };
 
+            grundbuch_neu.ids_uebernehmen(&open_file.analysiert);
             open_file.analysiert = grundbuch_neu;
 
             open_file.speichern();
@@ -5457,6 +6299,65 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
             ));
         }
         Cmd::ToggleCheckBox { checkbox_id } => {
+            // bv:{zeile}:zustimmung-veraeusserung
+            if checkbox_id.starts_with("bv:") {
+                use crate::digital::Zustimmungsvorbehalt;
+
+                let split = checkbox_id.split(":").collect::<Vec<_>>();
+
+                let zeile_pfad = match split.get(1) {
+                    Some(s) => s,
+                    None => return,
+                };
+
+                let zustimmungsvorbehalt =
+                    match split.get(2).and_then(|s| Zustimmungsvorbehalt::from_id(s)) {
+                        Some(s) => s,
+                        None => return,
+                    };
+
+                let open_file = match data
+                    .open_page
+                    .clone()
+                    .and_then(|(file, _)| data.loaded_files.get_mut(&file))
+                {
+                    Some(s) => s,
+                    None => return,
+                };
+
+                let row = match open_file.analysiert.zeile_aufloesen("bv", zeile_pfad) {
+                    Some(s) => s,
+                    None => return,
+                };
+
+                let erbbaurecht = match open_file
+                    .analysiert
+                    .bestandsverzeichnis
+                    .eintraege
+                    .get_mut(row)
+                    .and_then(|bv| bv.get_erbbaurecht_mut())
+                {
+                    Some(s) => s,
+                    None => return,
+                };
+
+                if erbbaurecht
+                    .zustimmungsvorbehalte
+                    .contains(&zustimmungsvorbehalt)
+                {
+                    erbbaurecht
+                        .zustimmungsvorbehalte
+                        .retain(|z| *z != zustimmungsvorbehalt);
+                } else {
+                    erbbaurecht.zustimmungsvorbehalte.push(zustimmungsvorbehalt);
+                    erbbaurecht.zustimmungsvorbehalte.sort();
+                }
+
+                open_file.speichern();
+                let _ = webview.evaluate_script("saveState();");
+                return;
+            }
+
             match checkbox_id.as_str() {
                 "konfiguration-zeilenumbrueche-in-ocr-text" => {
                     data.konfiguration.zeilenumbrueche_in_ocr_text =
@@ -5533,25 +6434,73 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
             let tsv = get_nebenbeteiligte_tsv(&data);
             let _ = fs::write(f_name, tsv.as_bytes());
 
-            let open_file = match data
-                .open_page
-                .clone()
-                .and_then(|(file, _)| data.loaded_files.get(&file))
-            {
+            analyse_grundbuch_ersetzen(webview, data);
+        }
+        Cmd::ImportGemarkungen => {
+            let file_dialog_result = tinyfiledialogs::open_file_dialog(
+                "Gemarkungskatalog auswählen",
+                "",
+                Some((&["*.tsv"], "Gemarkungen")),
+            );
+
+            let f_name = match file_dialog_result {
                 Some(s) => s,
                 None => return,
             };
 
-            let analyse = open_file.cache.start_analyzing(
-                &open_file.analysiert,
-                &data.vm,
-                &data.loaded_nb,
-                &data.konfiguration,
+            let fs = match fs::read_to_string(&f_name).ok() {
+                Some(s) => s,
+                None => return,
+            };
+
+            let gemarkungen = match alkis::gemarkungen_lesen(&fs) {
+                Ok(o) => o,
+                Err(e) => {
+                    tinyfiledialogs::message_box_ok(
+                        "Gemarkungskatalog konnte nicht gelesen werden",
+                        &e.replace("\"", "").replace("'", ""),
+                        MessageBoxIcon::Error,
+                    );
+                    return;
+                }
+            };
+
+            alkis::gemarkungen_zusammenfuehren(&mut data.konfiguration.gemarkungen, gemarkungen);
+            data.konfiguration.speichern();
+
+            analyse_grundbuch_ersetzen(webview, data);
+        }
+        Cmd::ImportAlkis => {
+            let file_dialog_result = tinyfiledialogs::open_file_dialog(
+                "ALKIS-Auszug (NAS / GeoJSON) auswählen",
+                "",
+                Some((&["*.xml", "*.gml", "*.geojson", "*.json"], "ALKIS-Auszug")),
             );
-            let _ = webview.evaluate_script(&format!(
-                "replaceAnalyseGrundbuch(`{}`);",
-                ui::render_analyse_grundbuch(&analyse, false, false)
-            ));
+
+            let f_name = match file_dialog_result {
+                Some(s) => s,
+                None => return,
+            };
+
+            let bestand = match alkis::AlkisBestand::datei_laden(Path::new(&f_name)) {
+                Ok(o) => o,
+                Err(e) => {
+                    tinyfiledialogs::message_box_ok(
+                        "ALKIS-Auszug konnte nicht gelesen werden",
+                        &e.replace("\"", "").replace("'", ""),
+                        MessageBoxIcon::Error,
+                    );
+                    return;
+                }
+            };
+
+            if !data.konfiguration.alkis_dateien.contains(&f_name) {
+                data.konfiguration.alkis_dateien.push(f_name);
+            }
+            Arc::make_mut(&mut data.konfiguration.alkis).zusammenfuehren(bestand);
+            data.konfiguration.speichern();
+
+            analyse_grundbuch_ersetzen(webview, data);
         }
         Cmd::ExportNebenbeteiligte => {
             if data.loaded_files.is_empty() {
@@ -5576,6 +6525,146 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
 
             let _ = std::fs::write(&f, tsv.as_bytes());
         }
+        Cmd::ExportRangverhaeltnisse => {
+            if data.loaded_files.is_empty() {
+                return;
+            }
+
+            let file_dialog_result =
+                tinyfiledialogs::save_file_dialog("Rangverhältnisse .TSV speichern unter", "");
+
+            let f = match file_dialog_result {
+                Some(f) => {
+                    if f.ends_with(".tsv") {
+                        f
+                    } else {
+                        format!("{}.tsv", f)
+                    }
+                }
+                None => return,
+            };
+
+            let tsv = get_rangverhaeltnisse_tsv(&data);
+
+            let _ = std::fs::write(&f, tsv.as_bytes());
+        }
+        Cmd::ExportGeoJson => {
+            if data.loaded_files.is_empty() {
+                return;
+            }
+
+            if data.konfiguration.gemarkungen.is_empty() {
+                tinyfiledialogs::message_box_ok(
+                    "Kein Gemarkungskatalog",
+                    "Für den GeoJSON-Export wird das Flurstückskennzeichen benötigt. Bitte zuerst einen Gemarkungskatalog importieren.",
+                    MessageBoxIcon::Warning,
+                );
+                return;
+            }
+
+            let file_dialog_result = tinyfiledialogs::open_file_dialog(
+                "Flurstücksgeometrien (GeoJSON / Shapefile) auswählen",
+                "",
+                Some((&["*.geojson", "*.json", "*.shp"], "Flurstücksgeometrien")),
+            );
+
+            let geometrie_datei = match file_dialog_result {
+                Some(s) => s,
+                None => return,
+            };
+
+            let geometrien =
+                match alkis::FlurstueckGeometrien::datei_laden(Path::new(&geometrie_datei)) {
+                    Ok(o) => o,
+                    Err(e) => {
+                        tinyfiledialogs::message_box_ok(
+                            "Flurstücksgeometrien konnten nicht gelesen werden",
+                            &e.replace("\"", "").replace("'", ""),
+                            MessageBoxIcon::Error,
+                        );
+                        return;
+                    }
+                };
+
+            let analysiert = data
+                .loaded_files
+                .values()
+                .map(|file| {
+                    file.cache.start_and_block_until_finished(
+                        &file.analysiert,
+                        &data.vm,
+                        &data.loaded_nb,
+                        &data.konfiguration,
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            use tinyfiledialogs::YesNo;
+            let je_blatt = analysiert.len() > 1
+                && tinyfiledialogs::message_box_yes_no(
+                    "Eine Datei je Blatt?",
+                    &format!("Es sind {} Blätter geöffnet.\r\n\r\nSoll für jedes Blatt eine eigene .geojson-Datei erstellt werden? Sonst werden alle Blätter in eine Datei exportiert.", analysiert.len()),
+                    MessageBoxIcon::Question,
+                    YesNo::No,
+                ) == YesNo::Yes;
+
+            let file_dialog_result =
+                tinyfiledialogs::save_file_dialog(".geojson-Datei speichern unter", "");
+
+            let f = match file_dialog_result {
+                Some(f) => f.trim_end_matches(".geojson").to_string(),
+                None => return,
+            };
+
+            let exporte = if je_blatt {
+                analysiert
+                    .iter()
+                    .map(|gb| {
+                        let datei = format!(
+                            "{}_{}_{}.geojson",
+                            f, gb.titelblatt.grundbuch_von, gb.titelblatt.blatt
+                        );
+                        (datei, std::slice::from_ref(gb))
+                    })
+                    .collect::<Vec<_>>()
+            } else {
+                vec![(format!("{}.geojson", f), &analysiert[..])]
+            };
+
+            let mut fehler = Vec::new();
+            for (datei, blaetter) in exporte {
+                let (geojson, nicht_exportiert) = alkis::belastungen_geojson(
+                    blaetter,
+                    &data.konfiguration.gemarkungen,
+                    &geometrien,
+                );
+                fehler.extend(nicht_exportiert);
+                let json = match serde_json::to_string_pretty(&geojson) {
+                    Ok(o) => o,
+                    Err(_) => continue,
+                };
+                let _ = std::fs::write(&datei, json.as_bytes());
+            }
+
+            if !fehler.is_empty() {
+                tinyfiledialogs::message_box_ok(
+                    "Nicht alle Flurstücke exportiert",
+                    &format!(
+                        "{} Flurstücke konnten nicht exportiert werden:\r\n\r\n{}",
+                        fehler.len(),
+                        fehler
+                            .iter()
+                            .take(20)
+                            .cloned()
+                            .collect::<Vec<_>>()
+                            .join("\r\n")
+                    )
+                    .replace("\"", "")
+                    .replace("'", ""),
+                    MessageBoxIcon::Warning,
+                );
+            }
+        }
         Cmd::GrundbuchExportieren {
             was_exportieren,
             exportiere_bv,
@@ -5836,6 +6925,95 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
 
             let _ = std::fs::write(&f, html.as_bytes());
         }
+        Cmd::KonfigurationVergleichen => {
+            let konfiguration_laden = |titel: &str| -> Option<Konfiguration> {
+                let pfad = tinyfiledialogs::open_file_dialog(
+                    titel,
+                    "",
+                    Some((&["*.json"], "Konfiguration")),
+                )?;
+                match std::fs::read_to_string(&pfad)
+                    .map_err(|e| format!("{e}"))
+                    .and_then(|s| {
+                        serde_json::from_str::<Konfiguration>(&s).map_err(|e| format!("{e}"))
+                    }) {
+                    Ok(mut o) => {
+                        let _ = o.alkis_laden();
+                        Some(o)
+                    }
+                    Err(e) => {
+                        tinyfiledialogs::message_box_ok(
+                            &format!("Fehler beim Laden von {pfad}"),
+                            &e,
+                            MessageBoxIcon::Error,
+                        );
+                        None
+                    }
+                }
+            };
+
+            let alt = match konfiguration_laden("Alte Konfiguration auswählen") {
+                Some(s) => s,
+                None => return,
+            };
+            let neu = match konfiguration_laden("Neue Konfiguration auswählen") {
+                Some(s) => s,
+                None => return,
+            };
+            let ordner = match tinyfiledialogs::select_folder_dialog(
+                "Ordner mit .gbx-Dateien auswählen",
+                "",
+            ) {
+                Some(s) => s,
+                None => return,
+            };
+
+            let file_dialog_result =
+                tinyfiledialogs::save_file_dialog("Vergleich .HTML speichern unter", "");
+
+            let f = match file_dialog_result {
+                Some(f) => f.trim_end_matches(".html").to_string(),
+                None => return,
+            };
+
+            // Jedes Blatt wird zweimal analysiert, daher nicht im Thread der Oberfläche
+            let vm = data.vm.clone();
+            let nb = data.loaded_nb.clone();
+            std::thread::spawn(move || {
+                let vergleich = vergleich::konfigurationen_vergleichen(
+                    Path::new(&ordner),
+                    &alt,
+                    &neu,
+                    &vm,
+                    &nb,
+                    |fertig, gesamt| {
+                        analyse::ereignis_senden(Cmd::KonfigurationVergleichFortschritt {
+                            fertig,
+                            gesamt,
+                        })
+                    },
+                );
+
+                let meldung = match std::fs::write(&format!("{f}.html"), vergleich.als_html())
+                    .and_then(|_| std::fs::write(&format!("{f}.tsv"), vergleich.als_tsv()))
+                {
+                    Ok(()) => vergleich.zusammenfassung(),
+                    Err(e) => format!("Fehler beim Speichern von {f}: {e}"),
+                };
+                analyse::ereignis_senden(Cmd::KonfigurationVergleichFertig { meldung });
+            });
+        }
+        Cmd::KonfigurationVergleichFortschritt { fertig, gesamt } => {
+            let _ = webview.evaluate_script(&format!(
+                "replaceKonfigurationVergleichFortschritt(`{fertig} von {gesamt} Blättern verglichen...`);"
+            ));
+        }
+        Cmd::KonfigurationVergleichFertig { meldung } => {
+            let _ = webview.evaluate_script(&format!(
+                "replaceKonfigurationVergleichFortschritt(`{}`);",
+                html_escape::encode_safe(meldung)
+            ));
+        }
         Cmd::ExportLefis => {
             if data.loaded_files.is_empty() {
                 return;
@@ -5855,7 +7033,12 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 })
                 .collect::<Vec<_>>();
 
-            let json = match serde_json::to_string_pretty(&analysiert) {
+            let json = match serde_json::to_string_pretty(
+                &analysiert
+                    .iter()
+                    .map(|l| l.lefis_json())
+                    .collect::<Vec<_>>(),
+            ) {
                 Ok(o) => o,
                 Err(_) => return,
             };
@@ -5867,14 +7050,14 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 .iter()
                 .flat_map(|l| {
                     l.rechte
-                        .abt2
+                        .abt1
                         .iter()
                         .filter_map(|f| {
-                            if f.fehler.is_empty() {
+                            if f.fehler.iter().all(|e| e.quittierung.is_some()) {
                                 None
                             } else {
                                 Some(format!(
-                                    "{} Blatt {}, Abt 2 lfd. Nr. {}",
+                                    "{} Blatt {}, Abt 1 lfd. Nr. {}",
                                     l.titelblatt.grundbuch_von, l.titelblatt.blatt, f.lfd_nr
                                 ))
                             }
@@ -5883,12 +7066,29 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 })
                 .collect::<Vec<_>>();
 
+            fehler.extend(analysiert.iter().flat_map(|l| {
+                l.rechte
+                    .abt2
+                    .iter()
+                    .filter_map(|f| {
+                        if f.fehler.iter().all(|e| e.quittierung.is_some()) {
+                            None
+                        } else {
+                            Some(format!(
+                                "{} Blatt {}, Abt 2 lfd. Nr. {}",
+                                l.titelblatt.grundbuch_von, l.titelblatt.blatt, f.lfd_nr
+                            ))
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            }));
+
             fehler.extend(analysiert.iter().flat_map(|l| {
                 l.rechte
                     .abt3
                     .iter()
                     .filter_map(|f| {
-                        if f.fehler.is_empty() {
+                        if f.fehler.iter().all(|e| e.quittierung.is_some()) {
                             None
                         } else {
                             Some(format!(
@@ -5942,14 +7142,25 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 .cloned()
                 .unwrap_or_default();
             data.konfiguration.regex.remove(&old_key);
-            data.konfiguration.regex.insert(new_key, cur_value);
+            data.konfiguration.regex.insert(new_key.clone(), cur_value);
+            let testfaelle_regex = data
+                .konfiguration
+                .testfaelle
+                .remove(&TestZiel::Regex(old_key).id());
+            if let Some(t) = testfaelle_regex {
+                data.konfiguration
+                    .testfaelle
+                    .insert(TestZiel::Regex(new_key).id(), t);
+            }
             data.konfiguration.speichern();
+            testfaelle_nach_aenderung(webview, data);
         }
         Cmd::EditRegexValue { key, value } => {
             let key: String = key.chars().filter(|c| !c.is_whitespace()).collect();
             let value: String = value.chars().filter(|c| *c != '\n').collect();
             data.konfiguration.regex.insert(key, value);
             data.konfiguration.speichern();
+            testfaelle_nach_aenderung(webview, data);
         }
         Cmd::InsertRegex { regex_key } => {
             data.konfiguration
@@ -5974,24 +7185,256 @@ fn webview_cb(webview: &WebView, arg: &Cmd, data: &mut RpcData) {
                 ui::render_entire_screen(data)
             ));
         }
-        Cmd::TesteRegex { regex_id, text } => {
-            let result = teste_regex(&regex_id, text.trim(), &data.konfiguration);
-            let result = match result {
-                Ok(o) => {
-                    if o.is_empty() {
-                        format!("[]")
-                    } else {
-                        o.into_iter()
-                            .enumerate()
-                            .map(|(col, v)| format!("[{}]: \"{}\"", col, v))
-                            .collect::<Vec<_>>()
-                            .join("\r\n")
-                    }
+        Cmd::HistorieVergleichen { alt, neu } => {
+            let _ = webview.evaluate_script(&format!(
+                "replaceHistorieDiff(`{}`);",
+                ui::render_historie_diff(alt, neu)
+            ));
+        }
+        Cmd::HistorieFehler { meldung } => {
+            tinyfiledialogs::message_box_ok(
+                "Version der Konfiguration nicht gespeichert",
+                meldung,
+                MessageBoxIcon::Warning,
+            );
+        }
+        Cmd::HistorieWiederherstellen { id } => {
+            use tinyfiledialogs::YesNo;
+
+            let version = match historie::version_laden(id) {
+                Ok(o) => o,
+                Err(e) => {
+                    tinyfiledialogs::message_box_ok(
+                        "Version konnte nicht geladen werden",
+                        &e,
+                        MessageBoxIcon::Error,
+                    );
+                    return;
                 }
+            };
+
+            let kurz_id = id.get(..7).unwrap_or(id);
+            if tinyfiledialogs::message_box_yes_no(
+                "Version wiederherstellen?",
+                &format!("Die Skripte und regulären Ausdrücke werden durch die aus Version {kurz_id} ersetzt, Einstellungen und Testfälle bleiben erhalten. Der aktuelle Stand bleibt in der Versionsgeschichte erhalten. Fortfahren?"),
+                MessageBoxIcon::Warning,
+                YesNo::No,
+            ) == YesNo::No
+            {
+                return;
+            }
+
+            // Ohne gesicherten aktuellen Stand wird nichts ersetzt
+            if let Err(e) = data.konfiguration.version_speichern(None) {
+                tinyfiledialogs::message_box_ok(
+                    "Version nicht wiederhergestellt",
+                    &e,
+                    MessageBoxIcon::Error,
+                );
+                return;
+            }
+
+            data.konfiguration = historie::version_wiederherstellen(&data.konfiguration, &version);
+            data.konfiguration.speichern();
+            if let Err(e) = data
+                .konfiguration
+                .version_speichern(Some(&format!("Version {kurz_id} wiederhergestellt")))
+            {
+                tinyfiledialogs::message_box_ok(
+                    "Version der Konfiguration nicht gespeichert",
+                    &e,
+                    MessageBoxIcon::Warning,
+                );
+            }
+            let _ = webview.evaluate_script(&format!(
+                "replaceEntireScreen(`{}`)",
+                ui::render_entire_screen(data)
+            ));
+        }
+        Cmd::TestfallSpeichern {
+            ziel,
+            eingabe,
+            bv_nr,
+        } => {
+            let ziel = match TestZiel::from_id(ziel) {
+                Some(s) => s,
+                None => return,
+            };
+            // Eine Fehlermeldung des Skripts ist keine erwartete Ausgabe
+            let erwartet = match testfaelle::ausgabe(
+                &ziel,
+                eingabe,
+                bv_nr,
+                data.vm.clone(),
+                &data.konfiguration,
+            ) {
+                Ok(o) => o,
                 Err(e) => {
-                    format!("{}", e)
+                    tinyfiledialogs::message_box_ok(
+                        "Testfall nicht gespeichert",
+                        &format!("Das Skript liefert für die Testeingabe einen Fehler:\r\n{e}"),
+                        MessageBoxIcon::Error,
+                    );
+                    return;
+                }
+            };
+            let anzahl = data
+                .konfiguration
+                .testfaelle
+                .get(&ziel.id())
+                .map(|t| t.len())
+                .unwrap_or_default();
+            let name = match tinyfiledialogs::input_box(
+                "Testfall speichern",
+                &format!(
+                    "Name des Testfalls für {}, die aktuelle Ausgabe wird als erwartete Ausgabe gespeichert:",
+                    ziel.titel()
+                ),
+                &format!("Testfall {}", anzahl + 1),
+            ) {
+                Some(s) if !s.trim().is_empty() => s.trim().to_string(),
+                _ => return,
+            };
+            data.konfiguration
+                .testfaelle
+                .entry(ziel.id())
+                .or_default()
+                .push(testfaelle::Testfall {
+                    name,
+                    eingabe: eingabe.clone(),
+                    bv_nr: bv_nr.clone(),
+                    erwartet,
+                });
+            data.konfiguration.speichern();
+            testfaelle_ausfuehren(webview, data);
+        }
+        Cmd::TestfallLoeschen { ziel, index } => {
+            if let Some(t) = data.konfiguration.testfaelle.get_mut(ziel) {
+                if *index < t.len() {
+                    t.remove(*index);
                 }
+                if t.is_empty() {
+                    data.konfiguration.testfaelle.remove(ziel);
+                }
+            }
+            data.konfiguration.speichern();
+            testfaelle_ausfuehren(webview, data);
+        }
+        Cmd::TestfallAusEintrag { abteilung, lfd_nr } => {
+            let datei = match data.open_page.as_ref() {
+                Some((file, _)) => file.clone(),
+                None => return,
+            };
+            let open_file = match data.loaded_files.get(&datei) {
+                Some(s) => s,
+                None => return,
+            };
+            let analyse = open_file.cache.start_analyzing(
+                &open_file.analysiert,
+                &data.vm,
+                &data.loaded_nb,
+                &data.konfiguration,
+            );
+            let text_original = match *abteilung {
+                1 => analyse
+                    .abt1
+                    .iter()
+                    .find(|a| a.lfd_nr == *lfd_nr)
+                    .map(|a| a.text_original.clone()),
+                2 => analyse
+                    .abt2
+                    .iter()
+                    .find(|a| a.lfd_nr == *lfd_nr)
+                    .map(|a| a.text_original.clone()),
+                3 => analyse
+                    .abt3
+                    .iter()
+                    .find(|a| a.lfd_nr == *lfd_nr)
+                    .map(|a| a.text_original.clone()),
+                _ => None,
             };
+            let text_original = match text_original {
+                Some(s) => s,
+                None => return,
+            };
+
+            // Ein erneutes Anlegen aus demselben Eintrag ersetzt den alten Testfall,
+            // liefert ein Skript einen Fehler, wird dafür kein Testfall angelegt
+            let name = format!("{datei} A{abteilung}/{lfd_nr}");
+            let mut angelegt = Vec::new();
+            let mut fehler = Vec::new();
+            for ziel in TestZiel::fuer_abteilung(*abteilung) {
+                let erwartet = match testfaelle::ausgabe(
+                    &ziel,
+                    &text_original,
+                    "",
+                    data.vm.clone(),
+                    &data.konfiguration,
+                ) {
+                    Ok(o) => o,
+                    Err(e) => {
+                        fehler.push(format!("{}: {e}", ziel.titel()));
+                        continue;
+                    }
+                };
+                let testfall = testfaelle::Testfall {
+                    name: name.clone(),
+                    eingabe: text_original.clone(),
+                    bv_nr: String::new(),
+                    erwartet,
+                };
+                let liste = data.konfiguration.testfaelle.entry(ziel.id()).or_default();
+                match liste.iter_mut().find(|t| t.name == name) {
+                    Some(t) => *t = testfall,
+                    None => liste.push(testfall),
+                }
+                angelegt.push(ziel.titel());
+            }
+            data.konfiguration.speichern();
+            testfaelle_nach_aenderung(webview, data);
+
+            let mut meldung = format!(
+                "Testfall \"{name}\" angelegt für:\r\n{}",
+                angelegt.join("\r\n")
+            );
+            if !fehler.is_empty() {
+                meldung.push_str(&format!(
+                    "\r\n\r\nNicht angelegt, das Skript liefert einen Fehler:\r\n{}",
+                    fehler.join("\r\n")
+                ));
+            }
+            tinyfiledialogs::message_box_ok(
+                "Testfall angelegt",
+                &meldung,
+                if fehler.is_empty() {
+                    MessageBoxIcon::Info
+                } else {
+                    MessageBoxIcon::Warning
+                },
+            );
+        }
+        Cmd::TestfaelleAusfuehren => {
+            testfaelle_ausfuehren(webview, data);
+        }
+        Cmd::TestfaelleStarten { lauf } => {
+            // Seitdem erneut geändert, der spätere Aufruf startet die Testfälle
+            if *lauf != data.testfaelle_lauf {
+                return;
+            }
+            testfaelle_ausfuehren(webview, data);
+        }
+        Cmd::TestfaelleFertig { lauf, html } => {
+            if *lauf != data.testfaelle_lauf {
+                return;
+            }
+            let _ = webview.evaluate_script(&format!("replaceTestfaelle(`{html}`);"));
+        }
+        Cmd::TesteRegex { regex_id, text } => {
+            let ziel = TestZiel::Regex(regex_id.clone());
+            let result =
+                match testfaelle::ausgabe(&ziel, text, "", data.vm.clone(), &data.konfiguration) {
+                    Ok(o) | Err(o) => o,
+                };
             let _ = webview.evaluate_script(&format!("replaceRegexTestOutput(`{}`);", result));
         }
         Cmd::SetActiveRibbonTab { new_tab } => {
@@ -6300,7 +7743,14 @@ fn get_alle_teilbelastungen_html(data: &RpcData) -> String {
 fn get_alle_herrschvermerke_html(data: &RpcData) -> String {
     let mut entries = String::new();
 
-    for (f_name, f) in data.loaded_files.iter() {
+    let abgleich = alle_blaetter_abgleichen(data, true);
+    let titelblaetter = data
+        .loaded_files
+        .values()
+        .map(|f| &f.analysiert.titelblatt)
+        .collect::<Vec<_>>();
+
+    for (i, (f_name, f)) in data.loaded_files.iter().enumerate() {
         let blatt = &f.analysiert.titelblatt.grundbuch_von;
         let nr = &f.analysiert.titelblatt.blatt;
         entries.push_str(&format!("<div><p>{blatt} Nr. {nr}</p>",));
@@ -6319,8 +7769,25 @@ fn get_alle_herrschvermerke_html(data: &RpcData) -> String {
             let lfd_nr = bv_eintrag.lfd_nr;
             let zu_nr = bv_eintrag.zu_nr.text();
             let text = bv_eintrag.text.text();
+            let dienend = match abgleich.get_herrschvermerk(i, lfd_nr) {
+                Some(blaetter::HerrschvermerkZuordnung {
+                    dienendes_blatt: Some(j),
+                    abt2_lfd_nr,
+                    ..
+                }) => {
+                    let t = titelblaetter[*j];
+                    match abt2_lfd_nr {
+                        Some(a2) => format!("{} Nr. {}, A2 / {a2}", t.grundbuch_von, t.blatt),
+                        None => format!(
+                            "{} Nr. {}, Abt. 2 nicht zugeordnet",
+                            t.grundbuch_von, t.blatt
+                        ),
+                    }
+                }
+                _ => "dienendes Blatt nicht geladen".to_string(),
+            };
             entries.push_str(&format!(
-                "<div><p>{lfd_nr} (zu Nr. {zu_nr})</p><p>{text}</p></div>"
+                "<div><p>{lfd_nr} (zu Nr. {zu_nr})</p><p>{text}</p><p>&rarr; {dienend}</p></div>"
             ));
         }
 
@@ -6366,12 +7833,14 @@ fn get_alle_fehler_html(data: &RpcData) -> String {
     let mut entries = Vec::new();
 
     for (f_name, f) in data.loaded_files.iter() {
-        let gb_analysiert = f.cache.start_and_block_until_finished(
+        let mut gb_analysiert = f.cache.start_and_block_until_finished(
             &f.analysiert,
             &data.vm,
             &data.loaded_nb,
             &data.konfiguration,
         );
+        erbbaurechte_abgleichen(data, &mut gb_analysiert, true);
+        crate::analyse::quittierungen_anwenden(&f.analysiert, &mut gb_analysiert);
         entries.push(crate::ui::render_analyse_grundbuch(
             &gb_analysiert,
             true,
@@ -6382,6 +7851,139 @@ fn get_alle_fehler_html(data: &RpcData) -> String {
     entries.join("\r\n")
 }
 
+// Gleicht die Erbbaurechte im BV mit Abt. 2 der Grundstücks-Blätter ab,
+// sofern diese ebenfalls geladen sind
+fn erbbaurechte_abgleichen(data: &RpcData, analyse: &mut GrundbuchAnalysiert, blockieren: bool) {
+    let grundstuecks_blaetter = analyse
+        .erbbaurechte
+        .iter()
+        .map(|e| {
+            format!(
+                "{}_{}",
+                e.grundstueck.grundbuch_von.trim(),
+                e.grundstueck.blatt.trim()
+            )
+        })
+        .collect::<BTreeSet<_>>();
+
+    for file_name in grundstuecks_blaetter {
+        let f = match data.loaded_files.get(&file_name) {
+            Some(s) => s,
+            None => continue,
+        };
+
+        let grundstueck_analysiert = if blockieren {
+            f.cache.start_and_block_until_finished(
+                &f.analysiert,
+                &data.vm,
+                &data.loaded_nb,
+                &data.konfiguration,
+            )
+        } else {
+            f.cache.start_analyzing(
+                &f.analysiert,
+                &data.vm,
+                &data.loaded_nb,
+                &data.konfiguration,
+            )
+        };
+
+        crate::analyse::erbbaurechte_abgleichen(
+            analyse,
+            &f.analysiert,
+            &grundstueck_analysiert.abt2,
+        );
+    }
+}
+
+// Gleicht Herrschvermerke, Gesamtrechte und Flurstücke zwischen allen
+// geladenen Blättern ab, Indizes in der Reihenfolge von data.loaded_files
+fn alle_blaetter_abgleichen(data: &RpcData, blockieren: bool) -> blaetter::BlaetterAnalysiert {
+    let analysen = data
+        .loaded_files
+        .values()
+        .map(|f| {
+            if blockieren {
+                f.cache.start_and_block_until_finished(
+                    &f.analysiert,
+                    &data.vm,
+                    &data.loaded_nb,
+                    &data.konfiguration,
+                )
+            } else {
+                f.cache.start_analyzing(
+                    &f.analysiert,
+                    &data.vm,
+                    &data.loaded_nb,
+                    &data.konfiguration,
+                )
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let blaetter = data
+        .loaded_files
+        .values()
+        .zip(analysen.iter())
+        .map(|(f, a)| blaetter::Blatt {
+            grundbuch: &f.analysiert,
+            abt2: &a.abt2,
+            abt3: &a.abt3,
+        })
+        .collect::<Vec<_>>();
+
+    blaetter::blaetter_abgleichen(&blaetter, &data.konfiguration.gemarkungen)
+}
+
+fn blaetter_abgleichen(data: &RpcData, analyse: &mut GrundbuchAnalysiert, blockieren: bool) {
+    if data.loaded_files.len() < 2 {
+        return;
+    }
+
+    let abgleich = alle_blaetter_abgleichen(data, blockieren);
+    let index = data
+        .loaded_files
+        .values()
+        .position(|f| f.analysiert.titelblatt == analyse.titelblatt);
+
+    if let Some(warnungen) = index.and_then(|i| abgleich.warnungen.get(i)) {
+        analyse.blaetter = warnungen.clone();
+    }
+}
+
+// Analyse eines Blatts mit dem Abgleich zwischen allen geladenen Blättern, die
+// Erbbaurechte erst, wenn das Blatt fertig analysiert ist
+fn analyse_abgleichen(data: &RpcData, datei: &str) -> Option<GrundbuchAnalysiert> {
+    let open_file = data.loaded_files.get(datei)?;
+    let mut analyse = open_file.cache.start_analyzing(
+        &open_file.analysiert,
+        &data.vm,
+        &data.loaded_nb,
+        &data.konfiguration,
+    );
+    if open_file.cache.offen() == 0 {
+        erbbaurechte_abgleichen(data, &mut analyse, false);
+    }
+    blaetter_abgleichen(data, &mut analyse, false);
+    crate::analyse::quittierungen_anwenden(&open_file.analysiert, &mut analyse);
+    Some(analyse)
+}
+
+fn analyse_grundbuch_ersetzen(webview: &WebView, data: &RpcData) {
+    let analyse = match data
+        .open_page
+        .as_ref()
+        .and_then(|(datei, _)| analyse_abgleichen(data, datei))
+    {
+        Some(s) => s,
+        None => return,
+    };
+    let _ = webview.evaluate_script(&format!(
+        "replaceAnalyseGrundbuch(`{}`);",
+        ui::render_analyse_grundbuch(&analyse, false, false)
+    ));
+}
+
 fn get_rangvermerke_tsv(data: &RpcData) -> String {
     let mut entries = Vec::new();
 
@@ -6406,6 +8008,50 @@ fn get_rangvermerke_tsv(data: &RpcData) -> String {
     format!("RECHT\tRVM\tTEXT\r\n{}", entries.join("\r\n"))
 }
 
+fn get_rangverhaeltnisse_tsv(data: &RpcData) -> String {
+    let mut entries = Vec::new();
+
+    for (f_name, f) in data.loaded_files.iter() {
+        let analysiert = f.cache.start_and_block_until_finished(
+            &f.analysiert,
+            &data.vm,
+            &[],
+            &data.konfiguration,
+        );
+
+        for rf in analysiert.rang.rangfolgen.iter() {
+            for (i, rangstelle) in rf.rangstellen.iter().enumerate() {
+                for recht in rangstelle.iter() {
+                    let rangvermerke = analysiert
+                        .rang
+                        .verhaeltnisse
+                        .iter()
+                        .filter(|r| r.recht == *recht)
+                        .map(|r| format!("{} {}", r.beziehung.to_str(), r.zu))
+                        .collect::<Vec<_>>();
+
+                    entries.push(format!(
+                        "{}\t{} Fl. {} Flst. {}\t{}{}\t{}\t{}",
+                        f_name,
+                        rf.bv_nr,
+                        rf.flur,
+                        rf.flurstueck,
+                        i + 1,
+                        if rf.eindeutig { "" } else { "?" },
+                        recht,
+                        rangvermerke.join(", ")
+                    ));
+                }
+            }
+        }
+    }
+
+    format!(
+        "BLATT\tFLURSTUECK\tRANGSTELLE\tRECHT\tRANGVERMERKE\r\n{}",
+        entries.join("\r\n")
+    )
+}
+
 fn get_nebenbeteiligte_tsv(data: &RpcData) -> String {
     let mut nb = data
         .loaded_files
@@ -6509,6 +8155,28 @@ fn render_pdf_seiten(webview: &WebView, pdfs: &mut Vec<(PdfFile, bool)>) {
     }
 }
 
+// Warnt, wenn beim Migrieren einer älteren .gbx-Datei Felder nicht übernommen werden konnten
+fn gbx_migration_melden(datei: &str, geladen: &migration::GbxGeladen) {
+    if !geladen.wurde_migriert() || geladen.nicht_uebernommen.is_empty() {
+        return;
+    }
+
+    let sicherung = migration::sicherungspfad(Path::new(datei), geladen.schema_version_alt);
+
+    tinyfiledialogs::message_box_ok(
+        &format!("Migration von {}", datei),
+        &format!(
+            "Die Datei wurde auf Format-Version {} aktualisiert, folgende Felder konnten nicht übernommen werden:\r\n{}\r\n\r\nDas Original wurde unter {} gesichert.",
+            migration::SCHEMA_VERSION,
+            geladen.nicht_uebernommen.join("\r\n"),
+            sicherung.display()
+        )
+        .replace("\"", "")
+        .replace("'", ""),
+        MessageBoxIcon::Warning,
+    );
+}
+
 fn reload_hocr_files(pdf_parsed: &PdfFile) -> PdfFile {
     let linien = pdf_parsed
         .datei
@@ -6543,6 +8211,27 @@ fn reload_hocr_files(pdf_parsed: &PdfFile) -> PdfFile {
             };
 
             let (breite, hoehe) = breite_hoehe_mm.get(s)?;
+
+            // Seiten ohne rote Vektorlinien sind meist eingescannt:
+            // Rötungen aus dem Seitenbild verwenden, falls das Bild nicht
+            // neu gerendert wurde, die in der Datei gespeicherten Rötungen
+            let rot = if rot.is_empty() {
+                std::fs::read_to_string(tempdir.join(format!("{s}.rote_linien.json")))
+                    .ok()
+                    .and_then(|json| serde_json::from_str::<RoteLinienBild>(&json).ok())
+                    .map(|r| r.in_mm(*breite, *hoehe))
+                    .or_else(|| {
+                        pdf_parsed
+                            .hocr
+                            .seiten
+                            .get(&s.to_string())
+                            .map(|seite| seite.rote_linien.clone())
+                    })
+                    .unwrap_or_default()
+            } else {
+                rot
+            };
+
             let seite = HocrSeite {
                 breite_mm: *breite,
                 hoehe_mm: *hoehe,
@@ -6618,6 +8307,7 @@ fn analyse_grundbuch(vm: PyVm, pdf: &PdfFile, konfguration: &Konfiguration) -> O
         abt1,
         abt2,
         abt3,
+        quittierungen: pdf.analysiert.quittierungen.clone(),
     };
 
     Some(clean_grundbuch(gb))
@@ -6795,7 +8485,7 @@ fn teste_regex(regex_id: &str, text: &str, konfig: &Konfiguration) -> Result<Vec
 #[serde(tag = "status")]
 pub enum PdfFileOrEmpty {
     #[serde(rename = "ok")]
-    Pdf(gbx::PdfFile),
+    Pdf(GbxDatei),
     #[serde(rename = "error")]
     NichtVorhanden(PdfFileNichtVorhanden),
 }
@@ -6829,7 +8519,7 @@ pub struct LoginResponseError {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 enum TryDownloadDatabaseResultOk {
-    Ok(gbx::PdfFile),
+    Ok(GbxDatei),
     DateiNochNichtVorhanden,
 }
 
@@ -6993,6 +8683,7 @@ fn main() -> wry::Result<()> {
 
     let event_loop = EventLoop::with_user_event();
     let proxy = event_loop.create_proxy();
+    analyse::analyse_fortschritt_senden_an(event_loop.create_proxy());
     let window = WindowBuilder::new()
         .with_title(APP_TITLE)
         .build(&event_loop)?;
@@ -7025,6 +8716,15 @@ fn main() -> wry::Result<()> {
             } => {
                 *control_flow = ControlFlow::Exit;
 
+                // Noch vorgemerkte Version nicht verlieren
+                if let Err(e) = historie::vorgemerkte_version_speichern() {
+                    tinyfiledialogs::message_box_ok(
+                        "Version der Konfiguration nicht gespeichert",
+                        &e,
+                        MessageBoxIcon::Warning,
+                    );
+                }
+
                 let _ = std::fs::remove_file(std::env::temp_dir().join("dgb").join("auth.json"));
 
                 if let Ok(original_value) = original_value.as_ref() {
@@ -0,0 +1,398 @@
+//! Versionierung des .gbx-Formats
+//!
+//! Jede .gbx-Datei trägt eine `schema_version`. Beim Laden wird die Datei
+//! als JSON gelesen und Schritt für Schritt auf `SCHEMA_VERSION` migriert,
+//! erst danach in ein `PdfFile` umgewandelt. Dateien ohne `schema_version`
+//! haben die Version 0.
+
+use crate::digital::Abteilung1;
+use crate::PdfFile;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Aktuelle Version des .gbx-Formats. Muss erhöht werden, sobald sich das Format
+/// so ändert, dass `#[serde(default)]` allein nicht mehr ausreicht - in diesem
+/// Fall ist ein neuer Schritt in `MIGRATIONEN` anzulegen.
+pub const SCHEMA_VERSION: usize = 2;
+
+// Liefert die JSON-Pfade der Felder, die der Schritt nicht übernehmen konnte
+type Migration = fn(&mut Value) -> Result<Vec<String>, String>;
+
+// MIGRATIONEN[n] migriert von Version n auf Version n + 1
+const MIGRATIONEN: &[Migration] = &[migriere_v0_zu_v1, migriere_v1_zu_v2];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MigrationFehler {
+    Lesen(String),
+    KeinJson(String),
+    ZuNeu { version: usize },
+    Migration { von: usize, fehler: String },
+    Format(String),
+}
+
+impl std::fmt::Display for MigrationFehler {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MigrationFehler::Lesen(e) => write!(f, "Datei konnte nicht gelesen werden: {}", e),
+            MigrationFehler::KeinJson(e) => write!(f, "Datei ist keine gültige .gbx-Datei: {}", e),
+            MigrationFehler::ZuNeu { version } => write!(
+                f,
+                "Datei wurde mit einer neueren Programmversion erstellt (Format-Version {}, \
+                 unterstützt wird bis Version {}). Bitte Programm aktualisieren.",
+                version, SCHEMA_VERSION
+            ),
+            MigrationFehler::Migration { von, fehler } => write!(
+                f,
+                "Datei konnte nicht von Format-Version {} auf {} migriert werden: {}",
+                von,
+                von + 1,
+                fehler
+            ),
+            MigrationFehler::Format(e) => write!(f, "Datei hat ein ungültiges Format: {}", e),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GbxGeladen {
+    pub pdf: PdfFile,
+    // Version der Datei vor der Migration
+    pub schema_version_alt: usize,
+    // JSON-Pfade von Feldern, die beim Migrieren nicht übernommen werden konnten
+    pub nicht_uebernommen: Vec<String>,
+}
+
+impl GbxGeladen {
+    pub fn wurde_migriert(&self) -> bool {
+        self.schema_version_alt < SCHEMA_VERSION
+    }
+}
+
+pub fn schema_version(json: &Value) -> usize {
+    json.get("schema_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as usize
+}
+
+/// Liest den Inhalt einer .gbx-Datei und migriert ihn auf `SCHEMA_VERSION`
+pub fn gbx_lesen(s: &str) -> Result<GbxGeladen, MigrationFehler> {
+    let mut json = serde_json::from_str::<Value>(s)
+        .map_err(|e| MigrationFehler::KeinJson(format!("{}", e)))?;
+
+    let schema_version_alt = schema_version(&json);
+    if schema_version_alt > SCHEMA_VERSION {
+        return Err(MigrationFehler::ZuNeu {
+            version: schema_version_alt,
+        });
+    }
+
+    let mut nicht_uebernommen = Vec::new();
+    for (von, migration) in MIGRATIONEN.iter().enumerate().skip(schema_version_alt) {
+        let mut fehlend =
+            migration(&mut json).map_err(|fehler| MigrationFehler::Migration { von, fehler })?;
+        nicht_uebernommen.append(&mut fehlend);
+    }
+
+    match json.as_object_mut() {
+        Some(o) => {
+            o.insert("schema_version".to_string(), SCHEMA_VERSION.into());
+        }
+        None => {
+            return Err(MigrationFehler::Format(
+                "Datei enthält kein JSON-Objekt".to_string(),
+            ))
+        }
+    }
+
+    let pdf = serde_json::from_value::<PdfFile>(json.clone())
+        .map_err(|e| MigrationFehler::Format(format!("{}", e)))?;
+
+    // Nur bei migrierten Dateien prüfen, ob Felder verloren gehen,
+    // da der Vergleich bei großen Dateien teuer ist
+    if schema_version_alt < SCHEMA_VERSION {
+        if let Ok(neu) = serde_json::to_value(&pdf) {
+            felder_vergleichen("", &json, &neu, &mut nicht_uebernommen);
+        }
+    }
+
+    Ok(GbxGeladen {
+        pdf,
+        schema_version_alt,
+        nicht_uebernommen,
+    })
+}
+
+/// Lädt eine .gbx-Datei von der Festplatte. Muss die Datei migriert werden,
+/// wird vorher eine Sicherung des Originals neben der Datei angelegt
+/// ("XXX.gbx.v0.bak"), eine bestehende Sicherung wird nicht überschrieben.
+pub fn gbx_datei_laden(pfad: &Path) -> Result<GbxGeladen, MigrationFehler> {
+    let s = std::fs::read_to_string(pfad).map_err(|e| MigrationFehler::Lesen(format!("{}", e)))?;
+
+    let geladen = gbx_lesen(&s)?;
+
+    if geladen.wurde_migriert() {
+        let sicherung = sicherungspfad(pfad, geladen.schema_version_alt);
+        if !sicherung.exists() {
+            std::fs::write(&sicherung, s.as_bytes()).map_err(|e| {
+                MigrationFehler::Lesen(format!(
+                    "Sicherung {} konnte nicht angelegt werden: {}",
+                    sicherung.display(),
+                    e
+                ))
+            })?;
+        }
+    }
+
+    Ok(geladen)
+}
+
+pub fn sicherungspfad(pfad: &Path, schema_version: usize) -> PathBuf {
+    let dateiname = pfad
+        .file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_default();
+    pfad.with_file_name(format!("{}.v{}.bak", dateiname, schema_version))
+}
+
+// V0 -> V1: Abt. 1 Einträge im alten Format (Eigentümer und Grundlage der
+// Eintragung in einem Eintrag) in Abt1EintragV2 + Abt1GrundEintragung aufteilen.
+// Die Aufteilung selbst übernimmt Abteilung1::migriere_v2. Unbekannte Felder
+// in Abt. 1 gehen dabei verloren und werden als nicht übernommen gemeldet, das
+// Original bleibt in der Sicherung erhalten.
+fn migriere_v0_zu_v1(json: &mut Value) -> Result<Vec<String>, String> {
+    let abt1 = match json.get_mut("analysiert").and_then(|a| a.get_mut("abt1")) {
+        Some(s) => s,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut abteilung1 =
+        serde_json::from_value::<Abteilung1>(abt1.clone()).map_err(|e| format!("Abt. 1: {}", e))?;
+
+    // Vor der Aufteilung vergleichen, danach haben die Einträge einen anderen Aufbau
+    let mut nicht_uebernommen = Vec::new();
+    let gelesen = serde_json::to_value(&abteilung1).map_err(|e| format!("Abt. 1: {}", e))?;
+    felder_vergleichen("analysiert.abt1", abt1, &gelesen, &mut nicht_uebernommen);
+
+    abteilung1.migriere_v2();
+    *abt1 = serde_json::to_value(&abteilung1).map_err(|e| format!("Abt. 1: {}", e))?;
+
+    Ok(nicht_uebernommen)
+}
+
+// V1 -> V2: Jeder Eintrag in BV und Abt. 1 - 3 erhält eine stabile ID
+fn migriere_v1_zu_v2(json: &mut Value) -> Result<Vec<String>, String> {
+    const LISTEN: &[(&str, &[&str])] = &[
+        (
+            "bestandsverzeichnis",
+            &["eintraege", "zuschreibungen", "abschreibungen"],
+        ),
+        (
+            "abt1",
+            &[
+                "eintraege",
+                "grundlagen_eintragungen",
+                "veraenderungen",
+                "loeschungen",
+            ],
+        ),
+        ("abt2", &["eintraege", "veraenderungen", "loeschungen"]),
+        ("abt3", &["eintraege", "veraenderungen", "loeschungen"]),
+    ];
+
+    let analysiert = match json.get_mut("analysiert") {
+        Some(s) => s,
+        None => return Ok(Vec::new()),
+    };
+
+    for (abschnitt, listen) in LISTEN.iter() {
+        for liste in listen.iter() {
+            let eintraege = match analysiert
+                .get_mut(*abschnitt)
+                .and_then(|a| a.get_mut(*liste))
+                .and_then(|l| l.as_array_mut())
+            {
+                Some(s) => s,
+                None => continue,
+            };
+
+            for e in eintraege.iter_mut() {
+                let e = match e.as_object_mut() {
+                    Some(s) => s,
+                    None => {
+                        return Err(format!("{}.{}: Eintrag ist kein Objekt", abschnitt, liste))
+                    }
+                };
+                let hat_id = e
+                    .get("id")
+                    .and_then(|id| id.as_str())
+                    .map(|id| !id.is_empty());
+                if hat_id != Some(true) {
+                    e.insert("id".to_string(), crate::digital::neue_id().into());
+                }
+            }
+        }
+    }
+
+    Ok(Vec::new())
+}
+
+// Sammelt alle Felder aus `alt`, die in `neu` fehlen und nicht leer sind
+fn felder_vergleichen(pfad: &str, alt: &Value, neu: &Value, fehlend: &mut Vec<String>) {
+    match (alt, neu) {
+        (Value::Object(alt), Value::Object(neu)) => {
+            for (k, v) in alt.iter() {
+                let unterpfad = if pfad.is_empty() {
+                    k.clone()
+                } else {
+                    format!("{}.{}", pfad, k)
+                };
+                match neu.get(k) {
+                    Some(n) => felder_vergleichen(&unterpfad, v, n, fehlend),
+                    None if !ist_leer(v) => fehlend.push(unterpfad),
+                    None => {}
+                }
+            }
+        }
+        (Value::Array(alt), Value::Array(neu)) if alt.len() == neu.len() => {
+            for (i, (a, n)) in alt.iter().zip(neu.iter()).enumerate() {
+                felder_vergleichen(&format!("{}[{}]", pfad, i), a, n, fehlend);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn ist_leer(v: &Value) -> bool {
+    match v {
+        Value::Null => true,
+        Value::Bool(b) => !b,
+        Value::String(s) => s.is_empty(),
+        Value::Array(a) => a.is_empty(),
+        Value::Object(o) => o.is_empty(),
+        Value::Number(_) => false,
+    }
+}
+
+#[test]
+fn test_gbx_migration() {
+    let v0 = r#"{
+        "analysiert": {
+            "titelblatt": { "amtsgericht": "Prenzlau", "grundbuch_von": "Ludwigsburg", "blatt": "254" },
+            "abt1": {
+                "eintraege": [{
+                    "lfd_nr": 1,
+                    "eigentuemer": "Max Mustermann",
+                    "bv_nr": "1",
+                    "grundlage_der_eintragung": "Auflassung vom 01.01.1990"
+                }]
+            }
+        }
+    }"#;
+
+    let geladen = gbx_lesen(v0).unwrap();
+    assert_eq!(geladen.schema_version_alt, 0);
+    assert!(geladen.nicht_uebernommen.is_empty());
+    assert_eq!(geladen.pdf.schema_version, SCHEMA_VERSION);
+    assert_eq!(geladen.pdf.analysiert.abt1.grundlagen_eintragungen.len(), 1);
+    assert!(!geladen.pdf.analysiert.abt1.grundlagen_eintragungen[0]
+        .id
+        .is_empty());
+    assert!(matches!(
+        geladen.pdf.analysiert.abt1.eintraege[0],
+        crate::digital::Abt1Eintrag::V2(_)
+    ));
+
+    let neu = v0.replacen(
+        '{',
+        &format!("{{ \"schema_version\": {},", SCHEMA_VERSION + 1),
+        1,
+    );
+    assert_eq!(
+        gbx_lesen(&neu).unwrap_err(),
+        MigrationFehler::ZuNeu {
+            version: SCHEMA_VERSION + 1
+        }
+    );
+}
+
+// Felder, die Abt. 1 beim Migrieren nicht kennt, dürfen nicht stillschweigend
+// verloren gehen
+#[test]
+fn test_gbx_migration_unbekanntes_feld_abt1() {
+    let v0 = r#"{
+        "analysiert": {
+            "titelblatt": { "amtsgericht": "Prenzlau", "grundbuch_von": "Ludwigsburg", "blatt": "254" },
+            "abt1": {
+                "eintraege": [{
+                    "lfd_nr": 1,
+                    "eigentuemer": "Max Mustermann",
+                    "bv_nr": "1",
+                    "grundlage_der_eintragung": "Auflassung vom 01.01.1990",
+                    "bemerkung": "Testamentsvollstreckung angeordnet"
+                }]
+            }
+        }
+    }"#;
+
+    let geladen = gbx_lesen(v0).unwrap();
+    assert_eq!(
+        geladen.nicht_uebernommen,
+        vec!["analysiert.abt1.eintraege[0].bemerkung".to_string()]
+    );
+}
+
+// Datei aus einer Programmversion vor Einführung der schema_version
+#[test]
+fn test_gbx_migration_alte_datei() {
+    use crate::digital::{Abt1Eintrag, BvEintrag};
+
+    let v0 = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/testdaten/Ludwigsburg_254.v0.gbx"
+    ));
+
+    let geladen = gbx_lesen(v0).unwrap();
+    assert_eq!(geladen.schema_version_alt, 0);
+    assert!(geladen.nicht_uebernommen.is_empty());
+
+    let grundbuch = &geladen.pdf.analysiert;
+    assert!(matches!(
+        grundbuch.bestandsverzeichnis.eintraege[0],
+        BvEintrag::Flurstueck(_)
+    ));
+
+    // Eigentümer als V2, Grundlagen der Eintragung in eigener Liste
+    assert!(grundbuch
+        .abt1
+        .eintraege
+        .iter()
+        .all(|e| matches!(e, Abt1Eintrag::V2(_))));
+    assert!(grundbuch.abt1.eintraege[0].ist_geroetet());
+    let grundlagen = &grundbuch.abt1.grundlagen_eintragungen;
+    assert_eq!(grundlagen.len(), 2);
+    assert_eq!(grundlagen[1].bv_nr.text(), "1");
+    assert!(grundlagen[1].text.text().starts_with("Erbschein"));
+    assert!(grundlagen[0].ist_geroetet());
+    assert_eq!(
+        grundlagen[0]
+            .position_in_pdf
+            .as_ref()
+            .map(|p| p.seite.as_str()),
+        Some("5")
+    );
+
+    // Jeder Eintrag hat eine ID
+    assert!(grundbuch
+        .abt1
+        .eintraege
+        .iter()
+        .all(|e| !e.get_id().is_empty()));
+    assert!(!grundbuch.abt2.eintraege[0].id.is_empty());
+    assert!(!grundbuch.abt3.eintraege[0].id.is_empty());
+
+    // Nach dem Speichern wird die Datei nicht erneut migriert
+    let gespeichert = serde_json::to_string(&geladen.pdf).unwrap();
+    let erneut = gbx_lesen(&gespeichert).unwrap();
+    assert!(!erneut.wurde_migriert());
+    assert_eq!(erneut.pdf.analysiert, geladen.pdf.analysiert);
+}
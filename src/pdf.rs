@@ -1,4 +1,4 @@
-use crate::digital::BvEintrag;
+use crate::digital::{ist_zeile_geroetet, BvEintrag, ZellenGeroetet};
 use crate::{Grundbuch, Titelblatt};
 use hyphenation::{Language, Load, Standard};
 use printpdf::{
@@ -6,7 +6,7 @@ use printpdf::{
     PdfLayerReference, Point, Pt,
 };
 use regex::Regex;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::Path;
 use textwrap::{Options, WordSplitter};
 
@@ -349,8 +349,11 @@ pub struct PdfTextRow {
     pub texts: Vec<String>,
     pub header: PdfHeader,
     pub geroetet: Geroetet,
-    pub teil_geroetet: BTreeMap<usize, String>,
+    // Spalte => gerötete Zeilen im Text der Spalte (vor dem Umbrechen)
+    pub teil_geroetet: BTreeMap<usize, BTreeSet<usize>>,
     pub force_single_line: Vec<usize>,
+    // Überschrift für Einträge, deren Text über die volle Breite geht
+    // (Herrschvermerk, Erbbaurecht)
     pub hvm_exception: Option<String>,
 }
 
@@ -410,8 +413,9 @@ impl PdfTextRow {
                 if self.force_single_line.contains(&col_id) {
                     EXTENT_PER_LINE
                 } else {
-                    let text_broken_lines = wordbreak_text(&text, max_col_width_for_column);
-                    text_broken_lines.lines().count() as f32 * EXTENT_PER_LINE
+                    self.get_umgebrochene_zeilen(col_id, text, max_col_width_for_column)
+                        .len() as f32
+                        * EXTENT_PER_LINE
                 }
             })
             .map(|s| (s * 1000.0).round() as usize)
@@ -425,6 +429,51 @@ impl PdfTextRow {
             }
     }
 
+    // Übernimmt die Rötung einzelner Zellen, spalten: (Spalte im PDF, Spalten-ID)
+    fn mit_zellen_roetung(mut self, zellen: &ZellenGeroetet, spalten: &[(usize, &str)]) -> Self {
+        for (col_id, spalte) in spalten.iter() {
+            let anzahl_zeilen = match self.texts.get(*col_id) {
+                Some(s) => s.lines().count(),
+                None => continue,
+            };
+            let zeilen = (0..anzahl_zeilen)
+                .filter(|i| ist_zeile_geroetet(zellen, spalte, *i))
+                .collect::<BTreeSet<_>>();
+            if !zeilen.is_empty() {
+                self.teil_geroetet.insert(*col_id, zeilen);
+            }
+        }
+        self
+    }
+
+    // Umgebrochene Zeilen einer Spalte, mit Rötung pro Zeile. Teilweise
+    // gerötete Spalten werden Zeile für Zeile umgebrochen, damit die
+    // Rötung bei der richtigen Zeile bleibt
+    fn get_umgebrochene_zeilen(
+        &self,
+        col_id: usize,
+        text: &str,
+        max_col_width: usize,
+    ) -> Vec<(String, bool)> {
+        match self.teil_geroetet.get(&col_id) {
+            Some(geroetet) => text
+                .lines()
+                .enumerate()
+                .flat_map(|(i, zeile)| {
+                    let rot = geroetet.contains(&i);
+                    wordbreak_text(zeile, max_col_width)
+                        .lines()
+                        .map(|l| (l.to_string(), rot))
+                        .collect::<Vec<_>>()
+                })
+                .collect(),
+            None => wordbreak_text(text, max_col_width)
+                .lines()
+                .map(|l| (l.to_string(), false))
+                .collect(),
+        }
+    }
+
     fn add_to_page(&self, layer: &mut PdfLayerReference, fonts: &PdfFonts, y_start: f32) {
         if self.geroetet.hat_links_rot() {
             layer.set_fill_color(Color::Cmyk(RED));
@@ -480,10 +529,7 @@ impl PdfTextRow {
                 Mm(y_start as f64 - 3.5),
             );
             layer.end_text_section();
-            layer.write_text(
-                &format!("HERRSCHVERMERK ZU LFD. NR. {}", unhyphenate(&s)),
-                &fonts.helvetica,
-            );
+            layer.write_text(s.clone(), &fonts.helvetica);
         }
 
         layer.set_font(&fonts.courier_bold, 10.0);
@@ -506,28 +552,68 @@ impl PdfTextRow {
                 self.header.get_max_col_width(col_id)
             };
 
-            let text_broken_lines = wordbreak_text(&text, max_col_width_for_column);
+            let text_broken_lines =
+                self.get_umgebrochene_zeilen(col_id, &text, max_col_width_for_column);
+            let spalte_rot = if col_id < col_id_half {
+                self.geroetet.hat_links_rot()
+            } else {
+                self.geroetet.hat_rechts_rot()
+            };
+            let y_text_start = y_start
+                - if self.hvm_exception.is_some() {
+                    7.5
+                } else {
+                    0.0
+                };
+
             layer.begin_text_section();
             layer.set_text_cursor(
                 Mm((self.header.get_starting_x_spalte_mm(col_id) + 1.0) as f64),
-                Mm(y_start as f64
-                    - if self.hvm_exception.is_some() {
-                        7.5
-                    } else {
-                        0.0
-                    }),
+                Mm(y_text_start as f64),
             );
 
             if self.force_single_line.contains(&col_id) {
                 layer.write_text(text.clone(), &fonts.courier_bold);
             } else {
-                for line in text_broken_lines.lines() {
-                    layer.write_text(line.clone(), &fonts.courier_bold);
+                for (line, rot) in text_broken_lines.iter() {
+                    if *rot && !spalte_rot {
+                        layer.set_fill_color(Color::Cmyk(RED));
+                        layer.write_text(line.clone(), &fonts.courier_bold);
+                        layer.set_fill_color(Color::Cmyk(BLACK));
+                    } else {
+                        layer.write_text(line.clone(), &fonts.courier_bold);
+                    }
                     layer.add_line_break();
                 }
             }
 
             layer.end_text_section();
+
+            // teilweise gerötete Zeilen rot durchstreichen
+            if !spalte_rot {
+                let x_start = self.header.get_starting_x_spalte_mm(col_id) + 1.0;
+                for (i, (line, _)) in text_broken_lines
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (_, rot))| *rot)
+                {
+                    let y = y_text_start - i as f32 * EXTENT_PER_LINE + 1.0;
+                    let x_end = x_start + line.chars().count() as f32 * EXTENT_PER_LINE_X;
+                    layer.save_graphics_state();
+                    layer.set_outline_color(Color::Cmyk(RED));
+                    layer.add_shape(Line {
+                        points: vec![
+                            (Point::new(Mm(x_start as f64), Mm(y as f64)), false),
+                            (Point::new(Mm(x_end as f64), Mm(y as f64)), false),
+                        ],
+                        is_closed: false,
+                        has_fill: false,
+                        has_stroke: true,
+                        is_clipping_path: false,
+                    });
+                    layer.restore_graphics_state();
+                }
+            }
         }
 
         if self.geroetet.hat_links_rot() || self.geroetet.hat_rechts_rot() {
@@ -2760,46 +2846,94 @@ fn get_text_rows(grundbuch: &Grundbuch, options: &PdfGrundbuchOptions) -> Vec<Pd
                     let ha_string = flst.groesse.get_ha_string();
                     let pad_ha_string = " ".repeat(6_usize.saturating_sub(ha_string.trim().len()));
 
-                    rows.push(PdfTextRow {
-                        texts: vec![
-                            format!("{}", flst.lfd_nr),
-                            flst.bisherige_lfd_nr
-                                .clone()
-                                .map(|b| format!("{}", b))
-                                .unwrap_or_default(),
-                            flst.gemarkung
-                                .clone()
-                                .map(|g| if g == grundbuch_von { String::new() } else { g })
-                                .unwrap_or_default(),
-                            format!("{}", flst.flur),
-                            format!("{}", flst.flurstueck),
-                            flst.bezeichnung.clone().unwrap_or_default().text(),
-                            format!("{pad_ha_string}{ha_string}"),
-                            flst.groesse.get_a_string(),
-                            flst.groesse.get_m2_string(),
-                        ],
-                        header: PdfHeader::Bestandsverzeichnis,
-                        geroetet: Geroetet::Ganz(bv.ist_geroetet()),
-                        teil_geroetet: BTreeMap::new(),
-                        force_single_line: vec![6],
-                        hvm_exception: None,
-                    });
+                    rows.push(
+                        PdfTextRow {
+                            texts: vec![
+                                format!("{}", flst.lfd_nr),
+                                flst.bisherige_lfd_nr
+                                    .clone()
+                                    .map(|b| format!("{}", b))
+                                    .unwrap_or_default(),
+                                flst.gemarkung
+                                    .clone()
+                                    .map(|g| if g == grundbuch_von { String::new() } else { g })
+                                    .unwrap_or_default(),
+                                format!("{}", flst.flur),
+                                format!("{}", flst.flurstueck),
+                                flst.bezeichnung.clone().unwrap_or_default().text(),
+                                format!("{pad_ha_string}{ha_string}"),
+                                flst.groesse.get_a_string(),
+                                flst.groesse.get_m2_string(),
+                            ],
+                            header: PdfHeader::Bestandsverzeichnis,
+                            geroetet: Geroetet::Ganz(bv.ist_geroetet()),
+                            teil_geroetet: BTreeMap::new(),
+                            force_single_line: vec![6],
+                            hvm_exception: None,
+                        }
+                        .mit_zellen_roetung(
+                            &flst.zellen_geroetet,
+                            &[
+                                (0, "lfd-nr"),
+                                (1, "bisherige-lfd-nr"),
+                                (2, "gemarkung"),
+                                (3, "flur"),
+                                (4, "flurstueck"),
+                                (5, "bezeichnung"),
+                                (6, "groesse"),
+                                (7, "groesse"),
+                                (8, "groesse"),
+                            ],
+                        ),
+                    );
                 }
                 BvEintrag::Recht(hvm) => {
+                    rows.push(
+                        PdfTextRow {
+                            texts: vec![
+                                format!("{}", hvm.lfd_nr),
+                                hvm.bisherige_lfd_nr
+                                    .clone()
+                                    .map(|b| format!("{}", b))
+                                    .unwrap_or_default(),
+                                hvm.text.clone().into(),
+                            ],
+                            header: PdfHeader::Bestandsverzeichnis,
+                            geroetet: Geroetet::Ganz(bv.ist_geroetet()),
+                            teil_geroetet: BTreeMap::new(),
+                            force_single_line: vec![6],
+                            hvm_exception: Some(format!(
+                                "HERRSCHVERMERK ZU LFD. NR. {}",
+                                unhyphenate(&hvm.zu_nr.text())
+                            )),
+                        }
+                        .mit_zellen_roetung(
+                            &hvm.zellen_geroetet,
+                            &[(0, "lfd-nr"), (1, "bisherige-lfd-nr"), (2, "recht-text")],
+                        ),
+                    );
+                }
+                BvEintrag::Erbbaurecht(erbbaurecht) => {
                     rows.push(PdfTextRow {
                         texts: vec![
-                            format!("{}", hvm.lfd_nr),
-                            hvm.bisherige_lfd_nr
+                            format!("{}", erbbaurecht.lfd_nr),
+                            erbbaurecht
+                                .bisherige_lfd_nr
                                 .clone()
                                 .map(|b| format!("{}", b))
                                 .unwrap_or_default(),
-                            hvm.text.clone().into(),
+                            erbbaurecht.text.clone().into(),
                         ],
                         header: PdfHeader::Bestandsverzeichnis,
                         geroetet: Geroetet::Ganz(bv.ist_geroetet()),
                         teil_geroetet: BTreeMap::new(),
                         force_single_line: vec![6],
-                        hvm_exception: Some(hvm.zu_nr.clone().text()),
+                        hvm_exception: Some(format!(
+                            "ERBBAURECHT AN {} BLATT {} LFD. NR. {}",
+                            erbbaurecht.grundstueck.grundbuch_von.to_uppercase(),
+                            erbbaurecht.grundstueck.blatt,
+                            unhyphenate(&erbbaurecht.grundstueck.bv_nr.text())
+                        )),
                     });
                 }
             }
@@ -3003,18 +3137,24 @@ fn get_text_rows(grundbuch: &Grundbuch, options: &PdfGrundbuchOptions) -> Vec<Pd
                 continue;
             }
 
-            rows.push(PdfTextRow {
-                texts: vec![
-                    format!("{}", abt2.lfd_nr),
-                    abt2.bv_nr.text(),
-                    abt2.text.text(),
-                ],
-                header: PdfHeader::Abteilung2,
-                geroetet: Geroetet::Ganz(abt2.ist_geroetet()),
-                teil_geroetet: BTreeMap::new(),
-                force_single_line: Vec::new(),
-                hvm_exception: None,
-            });
+            rows.push(
+                PdfTextRow {
+                    texts: vec![
+                        format!("{}", abt2.lfd_nr),
+                        abt2.bv_nr.text(),
+                        abt2.text.text(),
+                    ],
+                    header: PdfHeader::Abteilung2,
+                    geroetet: Geroetet::Ganz(abt2.ist_geroetet()),
+                    teil_geroetet: BTreeMap::new(),
+                    force_single_line: Vec::new(),
+                    hvm_exception: None,
+                }
+                .mit_zellen_roetung(
+                    &abt2.zellen_geroetet,
+                    &[(0, "lfd-nr"), (1, "bv-nr"), (2, "text")],
+                ),
+            );
         }
 
         let veraenderungen = if !mit_geroeteten_eintraegen {
@@ -3087,19 +3227,25 @@ fn get_text_rows(grundbuch: &Grundbuch, options: &PdfGrundbuchOptions) -> Vec<Pd
                 continue;
             }
 
-            rows.push(PdfTextRow {
-                texts: vec![
-                    format!("{}", abt3.lfd_nr),
-                    abt3.bv_nr.text(),
-                    abt3.betrag.text(),
-                    abt3.text.text(),
-                ],
-                header: PdfHeader::Abteilung3,
-                geroetet: Geroetet::Ganz(abt3.ist_geroetet()),
-                teil_geroetet: BTreeMap::new(),
-                force_single_line: Vec::new(),
-                hvm_exception: None,
-            });
+            rows.push(
+                PdfTextRow {
+                    texts: vec![
+                        format!("{}", abt3.lfd_nr),
+                        abt3.bv_nr.text(),
+                        abt3.betrag.text(),
+                        abt3.text.text(),
+                    ],
+                    header: PdfHeader::Abteilung3,
+                    geroetet: Geroetet::Ganz(abt3.ist_geroetet()),
+                    teil_geroetet: BTreeMap::new(),
+                    force_single_line: Vec::new(),
+                    hvm_exception: None,
+                }
+                .mit_zellen_roetung(
+                    &abt3.zellen_geroetet,
+                    &[(0, "lfd-nr"), (1, "bv-nr"), (2, "betrag"), (3, "text")],
+                ),
+            );
         }
 
         let veraenderungen = if !mit_geroeteten_eintraegen {
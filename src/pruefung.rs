@@ -0,0 +1,395 @@
+//! Regelbasierte Plausibilitätsprüfung des gesamten Grundbuchs
+//!
+//! Im Gegensatz zur Analyse der Rechte in Abt. 2 / Abt. 3 (Python-Skripte)
+//! prüfen diese Regeln die Struktur des Grundbuchs: Nummerierung, Verweise
+//! auf das Bestandsverzeichnis und Zu- / Abschreibungen. Jede Regel ist eine
+//! Funktion in `REGELN`, neue Regeln werden dort eingetragen.
+
+use crate::analyse::{nummern_lesen, BefundCode, PruefungAnalysiert};
+use crate::digital::{flurstueck_gleich, Abt1Eintrag, BvEintrag, StringOrLines};
+use crate::Grundbuch;
+use std::collections::{BTreeMap, BTreeSet};
+
+type PruefRegel = fn(&Grundbuch, &mut Vec<BefundCode>);
+
+const REGELN: &[PruefRegel] = &[
+    lfd_nr_pruefen,
+    bv_verweise_pruefen,
+    flurstuecke_doppelt_pruefen,
+    abschreibungen_pruefen,
+    eigentuemer_grundlage_pruefen,
+];
+
+fn regeln_anwenden(grundbuch: &Grundbuch) -> Vec<BefundCode> {
+    let mut befunde = Vec::new();
+    for regel in REGELN {
+        regel(grundbuch, &mut befunde);
+    }
+    befunde
+}
+
+/// Wendet alle Regeln auf das Grundbuch an
+pub fn grundbuch_pruefen(grundbuch: &Grundbuch) -> PruefungAnalysiert {
+    let (fehler, warnungen): (Vec<_>, Vec<_>) = regeln_anwenden(grundbuch)
+        .into_iter()
+        .partition(|b| b.ist_fehler());
+
+    PruefungAnalysiert {
+        warnungen: warnungen.into_iter().map(Into::into).collect(),
+        fehler: fehler.into_iter().map(Into::into).collect(),
+    }
+}
+
+fn luecken_und_doppelte(
+    abschnitt: &str,
+    nummern: &[usize],
+    doppelte_pruefen: bool,
+    befunde: &mut Vec<BefundCode>,
+) {
+    let mut gezaehlt = BTreeMap::new();
+    for n in nummern.iter().filter(|n| **n != 0) {
+        *gezaehlt.entry(*n).or_insert(0_usize) += 1;
+    }
+
+    let max = match gezaehlt.keys().next_back() {
+        Some(s) => *s,
+        None => return,
+    };
+
+    for n in 1..max {
+        if !gezaehlt.contains_key(&n) {
+            befunde.push(BefundCode::LfdNrLuecke {
+                abschnitt: abschnitt.to_string(),
+                lfd_nr: n,
+            });
+        }
+    }
+
+    if doppelte_pruefen {
+        for (n, anzahl) in gezaehlt {
+            if anzahl > 1 {
+                befunde.push(BefundCode::LfdNrDoppelt {
+                    abschnitt: abschnitt.to_string(),
+                    lfd_nr: n,
+                    anzahl,
+                });
+            }
+        }
+    }
+}
+
+// Lücken in allen Abschnitten, doppelte Nummern nur in Abt. 2 / Abt. 3 -
+// im BV stehen mehrere Flurstücke unter einer lfd. Nr., in Abt. 1 mehrere
+// Eigentümer
+fn lfd_nr_pruefen(grundbuch: &Grundbuch, befunde: &mut Vec<BefundCode>) {
+    let bv = grundbuch
+        .bestandsverzeichnis
+        .eintraege
+        .iter()
+        .filter(|e| !e.ist_leer())
+        .map(|e| e.get_lfd_nr())
+        .collect::<Vec<_>>();
+    luecken_und_doppelte("BV", &bv, false, befunde);
+
+    let abt1 = grundbuch
+        .abt1
+        .eintraege
+        .iter()
+        .map(|e| e.get_lfd_nr())
+        .collect::<Vec<_>>();
+    luecken_und_doppelte("Abt. 1", &abt1, false, befunde);
+
+    let abt2 = grundbuch
+        .abt2
+        .eintraege
+        .iter()
+        .filter(|e| !e.text.is_empty())
+        .map(|e| e.lfd_nr)
+        .collect::<Vec<_>>();
+    luecken_und_doppelte("Abt. 2", &abt2, true, befunde);
+
+    let abt3 = grundbuch
+        .abt3
+        .eintraege
+        .iter()
+        .filter(|e| !e.text.is_empty())
+        .map(|e| e.lfd_nr)
+        .collect::<Vec<_>>();
+    luecken_und_doppelte("Abt. 3", &abt3, true, befunde);
+}
+
+// BV-Nr. in Abt. 1 (Grundlagen) und in nicht geröteten Rechten in Abt. 2 / 3
+// müssen auf vorhandene, nicht gerötete BV-Einträge zeigen
+fn bv_verweise_pruefen(grundbuch: &Grundbuch, befunde: &mut Vec<BefundCode>) {
+    let bv = &grundbuch.bestandsverzeichnis.eintraege;
+
+    let mut verweise = Vec::new();
+    for (i, g) in grundbuch.abt1.grundlagen_eintragungen.iter().enumerate() {
+        if !g.ist_geroetet() {
+            verweise.push((format!("Abt. 1 Grundlage Zeile {}", i + 1), g.bv_nr.text()));
+        }
+    }
+    for e in grundbuch.abt1.eintraege.iter() {
+        if let Abt1Eintrag::V1(v1) = e {
+            if !e.ist_geroetet() {
+                verweise.push((format!("Abt. 1 lfd. Nr. {}", v1.lfd_nr), v1.bv_nr.text()));
+            }
+        }
+    }
+    for e in grundbuch.abt2.eintraege.iter() {
+        if e.ist_geroetet() {
+            continue;
+        }
+        verweise.push((
+            format!("Abt. 2 lfd. Nr. {}", e.lfd_nr),
+            e.get_bv_nr_ungeroetet(),
+        ));
+    }
+    for e in grundbuch.abt3.eintraege.iter() {
+        if e.ist_geroetet() {
+            continue;
+        }
+        verweise.push((
+            format!("Abt. 3 lfd. Nr. {}", e.lfd_nr),
+            e.get_bv_nr_ungeroetet(),
+        ));
+    }
+
+    for (eintrag, text) in verweise {
+        for nr in nummern_lesen(&text) {
+            let eintraege = bv
+                .iter()
+                .filter(|b| b.get_lfd_nr() == nr)
+                .collect::<Vec<_>>();
+
+            if eintraege.is_empty() {
+                befunde.push(BefundCode::BvVerweisFehlt {
+                    eintrag: eintrag.clone(),
+                    bv_nr: nr,
+                });
+            } else if eintraege.iter().all(|b| b.ist_geroetet()) {
+                befunde.push(BefundCode::BvVerweisGeroetet {
+                    eintrag: eintrag.clone(),
+                    bv_nr: nr,
+                });
+            }
+        }
+    }
+}
+
+// Ein Flurstück darf nur unter einer nicht geröteten lfd. Nr. im BV stehen
+fn flurstuecke_doppelt_pruefen(grundbuch: &Grundbuch, befunde: &mut Vec<BefundCode>) {
+    let grundbuch_von = &grundbuch.titelblatt.grundbuch_von;
+
+    let aktiv = grundbuch
+        .bestandsverzeichnis
+        .eintraege
+        .iter()
+        .filter(|b| !b.ist_geroetet() && !b.get_flurstueck().trim().is_empty())
+        .filter(|b| matches!(b, BvEintrag::Flurstueck(_)))
+        .collect::<Vec<_>>();
+
+    let mut gemeldet = BTreeSet::new();
+    for (i, a) in aktiv.iter().enumerate() {
+        let gemarkung = a.get_gemarkung().unwrap_or(grundbuch_von.clone());
+        let doppelt = aktiv[i + 1..]
+            .iter()
+            .filter(|b| {
+                b.get_lfd_nr() != a.get_lfd_nr()
+                    && b.get_gemarkung().unwrap_or(grundbuch_von.clone()) == gemarkung
+                    && b.get_flur() == a.get_flur()
+                    && flurstueck_gleich(&b.get_flurstueck(), &a.get_flurstueck())
+            })
+            .map(|b| b.get_lfd_nr())
+            .collect::<BTreeSet<_>>();
+
+        if doppelt.is_empty() || !gemeldet.insert(a.get_lfd_nr()) {
+            continue;
+        }
+        gemeldet.extend(doppelt.iter().copied());
+
+        befunde.push(BefundCode::FlurstueckDoppelt {
+            bv_nr: a.get_lfd_nr(),
+            flur: a.get_flur(),
+            flurstueck: a.get_flurstueck(),
+            auch_unter: doppelt.into_iter().collect(),
+        });
+    }
+}
+
+// Vollständig abgeschriebene BV-Nr. müssen gerötet sein. Teilweise Abschreibungen
+// ("Teilfläche", "teilweise") lassen den Eintrag bestehen und werden übersprungen.
+fn abschreibungen_pruefen(grundbuch: &Grundbuch, befunde: &mut Vec<BefundCode>) {
+    use lazy_static::lazy_static;
+    use regex::Regex;
+
+    lazy_static! {
+        // Nur ganze Wörter, sonst wären "Mitteilung" oder "Teilung" auch Teilabschreibungen
+        static ref REGEX_TEILWEISE: Regex = Regex::new(
+            r"(?i)\b(?:teil|teile|teilweise|teilfläche|teilflächen|teilabschreibung|tlw)\b"
+        )
+        .unwrap();
+    }
+
+    let bv = &grundbuch.bestandsverzeichnis;
+
+    for (i, a) in bv.abschreibungen.iter().enumerate() {
+        if a.ist_geroetet() || a.ist_leer() {
+            continue;
+        }
+
+        if REGEX_TEILWEISE.is_match(&a.text.text()) {
+            continue;
+        }
+
+        for nr in nummern_lesen(&a.bv_nr.text()) {
+            let nicht_geroetet = bv
+                .eintraege
+                .iter()
+                .any(|b| b.get_lfd_nr() == nr && !b.ist_geroetet());
+
+            if nicht_geroetet {
+                befunde.push(BefundCode::AbschreibungNichtGeroetet {
+                    zeile: i + 1,
+                    bv_nr: nr,
+                });
+            }
+        }
+    }
+}
+
+fn ist_leer(text: &StringOrLines) -> bool {
+    text.text().trim().is_empty()
+}
+
+// Eigentümer brauchen eine Grundlage der Eintragung: bei V1-Einträgen in
+// derselben Zeile, sonst mindestens eine nicht gerötete Grundlage in Abt. 1
+fn eigentuemer_grundlage_pruefen(grundbuch: &Grundbuch, befunde: &mut Vec<BefundCode>) {
+    let hat_grundlagen = grundbuch
+        .abt1
+        .grundlagen_eintragungen
+        .iter()
+        .any(|g| !g.ist_geroetet() && !ist_leer(&g.text));
+
+    for e in grundbuch.abt1.eintraege.iter() {
+        if e.ist_geroetet() || e.get_eigentuemer().trim().is_empty() {
+            continue;
+        }
+
+        let ohne_grundlage = match e {
+            Abt1Eintrag::V1(v1) => ist_leer(&v1.grundlage_der_eintragung) && !hat_grundlagen,
+            Abt1Eintrag::V2(_) => !hat_grundlagen,
+        };
+
+        if ohne_grundlage {
+            befunde.push(BefundCode::EigentuemerOhneGrundlage {
+                lfd_nr: e.get_lfd_nr(),
+            });
+        }
+    }
+}
+
+#[test]
+fn test_grundbuch_pruefen() {
+    use crate::digital::{Abt2Eintrag, Titelblatt};
+
+    let mut grundbuch = Grundbuch::new(Titelblatt {
+        amtsgericht: "Musterstadt".to_string(),
+        grundbuch_von: "Musterdorf".to_string(),
+        blatt: "123".to_string(),
+    });
+
+    let flurstueck = |lfd_nr: usize, flst: &str| {
+        let mut bv = BvEintrag::neu(lfd_nr);
+        if let BvEintrag::Flurstueck(f) = &mut bv {
+            f.flur = 2;
+            f.flurstueck = flst.to_string();
+        }
+        bv
+    };
+
+    grundbuch.bestandsverzeichnis.eintraege = vec![
+        flurstueck(1, "17/3"),
+        flurstueck(2, "18"),
+        flurstueck(4, "17 / 3"),
+    ];
+
+    grundbuch.abt2.eintraege = [(1, "1"), (3, "2, 5"), (3, "4")]
+        .iter()
+        .map(|(lfd_nr, bv_nr)| Abt2Eintrag {
+            bv_nr: bv_nr.to_string().into(),
+            text: "Leitungsrecht".to_string().into(),
+            ..Abt2Eintrag::new(*lfd_nr)
+        })
+        .collect();
+
+    let befunde = regeln_anwenden(&grundbuch)
+        .iter()
+        .map(|b| (b.ist_fehler(), b.to_string()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        befunde,
+        vec![
+            (
+                false,
+                "BV lfd. Nr. 3: lfd. Nr. fehlt (Lücke in der Nummerierung)".to_string()
+            ),
+            (
+                false,
+                "Abt. 2 lfd. Nr. 2: lfd. Nr. fehlt (Lücke in der Nummerierung)".to_string()
+            ),
+            (
+                true,
+                "Abt. 2 lfd. Nr. 3: lfd. Nr. ist 2 mal vergeben".to_string()
+            ),
+            (
+                true,
+                "Abt. 2 lfd. Nr. 3: BV-Nr. 5 existiert nicht im Bestandsverzeichnis".to_string()
+            ),
+            (
+                true,
+                "BV lfd. Nr. 1: Flur 2 Flst. 17/3 steht auch unter lfd. Nr. 4".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_abschreibungen_pruefen() {
+    use crate::digital::{BvAbschreibung, Titelblatt};
+
+    let mut grundbuch = Grundbuch::new(Titelblatt::default());
+    grundbuch.bestandsverzeichnis.eintraege = vec![BvEintrag::neu(1), BvEintrag::neu(2)];
+
+    let abschreibung = |text: &str| BvAbschreibung {
+        bv_nr: "1".to_string().into(),
+        text: text.to_string().into(),
+        ..Default::default()
+    };
+
+    let anzahl_befunde = |text: &str, grundbuch: &mut Grundbuch| {
+        grundbuch.bestandsverzeichnis.abschreibungen = vec![abschreibung(text)];
+        let mut befunde = Vec::new();
+        abschreibungen_pruefen(grundbuch, &mut befunde);
+        befunde.len()
+    };
+
+    // Teilweise abgeschrieben: Eintrag bleibt bestehen
+    for text in [
+        "Teilfläche übertragen nach Blatt 12",
+        "Tlw. übertragen nach Blatt 12",
+        "Ein Teil übertragen nach Blatt 12",
+        "Teilweise übertragen nach Blatt 12",
+    ] {
+        assert_eq!(anzahl_befunde(text, &mut grundbuch), 0, "{text}");
+    }
+
+    // "teil" nur als Wortbestandteil: vollständige Abschreibung
+    for text in [
+        "Übertragen nach Blatt 12 gemäß Mitteilung vom 01.02.2003",
+        "Nach Teilung übertragen nach Blatt 12",
+    ] {
+        assert_eq!(anzahl_befunde(text, &mut grundbuch), 1, "{text}");
+    }
+}
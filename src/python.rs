@@ -1,4 +1,4 @@
-use crate::analyse::AnalyseFehler;
+use crate::analyse::{AnalyseFehler, BefundCode};
 use crate::Konfiguration;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
@@ -51,6 +51,10 @@ pub enum PyOk {
     SchuldenArt(SchuldenArt),
     #[serde(rename = "betrag")]
     Betrag(Betrag),
+    #[serde(rename = "pruefung")]
+    Pruefung(PruefErgebnis),
+    #[serde(rename = "eigentuemer")]
+    Eigentuemer(Eigentuemerliste),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -212,184 +216,113 @@ pub struct ExecuteScriptOk {
 }
 
 impl ExecuteScriptOk {
+    fn falscher_typ(&self, id: &str, erwartet: &str, erhalten: &str) -> AnalyseFehler {
+        AnalyseFehler {
+            code: BefundCode::SkriptRueckgabe {
+                skript: id.to_string(),
+                erwartet: erwartet.to_string(),
+                erhalten: erhalten.to_string(),
+            },
+            quittierung: None,
+            traceback: None,
+            py_script: Some(self.script.clone()),
+        }
+    }
+
     pub fn downcast_str(&self, id: &str) -> Result<String, AnalyseFehler> {
         match &self.ok {
             PyOk::Str(s) => Ok(s.clone()),
-            PyOk::Betrag(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete Str, erhielt Betrag"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::List(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete Str, erhielt List"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::RechteArt(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete Str, erhielt RechteArt"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::SchuldenArt(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete Str, erhielt SchuldenArt"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::Spalte1(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete Str, erhielt Spalte1"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
+            PyOk::Betrag(_) => Err(self.falscher_typ(id, "Str", "Betrag")),
+            PyOk::List(_) => Err(self.falscher_typ(id, "Str", "List")),
+            PyOk::RechteArt(_) => Err(self.falscher_typ(id, "Str", "RechteArt")),
+            PyOk::SchuldenArt(_) => Err(self.falscher_typ(id, "Str", "SchuldenArt")),
+            PyOk::Spalte1(_) => Err(self.falscher_typ(id, "Str", "Spalte1")),
+            PyOk::Pruefung(_) => Err(self.falscher_typ(id, "Str", "Pruefung")),
+            PyOk::Eigentuemer(_) => Err(self.falscher_typ(id, "Str", "Eigentuemer")),
         }
     }
     pub fn downcast_list(&self, id: &str) -> Result<Vec<String>, AnalyseFehler> {
         match &self.ok {
             PyOk::List(s) => Ok(s.clone()),
-            PyOk::Betrag(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete List, erhielt Betrag"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::Str(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete List, erhielt Str"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::RechteArt(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete List, erhielt RechteArt"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::SchuldenArt(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete List, erhielt SchuldenArt"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::Spalte1(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete List, erhielt Spalte1"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
+            PyOk::Betrag(_) => Err(self.falscher_typ(id, "List", "Betrag")),
+            PyOk::Str(_) => Err(self.falscher_typ(id, "List", "Str")),
+            PyOk::RechteArt(_) => Err(self.falscher_typ(id, "List", "RechteArt")),
+            PyOk::SchuldenArt(_) => Err(self.falscher_typ(id, "List", "SchuldenArt")),
+            PyOk::Spalte1(_) => Err(self.falscher_typ(id, "List", "Spalte1")),
+            PyOk::Pruefung(_) => Err(self.falscher_typ(id, "List", "Pruefung")),
+            PyOk::Eigentuemer(_) => Err(self.falscher_typ(id, "List", "Eigentuemer")),
         }
     }
     pub fn downcast_spalte1(&self, id: &str) -> Result<Spalte1Eintraege, AnalyseFehler> {
         match &self.ok {
             PyOk::Spalte1(s) => Ok(s.clone()),
-            PyOk::Betrag(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete Spalte1, erhielt Betrag"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::Str(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete Spalte1, erhielt Str"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::RechteArt(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete Spalte1, erhielt RechteArt"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::SchuldenArt(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete Spalte1, erhielt SchuldenArt"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::List(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete Spalte1, erhielt List"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
+            PyOk::Betrag(_) => Err(self.falscher_typ(id, "Spalte1", "Betrag")),
+            PyOk::Str(_) => Err(self.falscher_typ(id, "Spalte1", "Str")),
+            PyOk::RechteArt(_) => Err(self.falscher_typ(id, "Spalte1", "RechteArt")),
+            PyOk::SchuldenArt(_) => Err(self.falscher_typ(id, "Spalte1", "SchuldenArt")),
+            PyOk::List(_) => Err(self.falscher_typ(id, "Spalte1", "List")),
+            PyOk::Pruefung(_) => Err(self.falscher_typ(id, "Spalte1", "Pruefung")),
+            PyOk::Eigentuemer(_) => Err(self.falscher_typ(id, "Spalte1", "Eigentuemer")),
         }
     }
     pub fn downcast_rechteart(&self, id: &str) -> Result<RechteArt, AnalyseFehler> {
         match &self.ok {
             PyOk::RechteArt(s) => Ok(s.clone()),
-            PyOk::Betrag(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete RechteArt, erhielt Betrag"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::Str(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete RechteArt, erhielt Str"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::Spalte1(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete RechteArt, erhielt Spalte1"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::SchuldenArt(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete RechteArt, erhielt SchuldenArt"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::List(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete RechteArt, erhielt List"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
+            PyOk::Betrag(_) => Err(self.falscher_typ(id, "RechteArt", "Betrag")),
+            PyOk::Str(_) => Err(self.falscher_typ(id, "RechteArt", "Str")),
+            PyOk::Spalte1(_) => Err(self.falscher_typ(id, "RechteArt", "Spalte1")),
+            PyOk::SchuldenArt(_) => Err(self.falscher_typ(id, "RechteArt", "SchuldenArt")),
+            PyOk::List(_) => Err(self.falscher_typ(id, "RechteArt", "List")),
+            PyOk::Pruefung(_) => Err(self.falscher_typ(id, "RechteArt", "Pruefung")),
+            PyOk::Eigentuemer(_) => Err(self.falscher_typ(id, "RechteArt", "Eigentuemer")),
         }
     }
     pub fn downcast_schuldenart(&self, id: &str) -> Result<SchuldenArt, AnalyseFehler> {
         match &self.ok {
             PyOk::SchuldenArt(s) => Ok(s.clone()),
-            PyOk::Betrag(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete SchuldenArt, erhielt Betrag"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::Str(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete SchuldenArt, erhielt Str"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::Spalte1(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete SchuldenArt, erhielt Spalte1"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::RechteArt(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete SchuldenArt, erhielt RechteArt"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::List(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete SchuldenArt, erhielt List"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
+            PyOk::Betrag(_) => Err(self.falscher_typ(id, "SchuldenArt", "Betrag")),
+            PyOk::Str(_) => Err(self.falscher_typ(id, "SchuldenArt", "Str")),
+            PyOk::Spalte1(_) => Err(self.falscher_typ(id, "SchuldenArt", "Spalte1")),
+            PyOk::RechteArt(_) => Err(self.falscher_typ(id, "SchuldenArt", "RechteArt")),
+            PyOk::List(_) => Err(self.falscher_typ(id, "SchuldenArt", "List")),
+            PyOk::Pruefung(_) => Err(self.falscher_typ(id, "SchuldenArt", "Pruefung")),
+            PyOk::Eigentuemer(_) => Err(self.falscher_typ(id, "SchuldenArt", "Eigentuemer")),
         }
     }
     pub fn downcast_betrag(&self, id: &str) -> Result<Betrag, AnalyseFehler> {
         match &self.ok {
             PyOk::Betrag(s) => Ok(s.clone()),
-            PyOk::SchuldenArt(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete Betrag, erhielt SchuldenArt"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::Str(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete Betrag, erhielt Str"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::Spalte1(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete Betrag, erhielt Spalte1"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::RechteArt(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete Betrag, erhielt RechteArt"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
-            PyOk::List(_) => Err(AnalyseFehler {
-                text: format!("{id}: Erwartete Betrag, erhielt List"),
-                traceback: None,
-                py_script: Some(self.script.clone()),
-            }),
+            PyOk::SchuldenArt(_) => Err(self.falscher_typ(id, "Betrag", "SchuldenArt")),
+            PyOk::Str(_) => Err(self.falscher_typ(id, "Betrag", "Str")),
+            PyOk::Spalte1(_) => Err(self.falscher_typ(id, "Betrag", "Spalte1")),
+            PyOk::RechteArt(_) => Err(self.falscher_typ(id, "Betrag", "RechteArt")),
+            PyOk::List(_) => Err(self.falscher_typ(id, "Betrag", "List")),
+            PyOk::Pruefung(_) => Err(self.falscher_typ(id, "Betrag", "Pruefung")),
+            PyOk::Eigentuemer(_) => Err(self.falscher_typ(id, "Betrag", "Eigentuemer")),
+        }
+    }
+    pub fn downcast_pruefung(&self, id: &str) -> Result<PruefErgebnis, AnalyseFehler> {
+        match &self.ok {
+            PyOk::Pruefung(s) => Ok(s.clone()),
+            PyOk::Str(_) => Err(self.falscher_typ(id, "Pruefung", "Str")),
+            PyOk::List(_) => Err(self.falscher_typ(id, "Pruefung", "List")),
+            PyOk::Spalte1(_) => Err(self.falscher_typ(id, "Pruefung", "Spalte1")),
+            PyOk::RechteArt(_) => Err(self.falscher_typ(id, "Pruefung", "RechteArt")),
+            PyOk::SchuldenArt(_) => Err(self.falscher_typ(id, "Pruefung", "SchuldenArt")),
+            PyOk::Betrag(_) => Err(self.falscher_typ(id, "Pruefung", "Betrag")),
+            PyOk::Eigentuemer(_) => Err(self.falscher_typ(id, "Pruefung", "Eigentuemer")),
+        }
+    }
+    pub fn downcast_eigentuemer(&self, id: &str) -> Result<Eigentuemerliste, AnalyseFehler> {
+        match &self.ok {
+            PyOk::Eigentuemer(s) => Ok(s.clone()),
+            PyOk::Str(_) => Err(self.falscher_typ(id, "Eigentuemer", "Str")),
+            PyOk::List(_) => Err(self.falscher_typ(id, "Eigentuemer", "List")),
+            PyOk::Spalte1(_) => Err(self.falscher_typ(id, "Eigentuemer", "Spalte1")),
+            PyOk::RechteArt(_) => Err(self.falscher_typ(id, "Eigentuemer", "RechteArt")),
+            PyOk::SchuldenArt(_) => Err(self.falscher_typ(id, "Eigentuemer", "SchuldenArt")),
+            PyOk::Betrag(_) => Err(self.falscher_typ(id, "Eigentuemer", "Betrag")),
+            PyOk::Pruefung(_) => Err(self.falscher_typ(id, "Eigentuemer", "Pruefung")),
         }
     }
 }
@@ -451,7 +384,11 @@ impl PyVm {
                 let last_line = lines.pop().unwrap_or_default();
 
                 return Err(AnalyseFehler {
-                    text: format!("{id}: {}", html_escape::encode_text(&last_line).to_string()),
+                    code: BefundCode::Skript {
+                        skript: id.to_string(),
+                        meldung: html_escape::encode_text(&last_line).to_string(),
+                    },
+                    quittierung: None,
                     traceback: if lines.is_empty() { None } else { Some(lines) },
                     py_script: Some(generated_lines.clone()),
                 });
@@ -469,7 +406,11 @@ impl PyVm {
         let mut module =
             unsafe { Module::deserialize(&store, self.python_compiled_module.clone()) }.map_err(
                 |e| AnalyseFehler {
-                    text: format!("{id}: failed to deserialize module: {e}"),
+                    code: BefundCode::Skript {
+                        skript: id.to_string(),
+                        meldung: format!("failed to deserialize module: {e}"),
+                    },
+                    quittierung: None,
                     traceback: None,
                     py_script: Some(generated_lines.clone()),
                 },
@@ -488,7 +429,11 @@ impl PyVm {
             "python",
         )
         .map_err(|e| AnalyseFehler {
-            text: format!("3: {id}: prepare_webc_env: {e}"),
+            code: BefundCode::Skript {
+                skript: id.to_string(),
+                meldung: format!("prepare_webc_env: {e}"),
+            },
+            quittierung: None,
             traceback: None,
             py_script: Some(generated_lines.clone()),
         })?;
@@ -502,7 +447,11 @@ impl PyVm {
         let _ = stdout_pipe.read_to_end(&mut buf);
 
         let result: PyResult = serde_json::from_slice(&buf).map_err(|e| AnalyseFehler {
-            text: format!("{id} (json decode): {}", e.to_string()),
+            code: BefundCode::Skript {
+                skript: id.to_string(),
+                meldung: format!("json decode: {e}"),
+            },
+            quittierung: None,
             traceback: None,
             py_script: Some(generated_lines.clone()),
         })?;
@@ -531,7 +480,11 @@ impl PyVm {
                 let last_line = lines.pop().unwrap_or_default();
 
                 return Err(AnalyseFehler {
-                    text: format!("{id}: {}", html_escape::encode_text(&last_line).to_string()),
+                    code: BefundCode::Skript {
+                        skript: id.to_string(),
+                        meldung: html_escape::encode_text(&last_line).to_string(),
+                    },
+                    quittierung: None,
                     traceback: if lines.is_empty() { None } else { Some(lines) },
                     py_script: Some(generated_lines.clone()),
                 });
@@ -781,6 +734,12 @@ impl Waehrung {
     }
 }
 
+impl fmt::Display for Waehrung {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.to_string())
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Hash, Serialize, Deserialize)]
 pub struct Betrag {
     pub wert: usize,
@@ -814,7 +773,95 @@ pub struct Spalte1Eintraege {
     pub warnungen: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// Ergebnis des Skripts "pruefe_eintrag"
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PruefErgebnis {
+    pub fehler: Vec<String>,
+    pub warnungen: Vec<String>,
+}
+
+// Ergebnis des Skripts "eigentuemer_auslesen_abt1"
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Eigentuemerliste {
+    pub eintraege: Vec<Eigentuemer>,
+    pub warnungen: Vec<String>,
+}
+
+// Einzelner Eigentümer aus Abt. 1, Spalte 2
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Eigentuemer {
+    pub art: EigentuemerArt,
+    // Nachname bzw. Firma
+    pub name: String,
+    #[serde(default)]
+    pub vorname: Option<String>,
+    #[serde(default)]
+    pub geburtsname: Option<String>,
+    // "TT.MM.JJJJ"
+    #[serde(default)]
+    pub geburtsdatum: Option<String>,
+    #[serde(default)]
+    pub wohnort: Option<String>,
+    // "1/2", leer bei Alleineigentum oder Gesamthandsgemeinschaft
+    #[serde(default)]
+    pub anteil: Option<String>,
+    #[serde(default)]
+    pub gemeinschaft: Gemeinschaft,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EigentuemerArt {
+    Person,
+    Firma,
+}
+
+// Gemeinschaftsverhältnis mehrerer Eigentümer (§ 47 GBO)
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Gemeinschaft {
+    #[default]
+    Alleineigentum,
+    Bruchteile,
+    Erbengemeinschaft,
+    GbR,
+    Guetergemeinschaft,
+    Sonstige,
+}
+
+impl Gemeinschaft {
+    pub fn to_string(&self) -> &'static str {
+        use self::Gemeinschaft::*;
+        match self {
+            Alleineigentum => "",
+            Bruchteile => "zu Bruchteilen",
+            Erbengemeinschaft => "in Erbengemeinschaft",
+            GbR => "in Gesellschaft bürgerlichen Rechts",
+            Guetergemeinschaft => "in Gütergemeinschaft",
+            Sonstige => "in Gemeinschaft",
+        }
+    }
+}
+
+// Ohne Anteil, damit derselbe Eigentümer in mehreren Blättern gleich heißt
+impl fmt::Display for Eigentuemer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.vorname.as_ref() {
+            Some(v) => write!(f, "{}, {}", self.name, v)?,
+            None => write!(f, "{}", self.name)?,
+        }
+        if let Some(g) = self.geburtsname.as_ref() {
+            write!(f, " geb. {}", g)?;
+        }
+        if let Some(g) = self.geburtsdatum.as_ref() {
+            write!(f, ", * {}", g)?;
+        }
+        if let Some(w) = self.wohnort.as_ref() {
+            write!(f, ", {}", w)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct FlurFlurstueck {
     pub flur: usize,
     pub flurstueck: String,
@@ -1016,6 +1063,40 @@ pub fn get_kurztext_abt3(
     result.downcast_str("get_kurztext_abt3")
 }
 
+pub fn pruefe_eintrag(
+    vm: PyVm,
+    eintrag: EintragPruefen,
+    konfiguration: &Konfiguration,
+) -> Result<PruefErgebnis, AnalyseFehler> {
+    // Ohne Skript gibt es nichts zu prüfen
+    if konfiguration
+        .pruefe_eintrag_script
+        .iter()
+        .all(|l| l.trim().is_empty())
+    {
+        return Ok(PruefErgebnis::default());
+    }
+
+    let result = vm.execute_script(konfiguration, ExecuteScriptType::PruefeEintrag(eintrag))?;
+
+    result.downcast_pruefung("pruefe_eintrag")
+}
+
+pub fn eigentuemer_auslesen_abt1(
+    vm: PyVm,
+    eigentuemer: &str,
+    konfiguration: &Konfiguration,
+) -> Result<Eigentuemerliste, AnalyseFehler> {
+    let result = vm.execute_script(
+        konfiguration,
+        ExecuteScriptType::EigentuemerAuslesenAbt1 {
+            eigentuemer: eigentuemer.to_string(),
+        },
+    )?;
+
+    result.downcast_eigentuemer("eigentuemer_auslesen_abt1")
+}
+
 #[test]
 fn test_pym_script_1() {
     let vm = PyVm::new().unwrap();
@@ -1029,6 +1110,145 @@ fn test_pym_script_1() {
     let ok = vm.execute_script(&konfiguration, args).unwrap();
 }
 
+#[test]
+fn test_pruefe_eintrag_script() {
+    let vm = PyVm::new().unwrap();
+    let konfiguration = Konfiguration {
+        pruefe_eintrag_script: vec![
+            "p = Pruefung()".to_string(),
+            "if abteilung == 3 and betrag[\"wert\"] == 0:".to_string(),
+            "    p.fehler(\"Grundschuld ohne Betrag\")".to_string(),
+            "if rechteart == RechteArt.LeitungsOderAnlagenrecht and not rechtsinhaber:".to_string(),
+            "    p.warnung(\"Leitungsrecht ohne Leitungsbetreiber\")".to_string(),
+            "return p".to_string(),
+        ],
+        ..Konfiguration::parse_from(Konfiguration::DEFAULT).unwrap()
+    };
+    let eintrag = |abteilung: usize, rechteart: &str, betrag: Option<Betrag>| EintragPruefen {
+        abteilung,
+        text: "Beschränkte persönliche Dienstbarkeit".to_string(),
+        kurztext: String::new(),
+        rechteart: rechteart.to_string(),
+        schuldenart: String::new(),
+        betrag,
+        flurstuecke: Vec::new(),
+        rechtsinhaber: String::new(),
+        ordnungsnummer: String::new(),
+    };
+
+    let abt2 = pruefe_eintrag(
+        vm.clone(),
+        eintrag(2, "LeitungsOderAnlagenrecht", None),
+        &konfiguration,
+    )
+    .unwrap();
+    assert!(abt2.fehler.is_empty());
+    assert_eq!(
+        abt2.warnungen,
+        vec!["Leitungsrecht ohne Leitungsbetreiber".to_string()]
+    );
+
+    let abt3 = pruefe_eintrag(
+        vm.clone(),
+        eintrag(3, "", Some(Betrag::default())),
+        &konfiguration,
+    )
+    .unwrap();
+    assert_eq!(abt3.fehler, vec!["Grundschuld ohne Betrag".to_string()]);
+    assert!(abt3.warnungen.is_empty());
+
+    // Leeres Skript: keine Prüfung
+    let leer = Konfiguration {
+        pruefe_eintrag_script: vec![String::new()],
+        ..konfiguration.clone()
+    };
+    let ergebnis = pruefe_eintrag(vm, eintrag(3, "", None), &leer).unwrap();
+    assert!(ergebnis.fehler.is_empty() && ergebnis.warnungen.is_empty());
+}
+
+#[test]
+fn test_eigentuemer_auslesen_abt1_script() {
+    let vm = PyVm::new().unwrap();
+    let konfiguration = Konfiguration::parse_from(Konfiguration::DEFAULT).unwrap();
+    let auslesen = |text: &str| {
+        eigentuemer_auslesen_abt1(vm.clone(), text, &konfiguration)
+            .map_err(|e| e.text())
+            .unwrap()
+    };
+
+    let bruchteile = auslesen(
+        "1) Müller, Hans, geb. 03.05.1961, Stuttgart - zu 1/2\n\
+         2) Müller, Erika, geb. Schmidt, geb. 04.06.1963, Stuttgart - zu ½",
+    );
+    assert!(bruchteile.warnungen.is_empty());
+    assert_eq!(
+        bruchteile.eintraege,
+        vec![
+            Eigentuemer {
+                art: EigentuemerArt::Person,
+                name: "Müller".to_string(),
+                vorname: Some("Hans".to_string()),
+                geburtsname: None,
+                geburtsdatum: Some("03.05.1961".to_string()),
+                wohnort: Some("Stuttgart".to_string()),
+                anteil: Some("1/2".to_string()),
+                gemeinschaft: Gemeinschaft::Bruchteile,
+            },
+            Eigentuemer {
+                art: EigentuemerArt::Person,
+                name: "Müller".to_string(),
+                vorname: Some("Erika".to_string()),
+                geburtsname: Some("Schmidt".to_string()),
+                geburtsdatum: Some("04.06.1963".to_string()),
+                wohnort: Some("Stuttgart".to_string()),
+                anteil: Some("1/2".to_string()),
+                gemeinschaft: Gemeinschaft::Bruchteile,
+            },
+        ]
+    );
+
+    let firma = auslesen("Stadtwerke Musterstadt GmbH, Musterstadt");
+    assert_eq!(firma.eintraege.len(), 1);
+    assert_eq!(firma.eintraege[0].art, EigentuemerArt::Firma);
+    assert_eq!(firma.eintraege[0].name, "Stadtwerke Musterstadt GmbH");
+    assert_eq!(firma.eintraege[0].wohnort.as_deref(), Some("Musterstadt"));
+    assert_eq!(
+        firma.eintraege[0].gemeinschaft,
+        Gemeinschaft::Alleineigentum
+    );
+
+    let erben =
+        auslesen("1) Meier, Anna, Berlin\n2) Meier, Paul, Hamburg\n- in Erbengemeinschaft -");
+    assert_eq!(
+        erben
+            .eintraege
+            .iter()
+            .map(|e| (e.vorname.as_deref(), e.wohnort.as_deref(), e.gemeinschaft))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                Some("Anna"),
+                Some("Berlin"),
+                Gemeinschaft::Erbengemeinschaft
+            ),
+            (
+                Some("Paul"),
+                Some("Hamburg"),
+                Gemeinschaft::Erbengemeinschaft
+            ),
+        ]
+    );
+
+    // Mehrere Eigentümer ohne Gemeinschaftsverhältnis
+    let unklar = auslesen("Müller, Hans\nSchulze, Eva");
+    assert_eq!(unklar.eintraege.len(), 2);
+    assert_eq!(unklar.warnungen.len(), 1);
+    assert!(unklar
+        .eintraege
+        .iter()
+        .all(|e| e.gemeinschaft == Gemeinschaft::Sonstige));
+}
+
 pub type RegexMap = BTreeMap<String, String>;
 
 impl ExecuteScriptType {
@@ -1049,6 +1269,8 @@ impl ExecuteScriptType {
             ExecuteScriptType::RechtsinhaberAuslesenAbt3 { .. } => {
                 "Rechtsinhaber auslesen (Abt. 3)"
             }
+            ExecuteScriptType::PruefeEintrag(_) => "Eintrag prüfen",
+            ExecuteScriptType::EigentuemerAuslesenAbt1 { .. } => "Eigentümer auslesen (Abt. 1)",
         }
     }
 }
@@ -1104,6 +1326,30 @@ pub enum ExecuteScriptType {
         schuldenart: String,
         rechtsinhaber: String,
     },
+    // pruefe_eintrag(abteilung: int, text: String, kurztext: String, rechteart: RechteArt, schuldenart: SchuldenArt, betrag: Betrag,
+    //                flurstuecke: [FlurFlurstueck], rechtsinhaber: String, ordnungsnummer: String, re: [String -> Regex]) -> Pruefung
+    PruefeEintrag(EintragPruefen),
+    // eigentuemer_auslesen_abt1(eigentuemer: String, zeilen: [String], re: [String -> Regex]) -> Eigentuemerliste
+    EigentuemerAuslesenAbt1 {
+        eigentuemer: String,
+    },
+}
+
+// Analysierter Eintrag in Abt. 2 / Abt. 3, Eingabe für "pruefe_eintrag"
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
+pub struct EintragPruefen {
+    pub abteilung: usize,
+    pub text: String,
+    pub kurztext: String,
+    // Abt. 2, sonst leer
+    pub rechteart: String,
+    // Abt. 3, sonst leer
+    pub schuldenart: String,
+    // Abt. 3, sonst None
+    pub betrag: Option<Betrag>,
+    pub flurstuecke: Vec<FlurFlurstueck>,
+    pub rechtsinhaber: String,
+    pub ordnungsnummer: String,
 }
 
 fn generate_script(konfiguration: &Konfiguration, script: &ExecuteScriptType) -> String {
@@ -1255,6 +1501,67 @@ fn generate_script(konfiguration: &Konfiguration, script: &ExecuteScriptType) ->
 
             s.push_str(&format!("recht_id = \"{recht_id}\"\n\n"));
 
+            s
+        }
+        ExecuteScriptType::PruefeEintrag(e) => {
+            let mut s = String::new();
+
+            s.push_str(&format!("abteilung = {}\n\n", e.abteilung));
+
+            for (name, wert) in [
+                ("text", &e.text),
+                ("kurztext", &e.kurztext),
+                ("rechtsinhaber", &e.rechtsinhaber),
+                ("ordnungsnummer", &e.ordnungsnummer),
+            ] {
+                s.push_str(&format!("{name} = \"\\n\".join([\n"));
+                for l in wert.lines() {
+                    s.push_str(&format!("    {:?},\n", l));
+                }
+                s.push_str("])\n\n");
+            }
+
+            s.push_str(&format!("rechteart = {:?}\n\n", e.rechteart));
+            s.push_str(&format!("schuldenart = {:?}\n\n", e.schuldenart));
+
+            match e.betrag.as_ref() {
+                Some(b) => s.push_str(&format!(
+                    "betrag = Betrag({}, {}, Waehrung.{:?})\n\n",
+                    b.wert, b.nachkomma, b.waehrung
+                )),
+                None => s.push_str("betrag = None\n\n"),
+            }
+
+            s.push_str("flurstuecke = [\n");
+            for f in e.flurstuecke.iter() {
+                let gemarkung = match f.gemarkung.as_ref() {
+                    Some(g) => format!("{:?}", g),
+                    None => "None".to_string(),
+                };
+                s.push_str(&format!(
+                    "    FlurFlurstueck({}, {:?}, {}),\n",
+                    f.flur, f.flurstueck, gemarkung
+                ));
+            }
+            s.push_str("]\n\n");
+
+            s
+        }
+        ExecuteScriptType::EigentuemerAuslesenAbt1 { eigentuemer } => {
+            let mut s = String::new();
+
+            s.push_str(&format!("eigentuemer = \"\\n\".join([\n"));
+            for l in eigentuemer.lines() {
+                s.push_str(&format!("    {:?},\n", l));
+            }
+            s.push_str("])\n\n");
+
+            s.push_str(&format!("zeilen = [\n"));
+            for l in eigentuemer.lines().filter(|l| !l.trim().is_empty()) {
+                s.push_str(&format!("    {:?},\n", l.trim()));
+            }
+            s.push_str("]\n\n");
+
             s
         }
     }
@@ -1307,6 +1614,9 @@ fn generate_script(konfiguration: &Konfiguration, script: &ExecuteScriptType) ->
                         &konfiguration.text_kuerzen_abt3_script,
                     ExecuteScriptType::RechtsinhaberAuslesenAbt3 { .. } =>
                         &konfiguration.rechtsinhaber_auslesen_abt3_script,
+                    ExecuteScriptType::PruefeEintrag(_) => &konfiguration.pruefe_eintrag_script,
+                    ExecuteScriptType::EigentuemerAuslesenAbt1 { .. } =>
+                        &konfiguration.eigentuemer_auslesen_abt1_script,
                 }
                 .iter()
                 .map(|l| {
@@ -0,0 +1,611 @@
+//! Testfälle für die Skripte und regulären Ausdrücke der Konfiguration
+//!
+//! Ein Testfall hält einen Eingabetext und die Ausgabe fest, die ein Skript bzw.
+//! ein regulärer Ausdruck dafür liefern soll. Die Testfälle werden nach jeder
+//! Änderung an einem Skript oder regulären Ausdruck (kurz verzögert) im
+//! Hintergrund erneut ausgeführt, so fällt sofort auf, wenn eine Änderung für
+//! einen anderen, bereits funktionierenden Text eine andere Ausgabe liefert.
+
+use crate::digital::Bestandsverzeichnis;
+use crate::python::PyVm;
+use crate::{ConfigurationView, Konfiguration, Titelblatt};
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Testfall {
+    pub name: String,
+    pub eingabe: String,
+    // Nur für "Flurstücke auslesen": BV-Nr. aus Spalte 1
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub bv_nr: String,
+    pub erwartet: String,
+}
+
+// Skript oder regulärer Ausdruck, zu dem ein Testfall gehört
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TestZiel {
+    Regex(String),
+    TextSaubern,
+    Abkuerzungen,
+    FlurstueckeAuslesen,
+    EigentuemerAbt1,
+    RechteArtAbt2,
+    RechtsinhaberAbt2,
+    RangvermerkAbt2,
+    KurzTextAbt2,
+    BetragAbt3,
+    SchuldenArtAbt3,
+    RechtsinhaberAbt3,
+    KurzTextAbt3,
+    PruefeEintragAbt2,
+    PruefeEintragAbt3,
+}
+
+impl TestZiel {
+    const SKRIPTE: [TestZiel; 14] = [
+        TestZiel::TextSaubern,
+        TestZiel::Abkuerzungen,
+        TestZiel::FlurstueckeAuslesen,
+        TestZiel::EigentuemerAbt1,
+        TestZiel::RechteArtAbt2,
+        TestZiel::RechtsinhaberAbt2,
+        TestZiel::RangvermerkAbt2,
+        TestZiel::KurzTextAbt2,
+        TestZiel::BetragAbt3,
+        TestZiel::SchuldenArtAbt3,
+        TestZiel::RechtsinhaberAbt3,
+        TestZiel::KurzTextAbt3,
+        TestZiel::PruefeEintragAbt2,
+        TestZiel::PruefeEintragAbt3,
+    ];
+
+    // Schlüssel in Konfiguration::testfaelle, für Skripte die ID der Ansicht
+    pub fn id(&self) -> String {
+        match self {
+            TestZiel::Regex(id) => format!("regex:{id}"),
+            TestZiel::TextSaubern => "text-saubern".to_string(),
+            TestZiel::Abkuerzungen => "abkuerzungen".to_string(),
+            TestZiel::FlurstueckeAuslesen => "flurstuecke-auslesen".to_string(),
+            TestZiel::EigentuemerAbt1 => "eigentuemer-auslesen-abt1".to_string(),
+            TestZiel::RechteArtAbt2 => "klassifizierung-rechteart-abt2".to_string(),
+            TestZiel::RechtsinhaberAbt2 => "rechtsinhaber-auslesen-abt2".to_string(),
+            TestZiel::RangvermerkAbt2 => "rangvermerk-auslesen-abt2".to_string(),
+            TestZiel::KurzTextAbt2 => "text-kuerzen-abt2".to_string(),
+            TestZiel::BetragAbt3 => "betrag-auslesen-abt3".to_string(),
+            TestZiel::SchuldenArtAbt3 => "klassifizierung-schuldenart-abt3".to_string(),
+            TestZiel::RechtsinhaberAbt3 => "rechtsinhaber-auslesen-abt3".to_string(),
+            TestZiel::KurzTextAbt3 => "text-kuerzen-abt3".to_string(),
+            TestZiel::PruefeEintragAbt2 => "pruefe-eintrag-abt2".to_string(),
+            TestZiel::PruefeEintragAbt3 => "pruefe-eintrag-abt3".to_string(),
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        if let Some(regex_id) = id.strip_prefix("regex:") {
+            return Some(TestZiel::Regex(regex_id.to_string()));
+        }
+        Self::SKRIPTE.iter().find(|z| z.id() == id).cloned()
+    }
+
+    pub fn titel(&self) -> String {
+        match self {
+            TestZiel::Regex(id) => format!("Regex {id}"),
+            TestZiel::TextSaubern => "Text säubern".to_string(),
+            TestZiel::Abkuerzungen => "Abkürzungen".to_string(),
+            TestZiel::FlurstueckeAuslesen => "Flurstücke auslesen".to_string(),
+            TestZiel::EigentuemerAbt1 => "Eigentümer auslesen (Abt. 1)".to_string(),
+            TestZiel::RechteArtAbt2 => "Klassifizierung RechteArt (Abt. 2)".to_string(),
+            TestZiel::RechtsinhaberAbt2 => "Rechtsinhaber auslesen (Abt. 2)".to_string(),
+            TestZiel::RangvermerkAbt2 => "Rangvermerk auslesen (Abt. 2)".to_string(),
+            TestZiel::KurzTextAbt2 => "Text kürzen (Abt. 2)".to_string(),
+            TestZiel::BetragAbt3 => "Betrag auslesen (Abt. 3)".to_string(),
+            TestZiel::SchuldenArtAbt3 => "Klassifizierung SchuldenArt (Abt. 3)".to_string(),
+            TestZiel::RechtsinhaberAbt3 => "Rechtsinhaber auslesen (Abt. 3)".to_string(),
+            TestZiel::KurzTextAbt3 => "Text kürzen (Abt. 3)".to_string(),
+            TestZiel::PruefeEintragAbt2 => "Eintrag prüfen (Abt. 2)".to_string(),
+            TestZiel::PruefeEintragAbt3 => "Eintrag prüfen (Abt. 3)".to_string(),
+        }
+    }
+
+    // Skripte, für die aus einem analysierten Eintrag Testfälle angelegt werden
+    pub fn fuer_abteilung(abteilung: usize) -> Vec<TestZiel> {
+        match abteilung {
+            1 => vec![TestZiel::EigentuemerAbt1],
+            2 => vec![
+                TestZiel::RechteArtAbt2,
+                TestZiel::RechtsinhaberAbt2,
+                TestZiel::RangvermerkAbt2,
+                TestZiel::KurzTextAbt2,
+                TestZiel::PruefeEintragAbt2,
+            ],
+            3 => vec![
+                TestZiel::BetragAbt3,
+                TestZiel::SchuldenArtAbt3,
+                TestZiel::RechtsinhaberAbt3,
+                TestZiel::KurzTextAbt3,
+                TestZiel::PruefeEintragAbt3,
+            ],
+            _ => Vec::new(),
+        }
+    }
+
+    // Skript, das in der Ansicht bearbeitet wird (ohne RegEx-Ansicht, "Eintrag
+    // prüfen" hat je ein Ziel für Abt. 2 und Abt. 3)
+    pub fn fuer_ansicht(ansicht: &ConfigurationView) -> Option<TestZiel> {
+        use crate::ConfigurationView::*;
+        match ansicht {
+            TextSaubern => Some(TestZiel::TextSaubern),
+            Abkuerzungen => Some(TestZiel::Abkuerzungen),
+            FlstAuslesen => Some(TestZiel::FlurstueckeAuslesen),
+            EigentuemerAuslesenAbt1 => Some(TestZiel::EigentuemerAbt1),
+            KlassifizierungRechteArt => Some(TestZiel::RechteArtAbt2),
+            RechtsinhaberAuslesenAbt2 => Some(TestZiel::RechtsinhaberAbt2),
+            RangvermerkAuslesenAbt2 => Some(TestZiel::RangvermerkAbt2),
+            TextKuerzenAbt2 => Some(TestZiel::KurzTextAbt2),
+            BetragAuslesenAbt3 => Some(TestZiel::BetragAbt3),
+            KlassifizierungSchuldenArtAbt3 => Some(TestZiel::SchuldenArtAbt3),
+            RechtsinhaberAuslesenAbt3 => Some(TestZiel::RechtsinhaberAbt3),
+            TextKuerzenAbt3 => Some(TestZiel::KurzTextAbt3),
+            _ => None,
+        }
+    }
+}
+
+// Testfälle, die in einer Ansicht ausgeführt werden: Reguläre Ausdrücke,
+// Text säubern und Abkürzungen wirken auf alle Skripte, daher dort alle
+pub fn ziele_fuer_ansicht(
+    ansicht: &ConfigurationView,
+    konfiguration: &Konfiguration,
+) -> Vec<TestZiel> {
+    use crate::ConfigurationView::*;
+    match ansicht {
+        RegEx | TextSaubern | Abkuerzungen => konfiguration
+            .testfaelle
+            .keys()
+            .filter_map(|id| TestZiel::from_id(id))
+            .collect(),
+        PruefeEintrag => vec![TestZiel::PruefeEintragAbt2, TestZiel::PruefeEintragAbt3],
+        _ => TestZiel::fuer_ansicht(ansicht).into_iter().collect(),
+    }
+}
+
+// Ausgabe des Skripts / regulären Ausdrucks für den Eingabetext, so wie sie
+// auch im Testfeld der Konfiguration angezeigt wird (bv_nr nur für
+// "Flurstücke auslesen")
+pub fn ausgabe(
+    ziel: &TestZiel,
+    text: &str,
+    bv_nr: &str,
+    vm: PyVm,
+    konfiguration: &Konfiguration,
+) -> Result<String, String> {
+    use crate::python::*;
+
+    match ziel {
+        TestZiel::Regex(regex_id) => {
+            let captures = crate::teste_regex(regex_id, text.trim(), konfiguration)?;
+            if captures.is_empty() {
+                return Ok("[]".to_string());
+            }
+            return Ok(captures
+                .into_iter()
+                .enumerate()
+                .map(|(col, v)| format!("[{}]: \"{}\"", col, v))
+                .collect::<Vec<_>>()
+                .join("\r\n"));
+        }
+        TestZiel::EigentuemerAbt1 => {
+            let o = eigentuemer_auslesen_abt1(vm, text, konfiguration)?;
+            return Ok(o
+                .eintraege
+                .iter()
+                .map(|e| {
+                    format!(
+                        "{:?}: {}{}{}",
+                        e.art,
+                        e,
+                        match e.anteil.as_ref() {
+                            Some(a) => format!(" - zu {a}"),
+                            None => String::new(),
+                        },
+                        match e.gemeinschaft.to_string() {
+                            "" => String::new(),
+                            g => format!(" - {g}"),
+                        },
+                    )
+                })
+                .chain(o.warnungen.iter().map(|w| format!("Warnung: {w}")))
+                .collect::<Vec<_>>()
+                .join("\r\n"));
+        }
+        // Testfälle laufen ohne geöffnete Datei, daher ohne BV
+        TestZiel::FlurstueckeAuslesen => {
+            return flurstuecke_auslesen(
+                vm,
+                text,
+                bv_nr,
+                &Bestandsverzeichnis::default(),
+                konfiguration,
+                &mut String::new(),
+            );
+        }
+        _ => {}
+    }
+
+    let (text_sauber, saetze_clean) =
+        crate::kurztext::text_saubern(vm.clone(), text, konfiguration)?;
+
+    let ausgabe = match ziel {
+        TestZiel::TextSaubern => text_sauber,
+        // Abkürzungen wirken auf die Aufteilung in Sätze
+        TestZiel::Abkuerzungen => saetze_clean
+            .iter()
+            .enumerate()
+            .map(|(i, s)| format!("[{i}]: \"{s}\""))
+            .collect::<Vec<_>>()
+            .join("\r\n"),
+        TestZiel::RechteArtAbt2 => format!(
+            "{:?}",
+            get_rechte_art_abt2(vm, "", &text_sauber, &saetze_clean, konfiguration)?
+        ),
+        TestZiel::RechtsinhaberAbt2 => {
+            get_rechtsinhaber_abt2(vm, "", &text_sauber, &saetze_clean, konfiguration)?
+        }
+        TestZiel::RangvermerkAbt2 => {
+            get_rangvermerk_abt2(vm, "", &text_sauber, &saetze_clean, konfiguration)?
+        }
+        TestZiel::KurzTextAbt2 => {
+            let rechtsinhaber =
+                get_rechtsinhaber_abt2(vm.clone(), "", &text_sauber, &saetze_clean, konfiguration)
+                    .ok();
+            let rangvermerk =
+                get_rangvermerk_abt2(vm.clone(), "", &text_sauber, &saetze_clean, konfiguration)
+                    .ok();
+            get_kurztext_abt2(
+                vm,
+                "",
+                &text_sauber,
+                rechtsinhaber,
+                rangvermerk,
+                &saetze_clean,
+                konfiguration,
+            )?
+        }
+        TestZiel::BetragAbt3 => format!(
+            "{:#?}",
+            get_betrag_abt3(vm, "", &text_sauber, &saetze_clean, konfiguration)?
+        ),
+        TestZiel::SchuldenArtAbt3 => format!(
+            "{:?}",
+            get_schulden_art_abt3(vm, "", &text_sauber, &saetze_clean, konfiguration)?
+        ),
+        TestZiel::RechtsinhaberAbt3 => {
+            get_rechtsinhaber_abt3(vm, "", &text_sauber, &saetze_clean, konfiguration)?
+        }
+        TestZiel::KurzTextAbt3 => {
+            let schuldenart =
+                get_schulden_art_abt3(vm.clone(), "", &text_sauber, &saetze_clean, konfiguration)?;
+            // Der Betrag wird im Kurztext nur eingesetzt, daher fester Testbetrag
+            let betrag = get_betrag_abt3(
+                vm.clone(),
+                "",
+                "100.000,00 EUR",
+                &["100.000,00 EUR".to_string()],
+                konfiguration,
+            )?;
+            let rechtsinhaber =
+                get_rechtsinhaber_abt3(vm.clone(), "", &text_sauber, &saetze_clean, konfiguration)?;
+            let betrag = format!(
+                "{} {}",
+                crate::kurztext::formatiere_betrag(&betrag),
+                betrag.waehrung.to_string()
+            );
+            get_kurztext_abt3(
+                vm,
+                "",
+                &text_sauber,
+                Some(betrag),
+                Some(schuldenart.to_string().to_string()),
+                Some(rechtsinhaber),
+                &saetze_clean,
+                konfiguration,
+            )?
+        }
+        // Im Testfeld gibt es kein BV, daher ohne belastete Flurstücke
+        TestZiel::PruefeEintragAbt2 => {
+            let rechteart =
+                get_rechte_art_abt2(vm.clone(), "", &text_sauber, &saetze_clean, konfiguration)?;
+            let rechtsinhaber =
+                get_rechtsinhaber_abt2(vm.clone(), "", &text_sauber, &saetze_clean, konfiguration)
+                    .ok();
+            let rangvermerk =
+                get_rangvermerk_abt2(vm.clone(), "", &text_sauber, &saetze_clean, konfiguration)
+                    .ok();
+            let kurztext = get_kurztext_abt2(
+                vm.clone(),
+                "",
+                &text_sauber,
+                rechtsinhaber.clone(),
+                rangvermerk,
+                &saetze_clean,
+                konfiguration,
+            )?;
+            let eintrag = EintragPruefen {
+                abteilung: 2,
+                text: text_sauber.clone(),
+                kurztext,
+                rechteart: match rechteart {
+                    RechteArt::SpeziellVormerkung { .. } => "SpeziellVormerkung".to_string(),
+                    r => format!("{:?}", r),
+                },
+                schuldenart: String::new(),
+                betrag: None,
+                flurstuecke: Vec::new(),
+                rechtsinhaber: rechtsinhaber.unwrap_or_default(),
+                ordnungsnummer: String::new(),
+            };
+            pruef_ergebnis_ausgabe(pruefe_eintrag(vm, eintrag, konfiguration)?)
+        }
+        TestZiel::PruefeEintragAbt3 => {
+            let betrag =
+                get_betrag_abt3(vm.clone(), "", &text_sauber, &saetze_clean, konfiguration)?;
+            let schuldenart =
+                get_schulden_art_abt3(vm.clone(), "", &text_sauber, &saetze_clean, konfiguration)?;
+            let rechtsinhaber =
+                get_rechtsinhaber_abt3(vm.clone(), "", &text_sauber, &saetze_clean, konfiguration)?;
+            let kurztext = get_kurztext_abt3(
+                vm.clone(),
+                "",
+                &text_sauber,
+                Some(format!(
+                    "{} {}",
+                    crate::kurztext::formatiere_betrag(&betrag),
+                    betrag.waehrung.to_string()
+                )),
+                Some(schuldenart.to_string().to_string()),
+                Some(rechtsinhaber.clone()),
+                &saetze_clean,
+                konfiguration,
+            )?;
+            let eintrag = EintragPruefen {
+                abteilung: 3,
+                text: text_sauber.clone(),
+                kurztext,
+                rechteart: String::new(),
+                schuldenart: format!("{:?}", schuldenart),
+                betrag: Some(betrag),
+                flurstuecke: Vec::new(),
+                rechtsinhaber,
+                ordnungsnummer: String::new(),
+            };
+            pruef_ergebnis_ausgabe(pruefe_eintrag(vm, eintrag, konfiguration)?)
+        }
+        TestZiel::Regex(_) | TestZiel::EigentuemerAbt1 | TestZiel::FlurstueckeAuslesen => {
+            unreachable!()
+        }
+    };
+
+    Ok(ausgabe)
+}
+
+// Spalte-1-Einträge für die BV-Nr. des Eintrags, bv ist das BV der geöffneten
+// Datei bzw. leer
+pub fn flurstuecke_auslesen(
+    vm: PyVm,
+    text: &str,
+    bv_nr: &str,
+    bv: &Bestandsverzeichnis,
+    konfiguration: &Konfiguration,
+    debug_log: &mut String,
+) -> Result<String, String> {
+    let (text_sauber, _) = crate::kurztext::text_saubern(vm.clone(), text, konfiguration)?;
+
+    let mut fehler = Vec::new();
+    let mut warnungen = Vec::new();
+    let mut spalte1_eintraege = Vec::new();
+    let mut fortfuehrungen = Vec::new();
+
+    crate::analyse::get_belastete_flurstuecke(
+        vm,
+        bv_nr,
+        &text_sauber,
+        &Titelblatt {
+            amtsgericht: "XXX".to_string(),
+            grundbuch_von: "Unbekannt".to_string(),
+            blatt: "0".to_string(),
+        },
+        &bv.eintraege,
+        &crate::analyse::bv_fortfuehrungen_lesen(bv),
+        konfiguration,
+        debug_log,
+        &mut spalte1_eintraege,
+        &mut fortfuehrungen,
+        &mut warnungen,
+        &mut fehler,
+    )?;
+
+    Ok(spalte1_eintraege
+        .iter()
+        .map(|e| format!("{e:#?}"))
+        .collect::<Vec<_>>()
+        .join("\r\n"))
+}
+
+fn pruef_ergebnis_ausgabe(ergebnis: crate::python::PruefErgebnis) -> String {
+    if ergebnis.fehler.is_empty() && ergebnis.warnungen.is_empty() {
+        return "Keine Fehler / Warnungen".to_string();
+    }
+    ergebnis
+        .fehler
+        .iter()
+        .map(|f| format!("Fehler: {f}"))
+        .chain(ergebnis.warnungen.iter().map(|w| format!("Warnung: {w}")))
+        .collect::<Vec<_>>()
+        .join("\r\n")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestErgebnis {
+    pub ziel: TestZiel,
+    pub index: usize,
+    pub testfall: Testfall,
+    // Ausgabe bzw. Fehlermeldung des Skripts, None solange nicht ausgeführt
+    pub ausgabe: Option<Result<String, String>>,
+}
+
+impl TestErgebnis {
+    pub fn ausgefuehrt(&self) -> bool {
+        self.ausgabe.is_some()
+    }
+
+    // Zeilenumbrüche aus dem Browser (\n) und aus der Ausgabe (\r\n) gelten als
+    // gleich, ein Fehler des Skripts gilt nie als bestanden
+    pub fn bestanden(&self) -> bool {
+        match self.ausgabe.as_ref() {
+            Some(Ok(o)) => zeilen(&self.testfall.erwartet) == zeilen(o),
+            _ => false,
+        }
+    }
+
+    pub fn diff(&self) -> Vec<DiffZeile> {
+        match self.ausgabe.as_ref() {
+            Some(Ok(o)) => zeilen_diff(&self.testfall.erwartet, o),
+            Some(Err(e)) => zeilen_diff(&self.testfall.erwartet, &format!("Fehler: {e}")),
+            None => Vec::new(),
+        }
+    }
+}
+
+fn zeilen(s: &str) -> Vec<&str> {
+    s.trim_end().lines().map(|l| l.trim_end()).collect()
+}
+
+// Testfälle der Ziele ohne sie auszuführen
+pub fn testfaelle_auflisten(
+    ziele: &[TestZiel],
+    konfiguration: &Konfiguration,
+) -> Vec<TestErgebnis> {
+    let mut ergebnisse = Vec::new();
+
+    for ziel in ziele {
+        let testfaelle = match konfiguration.testfaelle.get(&ziel.id()) {
+            Some(s) => s,
+            None => continue,
+        };
+
+        for (index, testfall) in testfaelle.iter().enumerate() {
+            ergebnisse.push(TestErgebnis {
+                ziel: ziel.clone(),
+                index,
+                testfall: testfall.clone(),
+                ausgabe: None,
+            });
+        }
+    }
+
+    ergebnisse
+}
+
+pub fn testfaelle_ausfuehren(
+    ziele: &[TestZiel],
+    vm: PyVm,
+    konfiguration: &Konfiguration,
+) -> Vec<TestErgebnis> {
+    let mut ergebnisse = testfaelle_auflisten(ziele, konfiguration);
+
+    for e in ergebnisse.iter_mut() {
+        e.ausgabe = Some(ausgabe(
+            &e.ziel,
+            &e.testfall.eingabe,
+            &e.testfall.bv_nr,
+            vm.clone(),
+            konfiguration,
+        ));
+    }
+
+    ergebnisse
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffZeile {
+    Gleich(String),
+    Entfernt(String),
+    Hinzugefuegt(String),
+}
+
+// Zeilenweiser Vergleich, Zeilenenden (\r\n / \n) werden vorher vereinheitlicht
+pub fn zeilen_diff(alt: &str, neu: &str) -> Vec<DiffZeile> {
+    let text = |s: &str| {
+        zeilen(s)
+            .into_iter()
+            .map(|l| format!("{l}\n"))
+            .collect::<String>()
+    };
+    let alt = text(alt);
+    let neu = text(neu);
+
+    similar::TextDiff::from_lines(&alt, &neu)
+        .iter_all_changes()
+        .map(|change| {
+            let zeile = change.value().trim_end_matches('\n').to_string();
+            match change.tag() {
+                similar::ChangeTag::Equal => DiffZeile::Gleich(zeile),
+                similar::ChangeTag::Delete => DiffZeile::Entfernt(zeile),
+                similar::ChangeTag::Insert => DiffZeile::Hinzugefuegt(zeile),
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_zeilen_diff() {
+    use DiffZeile::*;
+
+    let diff = zeilen_diff(
+        "if 'Wegerecht' in recht:\r\n    return 'Wegerecht'\r\nreturn ''",
+        "if 'Wegerecht' in recht:\n    return 'Wege- und Leitungsrecht'\nreturn ''\n",
+    );
+
+    assert_eq!(
+        diff,
+        vec![
+            Gleich("if 'Wegerecht' in recht:".to_string()),
+            Entfernt("    return 'Wegerecht'".to_string()),
+            Hinzugefuegt("    return 'Wege- und Leitungsrecht'".to_string()),
+            Gleich("return ''".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_test_ziel_id() {
+    for ziel in TestZiel::SKRIPTE.iter() {
+        assert_eq!(TestZiel::from_id(&ziel.id()).as_ref(), Some(ziel));
+    }
+    assert_eq!(
+        TestZiel::from_id("regex:DATUM"),
+        Some(TestZiel::Regex("DATUM".to_string()))
+    );
+    assert_eq!(TestZiel::from_id("unbekannt"), None);
+}
+
+#[test]
+fn test_testergebnis_bestanden() {
+    let ergebnis = |ausgabe| TestErgebnis {
+        ziel: TestZiel::TextSaubern,
+        index: 0,
+        testfall: Testfall {
+            name: "Testfall 1".to_string(),
+            eingabe: String::new(),
+            bv_nr: String::new(),
+            erwartet: "SyntaxError: invalid syntax".to_string(),
+        },
+        ausgabe,
+    };
+
+    assert!(ergebnis(Some(Ok("SyntaxError: invalid syntax\r\n".to_string()))).bestanden());
+    // Auch wenn die Fehlermeldung der erwarteten Ausgabe gleicht
+    assert!(!ergebnis(Some(Err("SyntaxError: invalid syntax".to_string()))).bestanden());
+    assert!(!ergebnis(None).bestanden());
+    assert!(ergebnis(None).diff().is_empty());
+}
@@ -1,11 +1,13 @@
 use crate::{
+    analyse::{Abt1Analysiert, Abt2Analysiert, Abt3Analysiert, BefundCode, Quittierung, GRUNDBUCH_BEFUND},
     digital::{
         Abt1Loeschung, Abt1Veraenderung, Abt2Loeschung, Abt2Veraenderung, Abt3Loeschung,
         Abt3Veraenderung, BvAbschreibung, BvZuschreibung, FocusType, HocrSeite, Nebenbeteiligter,
         ParsedHocr, SeiteParsed, StringOrLines, TextInputType,
     },
     python::PyVm,
-    GbxAenderungen, GrundbuchAnalysiert, GrundbuchSucheResponse, Konfiguration, PdfFile,
+    testfaelle::{DiffZeile, TestErgebnis, TestZiel},
+    ConfigurationView, GbxAenderungen, GrundbuchAnalysiert, GrundbuchSucheResponse, Konfiguration, PdfFile,
     PopoverState, RpcData,
 };
 
@@ -107,92 +109,115 @@ pub fn render_aenderung_diff(aenderungen: &GbxAenderungen, aktiv: usize) -> Stri
         out.push_str("</div>");
         out
     } else if aktiv < aenderungen.neue_dateien.len() + aenderungen.geaenderte_dateien.len() {
-        use crate::GbxAenderung;
-
-        let (alt, neu) = match aenderungen
+        let aenderung = match aenderungen
             .geaenderte_dateien
-            .iter()
+            .values()
             .nth(aktiv - aenderungen.neue_dateien.len())
         {
-            Some((_, GbxAenderung { alt, neu })) => (alt, neu),
+            Some(s) => s,
             None => return String::new(),
         };
 
-        let alt_json = match serde_json::to_string_pretty(&alt) {
-            Ok(o) => o,
-            Err(_) => return String::new(),
-        };
+        // Nur Änderungen außerhalb der Einträge (Titelblatt, ...): ganze Datei zeigen
+        if aenderung.eintraege.is_empty() {
+            let alt_json = match serde_json::to_string_pretty(&aenderung.alt) {
+                Ok(o) => o,
+                Err(_) => return String::new(),
+            };
 
-        let neu_json = match serde_json::to_string_pretty(&neu) {
-            Ok(o) => o,
-            Err(_) => return String::new(),
-        };
+            let neu_json = match serde_json::to_string_pretty(&aenderung.neu) {
+                Ok(o) => o,
+                Err(_) => return String::new(),
+            };
 
-        let diff = similar::TextDiff::from_lines(&alt_json, &neu_json);
+            return format!("<div>{}</div>", render_json_diff(&alt_json, &neu_json));
+        }
 
         let mut out = format!("<div>");
-        let mut lines = Vec::new();
+        for e in aenderung.eintraege.iter() {
+            let json = |v: &Option<serde_json::Value>| {
+                v.as_ref()
+                    .and_then(|v| serde_json::to_string_pretty(v).ok())
+                    .unwrap_or_default()
+            };
+            let art = match (&e.alt, &e.neu) {
+                (None, _) => "neu",
+                (_, None) => "entfernt",
+                _ => "geändert",
+            };
+            out.push_str(&format!(
+                "<span class='snip'><p>&nbsp;</p><p>{}: {} ({art})</p></span>",
+                e.abschnitt, e.id
+            ));
+            out.push_str(&render_json_diff(&json(&e.alt), &json(&e.neu)));
+        }
+        out.push_str("</div>");
+        out
+    } else {
+        String::new()
+    }
+}
 
-        for change in diff.iter_all_changes() {
-            let c = change.to_string().replace(" ", "&nbsp;");
-            match change.tag() {
-                similar::ChangeTag::Insert => {
-                    lines.push(format!("<span class='insert'><p>+</p><p>{c}</p></span>"));
-                }
-                similar::ChangeTag::Delete => {
-                    lines.push(format!("<span class='remove'><p>-</p><p>{c}</p></span>"));
-                }
-                similar::ChangeTag::Equal => {
-                    lines.push(format!(
-                        "<span class='equal'><p>&nbsp;</p><p>{c}</p></span>"
-                    ));
-                }
+fn render_json_diff(alt_json: &str, neu_json: &str) -> String {
+    let diff = similar::TextDiff::from_lines(alt_json, neu_json);
+
+    let mut lines = Vec::new();
+
+    for change in diff.iter_all_changes() {
+        let c = change.to_string().replace(" ", "&nbsp;");
+        match change.tag() {
+            similar::ChangeTag::Insert => {
+                lines.push(format!("<span class='insert'><p>+</p><p>{c}</p></span>"));
+            }
+            similar::ChangeTag::Delete => {
+                lines.push(format!("<span class='remove'><p>-</p><p>{c}</p></span>"));
+            }
+            similar::ChangeTag::Equal => {
+                lines.push(format!(
+                    "<span class='equal'><p>&nbsp;</p><p>{c}</p></span>"
+                ));
             }
         }
+    }
 
-        let lines_equal = lines
-            .iter()
-            .enumerate()
-            .filter_map(|(num, line)| {
-                if line.contains("<span class='equal'>") {
-                    Some(num)
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>();
+    let lines_equal = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(num, line)| {
+            if line.contains("<span class='equal'>") {
+                Some(num)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
 
-        let mut ranges = vec![(0, 0)];
-        let mut last_l = 0;
+    let mut ranges = vec![(0, 0)];
+    let mut last_l = 0;
 
-        for l in lines_equal.iter() {
-            if l - last_l > 1 {
-                // start new range
-                ranges.push((*l, *l));
-            }
+    for l in lines_equal.iter() {
+        if l - last_l > 1 {
+            // start new range
+            ranges.push((*l, *l));
+        }
 
-            last_l = *l;
+        last_l = *l;
 
-            if let Some((start, end)) = ranges.last_mut() {
-                *end = *l;
-            }
+        if let Some((start, end)) = ranges.last_mut() {
+            *end = *l;
         }
+    }
 
-        for (start, end) in ranges {
-            if end.saturating_sub(start) >= 5 {
-                for i in (start + 1)..(end - 1) {
-                    lines[i].clear();
-                }
-                lines[start + 2] = format!("<span class='snip'><p>&nbsp;</p><p>----</p></span>");
+    for (start, end) in ranges {
+        if end.saturating_sub(start) >= 5 {
+            for i in (start + 1)..(end - 1) {
+                lines[i].clear();
             }
+            lines[start + 2] = format!("<span class='snip'><p>&nbsp;</p><p>----</p></span>");
         }
-
-        out.push_str(&lines.join("\r\n"));
-        out.push_str("</div>");
-        out
-    } else {
-        String::new()
     }
+
+    lines.join("\r\n")
 }
 
 pub fn render_popover_content(rpc_data: &RpcData) -> String {
@@ -361,6 +386,47 @@ pub fn render_popover_content(rpc_data: &RpcData) -> String {
             </div>
             ")
         }
+        Some(PopoverState::BefundQuittieren { abteilung, lfd_nr, code }) => {
+            format!("
+            <div style='box-shadow:0px 0px 100px #22222288;pointer-events:initial;width:800px;display:flex;flex-direction:column;position:relative;margin:10px auto;border:1px solid grey;background:white;padding:100px;border-radius:5px;' onmousedown='event.stopPropagation();' onmouseup='event.stopPropagation();'>
+                
+                {close_button}
+
+                <h2 style='font-size:24px;font-family:sans-serif;margin-bottom:25px;'>Befund quittieren</h2>
+                
+                <div style='padding:5px 0px;display:flex;flex-grow:1;flex-direction:column;'>
+                    <p style='font-size:16px;font-family:sans-serif;margin-bottom:10px;'>{betrifft}:</p>
+                    <p style='font-size:16px;font-family:sans-serif;padding:4px 8px;background:rgb(255,255,167);'>{text}</p>
+                    <form onsubmit='befundQuittierenFinished(event)' action=''>
+                    <div style='display:flex;justify-content:space-between;padding:10px 0px;font-size:16px;'>
+                        <label style='font-size:20px;font-style:italic;'>Begründung</label>
+                        <input type='text' id='__application_befund_quittieren_begruendung' required style='font-size:20px;font-weight:bold;border-bottom:1px solid black;cursor:text;min-width:400px;'></input>
+                    </div>
+                    <div style='display:flex;justify-content:space-between;padding:10px 0px;font-size:16px;'>
+                        <label style='font-size:20px;font-style:italic;'>Quittiert von</label>
+                        <p style='font-size:20px;'>{email}</p>
+                    </div>
+                    <p style='font-size:14px;font-family:sans-serif;color:#555;'>{gueltigkeit}</p>
+                    <br/>
+                    <input type='submit' value='Quittieren' class='btn btn_neu' style='cursor:pointer;font-size:20px;height:unset;display:inline-block;flex-grow:0;max-width:320px;margin-top:20px;' />
+                    </form>
+                </div>
+            </div>
+            ",
+                betrifft = if *abteilung == GRUNDBUCH_BEFUND {
+                    "Grundbuch".to_string()
+                } else {
+                    format!("Abt. {abteilung} lfd. Nr. {lfd_nr}")
+                },
+                gueltigkeit = if *abteilung == GRUNDBUCH_BEFUND {
+                    "Die Quittierung gilt, solange derselbe Befund auftritt."
+                } else {
+                    "Die Quittierung wird ungültig, sobald sich der Text des Eintrags ändert."
+                },
+                text = code.text(),
+                email = html_escape::encode_safe(&rpc_data.konfiguration.server_email),
+            )
+        }
         Some(PopoverState::ExportPdf) => {
             format!("
             <div style='box-shadow:0px 0px 100px #22222288;pointer-events:initial;width:800px;display:flex;flex-direction:column;position:relative;margin:10px auto;border:1px solid grey;background:white;padding:100px;border-radius:5px;' onmousedown='event.stopPropagation();' onmouseup='event.stopPropagation();'>
@@ -492,6 +558,9 @@ pub fn render_popover_content(rpc_data: &RpcData) -> String {
             static IMG_FX: &[u8] = include_bytes!("./img/icons8-formula-fx-96.png");
             let img_fx = base64::encode(IMG_FX);
 
+            static IMG_HISTORIE: &[u8] = include_bytes!("./img/icons8-synchronize-48.png");
+            let img_historie = base64::encode(IMG_HISTORIE);
+
             let active_allgemein = if *cw == Allgemein { " active" } else { "" };
             let active_regex = if *cw == RegEx { " active" } else { "" };
             let active_text_saubern = if *cw == TextSaubern { " active" } else { "" };
@@ -529,6 +598,13 @@ pub fn render_popover_content(rpc_data: &RpcData) -> String {
                 ""
             };
             let active_text_kuerzen_abt3 = if *cw == TextKuerzenAbt3 { " active" } else { "" };
+            let active_pruefe_eintrag = if *cw == PruefeEintrag { " active" } else { "" };
+            let active_historie = if *cw == Historie { " active" } else { "" };
+            let active_eigentuemer_auslesen_abt1 = if *cw == EigentuemerAuslesenAbt1 {
+                " active"
+            } else {
+                ""
+            };
 
             let sidebar = format!("
                 <div class='__application_configuration_sidebar' style='display:flex;flex-direction:column;width:160px;min-height:750px;'>
@@ -562,6 +638,13 @@ pub fn render_popover_content(rpc_data: &RpcData) -> String {
                     
                     <hr/>
 
+                    <div class='__application_configuration_sidebar_section{active_eigentuemer_auslesen_abt1}' onmouseup='activateConfigurationView(event, \"eigentuemer-auslesen-abt1\")'>
+                        <img style='width:25px;height:25px;' src='data:image/png;base64,{img_fx}'></img>
+                        <p>Eigentümer auslesen (Abt. 1)</p>
+                    </div>
+                    
+                    <hr/>
+
                     <div class='__application_configuration_sidebar_section{active_klassifizierung_rechteart}' onmouseup='activateConfigurationView(event, \"klassifizierung-rechteart-abt2\")'>
                         <img style='width:25px;height:25px;' src='data:image/png;base64,{img_fx}'></img>
                         <p>Klassifizierung RechteArt (Abt. 2)</p>
@@ -600,6 +683,20 @@ pub fn render_popover_content(rpc_data: &RpcData) -> String {
                         <img style='width:25px;height:25px;' src='data:image/png;base64,{img_fx}'></img>
                         <p>Text kürzen (Abt. 3)</p>
                     </div>
+                    
+                    <hr/>
+
+                    <div class='__application_configuration_sidebar_section{active_pruefe_eintrag}' onmouseup='activateConfigurationView(event, \"pruefe-eintrag\")'>
+                        <img style='width:25px;height:25px;' src='data:image/png;base64,{img_fx}'></img>
+                        <p>Einträge prüfen (Abt. 2 / 3)</p>
+                    </div>
+                    
+                    <hr/>
+
+                    <div class='__application_configuration_sidebar_section{active_historie}' onmouseup='activateConfigurationView(event, \"historie\")'>
+                        <img style='width:25px;height:25px;' src='data:image/png;base64,{img_historie}'></img>
+                        <p>Versionen</p>
+                    </div>
                 </div>
             ");
 
@@ -643,6 +740,15 @@ pub fn render_popover_content(rpc_data: &RpcData) -> String {
                                 <input type='button' value='Datei auswählen...' class='btn btn_neu' data-file-input-id='__application_konfiguration_datenbank_private_key' onclick='document.getElementById(event.target.dataset.fileInputId).click();' />
                             </div>´
                         </div>
+
+                        <div style='margin-top:25px;'>
+                            <h2 style='font-size:20px;'>Konfigurationen vergleichen</h2>
+                            <p style='font-size:16px;padding:10px 0px;'>Analysiert alle .gbx-Dateien eines Ordners mit zwei Konfigurationen und speichert die geänderten Einträge als .html- und .tsv-Datei.</p>
+                            <div style='display:block;'>
+                                <button class='btn-grad' onclick='konfigurationVergleichen(event)'>Konfigurationen vergleichen...</button>
+                            </div>
+                            <p id='__application-konfiguration-vergleich-fortschritt' style='font-size:16px;padding:10px 0px;'></p>
+                        </div>
                     </div>
                 ",
                     server_url = rpc_data.konfiguration.server_url,
@@ -704,6 +810,11 @@ pub fn render_popover_content(rpc_data: &RpcData) -> String {
                             <p style='color:#4a4e6a;user-select:none;'>def text_säubern(recht: String) -> String:</p>
                             <div style='padding-left:34px;caret-color: #4a4e6a;' contenteditable='true' onkeydown='insertTabAtCaret(event);' oninput='editTextSaubernScript(event);'>{konfig_text_saubern_script}</div>
                         </div>
+                        
+                        <div style='display:flex;flex-direction:column;margin-top:10px;'>
+                            <textarea id='__application_konfiguration_test_eingabe' style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' oninput='textSaubernScriptTesten(event);'style='flex-grow:1;margin-right:10px;' placeholder='Test Eingabe...'></textarea>
+                            <textarea style='border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' id='__application_konfiguration_text_saubern_test' style='flex-grow:1;' placeholder='Ausgabe der Funktion'></textarea>
+                        </div>
                     </div>              
                 ", konfig_text_saubern_script = 
                     rpc_data.konfiguration.text_saubern_script.iter()
@@ -719,6 +830,11 @@ pub fn render_popover_content(rpc_data: &RpcData) -> String {
                             <p style='color:#4a4e6a;user-select:none;'>def abkuerzungen() -> [String]:</p>
                             <div style='padding-left:34px;caret-color: #4a4e6a;' contenteditable='true' onkeydown='insertTabAtCaret(event);' oninput='editAbkuerzungenScript(event);'>{konfig_abkuerzungen_script}</div>
                         </div>
+                        
+                        <div style='display:flex;flex-direction:column;margin-top:10px;'>
+                            <textarea id='__application_konfiguration_test_eingabe' style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' oninput='abkuerzungenScriptTesten(event);'style='flex-grow:1;margin-right:10px;' placeholder='Test Eingabe (wird in Sätze aufgeteilt)...'></textarea>
+                            <textarea style='border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' id='__application_konfiguration_abkuerzungen_test' style='flex-grow:1;' placeholder='Ausgabe der Funktion'></textarea>
+                        </div>
                     </div>
                 ", konfig_abkuerzungen_script = 
                         rpc_data.konfiguration.abkuerzungen_script.iter()
@@ -739,7 +855,7 @@ pub fn render_popover_content(rpc_data: &RpcData) -> String {
                         
                         <div style='display:flex;flex-direction:column;margin-top:10px;'>
                             <input type='text' style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;flex-grow:1;margin-right:10px;' id='__application_konfiguration_flurstueck_auslesen_bv_nr' placeholder='BV-Nr. (Spalte 1) eingeben...' />
-                            <textarea style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;'  rows='5' cols='45' oninput='flurstueckAuslesenScriptTesten(event);'style='flex-grow:1;margin-right:10px;' placeholder='Test Eingabe...'></textarea>
+                            <textarea id='__application_konfiguration_test_eingabe' style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;'  rows='5' cols='45' oninput='flurstueckAuslesenScriptTesten(event);'style='flex-grow:1;margin-right:10px;' placeholder='Test Eingabe...'></textarea>
                             <textarea style='border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' id='__application_konfiguration_flurstueck_auslesen_test' style='flex-grow:1;' placeholder='Ausgabe der Funktion'></textarea>
                         </div>
                     </div>     
@@ -763,7 +879,7 @@ pub fn render_popover_content(rpc_data: &RpcData) -> String {
                         </div>
                         
                         <div style='display:flex;flex-direction:column;margin-top:10px;'>
-                            <textarea style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' oninput='rechteArtScriptTesten(event);'style='flex-grow:1;margin-right:10px;' placeholder='Test Eingabe...'></textarea>
+                            <textarea id='__application_konfiguration_test_eingabe' style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' oninput='rechteArtScriptTesten(event);'style='flex-grow:1;margin-right:10px;' placeholder='Test Eingabe...'></textarea>
                             <textarea style='border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' id='__application_konfiguration_rechteart_test' style='flex-grow:1;' placeholder='Ausgabe der Funktion'></textarea>
                         </div>
                     </div>
@@ -787,7 +903,7 @@ pub fn render_popover_content(rpc_data: &RpcData) -> String {
                         </div>
                         
                         <div style='display:flex;flex-direction:column;margin-top:10px;'>
-                            <textarea style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' oninput='rechtsinhaberAbt2ScriptTesten(event);'style='flex-grow:1;margin-right:10px;' placeholder='Test Eingabe...'></textarea>
+                            <textarea id='__application_konfiguration_test_eingabe' style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' oninput='rechtsinhaberAbt2ScriptTesten(event);'style='flex-grow:1;margin-right:10px;' placeholder='Test Eingabe...'></textarea>
                             <textarea style='border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' id='__application_konfiguration_rechtsinhaber_abt2_test' style='flex-grow:1;' placeholder='Ausgabe der Funktion'></textarea>
                         </div>
                     </div>
@@ -810,7 +926,7 @@ pub fn render_popover_content(rpc_data: &RpcData) -> String {
                         </div>
                         
                         <div style='display:flex;flex-direction:column;margin-top:10px;'>
-                            <textarea style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' oninput='rangvermerkAuslesenAbt2ScriptTesten(event);'style='flex-grow:1;margin-right:10px;' placeholder='Test Eingabe...'></textarea>
+                            <textarea id='__application_konfiguration_test_eingabe' style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' oninput='rangvermerkAuslesenAbt2ScriptTesten(event);'style='flex-grow:1;margin-right:10px;' placeholder='Test Eingabe...'></textarea>
                             <textarea style='border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' id='__application_konfiguration_rangvermerk_auslesen_abt2_test' style='flex-grow:1;' placeholder='Ausgabe der Funktion'></textarea>
                         </div>
                     </div>
@@ -832,7 +948,7 @@ pub fn render_popover_content(rpc_data: &RpcData) -> String {
                         </div>
                         
                         <div style='display:flex;flex-direction:column;margin-top:10px;'>
-                            <textarea  style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' oninput='textKuerzenAbt2ScriptTesten(event);'style='flex-grow:1;margin-right:10px;' placeholder='Test Eingabe...'></textarea>
+                            <textarea id='__application_konfiguration_test_eingabe' style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' oninput='textKuerzenAbt2ScriptTesten(event);'style='flex-grow:1;margin-right:10px;' placeholder='Test Eingabe...'></textarea>
                             <textarea style='border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' id='__application_konfiguration_text_kuerzen_abt2_test' style='flex-grow:1;' placeholder='Ausgabe der Funktion'></textarea>
                         </div>
                     </div>
@@ -853,7 +969,7 @@ pub fn render_popover_content(rpc_data: &RpcData) -> String {
                         </div>
                         
                         <div style='display:flex;flex-direction:column;margin-top:10px;'>
-                            <textarea  style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' oninput='betragAuslesenScriptTesten(event);'style='flex-grow:1;margin-right:10px;' placeholder='Test Eingabe...'></textarea>
+                            <textarea id='__application_konfiguration_test_eingabe' style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' oninput='betragAuslesenScriptTesten(event);'style='flex-grow:1;margin-right:10px;' placeholder='Test Eingabe...'></textarea>
                             <textarea style='border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' id='__application_konfiguration_betrag_auslesen_test' style='flex-grow:1;' placeholder='Ausgabe der Funktion'></textarea>
                         </div>
                     </div>
@@ -876,7 +992,7 @@ pub fn render_popover_content(rpc_data: &RpcData) -> String {
                         </div>
                         
                         <div style='display:flex;flex-direction:column;margin-top:10px;'>
-                            <textarea style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' oninput='schuldenArtScriptTesten(event);'style='flex-grow:1;margin-right:10px;' placeholder='Test Eingabe...'></textarea>
+                            <textarea id='__application_konfiguration_test_eingabe' style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' oninput='schuldenArtScriptTesten(event);'style='flex-grow:1;margin-right:10px;' placeholder='Test Eingabe...'></textarea>
                             <textarea style='border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' id='__application_konfiguration_schuldenart_test' style='flex-grow:1;' placeholder='Ausgabe der Funktion'></textarea>
                         </div>
                     </div>
@@ -898,7 +1014,7 @@ pub fn render_popover_content(rpc_data: &RpcData) -> String {
                         </div>
                         
                         <div style='display:flex;flex-direction:column;margin-top:10px;'>
-                            <textarea style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' oninput='rechtsinhaberAbt3ScriptTesten(event);'style='flex-grow:1;margin-right:10px;' placeholder='Test Eingabe...'></textarea>
+                            <textarea id='__application_konfiguration_test_eingabe' style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' oninput='rechtsinhaberAbt3ScriptTesten(event);'style='flex-grow:1;margin-right:10px;' placeholder='Test Eingabe...'></textarea>
                             <textarea style='border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' id='__application_konfiguration_rechtsinhaber_abt3_test' style='flex-grow:1;' placeholder='Ausgabe der Funktion'></textarea>
                         </div>
                     </div>
@@ -919,7 +1035,7 @@ pub fn render_popover_content(rpc_data: &RpcData) -> String {
                         </div>
                         
                         <div style='display:flex;flex-direction:column;margin-top:10px;'>
-                            <textarea style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' oninput='textKuerzenAbt3ScriptTesten(event);'style='flex-grow:1;margin-right:10px;' placeholder='Test Eingabe...'></textarea>
+                            <textarea id='__application_konfiguration_test_eingabe' style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' oninput='textKuerzenAbt3ScriptTesten(event);'style='flex-grow:1;margin-right:10px;' placeholder='Test Eingabe...'></textarea>
                             <textarea style='border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' id='__application_konfiguration_text_kuerzen_abt3_test' style='flex-grow:1;' placeholder='Ausgabe der Funktion'></textarea>
                         </div>
                     </div>
@@ -931,9 +1047,112 @@ pub fn render_popover_content(rpc_data: &RpcData) -> String {
                     .collect::<Vec<String>>()
                     .join("\r\n"),
                 ),
+                PruefeEintrag => format!("
+                    <div style='padding:5px 0px;display:flex;flex-direction:column;flex-grow:1;'>
+                        
+                        <p style='font-size:14px;padding-bottom:5px;'>Wird nach der Analyse für jeden Eintrag in Abt. 2 / Abt. 3 ausgeführt. Mit p.fehler(...) und p.warnung(...) gemeldete Texte erscheinen in der Analyse. Leer = keine Prüfung.</p>
+                        
+                        <div style='display:flex;flex-grow:1;flex-direction:column;background:white;border:1px solid #efefef;margin-top:5px;font-weight:bold;font-size:14px;font-family:monospace;color:black;padding:10px;min-height:200px;max-height:450px;overflow-y:scroll;'>
+                            <p style='color:#4a4e6a;user-select:none;'>def pruefe_eintrag(abteilung: int, text: String, kurztext: String, rechteart: RechteArt, schuldenart: SchuldenArt, betrag: Betrag, flurstuecke: [FlurFlurstueck], rechtsinhaber: String, ordnungsnummer: String, re: Mapping[String, Regex]) -> Pruefung:</p>
+                            <div style='padding-left:34px;caret-color: #4a4e6a;' contenteditable='true' onkeydown='insertTabAtCaret(event);' oninput='editPruefeEintragScript(event);'>{konfig_pruefe_eintrag_script}</div>
+                        </div>
+                        
+                        <div style='display:flex;flex-direction:column;margin-top:10px;'>
+                            <select id='__application_konfiguration_pruefe_eintrag_abteilung' style='margin-bottom:5px;align-self:flex-start;' onchange='pruefeEintragScriptTesten(event);'>
+                                <option value='2'>Abt. 2</option>
+                                <option value='3' selected>Abt. 3</option>
+                            </select>
+                            <textarea id='__application_konfiguration_test_eingabe' style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' oninput='pruefeEintragScriptTesten(event);'style='flex-grow:1;margin-right:10px;' placeholder='Test Eingabe...'></textarea>
+                            <textarea style='border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' id='__application_konfiguration_pruefe_eintrag_test' style='flex-grow:1;' placeholder='Ausgabe der Funktion'></textarea>
+                        </div>
+                    </div>
+                ", konfig_pruefe_eintrag_script = 
+                    rpc_data.konfiguration.pruefe_eintrag_script.iter()
+                    .map(|l| l.replace(" ", "\u{00a0}"))
+                    .map(|l| l.replace("\\", "&bsol;"))
+                    .map(|l| if l.is_empty() { format!("<div>&nbsp;</div>") } else { format!("<div>{}</div>", l) })
+                    .collect::<Vec<String>>()
+                    .join("\r\n"),
+                ),
+                EigentuemerAuslesenAbt1 => format!("
+                    <div style='padding:5px 0px;display:flex;flex-direction:column;flex-grow:1;'>
+                        
+                        <p style='font-size:14px;padding-bottom:5px;'>Teilt die Eigentümer eines Eintrags in Abt. 1 in Personen und Firmen auf. Leer = Abt. 1 wird nicht analysiert.</p>
+                        
+                        <div style='display:flex;flex-grow:1;flex-direction:column;background:white;border:1px solid #efefef;margin-top:5px;font-weight:bold;font-size:14px;font-family:monospace;color:black;padding:10px;min-height:200px;max-height:450px;overflow-y:scroll;'>
+                            <p style='color:#4a4e6a;user-select:none;'>def eigentuemer_auslesen_abt1(eigentuemer: String, zeilen: [String], re: Mapping[String, Regex]) -> Eigentuemerliste:</p>
+                            <div style='padding-left:34px;caret-color: #4a4e6a;' contenteditable='true' onkeydown='insertTabAtCaret(event);' oninput='editEigentuemerAbt1Script(event);'>{konfig_eigentuemer_abt1_script}</div>
+                        </div>
+                        
+                        <div style='display:flex;flex-direction:column;margin-top:10px;'>
+                            <textarea id='__application_konfiguration_test_eingabe' style='margin-bottom:5px;border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' oninput='eigentuemerAbt1ScriptTesten(event);'style='flex-grow:1;margin-right:10px;' placeholder='Test Eingabe...'></textarea>
+                            <textarea style='border-radius:5px;padding:5px;border:1px solid #efefef;' rows='5' cols='45' id='__application_konfiguration_eigentuemer_abt1_test' style='flex-grow:1;' placeholder='Ausgabe der Funktion'></textarea>
+                        </div>
+                    </div>
+                ", konfig_eigentuemer_abt1_script = 
+                    rpc_data.konfiguration.eigentuemer_auslesen_abt1_script.iter()
+                    .map(|l| l.replace(" ", "\u{00a0}"))
+                    .map(|l| l.replace("\\", "&bsol;"))
+                    .map(|l| if l.is_empty() { format!("<div>&nbsp;</div>") } else { format!("<div>{}</div>", l) })
+                    .collect::<Vec<String>>()
+                    .join("\r\n"),
+                ),
+                Historie => match crate::historie::versionen(HISTORIE_MAX_VERSIONEN) {
+                    Ok(versionen) => {
+                        let optionen = |ausgewaehlt: usize| versionen.iter().enumerate().map(|(i, v)| {
+                            format!("<option value='{id}' {selected}>{zeit} ({kurz_id}) - {beschreibung}</option>",
+                                id = v.id,
+                                selected = if i == ausgewaehlt { "selected" } else { "" },
+                                zeit = v.zeit.format("%d.%m.%Y %H:%M:%S"),
+                                kurz_id = v.kurz_id(),
+                                beschreibung = html_escape::encode_safe(&v.beschreibung).replace('\\', "&bsol;"),
+                            )
+                        }).collect::<Vec<_>>().join("\r\n");
+
+                        format!("
+                            <div style='padding:5px 0px;display:flex;flex-direction:column;flex-grow:1;'>
+                                <p style='font-size:16px;padding:10px 0px;'>Jeder gespeicherte Stand wird als Version abgelegt, sobald einige Sekunden lang nichts mehr geändert wurde, spätestens beim Schließen der Konfiguration. Der Vergleich zeigt die geänderten Skripte und regulären Ausdrücke zwischen zwei Versionen, beim Wiederherstellen werden nur diese übernommen.</p>
+                                
+                                <div style='display:flex;flex-direction:row;align-items:center;padding:5px 0px;'>
+                                    <label style='font-size:16px;font-style:italic;min-width:50px;'>Alt</label>
+                                    <select id='__application_konfiguration_historie_alt' style='flex-grow:1;max-width:600px;' onchange='historieVergleichen(event)'>{optionen_alt}</select>
+                                </div>
+                                
+                                <div style='display:flex;flex-direction:row;align-items:center;padding:5px 0px;'>
+                                    <label style='font-size:16px;font-style:italic;min-width:50px;'>Neu</label>
+                                    <select id='__application_konfiguration_historie_neu' style='flex-grow:1;max-width:600px;' onchange='historieVergleichen(event)'>{optionen_neu}</select>
+                                </div>
+                                
+                                <div style='display:block;'>
+                                    <button class='btn-grad' onclick='historieWiederherstellen(event)'>Skripte der alten Version wiederherstellen</button>
+                                </div>
+                                
+                                <div id='__application_konfiguration_historie_diff' style='display:flex;flex-direction:column;max-height:450px;overflow-y:scroll;margin-top:10px;'>
+                                    {diff}
+                                </div>
+                            </div>
+                        ",
+                            optionen_alt = optionen(1_usize.min(versionen.len().saturating_sub(1))),
+                            optionen_neu = optionen(0),
+                            diff = match (versionen.get(1), versionen.first()) {
+                                (Some(alt), Some(neu)) => render_historie_diff(&alt.id, &neu.id),
+                                _ => String::new(),
+                            },
+                        )
+                    }
+                    Err(e) => format!("<p style='color:rgb(129,8,8);font-size:16px;'>{}</p>", html_escape::encode_safe(&e)),
+                },
             };
 
-            let main = format!("<div style='display:flex;flex-grow:1;padding:0px 20px;line-height: 1.2;'>{main_content}</div>");
+            let ziele = crate::testfaelle::ziele_fuer_ansicht(cw, &rpc_data.konfiguration);
+            let testfaelle = if ziele.is_empty() && testfall_speichern_ziel(cw).is_none() {
+                String::new()
+            } else {
+                let ergebnisse = crate::testfaelle::testfaelle_auflisten(&ziele, &rpc_data.konfiguration);
+                format!("<div id='__application_konfiguration_testfaelle'>{}</div>", render_testfaelle(cw, &ergebnisse, false))
+            };
+
+            let main = format!("<div style='display:flex;flex-direction:column;flex-grow:1;padding:0px 20px;line-height: 1.2;'>{main_content}{testfaelle}</div>");
 
             format!("
                 <div style='box-shadow:0px 0px 100px #22222288;pointer-events:initial;width:1000px;position:relative;display:flex;flex-direction:column;margin:10px auto;border:1px solid grey;background:white;padding:100px;border-radius:5px;' onmousedown='event.stopPropagation();' onmouseup='event.stopPropagation();'>
@@ -1345,6 +1564,70 @@ pub fn render_ribbon(rpc_data: &RpcData) -> String {
         ")
     };
 
+    let export_geojson = {
+        format!("
+            <div class='__application-ribbon-section-content'>
+                <label onmouseup='tab_functions.export_geojson(event)' class='__application-ribbon-action-vertical-large'>
+                    <div class='icon-wrapper'>
+                        <img class='icon {disabled}' src='data:image/png;base64,{icon_export_csv}'>
+                    </div>
+                    <div>
+                        <p>Belastungen</p>
+                        <p>in GeoJSON</p>
+                    </div>
+                </label>
+            </div>
+        ")
+    };
+
+    let gemarkungen = {
+        format!("
+            <div class='__application-ribbon-section 4'>
+                <div style='display:flex;flex-direction:row;'>
+                    <div class='__application-ribbon-section-content'>
+                        <label onmouseup='tab_functions.import_gemarkungen(event)' class='__application-ribbon-action-vertical-large'>
+                            <div class='icon-wrapper'>
+                                <img class='icon' src='data:image/png;base64,{icon_download_base64}'>
+                            </div>
+                            <div>
+                                <p>Gemarkungen</p>
+                                <p>importieren</p>
+                            </div>
+                        </label>
+                    </div>
+
+                    <div class='__application-ribbon-section-content'>
+                        <label onmouseup='tab_functions.import_alkis(event)' class='__application-ribbon-action-vertical-large'>
+                            <div class='icon-wrapper'>
+                                <img class='icon' src='data:image/png;base64,{icon_download_base64}'>
+                            </div>
+                            <div>
+                                <p>ALKIS</p>
+                                <p>importieren</p>
+                            </div>
+                        </label>
+                    </div>
+                </div>
+            </div>
+        ")
+    };
+
+    let export_rang = {
+        format!("
+            <div class='__application-ribbon-section-content'>
+                <label onmouseup='tab_functions.export_rang(event)' class='__application-ribbon-action-vertical-large'>
+                    <div class='icon-wrapper'>
+                        <img class='icon {disabled}' src='data:image/png;base64,{icon_export_csv}'>
+                    </div>
+                    <div>
+                        <p>Rangverh.</p>
+                        <p>in CSV</p>
+                    </div>
+                </label>
+            </div>
+        ")
+    };
+
     let grundbuch_oeffnen = {
         format!("
         <div class='__application-ribbon-section-content'>
@@ -1706,10 +1989,14 @@ pub fn render_ribbon(rpc_data: &RpcData) -> String {
 
                 {nebenbet}
 
+                {gemarkungen}
+
                 <div style='display:flex;flex-grow:1;'></div>
 
                 <div class='__application-ribbon-section 5'>
                     <div style='display:flex;flex-direction:row;'>
+                        {export_rang}
+                        {export_geojson}
                         {export_lefis}
                     </div>
                 </div>
@@ -1921,6 +2208,16 @@ pub fn render_page_list(rpc_data: &RpcData) -> String {
 pub fn render_main_container(rpc_data: &mut RpcData) -> String {
     let has_no_pdf = rpc_data.loaded_file_has_no_pdf();
 
+    // Vor dem &mut-Zugriff auf die Datei, der Abgleich braucht alle Blätter
+    let analyse = match rpc_data.open_page.as_ref() {
+        Some((datei, _)) if rpc_data.konfiguration.lefis_analyse_einblenden => rpc_data
+            .loaded_files
+            .get(datei)
+            .filter(|f| f.ist_geladen())
+            .and_then(|_| crate::analyse_abgleichen(rpc_data, datei)),
+        _ => None,
+    };
+
     let open_file = match rpc_data
         .open_page
         .as_mut()
@@ -2021,7 +2318,7 @@ pub fn render_main_container(rpc_data: &mut RpcData) -> String {
                     </div>
                 ", 
                     max_height = if has_no_pdf { "max-height:calc(100% - 43px);" } else { "max-height:525px;" },
-                    analyse = render_analyse_grundbuch(&open_file.cache.start_analyzing(&open_file.analysiert, &rpc_data.vm, &rpc_data.loaded_nb, &rpc_data.konfiguration), false, false)
+                    analyse = analyse.as_ref().map(|a| render_analyse_grundbuch(a, false, false)).unwrap_or_default()
                 )
             } else {
                 format!("")
@@ -2030,294 +2327,858 @@ pub fn render_main_container(rpc_data: &mut RpcData) -> String {
     }
 }
 
+struct AnalyseIcons {
+    pfeil_str: String,
+    warnung_str: String,
+    fehler_str: String,
+}
+
+impl AnalyseIcons {
+    fn neu() -> Self {
+        const PFEIL_PNG: &[u8] = include_bytes!("../src/img/icons8-arrow-48.png");
+        const WARNUNG_PNG: &[u8] = include_bytes!("../src/img/icons8-warning-48.png");
+        const FEHLER_PNG: &[u8] = include_bytes!("../src/img/icons8-high-priority-48.png");
+
+        AnalyseIcons {
+            pfeil_str: format!("data:image/png;base64,{}", base64::encode(&PFEIL_PNG)),
+            warnung_str: format!("data:image/png;base64,{}", base64::encode(&WARNUNG_PNG)),
+            fehler_str: format!("data:image/png;base64,{}", base64::encode(&FEHLER_PNG)),
+        }
+    }
+}
+
 pub fn render_analyse_grundbuch(
     gb_analysiert: &GrundbuchAnalysiert,
     fuer_druck: bool,
     nur_fehlerhafte_rechte: bool,
 ) -> String {
-    const PFEIL_PNG: &[u8] = include_bytes!("../src/img/icons8-arrow-48.png");
-    const WARNUNG_PNG: &[u8] = include_bytes!("../src/img/icons8-warning-48.png");
-    const FEHLER_PNG: &[u8] = include_bytes!("../src/img/icons8-high-priority-48.png");
-
-    let pfeil_str = format!("data:image/png;base64,{}", base64::encode(&PFEIL_PNG));
-    let warnung_str = format!("data:image/png;base64,{}", base64::encode(&WARNUNG_PNG));
-    let fehler_str = format!("data:image/png;base64,{}", base64::encode(&FEHLER_PNG));
+    let icons = AnalyseIcons::neu();
+    let AnalyseIcons { pfeil_str, .. } = &icons;
 
     normalize_for_js(format!("
         <div style='margin:10px;min-width:600px;'>
+            {fortschritt}
+            {pruefung_header}
+            {pruefung_analyse}
+            {erbbaurechte_header}
+            {erbbaurechte_analyse}
+            {a1_header}
+            {a1_analyse}
             {a2_header}
             {a2_analyse}
             {a3_header}
             {a3_analyse}
+            {loeschungen_header}
+            {loeschungen_analyse}
+            {rang_header}
+            {rang_analyse}
+            {alkis_header}
+            {alkis_analyse}
+            {blaetter_header}
+            {blaetter_analyse}
         </div>
         ",
+        fortschritt = if fuer_druck { String::new() } else {
+            let offen = gb_analysiert.abt1.iter().filter(|a| !a.fertig_analysiert).count()
+                + gb_analysiert.abt2.iter().filter(|a| !a.fertig_analysiert).count()
+                + gb_analysiert.abt3.iter().filter(|a| !a.fertig_analysiert).count();
+            format!("<div id='__application-analyse-fortschritt'>{}</div>", render_analyse_fortschritt(offen))
+        },
+        pruefung_header = if !gb_analysiert.pruefung.fehler.iter().any(|f| !nur_fehlerhafte_rechte || f.quittierung.is_none()) && (nur_fehlerhafte_rechte || gb_analysiert.pruefung.warnungen.is_empty()) {
+            String::new()
+        } else if fuer_druck {
+            format!("<h4>Plausibilitätsprüfung {} Blatt {}</h4>", gb_analysiert.titelblatt.grundbuch_von, gb_analysiert.titelblatt.blatt)
+        } else {
+            format!("<h4>Plausibilitätsprüfung</h4>")
+        },
+        pruefung_analyse = gb_analysiert.pruefung.fehler.iter()
+            .filter(|f| !nur_fehlerhafte_rechte || f.quittierung.is_none())
+            .map(|f| render_grundbuch_befund(&f.code, f.quittierung.as_ref(), true, &icons, "margin:5px", fuer_druck))
+            .chain(gb_analysiert.pruefung.warnungen.iter().filter(|_| !nur_fehlerhafte_rechte).map(|w| {
+                render_grundbuch_befund(&w.code, w.quittierung.as_ref(), false, &icons, "margin:5px", fuer_druck)
+            }))
+            .collect::<Vec<String>>().join("\r\n"),
+        erbbaurechte_header = if fuer_druck || gb_analysiert.erbbaurechte.is_empty() { "" } else { "<h4>Analyse Erbbaurechte</h4>" },
+        a1_header = if fuer_druck || gb_analysiert.abt1.is_empty() { "" } else { "<h4>Analyse Abt. 1</h4>" },
         a2_header = if fuer_druck { "" } else { "<h4>Analyse Abt. 2</h4>" },
         a3_header = if fuer_druck { "" } else { "<h4>Analyse Abt. 3</h4>" },
-
-        a2_analyse = gb_analysiert.abt2.iter()
-        .filter(|a2a| if nur_fehlerhafte_rechte { !a2a.fehler.is_empty() } else { true })
-        .map(|a2a| {
+        loeschungen_header = if fuer_druck || gb_analysiert.loeschungen.eintraege.iter().all(|l| l.warnungen.is_empty() && l.fehler.is_empty()) && gb_analysiert.loeschungen.warnungen.is_empty() { "" } else { "<h4>Abgleich Löschungen</h4>" },
+        rang_header = if fuer_druck || (!gb_analysiert.rang.fehler.iter().any(|f| !nur_fehlerhafte_rechte || f.quittierung.is_none()) && (nur_fehlerhafte_rechte || gb_analysiert.rang.warnungen.is_empty())) { "" } else { "<h4>Rangverhältnisse</h4>" },
+        alkis_header = if fuer_druck || nur_fehlerhafte_rechte || gb_analysiert.alkis.warnungen.is_empty() { "" } else { "<h4>Abgleich ALKIS</h4>" },
+        blaetter_header = if fuer_druck || nur_fehlerhafte_rechte || gb_analysiert.blaetter.is_empty() { "" } else { "<h4>Abgleich mit anderen Blättern</h4>" },
+
+        erbbaurechte_analyse = gb_analysiert.erbbaurechte.iter()
+        .filter(|ea| if nur_fehlerhafte_rechte { ea.fehler.iter().any(|f| f.quittierung.is_none()) } else { true })
+        .map(|ea| {
             format!("
             <div class='__application-abt2-analysiert' style='margin:5px;padding:10px;border:1px solid #efefef;page-break-inside:avoid;'>
-                <h5 style='font-family:sans-serif;font-size:14px;margin: 0px;margin-bottom: 10px;'>{lfd_nr}&nbsp;{rechteart}</h5>
+                <h5 style='font-family:sans-serif;font-size:14px;margin: 0px;margin-bottom: 10px;'>{lfd_nr}&nbsp;ERBBAURECHT</h5>
                 <div style='display:flex;flex-direction:row;'>
-                    <div style='min-width:{max_width};max-width:{max_width};margin-right:20px;'>
-                        <p style='font-family:sans-serif;'>{text_kurz}</p>
-                        {text_original}
-                    </div>
                     <div style='flex-grow:1;'>
-                        <p style='font-family:sans-serif;font-style:italic;display:flex;flex-grow:1;max-width:200px;'>{rechtsinhaber}</p>
-                        {rangvermerk}
-                        <div>{belastete_flurstuecke}</div>
+                        <span style='display:flex;align-items:center;'>
+                            <img src='{pfeil}' style='width:12px;height:12px;'/>
+                            <p style='font-family:sans-serif;display:inline-block;margin-left:10px;'>{grundstueck}</p>
+                        </span>
+                        <p style='font-family:sans-serif;'>{dauer}</p>
+                        <p style='font-family:sans-serif;font-style:italic;'>{zustimmungsvorbehalte}</p>
                     </div>
                 </div>
                 <div class='__application-warnungen-und-fehler'>
                     {fehler}
                     {warnungen}
                 </div>
-                </div>",
-                lfd_nr = if fuer_druck { format!("{} Bl. {} A2/{}",  gb_analysiert.titelblatt.grundbuch_von, gb_analysiert.titelblatt.blatt, a2a.lfd_nr) } else { format!("{}", a2a.lfd_nr) },
-                text_original = if fuer_druck { format!("<p style='margin-top:10px;font-family:sans-serif;'>{}</p>", a2a.text_original) } else { String::new() }, 
-                max_width = if fuer_druck { "600px" } else { "380px" },
-                text_kurz = a2a.text_kurz,
-                rechteart = format!("{:?}", a2a.rechteart).to_uppercase(),
-                rechtsinhaber = match a2a.nebenbeteiligter.ordnungsnummer.as_ref() { 
-                    Some(onr) => format!("{}/00 - {}", onr, a2a.rechtsinhaber),
-                    None => a2a.rechtsinhaber.clone(),
-                },
-                rangvermerk = match a2a.rangvermerk.as_ref() {
-                    Some(s) => format!("<span style='display:flex;align-items:center;'>
-                        <img src='{warnung}' style='width:12px;height:12px;'/>
-                        <p style='font-family:sans-serif;display:flex;flex-grow:1;margin-left:10px;max-width:190px;'>{rang}</p>
-                        </span>", 
-                        warnung = warnung_str,
-                        rang = s,
-                    ),
-                    None => String::new(),
-                },
-                belastete_flurstuecke = 
-                    a2a.belastete_flurstuecke.iter().map(|belastet| {
-                        use crate::digital::BvEintrag;
-                        match belastet {
-                            BvEintrag::Flurstueck(flst) => {
-                                format!("<span style='display:flex;align-items:center;max-width:200px;'>
-                                    <img src='{pfeil}' style='width:12px;height:12px;'/>
-                                    <p style='font-family:sans-serif;display:inline-block;margin-left:10px;'>BV-Nr. {bv_nr}: Fl. {flur}, Flst. {flurstueck}</p>
-                                    </span>", 
-                                    pfeil = pfeil_str,
-                                    flur = flst.flur,
-                                    flurstueck = flst.flurstueck,
-                                    bv_nr = flst.lfd_nr,
-                                ) 
-                            },
-                            BvEintrag::Recht(recht) => {
-                                format!("<span style='display:flex;align-items:center;max-width:200px;'>
-                                    <img src='{pfeil}' style='width:12px;height:12px;'/>
-                                    <p style='font-family:sans-serif;display:inline-block;margin-left:10px;'>BV-Nr. {bv_nr}: Grundstücksgl. Recht</p>
-                                    </span>", 
-                                    pfeil = pfeil_str,
-                                    bv_nr = recht.lfd_nr,
-                                ) 
-                            },
-                        }
-                    })
-                    .collect::<Vec<String>>()
-                    .join("\r\n"),
-                fehler = {
-                
-                    let mut fehler = a2a.fehler.clone();
-                    fehler.sort();
-                    fehler.dedup();
-                    
-                    fehler
-                    .iter()
-                    .map(|w| {
-                        format!("<span title='{alt_text}' style='display:flex;margin-top:5px;padding: 4px 8px; background:rgb(255,195,195);'>
-                                <img src='{fehler_icon}' style='width:12px;height:12px;'/>
-                                <p style='margin-left:10px;color:rgb(129,8,8);'>{text}</p>
-                                {opt_button}
-                            </span>", 
-                            fehler_icon = fehler_str,
-                            text = normalize_for_js(w.text.clone()),
-                            alt_text = match w.traceback.as_ref() {
-                                Some(s) => s.iter().map(|s| html_escape::encode_safe(&s).to_string()).collect::<Vec<_>>().join("&#013;"),
-                                None => String::new()
-                            },
-                            opt_button = match w.py_script.as_deref() {
-                                None => String::new(),
-                                Some(s) => {
-                                    let json = s.join("⠀")
-                                    .replace("\"", "⠁")
-                                    .replace("'", "⠂")
-                                    .replace('`', "⠇")
-                                    .replace("\\\"", "⠉")
-                                    .replace("\\", "⠊")
-                                    .replace(">", "⠍")
-                                    .replace("<", "⠎")
-                                    .replace(")", "⠏")
-                                    .replace("(", "⠑")
-                                    .replace("{", "⠟")
-                                    .replace("}", "⠠");
-                                    format!("<div style='display:flex;flex-grow:1;'></div><button class='__application-view-script' onmouseup='openScript(\"{json}\")' >Script ansehen</button>")
-                                },
-                            },
-                        )
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\r\n")
-                },
-                warnungen = {
-                
-                    let mut warnungen = a2a.warnungen.clone();
-                    warnungen.sort();
-                    warnungen.dedup();
-                    
-                    warnungen
-                    .iter()
-                    .filter(|w| if fuer_druck && w.as_str() == "Konnte keine Ordnungsnummer finden" { false } else { true })
-                    .map(|w| {
-                    format!("<span style='display:flex;margin-top:5px;padding: 4px 8px; background:rgb(255,255,167);'>
-                            <img src='{warnung_icon}' style='width:12px;height:12px;'/>
-                                <p style='display:inline-block;margin-left:10px;'>{text}</p>
-                            </span>", 
-                            warnung_icon = warnung_str,
-                            text = w,
-                        )
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\r\n")
+            </div>",
+                lfd_nr = if fuer_druck { format!("{} Bl. {} BV/{}",  gb_analysiert.titelblatt.grundbuch_von, gb_analysiert.titelblatt.blatt, ea.lfd_nr) } else { format!("{}", ea.lfd_nr) },
+                pfeil = pfeil_str,
+                grundstueck = format!(
+                    "{} Bl. {}, BV-Nr. {}{}",
+                    ea.grundstueck.grundbuch_von,
+                    ea.grundstueck.blatt,
+                    ea.grundstueck.bv_nr.text(),
+                    match ea.abt2_lfd_nr.or(ea.grundstueck.abt2_lfd_nr) {
+                        Some(s) => format!(" (A2/{s})"),
+                        None => String::new(),
+                    },
+                ),
+                dauer = ea.dauer,
+                zustimmungsvorbehalte = if ea.zustimmungsvorbehalte.is_empty() {
+                    String::new()
+                } else {
+                    format!(
+                        "Zustimmung zur {} erforderlich",
+                        ea.zustimmungsvorbehalte.iter().map(|z| z.to_string()).collect::<Vec<_>>().join(" und ")
+                    )
                 },
+                fehler = ea.fehler.iter().map(|f| {
+                    render_grundbuch_befund(&f.code, f.quittierung.as_ref(), true, &icons, "margin-top:5px", fuer_druck)
+                }).collect::<Vec<_>>().join("\r\n"),
+                warnungen = ea.warnungen.iter().map(|w| {
+                    render_grundbuch_befund(&w.code, w.quittierung.as_ref(), false, &icons, "margin-top:5px", fuer_druck)
+                }).collect::<Vec<_>>().join("\r\n"),
             )
         }).collect::<Vec<String>>().join("\r\n"),
-    
-        a3_analyse = gb_analysiert.abt3.iter()
-        .filter(|a3a| if nur_fehlerhafte_rechte { !a3a.fehler.is_empty() } else { true })
-        .map(|a3a| {
-                    
-            let waehrung_str = a3a.betrag.waehrung.to_string();
-            
-            format!("
-            <div class='__application-abt2-analysiert' style='margin:5px;padding:10px;border:1px solid #efefef;page-break-inside:avoid;'>
-                <h5 style='font-family:sans-serif;margin: 0px;margin-bottom: 10px;'>{lfd_nr}&nbsp;{schuldenart}&nbsp;{betrag}</h5>
-                    <div style='font-family:sans-serif;display:flex;flex-direction:row;'>
-                        <div style='min-width:{max_width};max-width:{max_width};margin-right:20px;'>
-                            <p style='font-family:sans-serif;'>{text_kurz}</p>
-                            {text_original}
-                        </div>
-                        <div style='flex-grow:1;'>
-                            <p style='font-family:sans-serif;font-style:italic'>{rechtsinhaber}</p>
-                            <div>{belastete_flurstuecke}</div>
-                        </div>
-                    </div>
+
+        a1_analyse = gb_analysiert.abt1.iter()
+        .filter(|a1a| if nur_fehlerhafte_rechte { a1a.fehler.iter().any(|f| f.quittierung.is_none()) } else { true })
+        .map(|a1a| render_abt1_analysiert(gb_analysiert, a1a, &icons, fuer_druck))
+        .collect::<Vec<String>>().join("\r\n"),
+
+        loeschungen_analyse = if fuer_druck { String::new() } else {
+            gb_analysiert.loeschungen.eintraege.iter()
+            .filter(|la| if nur_fehlerhafte_rechte { la.fehler.iter().any(|f| f.quittierung.is_none()) } else { !la.fehler.is_empty() || !la.warnungen.is_empty() })
+            .map(|la| {
+                format!("
+                <div class='__application-abt2-analysiert' style='margin:5px;padding:10px;border:1px solid #efefef;page-break-inside:avoid;'>
+                    <h5 style='font-family:sans-serif;font-size:14px;margin: 0px;margin-bottom: 10px;'>A{abteilung}/{lfd_nr}&nbsp;{art}</h5>
+                    <p style='font-family:sans-serif;'>{text}</p>
                     <div class='__application-warnungen-und-fehler'>
                         {fehler}
                         {warnungen}
                     </div>
                 </div>",
-                lfd_nr = if fuer_druck { format!("{} Bl. {} A3/{}",  gb_analysiert.titelblatt.grundbuch_von, gb_analysiert.titelblatt.blatt, a3a.lfd_nr) } else { format!("{}", a3a.lfd_nr) },
-                text_original = if fuer_druck { format!("<p style='margin-top:10px;font-family:sans-serif;'>{}</p>", a3a.text_original) } else { String::new() }, 
-                max_width = if fuer_druck { "600px" } else { "380px" },
-                text_kurz = a3a.text_kurz,
-                betrag = format!("{} {}", crate::kurztext::formatiere_betrag(&a3a.betrag), waehrung_str),
-                schuldenart = format!("{:?}", a3a.schuldenart).to_uppercase(),
-                rechtsinhaber = match a3a.nebenbeteiligter.ordnungsnummer.as_ref() { 
-                    Some(onr) => format!("{}/00 - {}", onr, a3a.rechtsinhaber),
-                    None => a3a.rechtsinhaber.clone(),
-                },
-                belastete_flurstuecke = 
-                    a3a.belastete_flurstuecke.iter().map(|belastet| {
-                        use crate::digital::BvEintrag;
-                        match belastet {
-                            BvEintrag::Flurstueck(flst) => {
-                                format!("<span style='display:flex;align-items:center;'>
-                                    <img src='{pfeil}' style='width:12px;height:12px;'/>
-                                    <p style='font-family:sans-serif;display:inline-block;margin-left:10px;'>BV-Nr. {bv_nr}: Fl. {flur}, Flst. {flurstueck}</p>
-                                    </span>", 
-                                    pfeil = pfeil_str,
-                                    flur = flst.flur,
-                                    flurstueck = flst.flurstueck,
-                                    bv_nr = flst.lfd_nr,
-                                ) 
-                            },
-                            BvEintrag::Recht(recht) => {
-                                format!("<span style='display:flex;align-items:center;'>
-                                    <img src='{pfeil}' style='width:12px;height:12px;'/>
-                                    <p style='font-family:sans-serif;display:inline-block;margin-left:10px;'>BV-Nr. {bv_nr}: Grundstücksgl. Recht</p>
-                                    </span>", 
-                                    pfeil = pfeil_str,
-                                    bv_nr = recht.lfd_nr,
-                                ) 
-                            },
-                        }
-                    })
-                    .collect::<Vec<String>>()
-                    .join("\r\n"),
-                fehler = {
-                    let mut fehler = a3a.fehler.clone();
-                    fehler.sort();
-                    fehler.dedup();
-                    
-                    fehler.iter().map(|w| {
-                        format!("
-                            <span title='{alt_text}' style='display:flex;margin-top:5px;padding: 4px 8px; background:rgb(255,195,195);'>
-                                <img src='{fehler_icon}' style='width:12px;height:12px;'/>
-                                <p style='margin-left:10px;color:rgb(129,8,8);'>{text}</p>
-                                {opt_button}
-                            </span>", 
-                            fehler_icon = fehler_str,
-                            text = normalize_for_js(w.text.clone()),
-                            alt_text = match w.traceback.as_ref() {
-                                Some(s) => s.iter().map(|s| html_escape::encode_safe(&s).to_string()).collect::<Vec<_>>().join("&#013;"),
-                                None => String::new()
-                            },
-                            opt_button = match w.py_script.as_deref() {
-                                None => String::new(),
-                                Some(s) => {
-                                    let json = s.join("⠀")
-                                    .replace("\"", "⠁")
-                                    .replace("'", "⠂")
-                                    .replace('`', "⠇")
-                                    .replace("\\\"", "⠉")
-                                    .replace("\\", "⠊")
-                                    .replace(">", "⠍")
-                                    .replace("<", "⠎")
-                                    .replace(")", "⠏")
-                                    .replace("(", "⠑")
-                                    .replace("{", "⠟")
-                                    .replace("}", "⠠");
-                                    format!("<div style='display:flex;flex-grow:1;'></div><button class='__application-view-script' onmouseup='openScript(\"{json}\")' >Script ansehen</button>")
-                                },
-                            },
-                        )
-                    }).collect::<Vec<_>>().join("\r\n")
+                    abteilung = la.abteilung,
+                    lfd_nr = la.lfd_nr.iter().map(|s| s.to_string()).collect::<Vec<_>>().join(", "),
+                    art = if la.teilweise { "TEILLÖSCHUNG" } else { "LÖSCHUNG" },
+                    text = normalize_for_js(la.text.clone()),
+                    fehler = la.fehler.iter().map(|f| {
+                        render_grundbuch_befund(&f.code, f.quittierung.as_ref(), true, &icons, "margin-top:5px", fuer_druck)
+                    }).collect::<Vec<_>>().join("\r\n"),
+                    warnungen = la.warnungen.iter().map(|w| {
+                        render_grundbuch_befund(&w.code, w.quittierung.as_ref(), false, &icons, "margin-top:5px", fuer_druck)
+                    }).collect::<Vec<_>>().join("\r\n"),
+                )
+            })
+            .chain(gb_analysiert.loeschungen.warnungen.iter().filter(|_| !nur_fehlerhafte_rechte).map(|w| {
+                render_grundbuch_befund(&w.code, w.quittierung.as_ref(), false, &icons, "margin:5px", fuer_druck)
+            }))
+            .collect::<Vec<String>>().join("\r\n")
+        },
+
+        rang_analyse = if fuer_druck { String::new() } else {
+            gb_analysiert.rang.fehler.iter()
+            .filter(|f| !nur_fehlerhafte_rechte || f.quittierung.is_none())
+            .map(|f| render_grundbuch_befund(&f.code, f.quittierung.as_ref(), true, &icons, "margin:5px", fuer_druck))
+            .chain(gb_analysiert.rang.warnungen.iter().filter(|_| !nur_fehlerhafte_rechte).map(|w| {
+                render_grundbuch_befund(&w.code, w.quittierung.as_ref(), false, &icons, "margin:5px", fuer_druck)
+            }))
+            .collect::<Vec<String>>().join("\r\n")
+        },
+
+        alkis_analyse = if fuer_druck || nur_fehlerhafte_rechte { String::new() } else {
+            gb_analysiert.alkis.warnungen.iter().map(|w| {
+                render_grundbuch_befund(&w.code, w.quittierung.as_ref(), false, &icons, "margin:5px", fuer_druck)
+            })
+            .collect::<Vec<String>>().join("\r\n")
+        },
+        blaetter_analyse = if fuer_druck || nur_fehlerhafte_rechte { String::new() } else {
+            gb_analysiert.blaetter.iter().map(|w| {
+                render_grundbuch_befund(&w.code, w.quittierung.as_ref(), false, &icons, "margin:5px", fuer_druck)
+            })
+            .collect::<Vec<String>>().join("\r\n")
+        },
+
+        a2_analyse = gb_analysiert.abt2.iter()
+        .filter(|a2a| if nur_fehlerhafte_rechte { a2a.fehler.iter().any(|f| f.quittierung.is_none()) } else { true })
+        .map(|a2a| render_abt2_analysiert(gb_analysiert, a2a, &icons, fuer_druck))
+        .collect::<Vec<String>>().join("\r\n"),
+    
+        a3_analyse = gb_analysiert.abt3.iter()
+        .filter(|a3a| if nur_fehlerhafte_rechte { a3a.fehler.iter().any(|f| f.quittierung.is_none()) } else { true })
+        .map(|a3a| render_abt3_analysiert(gb_analysiert, a3a, &icons, fuer_druck))
+        .collect::<Vec<String>>().join("\r\n"),
+    ))
+}
+
+fn render_abt1_analysiert(
+    gb_analysiert: &GrundbuchAnalysiert,
+    a1a: &Abt1Analysiert,
+    icons: &AnalyseIcons,
+    fuer_druck: bool,
+) -> String {
+    let AnalyseIcons { pfeil_str, warnung_str, fehler_str } = icons;
+    format!("
+    <div id='__application-analyse-a1-{lfd_nr_id}' class='__application-abt2-analysiert' style='margin:5px;padding:10px;border:1px solid #efefef;page-break-inside:avoid;'>
+        <div style='display:flex;align-items:center;margin-bottom: 10px;'>
+            <h5 style='font-family:sans-serif;font-size:14px;margin: 0px;'>{lfd_nr}&nbsp;EIGENTÜMER</h5>
+            {testfall}
+        </div>
+        <div style='display:flex;flex-direction:row;'>
+            <div style='flex-grow:1;'>
+                {eigentuemer}
+            </div>
+            <div style='min-width:200px;max-width:200px;'>
+                {grundlagen}
+            </div>
+        </div>
+        <div class='__application-warnungen-und-fehler'>
+            {fehler}
+            {warnungen}
+        </div>
+    </div>",
+        lfd_nr_id = a1a.lfd_nr,
+        testfall = render_testfall_button(1, a1a.lfd_nr, fuer_druck),
+        lfd_nr = if fuer_druck { format!("{} Bl. {} A1/{}",  gb_analysiert.titelblatt.grundbuch_von, gb_analysiert.titelblatt.blatt, a1a.lfd_nr) } else { format!("{}", a1a.lfd_nr) },
+        eigentuemer = a1a.eigentuemer.iter().map(|e| {
+            format!("<span style='display:flex;align-items:center;'>
+                <img src='{pfeil}' style='width:12px;height:12px;'/>
+                <p style='font-family:sans-serif;display:inline-block;margin-left:10px;'>{art}: {name}{anteil}{gemeinschaft}</p>
+                </span>",
+                pfeil = pfeil_str,
+                art = format!("{:?}", e.art).to_uppercase(),
+                name = html_escape::encode_safe(&e.to_string()),
+                anteil = match e.anteil.as_ref() {
+                    Some(a) => format!(" - zu {}", html_escape::encode_safe(a)),
+                    None => String::new(),
                 },
-                warnungen = {
-                    let mut warnungen = a3a.warnungen.clone();
-                    warnungen.sort();
-                    warnungen.dedup();
-                    
-                    warnungen
-                    .iter()
-                    .filter(|w| if fuer_druck && w.as_str() == "Konnte keine Ordnungsnummer finden" { false } else { true })
-                    .map(|w| {
-                    format!("<span style='display:flex;margin-top:5px;padding: 4px 8px; background:rgb(255,255,167);'>
-                            <img src='{warnung_icon}' style='width:12px;height:12px;'/>
-                                <p style='display:inline-block;margin-left:10px;'>{text}</p>
-                            </span>", 
-                            warnung_icon = warnung_str,
-                            text = w,
-                        )
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\r\n")
+                gemeinschaft = match e.gemeinschaft.to_string() {
+                    "" => String::new(),
+                    g => format!(" - {g}"),
                 },
             )
-        }).collect::<Vec<String>>().join("\r\n"),
+        }).collect::<Vec<_>>().join("\r\n"),
+        grundlagen = a1a.grundlagen.iter().map(|g| {
+            format!("<p style='font-family:sans-serif;font-style:italic;font-size:11px;color:#555;'>{}</p>", html_escape::encode_safe(&g.text))
+        }).collect::<Vec<_>>().join("\r\n"),
+        fehler = a1a.fehler.iter().map(|w| {
+            format!("<span style='display:flex;margin-top:5px;padding: 4px 8px; background:{hintergrund};'>
+                    <img src='{fehler_icon}' style='width:12px;height:12px;'/>
+                    <p style='margin-left:10px;color:rgb(129,8,8);'>{text}</p>
+                    {quittierung}
+                </span>", 
+                hintergrund = if w.quittierung.is_some() { "rgb(230,230,230)" } else { "rgb(255,195,195)" },
+                quittierung = render_quittierung(1, a1a.lfd_nr, &w.code, w.quittierung.as_ref(), fuer_druck || !a1a.fertig_analysiert),
+                fehler_icon = fehler_str,
+                text = normalize_for_js(w.text()),
+            )
+        }).collect::<Vec<_>>().join("\r\n"),
+        warnungen = a1a.warnungen.iter().map(|w| {
+            format!("<span style='display:flex;margin-top:5px;padding: 4px 8px; background:{hintergrund};'>
+                    <img src='{warnung_icon}' style='width:12px;height:12px;'/>
+                    <p style='display:inline-block;margin-left:10px;'>{text}</p>
+                    {quittierung}
+                </span>", 
+                hintergrund = if w.quittierung.is_some() { "rgb(230,230,230)" } else { "rgb(255,255,167)" },
+                quittierung = render_quittierung(1, a1a.lfd_nr, &w.code, w.quittierung.as_ref(), fuer_druck || !a1a.fertig_analysiert),
+                warnung_icon = warnung_str,
+                text = w.text(),
+            )
+        }).collect::<Vec<_>>().join("\r\n"),
+    )
+}
+
+fn render_abt2_analysiert(
+    gb_analysiert: &GrundbuchAnalysiert,
+    a2a: &Abt2Analysiert,
+    icons: &AnalyseIcons,
+    fuer_druck: bool,
+) -> String {
+    let AnalyseIcons { pfeil_str, warnung_str, fehler_str } = icons;
+    format!("
+    <div id='__application-analyse-a2-{lfd_nr_id}' class='__application-abt2-analysiert' style='margin:5px;padding:10px;border:1px solid #efefef;page-break-inside:avoid;'>
+        <div style='display:flex;align-items:center;margin-bottom: 10px;'>
+            <h5 style='font-family:sans-serif;font-size:14px;margin: 0px;'>{lfd_nr}&nbsp;{rechteart}</h5>
+            {testfall}
+        </div>
+        <div style='display:flex;flex-direction:row;'>
+            <div style='min-width:{max_width};max-width:{max_width};margin-right:20px;'>
+                <p style='font-family:sans-serif;'>{text_kurz}</p>
+                {text_original}
+            </div>
+            <div style='flex-grow:1;'>
+                <p style='font-family:sans-serif;font-style:italic;display:flex;flex-grow:1;max-width:200px;'>{rechtsinhaber}</p>
+                {rangvermerk}
+                <div>{belastete_flurstuecke}</div>
+                <div>{fortfuehrungen}</div>
+            </div>
+        </div>
+        <div class='__application-warnungen-und-fehler'>
+            {fehler}
+            {warnungen}
+        </div>
+        </div>",
+        lfd_nr_id = a2a.lfd_nr,
+        testfall = render_testfall_button(2, a2a.lfd_nr, fuer_druck),
+        lfd_nr = if fuer_druck { format!("{} Bl. {} A2/{}",  gb_analysiert.titelblatt.grundbuch_von, gb_analysiert.titelblatt.blatt, a2a.lfd_nr) } else { format!("{}", a2a.lfd_nr) },
+        text_original = if fuer_druck { format!("<p style='margin-top:10px;font-family:sans-serif;'>{}</p>", a2a.text_original) } else { String::new() }, 
+        max_width = if fuer_druck { "600px" } else { "380px" },
+        text_kurz = a2a.text_kurz,
+        rechteart = format!("{:?}", a2a.rechteart).to_uppercase(),
+        rechtsinhaber = match a2a.nebenbeteiligter.ordnungsnummer.as_ref() { 
+            Some(onr) => format!("{}/00 - {}", onr, a2a.rechtsinhaber),
+            None => a2a.rechtsinhaber.clone(),
+        },
+        rangvermerk = match a2a.rangvermerk.as_ref() {
+            Some(s) => format!("<span style='display:flex;align-items:center;'>
+                <img src='{warnung}' style='width:12px;height:12px;'/>
+                <p style='font-family:sans-serif;display:flex;flex-grow:1;margin-left:10px;max-width:190px;'>{rang}</p>
+                </span>", 
+                warnung = warnung_str,
+                rang = s,
+            ),
+            None => String::new(),
+        },
+        fortfuehrungen = a2a.fortfuehrungen.iter().map(|f| {
+            format!("<p title='{text}' style='font-family:sans-serif;font-size:11px;color:#555;max-width:200px;'>{beschreibung}</p>",
+                text = html_escape::encode_safe(&f.text),
+                beschreibung = f.beschreibung(),
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\r\n"),
+        belastete_flurstuecke = 
+            a2a.belastete_flurstuecke.iter().map(|belastet| {
+                use crate::digital::BvEintrag;
+                match belastet {
+                    BvEintrag::Flurstueck(flst) => {
+                        format!("<span style='display:flex;align-items:center;max-width:200px;'>
+                            <img src='{pfeil}' style='width:12px;height:12px;'/>
+                            <p style='font-family:sans-serif;display:inline-block;margin-left:10px;'>BV-Nr. {bv_nr}: Fl. {flur}, Flst. {flurstueck}</p>
+                            </span>", 
+                            pfeil = pfeil_str,
+                            flur = flst.flur,
+                            flurstueck = flst.flurstueck,
+                            bv_nr = flst.lfd_nr,
+                        ) 
+                    },
+                    BvEintrag::Recht(recht) => {
+                        format!("<span style='display:flex;align-items:center;max-width:200px;'>
+                            <img src='{pfeil}' style='width:12px;height:12px;'/>
+                            <p style='font-family:sans-serif;display:inline-block;margin-left:10px;'>BV-Nr. {bv_nr}: Grundstücksgl. Recht</p>
+                            </span>", 
+                            pfeil = pfeil_str,
+                            bv_nr = recht.lfd_nr,
+                        ) 
+                    },
+                    BvEintrag::Erbbaurecht(erbbaurecht) => {
+                        format!("<span style='display:flex;align-items:center;max-width:200px;'>
+                            <img src='{pfeil}' style='width:12px;height:12px;'/>
+                            <p style='font-family:sans-serif;display:inline-block;margin-left:10px;'>BV-Nr. {bv_nr}: Erbbaurecht</p>
+                            </span>", 
+                            pfeil = pfeil_str,
+                            bv_nr = erbbaurecht.lfd_nr,
+                        ) 
+                    },
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("\r\n"),
+        fehler = {
+        
+            let mut fehler = a2a.fehler.clone();
+            fehler.sort();
+            fehler.dedup();
+            
+            fehler
+            .iter()
+            .map(|w| {
+                format!("<span title='{alt_text}' style='display:flex;margin-top:5px;padding: 4px 8px; background:{hintergrund};'>
+                        <img src='{fehler_icon}' style='width:12px;height:12px;'/>
+                        <p style='margin-left:10px;color:rgb(129,8,8);'>{text}</p>
+                        {opt_button}
+                        {quittierung}
+                    </span>", 
+                    hintergrund = if w.quittierung.is_some() { "rgb(230,230,230)" } else { "rgb(255,195,195)" },
+                    quittierung = render_quittierung(2, a2a.lfd_nr, &w.code, w.quittierung.as_ref(), fuer_druck || !a2a.fertig_analysiert),
+                    fehler_icon = fehler_str,
+                    text = normalize_for_js(w.text()),
+                    alt_text = match w.traceback.as_ref() {
+                        Some(s) => s.iter().map(|s| html_escape::encode_safe(&s).to_string()).collect::<Vec<_>>().join("&#013;"),
+                        None => String::new()
+                    },
+                    opt_button = match w.py_script.as_deref() {
+                        None => String::new(),
+                        Some(s) => {
+                            let json = s.join("⠀")
+                            .replace("\"", "⠁")
+                            .replace("'", "⠂")
+                            .replace('`', "⠇")
+                            .replace("\\\"", "⠉")
+                            .replace("\\", "⠊")
+                            .replace(">", "⠍")
+                            .replace("<", "⠎")
+                            .replace(")", "⠏")
+                            .replace("(", "⠑")
+                            .replace("{", "⠟")
+                            .replace("}", "⠠");
+                            format!("<div style='display:flex;flex-grow:1;'></div><button class='__application-view-script' onmouseup='openScript(\"{json}\")' >Script ansehen</button>")
+                        },
+                    },
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\r\n")
+        },
+        warnungen = {
+        
+            let mut warnungen = a2a.warnungen.clone();
+            warnungen.sort();
+            warnungen.dedup();
+            
+            warnungen
+            .iter()
+            .filter(|w| !(fuer_druck && w.code == BefundCode::KeineOrdnungsnummer))
+            .map(|w| {
+            format!("<span style='display:flex;margin-top:5px;padding: 4px 8px; background:{hintergrund};'>
+                    <img src='{warnung_icon}' style='width:12px;height:12px;'/>
+                        <p style='display:inline-block;margin-left:10px;'>{text}</p>
+                        {quittierung}
+                    </span>", 
+                    hintergrund = if w.quittierung.is_some() { "rgb(230,230,230)" } else { "rgb(255,255,167)" },
+                    quittierung = render_quittierung(2, a2a.lfd_nr, &w.code, w.quittierung.as_ref(), fuer_druck || !a2a.fertig_analysiert),
+                    warnung_icon = warnung_str,
+                    text = w.text(),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\r\n")
+        },
+    )
+}
+
+fn render_abt3_analysiert(
+    gb_analysiert: &GrundbuchAnalysiert,
+    a3a: &Abt3Analysiert,
+    icons: &AnalyseIcons,
+    fuer_druck: bool,
+) -> String {
+    let AnalyseIcons { pfeil_str, warnung_str, fehler_str } = icons;
+    let waehrung_str = a3a.betrag.waehrung.to_string();
+
+    format!("
+    <div id='__application-analyse-a3-{lfd_nr_id}' class='__application-abt2-analysiert' style='margin:5px;padding:10px;border:1px solid #efefef;page-break-inside:avoid;'>
+        <div style='display:flex;align-items:center;margin-bottom: 10px;'>
+            <h5 style='font-family:sans-serif;margin: 0px;'>{lfd_nr}&nbsp;{schuldenart}&nbsp;{betrag}</h5>
+            {testfall}
+        </div>
+            <div style='font-family:sans-serif;display:flex;flex-direction:row;'>
+                <div style='min-width:{max_width};max-width:{max_width};margin-right:20px;'>
+                    <p style='font-family:sans-serif;'>{text_kurz}</p>
+                    {text_original}
+                </div>
+                <div style='flex-grow:1;'>
+                    <p style='font-family:sans-serif;font-style:italic'>{rechtsinhaber}</p>
+                    <div>{belastete_flurstuecke}</div>
+                    <div>{fortfuehrungen}</div>
+                </div>
+            </div>
+            <div class='__application-warnungen-und-fehler'>
+                {fehler}
+                {warnungen}
+            </div>
+        </div>",
+        lfd_nr_id = a3a.lfd_nr,
+        testfall = render_testfall_button(3, a3a.lfd_nr, fuer_druck),
+        lfd_nr = if fuer_druck { format!("{} Bl. {} A3/{}",  gb_analysiert.titelblatt.grundbuch_von, gb_analysiert.titelblatt.blatt, a3a.lfd_nr) } else { format!("{}", a3a.lfd_nr) },
+        text_original = if fuer_druck { format!("<p style='margin-top:10px;font-family:sans-serif;'>{}</p>", a3a.text_original) } else { String::new() }, 
+        max_width = if fuer_druck { "600px" } else { "380px" },
+        text_kurz = a3a.text_kurz,
+        betrag = format!("{} {}", crate::kurztext::formatiere_betrag(&a3a.betrag), waehrung_str),
+        schuldenart = format!("{:?}", a3a.schuldenart).to_uppercase(),
+        rechtsinhaber = match a3a.nebenbeteiligter.ordnungsnummer.as_ref() { 
+            Some(onr) => format!("{}/00 - {}", onr, a3a.rechtsinhaber),
+            None => a3a.rechtsinhaber.clone(),
+        },
+        fortfuehrungen = a3a.fortfuehrungen.iter().map(|f| {
+            format!("<p title='{text}' style='font-family:sans-serif;font-size:11px;color:#555;max-width:200px;'>{beschreibung}</p>",
+                text = html_escape::encode_safe(&f.text),
+                beschreibung = f.beschreibung(),
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\r\n"),
+        belastete_flurstuecke = 
+            a3a.belastete_flurstuecke.iter().map(|belastet| {
+                use crate::digital::BvEintrag;
+                match belastet {
+                    BvEintrag::Flurstueck(flst) => {
+                        format!("<span style='display:flex;align-items:center;'>
+                            <img src='{pfeil}' style='width:12px;height:12px;'/>
+                            <p style='font-family:sans-serif;display:inline-block;margin-left:10px;'>BV-Nr. {bv_nr}: Fl. {flur}, Flst. {flurstueck}</p>
+                            </span>", 
+                            pfeil = pfeil_str,
+                            flur = flst.flur,
+                            flurstueck = flst.flurstueck,
+                            bv_nr = flst.lfd_nr,
+                        ) 
+                    },
+                    BvEintrag::Recht(recht) => {
+                        format!("<span style='display:flex;align-items:center;'>
+                            <img src='{pfeil}' style='width:12px;height:12px;'/>
+                            <p style='font-family:sans-serif;display:inline-block;margin-left:10px;'>BV-Nr. {bv_nr}: Grundstücksgl. Recht</p>
+                            </span>", 
+                            pfeil = pfeil_str,
+                            bv_nr = recht.lfd_nr,
+                        ) 
+                    },
+                    BvEintrag::Erbbaurecht(erbbaurecht) => {
+                        format!("<span style='display:flex;align-items:center;'>
+                            <img src='{pfeil}' style='width:12px;height:12px;'/>
+                            <p style='font-family:sans-serif;display:inline-block;margin-left:10px;'>BV-Nr. {bv_nr}: Erbbaurecht</p>
+                            </span>", 
+                            pfeil = pfeil_str,
+                            bv_nr = erbbaurecht.lfd_nr,
+                        ) 
+                    },
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("\r\n"),
+        fehler = {
+            let mut fehler = a3a.fehler.clone();
+            fehler.sort();
+            fehler.dedup();
+            
+            fehler.iter().map(|w| {
+                format!("
+                    <span title='{alt_text}' style='display:flex;margin-top:5px;padding: 4px 8px; background:{hintergrund};'>
+                        <img src='{fehler_icon}' style='width:12px;height:12px;'/>
+                        <p style='margin-left:10px;color:rgb(129,8,8);'>{text}</p>
+                        {opt_button}
+                        {quittierung}
+                    </span>", 
+                    hintergrund = if w.quittierung.is_some() { "rgb(230,230,230)" } else { "rgb(255,195,195)" },
+                    quittierung = render_quittierung(3, a3a.lfd_nr, &w.code, w.quittierung.as_ref(), fuer_druck || !a3a.fertig_analysiert),
+                    fehler_icon = fehler_str,
+                    text = normalize_for_js(w.text()),
+                    alt_text = match w.traceback.as_ref() {
+                        Some(s) => s.iter().map(|s| html_escape::encode_safe(&s).to_string()).collect::<Vec<_>>().join("&#013;"),
+                        None => String::new()
+                    },
+                    opt_button = match w.py_script.as_deref() {
+                        None => String::new(),
+                        Some(s) => {
+                            let json = s.join("⠀")
+                            .replace("\"", "⠁")
+                            .replace("'", "⠂")
+                            .replace('`', "⠇")
+                            .replace("\\\"", "⠉")
+                            .replace("\\", "⠊")
+                            .replace(">", "⠍")
+                            .replace("<", "⠎")
+                            .replace(")", "⠏")
+                            .replace("(", "⠑")
+                            .replace("{", "⠟")
+                            .replace("}", "⠠");
+                            format!("<div style='display:flex;flex-grow:1;'></div><button class='__application-view-script' onmouseup='openScript(\"{json}\")' >Script ansehen</button>")
+                        },
+                    },
+                )
+            }).collect::<Vec<_>>().join("\r\n")
+        },
+        warnungen = {
+            let mut warnungen = a3a.warnungen.clone();
+            warnungen.sort();
+            warnungen.dedup();
+            
+            warnungen
+            .iter()
+            .filter(|w| !(fuer_druck && w.code == BefundCode::KeineOrdnungsnummer))
+            .map(|w| {
+            format!("<span style='display:flex;margin-top:5px;padding: 4px 8px; background:{hintergrund};'>
+                    <img src='{warnung_icon}' style='width:12px;height:12px;'/>
+                        <p style='display:inline-block;margin-left:10px;'>{text}</p>
+                        {quittierung}
+                    </span>", 
+                    hintergrund = if w.quittierung.is_some() { "rgb(230,230,230)" } else { "rgb(255,255,167)" },
+                    quittierung = render_quittierung(3, a3a.lfd_nr, &w.code, w.quittierung.as_ref(), fuer_druck || !a3a.fertig_analysiert),
+                    warnung_icon = warnung_str,
+                    text = w.text(),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\r\n")
+        },
+    )
+}
+
+// Einzelner Eintrag der Analyse, damit nach Abschluss der Analyse eines Eintrags
+// nicht die gesamte Analyse neu gerendert werden muss (siehe replaceAnalyseEintrag)
+pub fn render_analyse_eintrag(
+    gb_analysiert: &GrundbuchAnalysiert,
+    abteilung: usize,
+    lfd_nr: usize,
+) -> Option<String> {
+    let icons = AnalyseIcons::neu();
+    let html = match abteilung {
+        1 => render_abt1_analysiert(gb_analysiert, gb_analysiert.abt1.iter().find(|a| a.lfd_nr == lfd_nr)?, &icons, false),
+        2 => render_abt2_analysiert(gb_analysiert, gb_analysiert.abt2.iter().find(|a| a.lfd_nr == lfd_nr)?, &icons, false),
+        3 => render_abt3_analysiert(gb_analysiert, gb_analysiert.abt3.iter().find(|a| a.lfd_nr == lfd_nr)?, &icons, false),
+        _ => return None,
+    };
+    Some(normalize_for_js(html))
+}
+
+// Anzahl der Versionen, die zur Auswahl angezeigt werden
+const HISTORIE_MAX_VERSIONEN: usize = 500;
+
+// Anzahl unveränderter Zeilen, die um eine Änderung herum angezeigt werden
+const HISTORIE_KONTEXT_ZEILEN: usize = 3;
+
+// Geänderte Skripte / reguläre Ausdrücke zwischen zwei Versionen der
+// Konfiguration, lange unveränderte Abschnitte werden ausgelassen
+pub fn render_historie_diff(alt: &str, neu: &str) -> String {
+    let fehler = |e: String| normalize_for_js(format!("<p style='color:rgb(129,8,8);'>{}</p>", html_escape::encode_safe(&e)));
+    let alt = match crate::historie::version_laden(alt) {
+        Ok(o) => o,
+        Err(e) => return fehler(e),
+    };
+    let neu = match crate::historie::version_laden(neu) {
+        Ok(o) => o,
+        Err(e) => return fehler(e),
+    };
+
+    let abschnitte = crate::historie::konfiguration_diff(&alt, &neu);
+    if abschnitte.is_empty() {
+        return normalize_for_js("<p style='font-size:14px;'>Keine Änderungen an Skripten oder regulären Ausdrücken.</p>".to_string());
+    }
+
+    let text = |s: &str| html_escape::encode_safe(s).replace(' ', "&nbsp;").replace('\\', "&bsol;");
+
+    let abschnitte = abschnitte.iter().map(|a| {
+        // Zeilen, die angezeigt werden: Änderungen und ihr Kontext
+        let anzeigen = (0..a.diff.len()).map(|i| {
+            let von = i.saturating_sub(HISTORIE_KONTEXT_ZEILEN);
+            let bis = (i + HISTORIE_KONTEXT_ZEILEN + 1).min(a.diff.len());
+            a.diff[von..bis].iter().any(|z| !matches!(z, DiffZeile::Gleich(_)))
+        }).collect::<Vec<_>>();
+
+        let mut zeilen = Vec::new();
+        for (i, z) in a.diff.iter().enumerate() {
+            if !anzeigen[i] {
+                if i == 0 || anzeigen[i - 1] {
+                    zeilen.push("<span class='snip'><p>&nbsp;</p><p>----</p></span>".to_string());
+                }
+                continue;
+            }
+            zeilen.push(match z {
+                DiffZeile::Gleich(l) => format!("<span class='equal'><p>&nbsp;</p><p>{}</p></span>", text(l)),
+                DiffZeile::Entfernt(l) => format!("<span class='remove'><p>-</p><p>{}</p></span>", text(l)),
+                DiffZeile::Hinzugefuegt(l) => format!("<span class='insert'><p>+</p><p>{}</p></span>", text(l)),
+            });
+        }
+
+        format!("<h3 style='font-size:14px;margin:10px 0px 5px 0px;'>{titel}</h3>{zeilen}",
+            titel = html_escape::encode_safe(&a.titel),
+            zeilen = zeilen.join("\r\n"),
+        )
+    }).collect::<Vec<_>>().join("\r\n");
+
+    normalize_for_js(abschnitte)
+}
+
+// Legt aus dem Originaltext des Eintrags Testfälle für die Skripte der Abteilung an
+fn render_testfall_button(abteilung: usize, lfd_nr: usize, fuer_druck: bool) -> String {
+    if fuer_druck {
+        return String::new();
+    }
+    format!("<div style='display:flex;flex-grow:1;'></div><button class='__application-view-script' data-abteilung='{abteilung}' data-lfdNr='{lfd_nr}' onmouseup='testfallAusEintrag(event)'>Als Testfall</button>")
+}
+
+// Ziel für "Als Testfall speichern", in der RegEx-Ansicht wird die
+// Regex-ID erst im Browser aus dem Eingabefeld gelesen
+fn testfall_speichern_ziel(ansicht: &ConfigurationView) -> Option<String> {
+    match ansicht {
+        ConfigurationView::RegEx => Some("regex".to_string()),
+        // Abteilung wird erst im Browser aus der Auswahl gelesen
+        ConfigurationView::PruefeEintrag => Some("pruefe-eintrag".to_string()),
+        _ => TestZiel::fuer_ansicht(ansicht).map(|z| z.id()),
+    }
+}
+
+// Testfälle der Konfigurationsansicht, bei fehlgeschlagenen Testfällen mit
+// zeilenweisem Vergleich von erwarteter (-) und tatsächlicher (+) Ausgabe,
+// laeuft solange die Testfälle im Hintergrund ausgeführt werden
+pub fn render_testfaelle(ansicht: &ConfigurationView, ergebnisse: &[TestErgebnis], laeuft: bool) -> String {
+    let text = |s: &str| html_escape::encode_safe(s).replace('\\', "&bsol;");
+    let bestanden = ergebnisse.iter().filter(|e| e.bestanden()).count();
+    let ausgefuehrt = ergebnisse.iter().all(|e| e.ausgefuehrt());
+
+    let status = if laeuft {
+        "werden ausgeführt...".to_string()
+    } else if ausgefuehrt {
+        format!("{bestanden} / {gesamt} bestanden", gesamt = ergebnisse.len())
+    } else {
+        "nicht ausgeführt".to_string()
+    };
+
+    let ausfuehren = if ergebnisse.is_empty() || laeuft {
+        String::new()
+    } else {
+        "<button class='btn btn_neu' style='margin-right:5px;' onclick='testfaelleAusfuehren(event)'>Testfälle ausführen</button>".to_string()
+    };
+
+    let speichern = match testfall_speichern_ziel(ansicht) {
+        Some(ziel) => format!("<button class='btn btn_neu' data-ziel='{}' onclick='testfallSpeichern(event)'>Testeingabe als Testfall speichern</button>", text(&ziel)),
+        None => String::new(),
+    };
+
+    let testfaelle = ergebnisse.iter().map(|e| {
+        let diff = if !e.ausgefuehrt() || e.bestanden() {
+            String::new()
+        } else {
+            let zeilen = e.diff().iter().map(|z| match z {
+                DiffZeile::Gleich(l) => format!("<div style='white-space:pre;'>  {}</div>", text(l)),
+                DiffZeile::Entfernt(l) => format!("<div style='white-space:pre;background:rgb(255,225,225);'>- {}</div>", text(l)),
+                DiffZeile::Hinzugefuegt(l) => format!("<div style='white-space:pre;background:rgb(225,255,225);'>+ {}</div>", text(l)),
+            }).collect::<Vec<_>>().join("\r\n");
+            format!("<div style='font-family:monospace;margin-top:5px;padding:4px;background:white;overflow-x:auto;'>{zeilen}</div>")
+        };
+
+        format!("
+            <div style='margin-top:5px;padding:4px 8px;font-size:12px;background:{hintergrund};'>
+                <div style='display:flex;align-items:center;'>
+                    <p style='font-weight:bold;min-width:50px;'>{status}</p>
+                    <p>{titel}: {name}</p>
+                    <div style='display:flex;flex-grow:1;'></div>
+                    <button class='__application-view-script' data-ziel='{ziel}' data-index='{index}' onmouseup='testfallLoeschen(event)'>Löschen</button>
+                </div>
+                {diff}
+            </div>",
+            hintergrund = if !e.ausgefuehrt() { "rgb(240,240,240)" } else if e.bestanden() { "rgb(225,255,225)" } else { "rgb(255,195,195)" },
+            status = if !e.ausgefuehrt() { "-" } else if e.bestanden() { "OK" } else { "FEHLER" },
+            titel = text(&e.ziel.titel()),
+            name = text(&e.testfall.name),
+            ziel = text(&e.ziel.id()),
+            index = e.index,
+        )
+    }).collect::<Vec<_>>().join("\r\n");
+
+    normalize_for_js(format!("
+        <div style='margin-top:20px;margin-bottom:10px;'>
+            <div style='display:flex;align-items:center;'>
+                <h2 style='font-size:16px;'>Testfälle</h2>
+                <p style='margin-left:10px;font-size:12px;'>{status}</p>
+                <div style='display:flex;flex-grow:1;'></div>
+                {ausfuehren}
+                {speichern}
+            </div>
+            {testfaelle}
+        </div>
+    "))
+}
+
+pub fn render_analyse_fortschritt(offen: usize) -> String {
+    if offen == 0 {
+        return String::new();
+    }
+
+    normalize_for_js(format!(
+        "<p style='font-family:sans-serif;font-size:12px;color:#555;margin:5px;'>Analyse läuft, noch {offen} {eintraege} offen...</p>",
+        eintraege = if offen == 1 { "Eintrag" } else { "Einträge" },
     ))
 }
 
+// Quittieren-Button bzw. Hinweis auf die Quittierung eines Befunds,
+// ohne_buttons für den Druck und noch nicht fertig analysierte Rechte
+fn render_quittierung(
+    abteilung: usize,
+    lfd_nr: usize,
+    code: &BefundCode,
+    quittierung: Option<&Quittierung>,
+    ohne_buttons: bool,
+) -> String {
+    let befund = html_escape::encode_safe(&serde_json::to_string(code).unwrap_or_default())
+        .replace('\\', "&#92;");
+    let button = |text: &str, funktion: &str| {
+        format!(
+            "<div style='display:flex;flex-grow:1;'></div><button class='__application-view-script' data-abteilung='{abteilung}' data-lfdNr='{lfd_nr}' data-befund='{befund}' onmouseup='{funktion}(event)'>{text}</button>"
+        )
+    };
+
+    match quittierung {
+        Some(q) => format!(
+            "<p style='margin-left:10px;font-style:italic;color:#555;'>Quittiert von {email} am {datum}: {begruendung}</p>{aufheben}",
+            email = html_escape::encode_safe(&q.email),
+            datum = q.datum.format("%d.%m.%Y"),
+            begruendung = html_escape::encode_safe(&q.begruendung),
+            aufheben = if ohne_buttons { String::new() } else { button("Aufheben", "quittierungAufheben") },
+        ),
+        None if ohne_buttons => String::new(),
+        None => button("Quittieren", "befundQuittieren"),
+    }
+}
+
+// Warnung / Fehler, der keinem Recht in Abt. 1 bis Abt. 3 zugeordnet ist
+// (Prüfung, Erbbaurechte, Löschungen, Rang, ALKIS, andere Blätter)
+fn render_grundbuch_befund(
+    code: &BefundCode,
+    quittierung: Option<&Quittierung>,
+    fehler: bool,
+    icons: &AnalyseIcons,
+    abstand: &str,
+    ohne_buttons: bool,
+) -> String {
+    let (icon, hintergrund, stil) = if fehler {
+        (&icons.fehler_str, "rgb(255,195,195)", "margin-left:10px;color:rgb(129,8,8);")
+    } else {
+        (&icons.warnung_str, "rgb(255,255,167)", "display:inline-block;margin-left:10px;")
+    };
+
+    format!("<span style='display:flex;{abstand};padding: 4px 8px; background:{hintergrund};'>
+            <img src='{icon}' style='width:12px;height:12px;'/>
+            <p style='{stil}'>{text}</p>
+            {quittierung}
+        </span>",
+        hintergrund = if quittierung.is_some() { "rgb(230,230,230)" } else { hintergrund },
+        text = normalize_for_js(code.text()),
+        quittierung = render_quittierung(GRUNDBUCH_BEFUND, 0, code, quittierung, ohne_buttons),
+    )
+}
+
+// Zeile im Pfad von `Cmd::EditText`: die ID des Eintrags, damit die Bearbeitung
+// auch nach dem Einfügen / Löschen anderer Zeilen den richtigen Eintrag trifft,
+// für den Platzhalter einer leeren Tabelle der Index
+fn zeile_pfad(ids: &[String], zeile_nr: usize) -> String {
+    ids.get(zeile_nr)
+        .filter(|id| !id.is_empty())
+        .cloned()
+        .unwrap_or_else(|| zeile_nr.to_string())
+}
+
 pub fn render_bestandsverzeichnis(open_file: &PdfFile, konfiguration: &Konfiguration) -> String {
-    use crate::digital::BvEintrag;
+    use crate::digital::{BvEintrag, Zustimmungsvorbehalt};
+
+    let ids = open_file.analysiert.abschnitt_ids("bv");
 
     let mut bestandsverzeichnis = open_file.analysiert.bestandsverzeichnis.clone();
     if bestandsverzeichnis.eintraege.is_empty() {
@@ -2325,6 +3186,7 @@ pub fn render_bestandsverzeichnis(open_file: &PdfFile, konfiguration: &Konfigura
     }
 
     let bv = bestandsverzeichnis.eintraege.iter().enumerate().map(|(zeile_nr, bve)| {
+        let zeile = zeile_pfad(&ids, zeile_nr);
                 
         let bv_geroetet = if bve.ist_geroetet() { 
             "background:rgb(255,195,195);" 
@@ -2337,9 +3199,10 @@ pub fn render_bestandsverzeichnis(open_file: &PdfFile, konfiguration: &Konfigura
                 format!("
                 <div class='__application-bestandsverzeichnis-eintrag' style='display:flex;'>
                     
-                    <select style='width: 60px;{bv_geroetet}' id='bv_{zeile_nr}_typ' onchange='bvEintragTypAendern(\"bv:{zeile_nr}:typ\", this.options[this.selectedIndex].value)'>
+                    <select style='width: 60px;{bv_geroetet}' id='bv_{zeile_nr}_typ' onchange='bvEintragTypAendern(\"bv:{zeile}:typ\", this.options[this.selectedIndex].value)'>
                         <option value='flst' selected='selected'>Flst.</option>
                         <option value='recht'>Recht</option>
+                        <option value='erbbaurecht'>Erbbaur.</option>
                     </select>
                 
                     <div style='display:flex;flex-direction:row;flex-grow:1;max-width: none;width: 100%;margin-left:10px;'>
@@ -2353,49 +3216,54 @@ pub fn render_bestandsverzeichnis(open_file: &PdfFile, konfiguration: &Konfigura
 
                     <div style='display:flex;flex-direction:row;flex-grow:1;'>
                         <div style='display:flex;flex-grow:1'></div>
-                        <button onclick='eintragNeu(\"bv:{zeile_nr}\")' tabindex='-1' class='btn btn_neu' >neu</button>
-                        <button onclick='eintragRoeten(\"bv:{zeile_nr}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
-                        <button onclick='eintragLoeschen(\"bv:{zeile_nr}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
+                        <button onclick='eintragNeu(\"bv:{zeile}\")' tabindex='-1' class='btn btn_neu' >neu</button>
+                        <button onclick='eintragRoeten(\"bv:{zeile}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
+                        <button onclick='eintragLoeschen(\"bv:{zeile}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
                     </div>
                 </div>",
                     zeile_nr = zeile_nr,
-                    lfd_nr_textfield = StringOrLines::SingleLine(flst.lfd_nr.to_string()).get_html_editable_textfield(
+                    lfd_nr_textfield = StringOrLines::SingleLine(flst.lfd_nr.to_string()).get_html_editable_textfield_mit_roetung(
                         60, // px width
                         bve.ist_geroetet(),
+                        bve.get_zellen_geroetet(),
                         format!("bv_{zeile_nr}_lfd-nr"),
-                        format!("bv:{zeile_nr}:lfd-nr"),
+                        format!("bv:{zeile}:lfd-nr"),
                         TextInputType::Number,
                         FocusType::Focus,
                     ),
-                    bisherige_lfd_nr_textfield = StringOrLines::SingleLine(flst.bisherige_lfd_nr.map(|f| format!("{}", f)).unwrap_or_default()).get_html_editable_textfield(
+                    bisherige_lfd_nr_textfield = StringOrLines::SingleLine(flst.bisherige_lfd_nr.map(|f| format!("{}", f)).unwrap_or_default()).get_html_editable_textfield_mit_roetung(
                         60, // px width
                         bve.ist_geroetet(),
+                        bve.get_zellen_geroetet(),
                         format!("bv_{zeile_nr}_bisherige-lfd-nr"),
-                        format!("bv:{zeile_nr}:bisherige-lfd-nr"),
+                        format!("bv:{zeile}:bisherige-lfd-nr"),
                         TextInputType::Number,
                         FocusType::Focus,
                     ),
-                    gemarkung_textfield = StringOrLines::SingleLine(flst.gemarkung.clone().unwrap_or_default()).get_html_editable_textfield(
+                    gemarkung_textfield = StringOrLines::SingleLine(flst.gemarkung.clone().unwrap_or_default()).get_html_editable_textfield_mit_roetung(
                         150, // px width
                         bve.ist_geroetet(),
+                        bve.get_zellen_geroetet(),
                         format!("bv_{zeile_nr}_gemarkung"),
-                        format!("bv:{zeile_nr}:gemarkung"),
+                        format!("bv:{zeile}:gemarkung"),
                         TextInputType::Text,
                         FocusType::Focus,
                     ),
-                    flur_textfield = StringOrLines::SingleLine(flst.flur.to_string()).get_html_editable_textfield(
+                    flur_textfield = StringOrLines::SingleLine(flst.flur.to_string()).get_html_editable_textfield_mit_roetung(
                         60, // px width
                         bve.ist_geroetet(),
+                        bve.get_zellen_geroetet(),
                         format!("bv_{zeile_nr}_flur"),
-                        format!("bv:{zeile_nr}:flur"),
+                        format!("bv:{zeile}:flur"),
                         TextInputType::Number,
                         FocusType::Focus,
                     ),
-                    flurstueck_textfield = StringOrLines::SingleLine(flst.flurstueck.clone()).get_html_editable_textfield(
+                    flurstueck_textfield = StringOrLines::SingleLine(flst.flurstueck.clone()).get_html_editable_textfield_mit_roetung(
                         60, // px width
                         bve.ist_geroetet(),
+                        bve.get_zellen_geroetet(),
                         format!("bv_{zeile_nr}_flurstueck"),
-                        format!("bv:{zeile_nr}:flurstueck"),
+                        format!("bv:{zeile}:flurstueck"),
                         TextInputType::Text,
                         FocusType::Focus,
                     ),
@@ -2406,19 +3274,21 @@ pub fn render_bestandsverzeichnis(open_file: &PdfFile, konfiguration: &Konfigura
                             {beschreibung_textfield}
                             {groesse_textfield}
                         ", 
-                            beschreibung_textfield = flst.bezeichnung.clone().unwrap_or_default().get_html_editable_textfield(
+                            beschreibung_textfield = flst.bezeichnung.clone().unwrap_or_default().get_html_editable_textfield_mit_roetung(
                                 0, // px width
                                 bve.ist_geroetet(),
+                                bve.get_zellen_geroetet(),
                                 format!("bv_{zeile_nr}_bezeichnung"),
-                                format!("bv:{zeile_nr}:bezeichnung"),
+                                format!("bv:{zeile}:bezeichnung"),
                                 TextInputType::Text,
                                 FocusType::Focus,
                             ),
-                            groesse_textfield = StringOrLines::SingleLine(flst.groesse.get_m2().to_string()).get_html_editable_textfield(
+                            groesse_textfield = StringOrLines::SingleLine(flst.groesse.get_m2().to_string()).get_html_editable_textfield_mit_roetung(
                                 90, // px width
                                 bve.ist_geroetet(),
+                                bve.get_zellen_geroetet(),
                                 format!("bv_{zeile_nr}_groesse"),
-                                format!("bv:{zeile_nr}:groesse"),
+                                format!("bv:{zeile}:groesse"),
                                 TextInputType::Number,
                                 FocusType::Focus,
                             ),
@@ -2432,9 +3302,10 @@ pub fn render_bestandsverzeichnis(open_file: &PdfFile, konfiguration: &Konfigura
 
                 format!("
                 <div class='__application-bestandsverzeichnis-eintrag' style='display:flex;'>
-                    <select style='width: 60px;{bv_geroetet}' id='bv_{zeile_nr}_typ' onchange='bvEintragTypAendern(\"bv:{zeile_nr}:typ\", this.options[this.selectedIndex].value)'>
+                    <select style='width: 60px;{bv_geroetet}' id='bv_{zeile_nr}_typ' onchange='bvEintragTypAendern(\"bv:{zeile}:typ\", this.options[this.selectedIndex].value)'>
                         <option value='flst'>Flst.</option>
                         <option value='recht' selected='selected'>Recht</option>
+                        <option value='erbbaurecht'>Erbbaur.</option>
                     </select>
                     
                     <div style='background:#edfafe;padding:5px;display:flex;flex-direction:column;'>
@@ -2454,41 +3325,184 @@ pub fn render_bestandsverzeichnis(open_file: &PdfFile, konfiguration: &Konfigura
 
                     <div style='display:flex;flex-direction:row;flex-grow:1;'>
                         <div style='display:flex;flex-grow:1'></div>
-                        <button onclick='eintragNeu(\"bv:{zeile_nr}\")' tabindex='-1' class='btn btn_neu' >neu</button>
-                        <button onclick='eintragRoeten(\"bv:{zeile_nr}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
-                        <button onclick='eintragLoeschen(\"bv:{zeile_nr}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
+                        <button onclick='eintragNeu(\"bv:{zeile}\")' tabindex='-1' class='btn btn_neu' >neu</button>
+                        <button onclick='eintragRoeten(\"bv:{zeile}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
+                        <button onclick='eintragLoeschen(\"bv:{zeile}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
                     </div>
                 </div>",
                     zeile_nr = zeile_nr,
-                    lfd_nr_textfield = lfd_nr.get_html_editable_textfield(
+                    lfd_nr_textfield = lfd_nr.get_html_editable_textfield_mit_roetung(
                         30, // px width
                         bve.ist_geroetet(),
+                        bve.get_zellen_geroetet(),
                         format!("bv_{zeile_nr}_lfd-nr"),
-                        format!("bv:{zeile_nr}:lfd-nr"),
+                        format!("bv:{zeile}:lfd-nr"),
                         TextInputType::Number,
                         FocusType::Focus,
                     ),
-                    bisherige_lfd_nr_textfield = bisherige_lfd_nr.get_html_editable_textfield(
+                    bisherige_lfd_nr_textfield = bisherige_lfd_nr.get_html_editable_textfield_mit_roetung(
                         80, // px width
                         bve.ist_geroetet(),
+                        bve.get_zellen_geroetet(),
                         format!("bv_{zeile_nr}_bisherige-lfd-nr"),
-                        format!("bv:{zeile_nr}:bisherige-lfd-nr"),
+                        format!("bv:{zeile}:bisherige-lfd-nr"),
                         TextInputType::Number,
                         FocusType::Focus,
                     ),
-                    zu_nr_textfield = recht.zu_nr.clone().get_html_editable_textfield(
+                    zu_nr_textfield = recht.zu_nr.clone().get_html_editable_textfield_mit_roetung(
                         80, // px width
                         bve.ist_geroetet(),
+                        bve.get_zellen_geroetet(),
                         format!("bv_{zeile_nr}_zu-nr"),
-                        format!("bv:{zeile_nr}:zu-nr"),
+                        format!("bv:{zeile}:zu-nr"),
+                        TextInputType::Text,
+                        FocusType::Focus,
+                    ),
+                    text_recht_textfield = recht.text.clone().get_html_editable_textfield_mit_roetung(
+                        if konfiguration.lefis_analyse_einblenden { 320 } else { 620 }, // px width
+                        bve.ist_geroetet(),
+                        bve.get_zellen_geroetet(),
+                        format!("bv_{zeile_nr}_recht-text"),
+                        format!("bv:{zeile}:recht-text"),
+                        TextInputType::Text,
+                        FocusType::NoFocus,
+                    ),
+                )
+            },
+            BvEintrag::Erbbaurecht(erbbaurecht) => {
+                let lfd_nr: StringOrLines = format!("{}", erbbaurecht.lfd_nr).into();
+                let bisherige_lfd_nr: StringOrLines = erbbaurecht.bisherige_lfd_nr.map(|s| format!("{}", s)).unwrap_or_default().into();
+                let grundbuch_von: StringOrLines = erbbaurecht.grundstueck.grundbuch_von.clone().into();
+                let blatt: StringOrLines = erbbaurecht.grundstueck.blatt.clone().into();
+                let abt2_lfd_nr: StringOrLines = erbbaurecht.grundstueck.abt2_lfd_nr.map(|s| format!("{}", s)).unwrap_or_default().into();
+
+                format!("
+                <div class='__application-bestandsverzeichnis-eintrag' style='display:flex;'>
+                    <select style='width: 60px;{bv_geroetet}' id='bv_{zeile_nr}_typ' onchange='bvEintragTypAendern(\"bv:{zeile}:typ\", this.options[this.selectedIndex].value)'>
+                        <option value='flst'>Flst.</option>
+                        <option value='recht'>Recht</option>
+                        <option value='erbbaurecht' selected='selected'>Erbbaur.</option>
+                    </select>
+                    
+                    <div style='background:#fdf6e3;padding:5px;display:flex;flex-direction:column;'>
+                        <div style='padding:5px 0px;display:flex;flex-direction:row;flex-grow:1;'>
+                            <p style='width:30px;font-weight:bold;'>Nr.</p>
+                            <p style='width:80px;margin-left:10px;font-weight:bold;'>Nr. (alt)</p>
+                            <p style='width:150px;margin-left:10px;font-weight:bold;'>Grundbuch von</p>
+                            <p style='width:60px;margin-left:10px;font-weight:bold;'>Blatt</p>
+                            <p style='width:60px;margin-left:10px;font-weight:bold;'>BV-Nr.</p>
+                            <p style='width:60px;margin-left:10px;font-weight:bold;'>Abt. 2</p>
+                        </div>
+                        <div style='display:flex;flex-direction:row;flex-grow:1;'>
+                            {lfd_nr_textfield}
+                            {bisherige_lfd_nr_textfield}
+                            {grundbuch_von_textfield}
+                            {blatt_textfield}
+                            {bv_nr_textfield}
+                            {abt2_lfd_nr_textfield}
+                        </div>
+                        <div style='padding:5px 0px;display:flex;flex-direction:row;flex-grow:1;'>
+                            <p style='width:200px;font-weight:bold;'>Dauer</p>
+                            <p style='margin-left:10px;font-weight:bold;'>Zustimmung erforderlich zur</p>
+                        </div>
+                        <div style='display:flex;flex-direction:row;flex-grow:1;align-items:center;'>
+                            {dauer_textfield}
+                            <label style='margin-left:10px;display:flex;align-items:center;'>
+                                <input style='width:20px;height:20px;cursor:pointer;' type='checkbox' data-checkBoxId='bv:{zeile}:zustimmung-veraeusserung' {zustimmung_veraeusserung} onchange='toggleCheckbox(event)'>
+                                Veräußerung
+                            </label>
+                            <label style='margin-left:10px;display:flex;align-items:center;'>
+                                <input style='width:20px;height:20px;cursor:pointer;' type='checkbox' data-checkBoxId='bv:{zeile}:zustimmung-belastung' {zustimmung_belastung} onchange='toggleCheckbox(event)'>
+                                Belastung
+                            </label>
+                        </div>
+                        <div style='padding:5px 0px;display:flex;flex-direction:row;flex-grow:1;'>
+                            <p style='font-weight:bold;'>Text Erbbaurecht</p>
+                        </div>
+                        <div style='display:flex;flex-direction:row;flex-grow:1;'>
+                            {text_erbbaurecht_textfield}
+                        </div>
+                    </div>
+
+                    <div style='display:flex;flex-direction:row;flex-grow:1;'>
+                        <div style='display:flex;flex-grow:1'></div>
+                        <button onclick='eintragNeu(\"bv:{zeile}\")' tabindex='-1' class='btn btn_neu' >neu</button>
+                        <button onclick='eintragRoeten(\"bv:{zeile}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
+                        <button onclick='eintragLoeschen(\"bv:{zeile}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
+                    </div>
+                </div>",
+                    zeile_nr = zeile_nr,
+                    lfd_nr_textfield = lfd_nr.get_html_editable_textfield_mit_roetung(
+                        30, // px width
+                        bve.ist_geroetet(),
+                        bve.get_zellen_geroetet(),
+                        format!("bv_{zeile_nr}_lfd-nr"),
+                        format!("bv:{zeile}:lfd-nr"),
+                        TextInputType::Number,
+                        FocusType::Focus,
+                    ),
+                    bisherige_lfd_nr_textfield = bisherige_lfd_nr.get_html_editable_textfield_mit_roetung(
+                        80, // px width
+                        bve.ist_geroetet(),
+                        bve.get_zellen_geroetet(),
+                        format!("bv_{zeile_nr}_bisherige-lfd-nr"),
+                        format!("bv:{zeile}:bisherige-lfd-nr"),
+                        TextInputType::Number,
+                        FocusType::Focus,
+                    ),
+                    grundbuch_von_textfield = grundbuch_von.get_html_editable_textfield_mit_roetung(
+                        150, // px width
+                        bve.ist_geroetet(),
+                        bve.get_zellen_geroetet(),
+                        format!("bv_{zeile_nr}_erbbau-grundbuch-von"),
+                        format!("bv:{zeile}:erbbau-grundbuch-von"),
+                        TextInputType::Text,
+                        FocusType::Focus,
+                    ),
+                    blatt_textfield = blatt.get_html_editable_textfield_mit_roetung(
+                        60, // px width
+                        bve.ist_geroetet(),
+                        bve.get_zellen_geroetet(),
+                        format!("bv_{zeile_nr}_erbbau-blatt"),
+                        format!("bv:{zeile}:erbbau-blatt"),
+                        TextInputType::Text,
+                        FocusType::Focus,
+                    ),
+                    bv_nr_textfield = erbbaurecht.grundstueck.bv_nr.clone().get_html_editable_textfield_mit_roetung(
+                        60, // px width
+                        bve.ist_geroetet(),
+                        bve.get_zellen_geroetet(),
+                        format!("bv_{zeile_nr}_erbbau-bv-nr"),
+                        format!("bv:{zeile}:erbbau-bv-nr"),
+                        TextInputType::Text,
+                        FocusType::Focus,
+                    ),
+                    abt2_lfd_nr_textfield = abt2_lfd_nr.get_html_editable_textfield_mit_roetung(
+                        60, // px width
+                        bve.ist_geroetet(),
+                        bve.get_zellen_geroetet(),
+                        format!("bv_{zeile_nr}_erbbau-abt2-lfd-nr"),
+                        format!("bv:{zeile}:erbbau-abt2-lfd-nr"),
+                        TextInputType::Number,
+                        FocusType::Focus,
+                    ),
+                    dauer_textfield = erbbaurecht.dauer.clone().get_html_editable_textfield_mit_roetung(
+                        200, // px width
+                        bve.ist_geroetet(),
+                        bve.get_zellen_geroetet(),
+                        format!("bv_{zeile_nr}_erbbau-dauer"),
+                        format!("bv:{zeile}:erbbau-dauer"),
                         TextInputType::Text,
                         FocusType::Focus,
                     ),
-                    text_recht_textfield = recht.text.clone().get_html_editable_textfield(
+                    zustimmung_veraeusserung = if erbbaurecht.zustimmungsvorbehalte.contains(&Zustimmungsvorbehalt::Veraeusserung) { "checked" } else { "" },
+                    zustimmung_belastung = if erbbaurecht.zustimmungsvorbehalte.contains(&Zustimmungsvorbehalt::Belastung) { "checked" } else { "" },
+                    text_erbbaurecht_textfield = erbbaurecht.text.clone().get_html_editable_textfield_mit_roetung(
                         if konfiguration.lefis_analyse_einblenden { 320 } else { 620 }, // px width
                         bve.ist_geroetet(),
+                        bve.get_zellen_geroetet(),
                         format!("bv_{zeile_nr}_recht-text"),
-                        format!("bv:{zeile_nr}:recht-text"),
+                        format!("bv:{zeile}:recht-text"),
                         TextInputType::Text,
                         FocusType::NoFocus,
                     ),
@@ -2524,6 +3538,8 @@ pub fn render_bestandsverzeichnis(open_file: &PdfFile, konfiguration: &Konfigura
 }
 
 pub fn render_bestandsverzeichnis_zuschreibungen(open_file: &PdfFile) -> String {
+    let ids = open_file.analysiert.abschnitt_ids("bv-zuschreibung");
+
     let mut bv_zuschreibungen = open_file
         .analysiert
         .bestandsverzeichnis
@@ -2534,6 +3550,7 @@ pub fn render_bestandsverzeichnis_zuschreibungen(open_file: &PdfFile) -> String
     }
 
     let bv = bv_zuschreibungen.iter().enumerate().map(|(zeile_nr, bvz)| {
+        let zeile = zeile_pfad(&ids, zeile_nr);
         
         let bv_geroetet = if bvz.ist_geroetet() { 
             "background:rgb(255,195,195);" 
@@ -2551,9 +3568,9 @@ pub fn render_bestandsverzeichnis_zuschreibungen(open_file: &PdfFile) -> String
             
             <div style='display:flex;flex-direction:row;flex-grow:1;'>
                 <div style='display:flex;flex-grow:1'></div>
-                <button onclick='eintragNeu(\"bv-zuschreibung:{zeile_nr}\")' tabindex='-1' class='btn btn_neu' >neu</button>
-                <button onclick='eintragRoeten(\"bv-zuschreibung:{zeile_nr}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
-                <button onclick='eintragLoeschen(\"bv-zuschreibung:{zeile_nr}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
+                <button onclick='eintragNeu(\"bv-zuschreibung:{zeile}\")' tabindex='-1' class='btn btn_neu' >neu</button>
+                <button onclick='eintragRoeten(\"bv-zuschreibung:{zeile}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
+                <button onclick='eintragLoeschen(\"bv-zuschreibung:{zeile}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
             </div>
         </div>", 
             zeile_nr = zeile_nr,
@@ -2561,7 +3578,7 @@ pub fn render_bestandsverzeichnis_zuschreibungen(open_file: &PdfFile) -> String
                 90, // px width
                 bvz.ist_geroetet(),
                 format!("bv-zuschreibung_{zeile_nr}_bv-nr"),
-                format!("bv-zuschreibung:{zeile_nr}:bv-nr"),
+                format!("bv-zuschreibung:{zeile}:bv-nr"),
                 TextInputType::Text,
                 FocusType::Focus,
             ),
@@ -2569,7 +3586,7 @@ pub fn render_bestandsverzeichnis_zuschreibungen(open_file: &PdfFile) -> String
                 0, // px width
                 bvz.ist_geroetet(),
                 format!("bv-zuschreibung_{zeile_nr}_text"),
-                format!("bv-zuschreibung:{zeile_nr}:text"),
+                format!("bv-zuschreibung:{zeile}:text"),
                 TextInputType::Text,
                 FocusType::NoFocus,
             ),
@@ -2589,6 +3606,8 @@ pub fn render_bestandsverzeichnis_zuschreibungen(open_file: &PdfFile) -> String
 }
 
 pub fn render_bestandsverzeichnis_abschreibungen(open_file: &PdfFile) -> String {
+    let ids = open_file.analysiert.abschnitt_ids("bv-abschreibung");
+
     let mut bv_abschreibungen = open_file
         .analysiert
         .bestandsverzeichnis
@@ -2599,6 +3618,7 @@ pub fn render_bestandsverzeichnis_abschreibungen(open_file: &PdfFile) -> String
     }
 
     let bv = bv_abschreibungen.iter().enumerate().map(|(zeile_nr, bva)| {
+        let zeile = zeile_pfad(&ids, zeile_nr);
         
         let bv_geroetet = if bva.ist_geroetet() { 
             "background:rgb(255,195,195);" 
@@ -2616,9 +3636,9 @@ pub fn render_bestandsverzeichnis_abschreibungen(open_file: &PdfFile) -> String
             
             <div style='display:flex;flex-direction:row;flex-grow:1;'>
                 <div style='display:flex;flex-grow:1'></div>
-                <button onclick='eintragNeu(\"bv-abschreibung:{zeile_nr}\")' tabindex='-1' class='btn btn_neu' >neu</button>
-                <button onclick='eintragRoeten(\"bv-abschreibung:{zeile_nr}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
-                <button onclick='eintragLoeschen(\"bv-abschreibung:{zeile_nr}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
+                <button onclick='eintragNeu(\"bv-abschreibung:{zeile}\")' tabindex='-1' class='btn btn_neu' >neu</button>
+                <button onclick='eintragRoeten(\"bv-abschreibung:{zeile}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
+                <button onclick='eintragLoeschen(\"bv-abschreibung:{zeile}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
             </div>
         </div>", 
             zeile_nr = zeile_nr,
@@ -2626,7 +3646,7 @@ pub fn render_bestandsverzeichnis_abschreibungen(open_file: &PdfFile) -> String
                 90, // px width
                 bva.ist_geroetet(),
                 format!("bv-abschreibung_{zeile_nr}_bv-nr"),
-                format!("bv-abschreibung:{zeile_nr}:bv-nr"),
+                format!("bv-abschreibung:{zeile}:bv-nr"),
                 TextInputType::Text,
                 FocusType::Focus,
             ),
@@ -2634,7 +3654,7 @@ pub fn render_bestandsverzeichnis_abschreibungen(open_file: &PdfFile) -> String
                 0, // px width
                 bva.ist_geroetet(),
                 format!("bv-abschreibung_{zeile_nr}_text"),
-                format!("bv-abschreibung:{zeile_nr}:text"),
+                format!("bv-abschreibung:{zeile}:text"),
                 TextInputType::Text,
                 FocusType::NoFocus,
             ),
@@ -2656,6 +3676,8 @@ pub fn render_bestandsverzeichnis_abschreibungen(open_file: &PdfFile) -> String
 pub fn render_abt_1(open_file: &PdfFile) -> String {
     use crate::digital::Abt1Eintrag;
 
+    let ids = open_file.analysiert.abschnitt_ids("abt1");
+
     let mut abt1_eintraege = open_file.analysiert.abt1.eintraege.clone();
     if abt1_eintraege.is_empty() {
         abt1_eintraege = vec![Abt1Eintrag::new(1)];
@@ -2669,6 +3691,7 @@ pub fn render_abt_1(open_file: &PdfFile) -> String {
         Abt1Eintrag::V2(v2) => Some((zeile_nr, v2)),
     })
     .map(|(zeile_nr, abt1)| {
+        let zeile = zeile_pfad(&ids, zeile_nr);
     
         let bv_geroetet = if abt1.ist_geroetet() { 
             "background:rgb(255,195,195);" 
@@ -2686,9 +3709,9 @@ pub fn render_abt_1(open_file: &PdfFile) -> String {
             
             <div style='display:flex;flex-direction:row;flex-grow:1;'>
                 <div style='display:flex;flex-grow:1'></div>
-                <button onclick='eintragNeu(\"abt1:{zeile_nr}\")' tabindex='-1' class='btn btn_neu' >neu</button>
-                <button onclick='eintragRoeten(\"abt1:{zeile_nr}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
-                <button onclick='eintragLoeschen(\"abt1:{zeile_nr}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
+                <button onclick='eintragNeu(\"abt1:{zeile}\")' tabindex='-1' class='btn btn_neu' >neu</button>
+                <button onclick='eintragRoeten(\"abt1:{zeile}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
+                <button onclick='eintragLoeschen(\"abt1:{zeile}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
             </div>
             
         </div>", 
@@ -2697,7 +3720,7 @@ pub fn render_abt_1(open_file: &PdfFile) -> String {
                 90, // px width
                 abt1.ist_geroetet(),
                 format!("abt1_{zeile_nr}_lfd-nr"),
-                format!("abt1:{zeile_nr}:lfd-nr"),
+                format!("abt1:{zeile}:lfd-nr"),
                 TextInputType::Number,
                 FocusType::Focus,
             ),
@@ -2705,7 +3728,7 @@ pub fn render_abt_1(open_file: &PdfFile) -> String {
                 0, // px width
                 abt1.ist_geroetet(),
                 format!("abt1_{zeile_nr}_eigentuemer"),
-                format!("abt1:{zeile_nr}:eigentuemer"),
+                format!("abt1:{zeile}:eigentuemer"),
                 TextInputType::Text,
                 FocusType::NoFocus,
             ),
@@ -2731,6 +3754,8 @@ pub fn render_abt_1(open_file: &PdfFile) -> String {
 pub fn render_abt_1_grundlagen_eintragungen(open_file: &PdfFile) -> String {
     use crate::digital::Abt1GrundEintragung;
 
+    let ids = open_file.analysiert.abschnitt_ids("abt1-grundlage-eintragung");
+
     let mut abt1_eintraege = open_file.analysiert.abt1.grundlagen_eintragungen.clone();
     if abt1_eintraege.is_empty() {
         abt1_eintraege = vec![Abt1GrundEintragung::new()];
@@ -2740,6 +3765,7 @@ pub fn render_abt_1_grundlagen_eintragungen(open_file: &PdfFile) -> String {
     .iter()
     .enumerate()
     .map(|(zeile_nr, abt1)| {
+        let zeile = zeile_pfad(&ids, zeile_nr);
     
         let bv_geroetet = if abt1.ist_geroetet() { 
             "background:rgb(255,195,195);" 
@@ -2757,9 +3783,9 @@ pub fn render_abt_1_grundlagen_eintragungen(open_file: &PdfFile) -> String {
             
             <div style='display:flex;flex-direction:row;flex-grow:1;'>
                 <div style='display:flex;flex-grow:1'></div>
-                <button onclick='eintragNeu(\"abt1-grundlage-eintragung:{zeile_nr}\")' tabindex='-1' class='btn btn_neu' >neu</button>
-                <button onclick='eintragRoeten(\"abt1-grundlage-eintragung:{zeile_nr}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
-                <button onclick='eintragLoeschen(\"abt1-grundlage-eintragung:{zeile_nr}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
+                <button onclick='eintragNeu(\"abt1-grundlage-eintragung:{zeile}\")' tabindex='-1' class='btn btn_neu' >neu</button>
+                <button onclick='eintragRoeten(\"abt1-grundlage-eintragung:{zeile}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
+                <button onclick='eintragLoeschen(\"abt1-grundlage-eintragung:{zeile}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
             </div>
             
         </div>", 
@@ -2769,7 +3795,7 @@ pub fn render_abt_1_grundlagen_eintragungen(open_file: &PdfFile) -> String {
                 60, // px width
                 abt1.ist_geroetet(),
                 format!("abt1-grundlage-eintragung_{zeile_nr}_bv-nr"),
-                format!("abt1-grundlage-eintragung:{zeile_nr}:bv-nr"),
+                format!("abt1-grundlage-eintragung:{zeile}:bv-nr"),
                 TextInputType::Text,
                 FocusType::Focus,
             ),
@@ -2778,7 +3804,7 @@ pub fn render_abt_1_grundlagen_eintragungen(open_file: &PdfFile) -> String {
                 0, // px width
                 abt1.ist_geroetet(),
                 format!("abt1-grundlage-eintragung_{zeile_nr}_text"),
-                format!("abt1-grundlage-eintragung:{zeile_nr}:text"),
+                format!("abt1-grundlage-eintragung:{zeile}:text"),
                 TextInputType::Text,
                 FocusType::NoFocus,
             ),
@@ -2800,12 +3826,15 @@ pub fn render_abt_1_grundlagen_eintragungen(open_file: &PdfFile) -> String {
 }
 
 pub fn render_abt_1_veraenderungen(open_file: &PdfFile) -> String {
+    let ids = open_file.analysiert.abschnitt_ids("abt1-veraenderung");
+
     let mut abt1_veraenderungen = open_file.analysiert.abt1.veraenderungen.clone();
     if abt1_veraenderungen.is_empty() {
         abt1_veraenderungen = vec![Abt1Veraenderung::default()];
     }
 
     let abt1_veraenderungen = abt1_veraenderungen.iter().enumerate().map(|(zeile_nr, abt1_a)| {
+        let zeile = zeile_pfad(&ids, zeile_nr);
         
         let bv_geroetet = if abt1_a.ist_geroetet() { 
             "background:rgb(255,195,195);" 
@@ -2823,9 +3852,9 @@ pub fn render_abt_1_veraenderungen(open_file: &PdfFile) -> String {
             
             <div style='display:flex;flex-direction:row;flex-grow:1;'>
                 <div style='display:flex;flex-grow:1'></div>
-                <button onclick='eintragNeu(\"abt1-veraenderung:{zeile_nr}\")' tabindex='-1' class='btn btn_neu' >neu</button>
-                <button onclick='eintragRoeten(\"abt1-veraenderung:{zeile_nr}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
-                <button onclick='eintragLoeschen(\"abt1-veraenderung:{zeile_nr}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
+                <button onclick='eintragNeu(\"abt1-veraenderung:{zeile}\")' tabindex='-1' class='btn btn_neu' >neu</button>
+                <button onclick='eintragRoeten(\"abt1-veraenderung:{zeile}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
+                <button onclick='eintragLoeschen(\"abt1-veraenderung:{zeile}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
             </div>
         </div>", 
             zeile_nr = zeile_nr,
@@ -2833,7 +3862,7 @@ pub fn render_abt_1_veraenderungen(open_file: &PdfFile) -> String {
                 90, // px width
                 abt1_a.ist_geroetet(),
                 format!("abt1-veraenderung_{zeile_nr}_lfd-nr"),
-                format!("abt1-veraenderung:{zeile_nr}:lfd-nr"),
+                format!("abt1-veraenderung:{zeile}:lfd-nr"),
                 TextInputType::Text,
                 FocusType::Focus,
             ),
@@ -2841,7 +3870,7 @@ pub fn render_abt_1_veraenderungen(open_file: &PdfFile) -> String {
                 0, // px width
                 abt1_a.ist_geroetet(),
                 format!("abt1-veraenderung_{zeile_nr}_text"),
-                format!("abt1-veraenderung:{zeile_nr}:text"),
+                format!("abt1-veraenderung:{zeile}:text"),
                 TextInputType::Text,
                 FocusType::NoFocus,
             ),
@@ -2861,12 +3890,15 @@ pub fn render_abt_1_veraenderungen(open_file: &PdfFile) -> String {
 }
 
 pub fn render_abt_1_loeschungen(open_file: &PdfFile) -> String {
+    let ids = open_file.analysiert.abschnitt_ids("abt1-loeschung");
+
     let mut abt1_loeschungen = open_file.analysiert.abt1.loeschungen.clone();
     if abt1_loeschungen.is_empty() {
         abt1_loeschungen = vec![Abt1Loeschung::default()];
     }
 
     let abt1_loeschungen = abt1_loeschungen.iter().enumerate().map(|(zeile_nr, abt1_l)| {
+        let zeile = zeile_pfad(&ids, zeile_nr);
         
         let bv_geroetet = if abt1_l.ist_geroetet() { 
             "background:rgb(255,195,195);" 
@@ -2884,9 +3916,9 @@ pub fn render_abt_1_loeschungen(open_file: &PdfFile) -> String {
             
             <div style='display:flex;flex-direction:row;flex-grow:1;'>
                 <div style='display:flex;flex-grow:1'></div>
-                <button onclick='eintragNeu(\"abt1-loeschung:{zeile_nr}\")' tabindex='-1' class='btn btn_neu' >neu</button>
-                <button onclick='eintragRoeten(\"abt1-loeschung:{zeile_nr}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
-                <button onclick='eintragLoeschen(\"abt1-loeschung:{zeile_nr}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
+                <button onclick='eintragNeu(\"abt1-loeschung:{zeile}\")' tabindex='-1' class='btn btn_neu' >neu</button>
+                <button onclick='eintragRoeten(\"abt1-loeschung:{zeile}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
+                <button onclick='eintragLoeschen(\"abt1-loeschung:{zeile}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
             </div>
         </div>", 
             zeile_nr = zeile_nr,
@@ -2894,7 +3926,7 @@ pub fn render_abt_1_loeschungen(open_file: &PdfFile) -> String {
                 90, // px width
                 abt1_l.ist_geroetet(),
                 format!("abt1-loeschung_{zeile_nr}_lfd-nr"),
-                format!("abt1-loeschung:{zeile_nr}:lfd-nr"),
+                format!("abt1-loeschung:{zeile}:lfd-nr"),
                 TextInputType::Text,
                 FocusType::Focus,
             ),
@@ -2902,7 +3934,7 @@ pub fn render_abt_1_loeschungen(open_file: &PdfFile) -> String {
                 0, // px width
                 abt1_l.ist_geroetet(),
                 format!("abt1-loeschung_{zeile_nr}_text"),
-                format!("abt1-loeschung:{zeile_nr}:text"),
+                format!("abt1-loeschung:{zeile}:text"),
                 TextInputType::Text,
                 FocusType::NoFocus,
             ),
@@ -2924,12 +3956,15 @@ pub fn render_abt_1_loeschungen(open_file: &PdfFile) -> String {
 pub fn render_abt_2(open_file: &PdfFile) -> String {
     use crate::digital::Abt2Eintrag;
 
+    let ids = open_file.analysiert.abschnitt_ids("abt2");
+
     let mut abt2_eintraege = open_file.analysiert.abt2.eintraege.clone();
     if abt2_eintraege.is_empty() {
         abt2_eintraege = vec![Abt2Eintrag::new(1)];
     }
 
     let abt2 = abt2_eintraege.iter().enumerate().map(|(zeile_nr, abt2)| {
+        let zeile = zeile_pfad(&ids, zeile_nr);
     
         let bv_geroetet = if abt2.ist_geroetet() { 
             "background:rgb(255,195,195);" 
@@ -2948,33 +3983,36 @@ pub fn render_abt_2(open_file: &PdfFile) -> String {
             
             <div style='display:flex;flex-direction:row;flex-grow:1;'>
                 <div style='display:flex;flex-grow:1'></div>
-                <button onclick='eintragNeu(\"abt2:{zeile_nr}\")' tabindex='-1' class='btn btn_neu' >neu</button>
-                <button onclick='eintragRoeten(\"abt2:{zeile_nr}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
-                <button onclick='eintragLoeschen(\"abt2:{zeile_nr}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
+                <button onclick='eintragNeu(\"abt2:{zeile}\")' tabindex='-1' class='btn btn_neu' >neu</button>
+                <button onclick='eintragRoeten(\"abt2:{zeile}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
+                <button onclick='eintragLoeschen(\"abt2:{zeile}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
             </div>
         </div>", 
             zeile_nr = zeile_nr,
-            lfd_nr_textfield =  StringOrLines::SingleLine(abt2.lfd_nr.to_string()).get_html_editable_textfield(
+            lfd_nr_textfield =  StringOrLines::SingleLine(abt2.lfd_nr.to_string()).get_html_editable_textfield_mit_roetung(
                 90, // px width
                 abt2.ist_geroetet(),
+                Some(&abt2.zellen_geroetet),
                 format!("abt2_{zeile_nr}_lfd-nr"),
-                format!("abt2:{zeile_nr}:lfd-nr"),
+                format!("abt2:{zeile}:lfd-nr"),
                 TextInputType::Text,
                 FocusType::Focus,
             ),
-            bv_nr_textfield = abt2.bv_nr.get_html_editable_textfield(
+            bv_nr_textfield = abt2.bv_nr.get_html_editable_textfield_mit_roetung(
                 90, // px width
                 abt2.ist_geroetet(),
+                Some(&abt2.zellen_geroetet),
                 format!("abt2_{zeile_nr}_bv-nr"),
-                format!("abt2:{zeile_nr}:bv-nr"),
+                format!("abt2:{zeile}:bv-nr"),
                 TextInputType::Text,
                 FocusType::Focus,
             ),
-            recht_textfield = abt2.text.get_html_editable_textfield(
+            recht_textfield = abt2.text.get_html_editable_textfield_mit_roetung(
                 0, // px width
                 abt2.ist_geroetet(),
+                Some(&abt2.zellen_geroetet),
                 format!("abt2_{zeile_nr}_text"),
-                format!("abt2:{zeile_nr}:text"),
+                format!("abt2:{zeile}:text"),
                 TextInputType::Text,
                 FocusType::NoFocus,
             ),
@@ -2999,12 +4037,15 @@ pub fn render_abt_2(open_file: &PdfFile) -> String {
 }
 
 pub fn render_abt_2_veraenderungen(open_file: &PdfFile) -> String {
+    let ids = open_file.analysiert.abschnitt_ids("abt2-veraenderung");
+
     let mut abt2_veraenderungen = open_file.analysiert.abt2.veraenderungen.clone();
     if abt2_veraenderungen.is_empty() {
         abt2_veraenderungen = vec![Abt2Veraenderung::default()];
     }
 
     let abt2_veraenderungen = abt2_veraenderungen.iter().enumerate().map(|(zeile_nr, abt2_a)| {
+        let zeile = zeile_pfad(&ids, zeile_nr);
         
         let bv_geroetet = if abt2_a.ist_geroetet() { 
             "background:rgb(255,195,195);" 
@@ -3023,9 +4064,9 @@ pub fn render_abt_2_veraenderungen(open_file: &PdfFile) -> String {
             
             <div style='display:flex;flex-direction:row;flex-grow:1;'>
                 <div style='display:flex;flex-grow:1'></div>
-                <button onclick='eintragNeu(\"abt2-veraenderung:{zeile_nr}\")' tabindex='-1' class='btn btn_neu' >neu</button>
-                <button onclick='eintragRoeten(\"abt2-veraenderung:{zeile_nr}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
-                <button onclick='eintragLoeschen(\"abt2-veraenderung:{zeile_nr}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
+                <button onclick='eintragNeu(\"abt2-veraenderung:{zeile}\")' tabindex='-1' class='btn btn_neu' >neu</button>
+                <button onclick='eintragRoeten(\"abt2-veraenderung:{zeile}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
+                <button onclick='eintragLoeschen(\"abt2-veraenderung:{zeile}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
             </div>
         </div>", 
             zeile_nr = zeile_nr,
@@ -3034,7 +4075,7 @@ pub fn render_abt_2_veraenderungen(open_file: &PdfFile) -> String {
                 90, // px width
                 abt2_a.ist_geroetet(),
                 format!("abt2-veraenderung_{zeile_nr}_lfd-nr"),
-                format!("abt2-veraenderung:{zeile_nr}:lfd-nr"),
+                format!("abt2-veraenderung:{zeile}:lfd-nr"),
                 TextInputType::Text,
                 FocusType::Focus,
             ),
@@ -3042,7 +4083,7 @@ pub fn render_abt_2_veraenderungen(open_file: &PdfFile) -> String {
                 320, // px width
                 abt2_a.ist_geroetet(),
                 format!("abt2-veraenderung_{zeile_nr}_text"),
-                format!("abt2-veraenderung:{zeile_nr}:text"),
+                format!("abt2-veraenderung:{zeile}:text"),
                 TextInputType::Text,
                 FocusType::NoFocus,
             ),
@@ -3062,12 +4103,15 @@ pub fn render_abt_2_veraenderungen(open_file: &PdfFile) -> String {
 }
 
 pub fn render_abt_2_loeschungen(open_file: &PdfFile) -> String {
+    let ids = open_file.analysiert.abschnitt_ids("abt2-loeschung");
+
     let mut abt2_loeschungen = open_file.analysiert.abt2.loeschungen.clone();
     if abt2_loeschungen.is_empty() {
         abt2_loeschungen = vec![Abt2Loeschung::default()];
     }
 
     let abt2_loeschungen = abt2_loeschungen.iter().enumerate().map(|(zeile_nr, abt2_l)| {
+        let zeile = zeile_pfad(&ids, zeile_nr);
     
         let bv_geroetet = if abt2_l.ist_geroetet() { 
             "background:rgb(255,195,195);" 
@@ -3085,9 +4129,9 @@ pub fn render_abt_2_loeschungen(open_file: &PdfFile) -> String {
             
             <div style='display:flex;flex-direction:row;flex-grow:1;'>
                 <div style='display:flex;flex-grow:1'></div>
-                <button onclick='eintragNeu(\"abt2-loeschung:{zeile_nr}\")' tabindex='-1' class='btn btn_neu' >neu</button>
-                <button onclick='eintragRoeten(\"abt2-loeschung:{zeile_nr}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
-                <button onclick='eintragLoeschen(\"abt2-loeschung:{zeile_nr}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
+                <button onclick='eintragNeu(\"abt2-loeschung:{zeile}\")' tabindex='-1' class='btn btn_neu' >neu</button>
+                <button onclick='eintragRoeten(\"abt2-loeschung:{zeile}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
+                <button onclick='eintragLoeschen(\"abt2-loeschung:{zeile}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
             </div>
         </div>", 
             zeile_nr = zeile_nr,
@@ -3095,7 +4139,7 @@ pub fn render_abt_2_loeschungen(open_file: &PdfFile) -> String {
                 90, // px width
                 abt2_l.ist_geroetet(),
                 format!("abt2-loeschung_{zeile_nr}_lfd-nr"),
-                format!("abt2-loeschung:{zeile_nr}:lfd-nr"),
+                format!("abt2-loeschung:{zeile}:lfd-nr"),
                 TextInputType::Text,
                 FocusType::Focus,
             ),
@@ -3103,7 +4147,7 @@ pub fn render_abt_2_loeschungen(open_file: &PdfFile) -> String {
                 320, // px width
                 abt2_l.ist_geroetet(),
                 format!("abt2-loeschung_{zeile_nr}_text"),
-                format!("abt2-loeschung:{zeile_nr}:text"),
+                format!("abt2-loeschung:{zeile}:text"),
                 TextInputType::Text,
                 FocusType::NoFocus,
             ),
@@ -3125,12 +4169,15 @@ pub fn render_abt_2_loeschungen(open_file: &PdfFile) -> String {
 pub fn render_abt_3(open_file: &PdfFile, show_lefis: bool) -> String {
     use crate::digital::Abt3Eintrag;
 
+    let ids = open_file.analysiert.abschnitt_ids("abt3");
+
     let mut abt3_eintraege = open_file.analysiert.abt3.eintraege.clone();
     if abt3_eintraege.is_empty() {
         abt3_eintraege = vec![Abt3Eintrag::new(1)];
     }
 
     let abt3 = abt3_eintraege.iter().enumerate().map(|(zeile_nr, abt3)| {
+        let zeile = zeile_pfad(&ids, zeile_nr);
             
         let bv_geroetet = if abt3.ist_geroetet() { 
             "background:rgb(255,195,195);" 
@@ -3150,44 +4197,48 @@ pub fn render_abt_3(open_file: &PdfFile, show_lefis: bool) -> String {
             
             <div style='display:flex;flex-direction:row;flex-grow:1;'>
                 <div style='display:flex;flex-grow:1'></div>
-                <button onclick='eintragNeu(\"abt3:{zeile_nr}\")' tabindex='-1' class='btn btn_neu' >neu</button>
-                <button onclick='eintragRoeten(\"abt3:{zeile_nr}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
-                <button onclick='eintragLoeschen(\"abt3:{zeile_nr}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
+                <button onclick='eintragNeu(\"abt3:{zeile}\")' tabindex='-1' class='btn btn_neu' >neu</button>
+                <button onclick='eintragRoeten(\"abt3:{zeile}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
+                <button onclick='eintragLoeschen(\"abt3:{zeile}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
             </div>
         </div>",
             zeile_nr = zeile_nr,            
-            lfd_nr_textfield = abt3.bv_nr.get_html_editable_textfield(
+            lfd_nr_textfield = abt3.bv_nr.get_html_editable_textfield_mit_roetung(
                 30, // px width
                 abt3.ist_geroetet(),
+                Some(&abt3.zellen_geroetet),
                 format!("abt3_{zeile_nr}_lfd-nr"),
-                format!("abt3:{zeile_nr}:lfd-nr"),
+                format!("abt3:{zeile}:lfd-nr"),
                 TextInputType::Number,
                 FocusType::Focus,
             ),
             
-            bv_nr_textfield = abt3.bv_nr.get_html_editable_textfield(
+            bv_nr_textfield = abt3.bv_nr.get_html_editable_textfield_mit_roetung(
                 if show_lefis { 40 } else { 60 }, // px width
                 abt3.ist_geroetet(),
+                Some(&abt3.zellen_geroetet),
                 format!("abt3_{zeile_nr}_bv-nr"),
-                format!("abt3:{zeile_nr}:bv-nr"),
+                format!("abt3:{zeile}:bv-nr"),
                 TextInputType::Text,
                 FocusType::Focus,
             ),
             
-            betrag_textfield = abt3.betrag.get_html_editable_textfield(
+            betrag_textfield = abt3.betrag.get_html_editable_textfield_mit_roetung(
                 if show_lefis { 90 } else { 180 }, // px width
                 abt3.ist_geroetet(),
+                Some(&abt3.zellen_geroetet),
                 format!("abt3_{zeile_nr}_betrag"),
-                format!("abt3:{zeile_nr}:betrag"),
+                format!("abt3:{zeile}:betrag"),
                 TextInputType::Text,
                 FocusType::Focus,
             ),
             
-            recht_textfield = abt3.text.get_html_editable_textfield(
+            recht_textfield = abt3.text.get_html_editable_textfield_mit_roetung(
                 0, // px width
                 abt3.ist_geroetet(),
+                Some(&abt3.zellen_geroetet),
                 format!("abt3_{zeile_nr}_text"),
-                format!("abt3:{zeile_nr}:text"),
+                format!("abt3:{zeile}:text"),
                 TextInputType::Text,
                 FocusType::NoFocus,
             ),
@@ -3211,12 +4262,15 @@ pub fn render_abt_3(open_file: &PdfFile, show_lefis: bool) -> String {
 }
 
 pub fn render_abt_3_veraenderungen(open_file: &PdfFile) -> String {
+    let ids = open_file.analysiert.abschnitt_ids("abt3-veraenderung");
+
     let mut abt3_veraenderungen = open_file.analysiert.abt3.veraenderungen.clone();
     if abt3_veraenderungen.is_empty() {
         abt3_veraenderungen = vec![Abt3Veraenderung::default()];
     }
 
     let abt3_veraenderungen = abt3_veraenderungen.iter().enumerate().map(|(zeile_nr, abt3_a)| {
+        let zeile = zeile_pfad(&ids, zeile_nr);
         
         let bv_geroetet = if abt3_a.ist_geroetet() { 
             "background:rgb(255,195,195);" 
@@ -3235,9 +4289,9 @@ pub fn render_abt_3_veraenderungen(open_file: &PdfFile) -> String {
             
             <div style='display:flex;flex-direction:row;flex-grow:1;'>
                 <div style='display:flex;flex-grow:1'></div>
-                <button onclick='eintragNeu(\"abt3-veraenderung:{zeile_nr}\")' tabindex='-1' class='btn btn_neu' >neu</button>
-                <button onclick='eintragRoeten(\"abt3-veraenderung:{zeile_nr}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
-                <button onclick='eintragLoeschen(\"abt3-veraenderung:{zeile_nr}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
+                <button onclick='eintragNeu(\"abt3-veraenderung:{zeile}\")' tabindex='-1' class='btn btn_neu' >neu</button>
+                <button onclick='eintragRoeten(\"abt3-veraenderung:{zeile}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
+                <button onclick='eintragLoeschen(\"abt3-veraenderung:{zeile}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
             </div>
         </div>",
             zeile_nr = zeile_nr,
@@ -3246,7 +4300,7 @@ pub fn render_abt_3_veraenderungen(open_file: &PdfFile) -> String {
                 90, // px width
                 abt3_a.ist_geroetet(),
                 format!("abt3-veraenderung_{zeile_nr}_lfd-nr"),
-                format!("abt3-veraenderung:{zeile_nr}:lfd-nr"),
+                format!("abt3-veraenderung:{zeile}:lfd-nr"),
                 TextInputType::Text,
                 FocusType::Focus,
             ),
@@ -3255,7 +4309,7 @@ pub fn render_abt_3_veraenderungen(open_file: &PdfFile) -> String {
                 120, // px width
                 abt3_a.ist_geroetet(),
                 format!("abt3-veraenderung_{zeile_nr}_betrag"),
-                format!("abt3-veraenderung:{zeile_nr}:betrag"),
+                format!("abt3-veraenderung:{zeile}:betrag"),
                 TextInputType::Text,
                 FocusType::Focus,
             ),
@@ -3264,7 +4318,7 @@ pub fn render_abt_3_veraenderungen(open_file: &PdfFile) -> String {
                 320, // px width
                 abt3_a.ist_geroetet(),
                 format!("abt3-veraenderung_{zeile_nr}_text"),
-                format!("abt3-veraenderung:{zeile_nr}:text"),
+                format!("abt3-veraenderung:{zeile}:text"),
                 TextInputType::Text,
                 FocusType::NoFocus,
             ),
@@ -3285,12 +4339,15 @@ pub fn render_abt_3_veraenderungen(open_file: &PdfFile) -> String {
 }
 
 pub fn render_abt_3_loeschungen(open_file: &PdfFile) -> String {
+    let ids = open_file.analysiert.abschnitt_ids("abt3-loeschung");
+
     let mut abt3_loeschungen = open_file.analysiert.abt3.loeschungen.clone();
     if abt3_loeschungen.is_empty() {
         abt3_loeschungen = vec![Abt3Loeschung::default()];
     }
 
     let abt3_loeschungen = abt3_loeschungen.iter().enumerate().map(|(zeile_nr, abt3_l)| {
+        let zeile = zeile_pfad(&ids, zeile_nr);
             
         let bv_geroetet = if abt3_l.ist_geroetet() { 
             "background:rgb(255,195,195);" 
@@ -3308,9 +4365,9 @@ pub fn render_abt_3_loeschungen(open_file: &PdfFile) -> String {
             
             <div style='display:flex;flex-direction:row;flex-grow:1;'>
                 <div style='display:flex;flex-grow:1'></div>
-                <button onclick='eintragNeu(\"abt3-loeschung:{zeile_nr}\")' tabindex='-1' class='btn btn_neu' >neu</button>
-                <button onclick='eintragRoeten(\"abt3-loeschung:{zeile_nr}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
-                <button onclick='eintragLoeschen(\"abt3-loeschung:{zeile_nr}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
+                <button onclick='eintragNeu(\"abt3-loeschung:{zeile}\")' tabindex='-1' class='btn btn_neu' >neu</button>
+                <button onclick='eintragRoeten(\"abt3-loeschung:{zeile}\")' tabindex='-1' class='btn btn_roeten'>röten</button>
+                <button onclick='eintragLoeschen(\"abt3-loeschung:{zeile}\")' tabindex='-1' class='btn btn_loeschen'>löschen</button>
             </div>
         </div>", 
             zeile_nr = zeile_nr,
@@ -3318,7 +4375,7 @@ pub fn render_abt_3_loeschungen(open_file: &PdfFile) -> String {
                 90, // px width
                 abt3_l.ist_geroetet(),
                 format!("abt3-loeschung_{zeile_nr}_lfd-nr"),
-                format!("abt3-loeschung:{zeile_nr}:lfd-nr"),
+                format!("abt3-loeschung:{zeile}:lfd-nr"),
                 TextInputType::Text,
                 FocusType::Focus,
             ),
@@ -3326,7 +4383,7 @@ pub fn render_abt_3_loeschungen(open_file: &PdfFile) -> String {
                 120, // px width
                 abt3_l.ist_geroetet(),
                 format!("abt3-loeschung_{zeile_nr}_betrag"),
-                format!("abt3-loeschung:{zeile_nr}:betrag"),
+                format!("abt3-loeschung:{zeile}:betrag"),
                 TextInputType::Text,
                 FocusType::Focus,
             ),
@@ -3334,7 +4391,7 @@ pub fn render_abt_3_loeschungen(open_file: &PdfFile) -> String {
                 320, // px width
                 abt3_l.ist_geroetet(),
                 format!("abt3-loeschung_{zeile_nr}_text"),
-                format!("abt3-loeschung:{zeile_nr}:text"),
+                format!("abt3-loeschung:{zeile}:text"),
                 TextInputType::Text,
                 FocusType::NoFocus,
             ),
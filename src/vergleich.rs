@@ -0,0 +1,436 @@
+//! Vergleich zweier Konfigurationen an einem Ordner mit .gbx-Dateien
+//!
+//! Analysiert Abt. 2 und Abt. 3 aller Blätter einmal mit der alten und einmal
+//! mit der neuen Konfiguration und listet je Eintrag die Felder auf, die sich
+//! geändert haben. So ist vor dem Weitergeben einer Konfiguration sichtbar,
+//! welche Kurztexte, Rechtsinhaber usw. sich durch die Änderung eines Skripts
+//! oder regulären Ausdrucks verschieben. Läuft in einem eigenen Thread, der
+//! Fortschritt wird nach jedem Blatt gemeldet.
+
+use crate::analyse::{Abt2Analysiert, Abt3Analysiert, AnalyseFehler, GrundbuchAnalysiertCache};
+use crate::digital::{BvEintrag, Nebenbeteiligter};
+use crate::kurztext::formatiere_betrag;
+use crate::migration;
+use crate::python::PyVm;
+use crate::Konfiguration;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Aenderung {
+    pub datei: String,
+    pub abteilung: usize,
+    pub lfd_nr: usize,
+    pub feld: &'static str,
+    pub alt: String,
+    pub neu: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct KonfigurationVergleich {
+    pub dateien: usize,
+    pub eintraege: usize,
+    pub aenderungen: Vec<Aenderung>,
+    // Dateien, die nicht gelesen werden konnten
+    pub fehler: Vec<String>,
+}
+
+// Verglichene Felder eines Eintrags, in der Reihenfolge des Berichts
+type Felder = Vec<(&'static str, String)>;
+
+pub fn konfigurationen_vergleichen(
+    ordner: &Path,
+    alt: &Konfiguration,
+    neu: &Konfiguration,
+    vm: &PyVm,
+    nb: &[Nebenbeteiligter],
+    fortschritt: impl Fn(usize, usize),
+) -> KonfigurationVergleich {
+    let mut vergleich = KonfigurationVergleich::default();
+
+    let mut dateien = match std::fs::read_dir(ordner) {
+        Ok(o) => o
+            .filter_map(|e| Some(e.ok()?.path()))
+            .filter(|p| {
+                let name = p.to_string_lossy();
+                name.ends_with(".gbx") && !name.ends_with(".cache.gbx")
+            })
+            .collect::<Vec<_>>(),
+        Err(e) => {
+            vergleich
+                .fehler
+                .push(format!("{}: {}", ordner.display(), e));
+            return vergleich;
+        }
+    };
+    dateien.sort();
+
+    let gesamt = dateien.len();
+    fortschritt(0, gesamt);
+
+    for (i, pfad) in dateien.into_iter().enumerate() {
+        // Nicht gbx_datei_laden, damit im Ordner keine Sicherungen angelegt werden
+        let geladen = match std::fs::read_to_string(&pfad)
+            .map_err(|e| e.to_string())
+            .and_then(|s| migration::gbx_lesen(&s).map_err(|e| e.to_string()))
+        {
+            Ok(o) => o,
+            Err(e) => {
+                vergleich.fehler.push(format!("{}: {}", pfad.display(), e));
+                fortschritt(i + 1, gesamt);
+                continue;
+            }
+        };
+
+        let grundbuch = &geladen.pdf.analysiert;
+        let datei = format!(
+            "{}_{}",
+            grundbuch.titelblatt.grundbuch_von, grundbuch.titelblatt.blatt
+        );
+
+        let analyse_alt = GrundbuchAnalysiertCache::default()
+            .start_and_block_until_finished(grundbuch, vm, nb, alt);
+        let analyse_neu = GrundbuchAnalysiertCache::default()
+            .start_and_block_until_finished(grundbuch, vm, nb, neu);
+
+        let abt2_alt = analyse_alt.abt2.iter().map(|a| (a.lfd_nr, abt2_felder(a)));
+        let abt2_neu = analyse_neu.abt2.iter().map(|a| (a.lfd_nr, abt2_felder(a)));
+        let abt3_alt = analyse_alt.abt3.iter().map(|a| (a.lfd_nr, abt3_felder(a)));
+        let abt3_neu = analyse_neu.abt3.iter().map(|a| (a.lfd_nr, abt3_felder(a)));
+
+        for (abteilung, felder_alt, felder_neu) in [
+            (2, abt2_alt.collect::<BTreeMap<_, _>>(), abt2_neu.collect()),
+            (3, abt3_alt.collect::<BTreeMap<_, _>>(), abt3_neu.collect()),
+        ] {
+            vergleich.eintraege += felder_alt
+                .keys()
+                .chain(felder_neu.keys())
+                .collect::<BTreeSet<_>>()
+                .len();
+            vergleich.aenderungen.extend(eintraege_vergleichen(
+                &datei,
+                abteilung,
+                &felder_alt,
+                &felder_neu,
+            ));
+        }
+
+        vergleich.dateien += 1;
+        fortschritt(i + 1, gesamt);
+    }
+
+    vergleich
+}
+
+fn abt2_felder(a: &Abt2Analysiert) -> Felder {
+    vec![
+        ("Kurztext", a.text_kurz.clone()),
+        ("RechteArt", format!("{:?}", a.rechteart)),
+        ("Rechtsinhaber", a.rechtsinhaber.clone()),
+        (
+            "Belastete Flurstücke",
+            flurstuecke(&a.belastete_flurstuecke),
+        ),
+        ("Fehler", fehler(&a.fehler)),
+    ]
+}
+
+fn abt3_felder(a: &Abt3Analysiert) -> Felder {
+    vec![
+        ("Kurztext", a.text_kurz.clone()),
+        (
+            "Betrag",
+            format!("{} {}", formatiere_betrag(&a.betrag), a.betrag.waehrung),
+        ),
+        ("SchuldenArt", format!("{:?}", a.schuldenart)),
+        ("Rechtsinhaber", a.rechtsinhaber.clone()),
+        (
+            "Belastete Flurstücke",
+            flurstuecke(&a.belastete_flurstuecke),
+        ),
+        ("Fehler", fehler(&a.fehler)),
+    ]
+}
+
+fn flurstuecke(belastet: &[BvEintrag]) -> String {
+    belastet
+        .iter()
+        .map(|b| match b {
+            BvEintrag::Flurstueck(flst) => format!(
+                "BV-Nr. {}: Fl. {}, Flst. {}",
+                flst.lfd_nr, flst.flur, flst.flurstueck
+            ),
+            BvEintrag::Recht(recht) => format!("BV-Nr. {}: Grundstücksgl. Recht", recht.lfd_nr),
+            BvEintrag::Erbbaurecht(erbbaurecht) => {
+                format!("BV-Nr. {}: Erbbaurecht", erbbaurecht.lfd_nr)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn fehler(fehler: &[AnalyseFehler]) -> String {
+    fehler
+        .iter()
+        .map(|f| f.text())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+// Einträge, die nur in einer der beiden Analysen vorkommen, werden mit leeren
+// Feldern verglichen
+fn eintraege_vergleichen(
+    datei: &str,
+    abteilung: usize,
+    alt: &BTreeMap<usize, Felder>,
+    neu: &BTreeMap<usize, Felder>,
+) -> Vec<Aenderung> {
+    let mut aenderungen = Vec::new();
+    let leer = Felder::new();
+
+    for lfd_nr in alt.keys().chain(neu.keys()).collect::<BTreeSet<_>>() {
+        let felder_alt = alt.get(lfd_nr).unwrap_or(&leer);
+        let felder_neu = neu.get(lfd_nr).unwrap_or(&leer);
+
+        let mut namen = felder_alt.iter().map(|(f, _)| *f).collect::<Vec<_>>();
+        for (f, _) in felder_neu.iter() {
+            if !namen.contains(f) {
+                namen.push(*f);
+            }
+        }
+
+        for feld in namen {
+            let wert = |felder: &Felder| {
+                felder
+                    .iter()
+                    .find(|(f, _)| *f == feld)
+                    .map(|(_, w)| w.clone())
+                    .unwrap_or_default()
+            };
+            let wert_alt = wert(felder_alt);
+            let wert_neu = wert(felder_neu);
+            if wert_alt != wert_neu {
+                aenderungen.push(Aenderung {
+                    datei: datei.to_string(),
+                    abteilung,
+                    lfd_nr: *lfd_nr,
+                    feld,
+                    alt: wert_alt,
+                    neu: wert_neu,
+                });
+            }
+        }
+    }
+
+    aenderungen
+}
+
+impl KonfigurationVergleich {
+    pub fn zusammenfassung(&self) -> String {
+        let geaenderte_eintraege = self
+            .aenderungen
+            .iter()
+            .map(|a| (&a.datei, a.abteilung, a.lfd_nr))
+            .collect::<BTreeSet<_>>()
+            .len();
+
+        format!(
+            "{} Blätter mit {} Einträgen verglichen, {} Einträge geändert",
+            self.dateien, self.eintraege, geaenderte_eintraege
+        )
+    }
+
+    pub fn als_tsv(&self) -> String {
+        let feld = |s: &str| s.replace(['\t', '\r', '\n'], " ");
+
+        let zeilen = self
+            .aenderungen
+            .iter()
+            .map(|a| {
+                format!(
+                    "{}\t{}\t{}\t{}\t{}\t{}",
+                    a.datei,
+                    a.abteilung,
+                    a.lfd_nr,
+                    a.feld,
+                    feld(&a.alt),
+                    feld(&a.neu),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\r\n");
+
+        format!("DATEI\tABTEILUNG\tLFD_NR\tFELD\tALT\tNEU\r\n{}", zeilen)
+    }
+
+    pub fn als_html(&self) -> String {
+        let zeilen = self
+            .aenderungen
+            .iter()
+            .map(|a| {
+                format!(
+                    "<tr>
+                        <td>{datei}</td>
+                        <td>A{abteilung}/{lfd_nr}</td>
+                        <td>{feld}</td>
+                        <td style='background:rgb(255,225,225);'>{alt}</td>
+                        <td style='background:rgb(225,255,225);'>{neu}</td>
+                    </tr>",
+                    datei = html_escape::encode_safe(&a.datei),
+                    abteilung = a.abteilung,
+                    lfd_nr = a.lfd_nr,
+                    feld = a.feld,
+                    alt = html_escape::encode_safe(&a.alt),
+                    neu = html_escape::encode_safe(&a.neu),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\r\n");
+
+        let fehler = self
+            .fehler
+            .iter()
+            .map(|f| {
+                format!(
+                    "<p style='color:rgb(129,8,8);'>{}</p>",
+                    html_escape::encode_safe(f)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\r\n");
+
+        format!(
+            "<html>
+            <head>
+                <meta charset='utf-8' />
+                <style>
+                    * {{ font-family:sans-serif;font-size:12px; }}
+                    table {{ border-collapse:collapse; }}
+                    td, th {{ border:1px solid #efefef;padding:4px 8px;text-align:left;vertical-align:top; }}
+                </style>
+            </head>
+            <body>
+                <h4>Vergleich Konfiguration</h4>
+                <p>{zusammenfassung}</p>
+                {fehler}
+                <table>
+                    <tr><th>Datei</th><th>Eintrag</th><th>Feld</th><th>Alt</th><th>Neu</th></tr>
+                    {zeilen}
+                </table>
+            </body>
+            </html>",
+            zusammenfassung = self.zusammenfassung(),
+        )
+    }
+}
+
+#[test]
+fn test_eintraege_vergleichen() {
+    let felder = |kurztext: &str, rechtsinhaber: &str| -> Felder {
+        vec![
+            ("Kurztext", kurztext.to_string()),
+            ("Rechtsinhaber", rechtsinhaber.to_string()),
+        ]
+    };
+
+    let alt = BTreeMap::from([
+        (1, felder("Wegerecht", "Gemeinde Musterdorf")),
+        (2, felder("Leitungsrecht", "Stadtwerke")),
+    ]);
+    let neu = BTreeMap::from([
+        (1, felder("Wegerecht", "Gemeinde Musterdorf")),
+        (2, felder("Leitungsrecht", "Stadtwerke Musterstadt GmbH")),
+        (3, felder("Vorkaufsrecht", "")),
+    ]);
+
+    let aenderungen = eintraege_vergleichen("Musterdorf_100", 2, &alt, &neu);
+
+    assert_eq!(
+        aenderungen,
+        vec![
+            Aenderung {
+                datei: "Musterdorf_100".to_string(),
+                abteilung: 2,
+                lfd_nr: 2,
+                feld: "Rechtsinhaber",
+                alt: "Stadtwerke".to_string(),
+                neu: "Stadtwerke Musterstadt GmbH".to_string(),
+            },
+            Aenderung {
+                datei: "Musterdorf_100".to_string(),
+                abteilung: 2,
+                lfd_nr: 3,
+                feld: "Kurztext",
+                alt: String::new(),
+                neu: "Vorkaufsrecht".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_konfigurationen_vergleichen_ordner() {
+    let ordner = std::env::temp_dir().join(format!("dgb-vergleich-test-{}", rand::random::<u32>()));
+    std::fs::create_dir_all(&ordner).unwrap();
+
+    let gbx = r#"{
+        "analysiert": {
+            "titelblatt": { "amtsgericht": "Prenzlau", "grundbuch_von": "Ludwigsburg", "blatt": "254" },
+            "abt1": {
+                "eintraege": [{
+                    "lfd_nr": 1,
+                    "eigentuemer": "Max Mustermann",
+                    "bv_nr": "1",
+                    "grundlage_der_eintragung": "Auflassung vom 01.01.1990"
+                }]
+            }
+        }
+    }"#;
+    std::fs::write(ordner.join("Ludwigsburg_254.gbx"), gbx).unwrap();
+    // Zwischenspeicher werden übersprungen, unlesbare Dateien als Fehler gemeldet
+    std::fs::write(ordner.join("Ludwigsburg_254.cache.gbx"), "kein JSON").unwrap();
+    std::fs::write(ordner.join("Ludwigsburg_255.gbx"), "kein JSON").unwrap();
+
+    let konfiguration = Konfiguration::parse_from(Konfiguration::DEFAULT).unwrap();
+    let vm = PyVm::new().unwrap();
+    let vergleich =
+        konfigurationen_vergleichen(&ordner, &konfiguration, &konfiguration, &vm, &[], |_, _| {});
+    let _ = std::fs::remove_dir_all(&ordner);
+
+    assert_eq!(vergleich.dateien, 1);
+    assert!(vergleich.aenderungen.is_empty());
+    assert_eq!(vergleich.fehler.len(), 1);
+    assert!(vergleich.fehler[0].contains("Ludwigsburg_255.gbx"));
+}
+
+#[test]
+fn test_konfiguration_vergleich_export() {
+    let vergleich = KonfigurationVergleich {
+        dateien: 1,
+        eintraege: 1,
+        aenderungen: vec![Aenderung {
+            datei: "Musterdorf_100".to_string(),
+            abteilung: 2,
+            lfd_nr: 1,
+            feld: "Rechtsinhaber",
+            alt: "Müller\tSchulze\r\nGbR".to_string(),
+            neu: "<b>Müller & Co.</b>".to_string(),
+        }],
+        fehler: vec!["<kaputt>.gbx: kein JSON".to_string()],
+    };
+
+    // Tabulatoren und Zeilenumbrüche würden die Spalten verschieben
+    assert_eq!(
+        vergleich.als_tsv(),
+        "DATEI\tABTEILUNG\tLFD_NR\tFELD\tALT\tNEU\r\n\
+         Musterdorf_100\t2\t1\tRechtsinhaber\tMüller Schulze  GbR\t<b>Müller & Co.</b>"
+    );
+
+    let html = vergleich.als_html();
+    assert!(html.contains("&lt;b&gt;Müller &amp; Co.&lt;"));
+    assert!(html.contains("&lt;kaputt&gt;.gbx: kein JSON"));
+    assert!(!html.contains("<b>"));
+    assert!(!html.contains("<kaputt>"));
+}
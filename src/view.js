@@ -15,6 +15,9 @@ let rpc = {
   grundbuch_anlegen: function(grundbuch_von, amtsgericht, blatt) { rpc.invoke({ cmd : 'grundbuch_anlegen', grundbuch_von: grundbuch_von, amtsgericht: amtsgericht, blatt: blatt }); },
   grundbuch_meta_aendern: function(grundbuch_von, amtsgericht, blatt) { rpc.invoke({ cmd : 'grundbuch_meta_aendern', grundbuch_von: grundbuch_von, amtsgericht: amtsgericht, blatt: blatt }); },
   grundbuch_meta_aendern_finished: function(grundbuch_von, amtsgericht, blatt) { rpc.invoke({ cmd : 'grundbuch_meta_aendern_finished', grundbuch_von: grundbuch_von, amtsgericht: amtsgericht, blatt: blatt }); },
+  befund_quittieren: function(abteilung, lfd_nr, code) { rpc.invoke({ cmd : 'befund_quittieren', abteilung: abteilung, lfd_nr: lfd_nr, code: code }); },
+  befund_quittieren_finished: function(begruendung) { rpc.invoke({ cmd : 'befund_quittieren_finished', begruendung: begruendung }); },
+  quittierung_aufheben: function(abteilung, lfd_nr, code) { rpc.invoke({ cmd : 'quittierung_aufheben', abteilung: abteilung, lfd_nr: lfd_nr, code: code }); },
   undo:  function() { rpc.invoke({ cmd : 'undo' }); },
   redo:  function() { rpc.invoke({ cmd : 'redo' }); },
   export_nb:  function() { rpc.invoke({ cmd : 'export_nb' }); },
@@ -52,22 +55,25 @@ let rpc = {
   toggle_lefis_analyse: function() { rpc.invoke({ cmd : 'toggle_lefis_analyse' }); },
   toggle_dateiliste: function(arg) { rpc.invoke({ cmd : 'toggle_dateiliste', toggle: arg }) },
   select_tab: function(arg) { rpc.invoke({ cmd: 'select_tab', tab: arg }) },
-  check_pdf_for_errors: function() { rpc.invoke({ cmd : 'check_pdf_for_errors' }); },
 
   open_grundbuch_suchen_dialog: function()  { rpc.invoke({ cmd : 'open_grundbuch_suchen_dialog' }); },
   search: function(search_text) { rpc.invoke({ cmd : 'search', search_text: search_text }); },
   open_grundbuch_upload_dialog: function()  { rpc.invoke({ cmd : 'open_grundbuch_upload_dialog' }); },
 
   import_nb:  function() { rpc.invoke({ cmd : 'import_nb' }); },
+  import_gemarkungen:  function() { rpc.invoke({ cmd : 'import_gemarkungen' }); },
+  import_alkis:  function() { rpc.invoke({ cmd : 'import_alkis' }); },
   export_lefis:  function() { rpc.invoke({ cmd : 'export_lefis' }); },
+  export_rang:  function() { rpc.invoke({ cmd : 'export_rang' }); },
+  export_geojson:  function() { rpc.invoke({ cmd : 'export_geojson' }); },
   delete_nb: function() { rpc.invoke({ cmd : 'delete_nb' }); },
   open_info: function() { rpc.invoke({ cmd : 'open_info' }); },
   open_help: function() { rpc.invoke({ cmd : 'open_help' }); },
   open_export_pdf: function() { rpc.invoke({ cmd : 'open_export_pdf' }); },
+  konfiguration_vergleichen: function() { rpc.invoke({ cmd : 'konfiguration_vergleichen' }); },
   open_configuration: function() { rpc.invoke({ cmd : 'open_configuration' }); },
   set_configuration_view: function(section_id) { rpc.invoke({ cmd : 'set_configuration_view', section_id: section_id }); },
   save_state: function() { rpc.invoke({ cmd : 'save_state' }); },
-  check_for_grundbuch_loaded: function() { rpc.invoke({ cmd : 'check_for_grundbuch_loaded' }); },
 
   reset_ocr_selection: function() { rpc.invoke({ cmd : 'reset_ocr_selection' }); },
   select_ocr: function(file_name, page, min_x, min_y, max_x, max_y, page_width, page_height) { rpc.invoke({ 
@@ -88,6 +94,7 @@ let rpc = {
   eintrag_neu: function(arg) { rpc.invoke({ cmd : 'eintrag_neu', path: arg }); },
   eintrag_loeschen: function(arg) { rpc.invoke({ cmd : 'eintrag_loeschen', path: arg }); },
   eintrag_roeten: function(arg) { rpc.invoke({ cmd : 'eintrag_roeten', path: arg }); },
+  zelle_roeten: function(arg, zeile) { rpc.invoke({ cmd : 'zelle_roeten', path: arg, zeile: zeile }); },
   open_context_menu: function(x, y, seite) { rpc.invoke({ cmd : 'open_context_menu', x: x, y: y, seite: seite }); },
   close_pop_over:  function() { rpc.invoke({ cmd : 'close_pop_over' }); },
   close_file: function(arg) { rpc.invoke({ cmd : 'close_file', file_name: arg }); },
@@ -97,9 +104,17 @@ let rpc = {
   insert_regex: function(arg) { rpc.invoke({ cmd : 'insert_regex', regex_key: arg });},
   teste_regex: function(regex_id, text) { rpc.invoke({ cmd: 'teste_regex', regex_id: regex_id, text: text }); },
   regex_loeschen: function(arg) { rpc.invoke({ cmd : 'regex_loeschen', regex_key: arg });},
+  testfall_speichern: function(ziel, eingabe, bv_nr) { rpc.invoke({ cmd : 'testfall_speichern', ziel: ziel, eingabe: eingabe, bv_nr: bv_nr });},
+  testfall_loeschen: function(ziel, index) { rpc.invoke({ cmd : 'testfall_loeschen', ziel: ziel, index: index });},
+  testfall_aus_eintrag: function(abteilung, lfd_nr) { rpc.invoke({ cmd : 'testfall_aus_eintrag', abteilung: abteilung, lfd_nr: lfd_nr });},
+  testfaelle_ausfuehren: function() { rpc.invoke({ cmd : 'testfaelle_ausfuehren' });},
+  historie_vergleichen: function(alt, neu) { rpc.invoke({ cmd : 'historie_vergleichen', alt: alt, neu: neu });},
+  historie_wiederherstellen: function(id) { rpc.invoke({ cmd : 'historie_wiederherstellen', id: id });},
 
   edit_abkuerzungen_script: function(arg) { rpc.invoke({ cmd : 'edit_abkuerzungen_script', script: arg });},
   edit_text_saubern_script: function(arg) { rpc.invoke({ cmd : 'edit_text_saubern_script', script: arg });},
+  text_saubern_script_testen: function(arg) { rpc.invoke({ cmd: 'text_saubern_script_testen', text: arg }); },
+  abkuerzungen_script_testen: function(arg) { rpc.invoke({ cmd: 'abkuerzungen_script_testen', text: arg }); },
   edit_flurstuecke_auslesen_script: function(arg) { rpc.invoke({ cmd : 'edit_flurstuecke_auslesen_script', script: arg });},
 
   edit_text_kuerzen_abt2_script: function(arg) { rpc.invoke({ cmd : 'edit_text_kuerzen_abt2_script', script: arg });},
@@ -121,6 +136,10 @@ let rpc = {
   schuldenart_script_testen: function(arg) { rpc.invoke({ cmd: 'schuldenart_script_testen', text: arg }); },
   edit_rechtsinhaber_auslesen_abt3_script: function(neu) { rpc.invoke({ cmd: 'edit_rechtsinhaber_auslesen_abt3_script', neu: neu }); },
   rechtsinhaber_auslesen_abt3_script_testen: function(arg) { rpc.invoke({ cmd: 'rechtsinhaber_auslesen_abt3_script_testen', text: arg }); },
+  edit_pruefe_eintrag_script: function(neu) { rpc.invoke({ cmd: 'edit_pruefe_eintrag_script', neu: neu }); },
+  pruefe_eintrag_script_testen: function(arg, abteilung) { rpc.invoke({ cmd: 'pruefe_eintrag_script_testen', text: arg, abteilung: abteilung }); },
+  edit_eigentuemer_auslesen_abt1_script: function(neu) { rpc.invoke({ cmd: 'edit_eigentuemer_auslesen_abt1_script', neu: neu }); },
+  eigentuemer_auslesen_abt1_script_testen: function(arg) { rpc.invoke({ cmd: 'eigentuemer_auslesen_abt1_script_testen', text: arg }); },
   bv_eintrag_typ_aendern: function(path, value) { rpc.invoke({ cmd: 'bv_eintrag_typ_aendern', path: path, value: value }); },
   copy_text_to_clipboard: function(text) { rpc.invoke({ cmd: 'copy_text_to_clipboard', text: text }); },
   
@@ -163,6 +182,8 @@ let tab_functions = {
     upload_grundbuch: function(event) { rpc.open_grundbuch_upload_dialog() },
     export_nb: function(event) { rpc.export_nb() },
     import_nb: function(event) { rpc.import_nb() },
+    import_gemarkungen: function(event) { rpc.import_gemarkungen() },
+    import_alkis: function(event) { rpc.import_alkis() },
     delete_nb: function(event) { rpc.delete_nb() },
     export_alle_rechte: function(event) { rpc.export_alle_rechte() },
     export_alle_fehler: function(event) { rpc.export_alle_fehler() },
@@ -170,6 +191,8 @@ let tab_functions = {
     export_alle_abt1: function(event) { rpc.export_alle_abt1() },
     export_alle_hvm: function(event) { rpc.export_alle_hvm() },
     export_lefis: function(event) { rpc.export_lefis() },
+    export_rang: function(event) { rpc.export_rang() },
+    export_geojson: function(event) { rpc.export_geojson() },
     export_pdf: function(event) { rpc.export_pdf() },
     open_configuration: function(event) { rpc.open_configuration() },
     open_help: function(event) { rpc.open_help() },
@@ -186,10 +209,6 @@ setInterval(function(){
 }, 1000);
 
 
-setInterval(function(){
-    rpc.check_for_grundbuch_loaded();
-}, 2594);
-
 function startCheckingForPageLoaded(filepath, filename, pdf_file_name, reload_hocr) {
     files_to_check[filepath] = {
         filename: filename, 
@@ -262,11 +281,6 @@ function editCommitTitle(event) {
     rpc.edit_commit_title(event.target.value);
 }
 
-function startCheckingForPdfErrors() {
-    setInterval(function(){
-        rpc.check_pdf_for_errors();
-    }, 1278);
-}
 
 function toggleLefisAnalyse(event) {
     rpc.toggle_lefis_analyse();
@@ -428,6 +442,23 @@ function eintragLoeschen(path) {
     rpc.eintrag_loeschen(path);        
 }
 
+// Rechtsklick auf eine Zeile in einer Zelle rötet nur diese Zeile,
+// mit gedrückter Umschalttaste die ganze Zelle
+function zelleRoeten(event, path) {
+    event.preventDefault();
+    var zelle = event.currentTarget;
+    var zeile = event.target;
+    while (zeile && zeile.parentElement !== zelle) {
+        zeile = zeile.parentElement;
+    }
+    var index = zeile ? Array.prototype.indexOf.call(zelle.children, zeile) : -1;
+    if (event.shiftKey || index < 0) {
+        rpc.zelle_roeten(path, null);
+    } else {
+        rpc.zelle_roeten(path, index);
+    }
+}
+
 function editKonfigurationSchluesseldatei(event) {
     
 	var file = event.target.files[0];
@@ -683,6 +714,18 @@ function replaceAnalyseGrundbuch(s) {
     fixupCSS();
 }
 
+function replaceAnalyseEintrag(abteilung, lfd_nr, s) {
+    let e = document.getElementById("__application-analyse-a" + abteilung + "-" + lfd_nr);
+    if (e)
+        e.outerHTML = s;
+}
+
+function replaceAnalyseFortschritt(s) {
+    let e = document.getElementById("__application-analyse-fortschritt");
+    if (e)
+        e.innerHTML = s;
+}
+
 
 function openConfiguration(e) {
     rpc.open_configuration();
@@ -794,6 +837,16 @@ function neueRegexOnEnter(event) {
     }
 }
 
+function konfigurationVergleichen(event) {
+    rpc.konfiguration_vergleichen();
+}
+
+function replaceKonfigurationVergleichFortschritt(s) {
+    let e = document.getElementById("__application-konfiguration-vergleich-fortschritt");
+    if (e)
+        e.innerHTML = s;
+}
+
 function insertRegexFromButton(event) {
 
     let regex_key = event.target.getAttribute("data-regex-id");
@@ -911,6 +964,30 @@ function flurstueckAuslesenScriptTesten(e) {
 }
 
 
+function textSaubernScriptTesten(e) {
+    if (e.target.value) {
+        rpc.text_saubern_script_testen(e.target.value);
+    }
+}
+
+function replaceTextSaubernTestOutput(s) {
+    let test_input = document.getElementById("__application_konfiguration_text_saubern_test");
+    if (test_input)
+         test_input.value = s;
+}
+
+function abkuerzungenScriptTesten(e) {
+    if (e.target.value) {
+        rpc.abkuerzungen_script_testen(e.target.value);
+    }
+}
+
+function replaceAbkuerzungenTestOutput(s) {
+    let test_input = document.getElementById("__application_konfiguration_abkuerzungen_test");
+    if (test_input)
+         test_input.value = s;
+}
+
 function textKuerzenAbt2ScriptTesten(e) {
     if (e.target.value) {
         rpc.kurztext_abt2_script_testen(e.target.value);        
@@ -1059,6 +1136,84 @@ function replaceRegexTestOutput(s) {
          test_input.value = s;
 }
 
+function testfallSpeichern(event) {
+    let ziel = event.target.getAttribute("data-ziel");
+    if (!ziel) {
+        return;
+    }
+    
+    let eingabe_id = ziel == "regex" ? "__application_konfiguration_regex_test_text" : "__application_konfiguration_test_eingabe";
+    let eingabe = document.getElementById(eingabe_id);
+    if (!eingabe || !eingabe.value) {
+        return;
+    }
+    
+    if (ziel == "regex") {
+        let regex_id = document.getElementById("__application_konfiguration_regex_id");
+        if (!regex_id || !regex_id.value) {
+            return;
+        }
+        ziel = "regex:" + regex_id.value;
+    }
+    
+    if (ziel == "pruefe-eintrag") {
+        ziel = "pruefe-eintrag-abt" + pruefeEintragAbteilung();
+    }
+    
+    let bv_nr = "";
+    if (ziel == "flurstuecke-auslesen") {
+        let bv_input = document.getElementById("__application_konfiguration_flurstueck_auslesen_bv_nr");
+        if (!bv_input || !bv_input.value) {
+            return;
+        }
+        bv_nr = bv_input.value;
+    }
+    
+    rpc.testfall_speichern(ziel, eingabe.value, bv_nr);
+}
+
+function testfaelleAusfuehren(event) {
+    rpc.testfaelle_ausfuehren();
+}
+
+function testfallLoeschen(event) {
+    let ziel = event.target.getAttribute("data-ziel");
+    let index = event.target.getAttribute("data-index");
+    if (!ziel || !index) {
+        return;
+    }
+    rpc.testfall_loeschen(ziel, parseInt(index));
+}
+
+function replaceTestfaelle(s) {
+    let testfaelle = document.getElementById("__application_konfiguration_testfaelle");
+    if (testfaelle)
+        testfaelle.innerHTML = s;
+}
+
+function historieVergleichen(event) {
+    let alt = document.getElementById("__application_konfiguration_historie_alt");
+    let neu = document.getElementById("__application_konfiguration_historie_neu");
+    if (!alt || !neu || !alt.value || !neu.value) {
+        return;
+    }
+    rpc.historie_vergleichen(alt.value, neu.value);
+}
+
+function historieWiederherstellen(event) {
+    let alt = document.getElementById("__application_konfiguration_historie_alt");
+    if (!alt || !alt.value) {
+        return;
+    }
+    rpc.historie_wiederherstellen(alt.value);
+}
+
+function replaceHistorieDiff(s) {
+    let diff = document.getElementById("__application_konfiguration_historie_diff");
+    if (diff)
+        diff.innerHTML = s;
+}
+
 function rechteArtScriptTesten(e) {
     if (e.target.value) {
         rpc.rechteart_script_testen(e.target.value);        
@@ -1202,6 +1357,56 @@ function replaceRechtsinhaberAbt3TestOutput(s) {
          test_input.value = s;
 }
 
+function editPruefeEintragScript(e) {
+    // using innerText here because it preserves newlines
+    var innerText = e.target.innerText;
+    if(innerText[innerText.length-1] === '\n') {
+        innerText = innerText.slice(0,-1);     
+    }
+    
+    rpc.edit_pruefe_eintrag_script(innerText);
+}
+
+function pruefeEintragAbteilung() {
+    let abteilung = document.getElementById("__application_konfiguration_pruefe_eintrag_abteilung");
+    return abteilung ? parseInt(abteilung.value) : 3;
+}
+
+function pruefeEintragScriptTesten(e) {
+    let eingabe = document.getElementById("__application_konfiguration_test_eingabe");
+    if (eingabe && eingabe.value) {
+        rpc.pruefe_eintrag_script_testen(eingabe.value, pruefeEintragAbteilung());        
+    }
+}
+
+function replacePruefeEintragTestOutput(s) {
+    let test_input = document.getElementById("__application_konfiguration_pruefe_eintrag_test");
+    if (test_input)
+         test_input.value = s;
+}
+
+function editEigentuemerAbt1Script(e) {
+    // using innerText here because it preserves newlines
+    var innerText = e.target.innerText;
+    if(innerText[innerText.length-1] === '\n') {
+        innerText = innerText.slice(0,-1);     
+    }
+    
+    rpc.edit_eigentuemer_auslesen_abt1_script(innerText);
+}
+
+function eigentuemerAbt1ScriptTesten(e) {
+    if (e.target.value) {
+        rpc.eigentuemer_auslesen_abt1_script_testen(e.target.value);        
+    }
+}
+
+function replaceEigentuemerAbt1TestOutput(s) {
+    let test_input = document.getElementById("__application_konfiguration_eigentuemer_abt1_test");
+    if (test_input)
+         test_input.value = s;
+}
+
 
 // ---
 
@@ -1594,6 +1799,47 @@ function grundbuchMetaAendernFinished(event) {
     return false;
 }
 
+function befundQuittieren(event) {
+    var abteilung = event.target.getAttribute("data-abteilung");
+    var lfd_nr = event.target.getAttribute("data-lfdNr");
+    var befund = event.target.getAttribute("data-befund");
+    if (!abteilung || !lfd_nr || !befund)
+        return;
+    
+    rpc.befund_quittieren(parseInt(abteilung), parseInt(lfd_nr), JSON.parse(befund));
+}
+
+function befundQuittierenFinished(event) {
+    event.preventDefault();
+    
+    var begruendung = document.getElementById("__application_befund_quittieren_begruendung");
+    if (!begruendung)
+        return;
+    
+    rpc.befund_quittieren_finished(begruendung.value);
+    
+    return false;
+}
+
+function quittierungAufheben(event) {
+    var abteilung = event.target.getAttribute("data-abteilung");
+    var lfd_nr = event.target.getAttribute("data-lfdNr");
+    var befund = event.target.getAttribute("data-befund");
+    if (!abteilung || !lfd_nr || !befund)
+        return;
+    
+    rpc.quittierung_aufheben(parseInt(abteilung), parseInt(lfd_nr), JSON.parse(befund));
+}
+
+function testfallAusEintrag(event) {
+    var abteilung = event.target.getAttribute("data-abteilung");
+    var lfd_nr = event.target.getAttribute("data-lfdNr");
+    if (!abteilung || !lfd_nr)
+        return;
+    
+    rpc.testfall_aus_eintrag(parseInt(abteilung), parseInt(lfd_nr));
+}
+
 function grundbuchAnlegen(event) {
     event.preventDefault();
     
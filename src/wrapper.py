@@ -218,6 +218,56 @@ class Spalte1Eintrag(dict):
         self["voll_belastet"] = False
         self["nur_lastend_an"].extend(nur_lastend_an)
 
+class Pruefung(dict):
+
+    def __init__(self):
+        self.fehlermeldungen = []
+        self.warnungen = []
+
+    def fehler(self, fehler):
+        self.fehlermeldungen.append(fehler)
+
+    def warnung(self, warnung):
+        self.warnungen.append(warnung)
+
+class Gemeinschaft(str):
+    Alleineigentum = 'Alleineigentum'
+    Bruchteile = 'Bruchteile'
+    Erbengemeinschaft = 'Erbengemeinschaft'
+    GbR = 'GbR'
+    Guetergemeinschaft = 'Guetergemeinschaft'
+    Sonstige = 'Sonstige'
+
+class Eigentuemer(dict):
+
+    def __init__(self, name, vorname = None, geburtsname = None, geburtsdatum = None, wohnort = None, anteil = None, gemeinschaft = Gemeinschaft.Alleineigentum, firma = False):
+        self["art"] = "Firma" if firma else "Person"
+        self["name"] = name
+        self["vorname"] = vorname
+        self["geburtsname"] = geburtsname
+        self["geburtsdatum"] = geburtsdatum # "TT.MM.JJJJ"
+        self["wohnort"] = wohnort
+        self["anteil"] = anteil # "1/2"
+        self["gemeinschaft"] = gemeinschaft
+
+class Eigentuemerliste(dict):
+
+    def __init__(self):
+        self.eintraege = [] # List[Eigentuemer]
+        self.warnungen = []
+
+    def __len__(self):
+        return len(self.eintraege)
+
+    def __getitem__(self, index):
+        return self.eintraege[index]
+
+    def append(self, eigentuemer):
+        self.eintraege.append(eigentuemer)
+
+    def warnung(self, warnung):
+        self.warnungen.append(warnung)
+
 class PyResult(dict):
     
     def err(self, any):
@@ -249,6 +299,10 @@ class PyResult(dict):
                 return "{\"result\": \"ok\", \"data\": { \"type\": \"list\", \"data\": " + json.dumps(self.ok) + " } }"
             elif isinstance(self.ok, Spalte1Eintraege):
                 return "{\"result\": \"ok\", \"data\": { \"type\": \"spalte1\", \"data\": { \"eintraege\": " + json.dumps(self.ok.eintraege) + ", \"warnungen\": " + json.dumps(self.ok.warnungen) + "} } }"
+            elif isinstance(self.ok, Pruefung):
+                return "{\"result\": \"ok\", \"data\": { \"type\": \"pruefung\", \"data\": { \"fehler\": " + json.dumps(self.ok.fehlermeldungen) + ", \"warnungen\": " + json.dumps(self.ok.warnungen) + "} } }"
+            elif isinstance(self.ok, Eigentuemerliste):
+                return "{\"result\": \"ok\", \"data\": { \"type\": \"eigentuemer\", \"data\": { \"eintraege\": " + json.dumps(self.ok.eintraege) + ", \"warnungen\": " + json.dumps(self.ok.warnungen) + "} } }"
             elif isinstance(self.ok, Betrag):
                 return "{\"result\": \"ok\", \"data\": { \"type\": \"betrag\", \"data\": " + json.dumps(self.ok) + " } }"
             else: